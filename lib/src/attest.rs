@@ -0,0 +1,94 @@
+//! Attester-signed IPs, for proofs about an address someone else saw.
+//!
+//! Without an attestation the private IP is whatever the prover typed, so a proof only
+//! shows that some IP is clear. An attester, such as a website the user connected to,
+//! signs `attestation_digest` of the address it saw, a nonce and a timestamp with a
+//! secp256k1 or Ed25519 key. The guest checks the signature against the IP it proves, with
+//! SP1's patched `k256` or `curve25519-dalek`, and commits the attester's key, the nonce
+//! and the timestamp, so a verifier that trusts the key knows the proof is about that
+//! connection. The key's length tells the schemes apart: 33 bytes for a compressed
+//! secp256k1 key, 32 for an Ed25519 key.
+
+use alloc::vec::Vec;
+use k256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{commitment::hash_address, policy::Address, AttestationValues};
+
+/// Domain tag prefixed to the signed attestation message.
+const ATTESTATION_DOMAIN: &[u8] = b"zkip-attestation-v2";
+
+/// An attester's signature over one IP: read by the guest, committed without the signature.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttestationInput {
+    /// Public key of the attester: SEC1-compressed secp256k1 (33 bytes) or Ed25519 (32).
+    pub pubkey: Vec<u8>,
+    /// Over `attestation_digest`, 64 bytes: ECDSA `r || s`, or an Ed25519 signature of the
+    /// digest as the message.
+    pub signature: Vec<u8>,
+    /// Chosen by the attester, so a verifier can tie the proof to one connection.
+    pub nonce: [u8; 32],
+    /// When the attester saw the IP, in Unix seconds.
+    pub attested_at: u64,
+}
+
+/// The message an attester signs: SHA-256 over a domain tag, the address family (4 or 6),
+/// the big-endian address, the nonce and the big-endian timestamp.
+pub fn attestation_digest(ip: Address, nonce: &[u8; 32], attested_at: u64) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(ATTESTATION_DOMAIN);
+    hash_address(&mut hasher, ip);
+    hasher.update(nonce);
+    hasher.update(attested_at.to_be_bytes());
+    hasher.finalize().into()
+}
+
+impl AttestationInput {
+    /// Whether the signature is the attester's over `ip` and this nonce and timestamp.
+    /// Malformed keys and signatures fail, as do high-S ECDSA and non-canonical or
+    /// small-order Ed25519 ones (`verify_strict`).
+    pub fn verify(&self, ip: Address) -> bool {
+        let digest = attestation_digest(ip, &self.nonce, self.attested_at);
+        verify_signature(&self.pubkey, &self.signature, &digest)
+    }
+
+    /// What a proof commits for this attestation.
+    pub fn values(&self) -> AttestationValues {
+        AttestationValues {
+            pubkey: self.pubkey.clone(),
+            nonce: self.nonce,
+            attested_at: self.attested_at,
+        }
+    }
+}
+
+/// Whether `signature` is `pubkey`'s over `digest`, telling the schemes apart by the key's
+/// length.
+pub(crate) fn verify_signature(pubkey: &[u8], signature: &[u8], digest: &[u8; 32]) -> bool {
+    match pubkey.len() {
+        33 => verify_secp256k1(pubkey, signature, digest),
+        32 => verify_ed25519(pubkey, signature, digest),
+        _ => false,
+    }
+}
+
+fn verify_secp256k1(pubkey: &[u8], signature: &[u8], digest: &[u8; 32]) -> bool {
+    let Ok(key) = VerifyingKey::from_sec1_bytes(pubkey) else {
+        return false;
+    };
+    let Ok(signature) = Signature::from_slice(signature) else {
+        return false;
+    };
+    key.verify_prehash(digest, &signature).is_ok()
+}
+
+fn verify_ed25519(pubkey: &[u8], signature: &[u8], digest: &[u8; 32]) -> bool {
+    let Ok(key) = ed25519_dalek::VerifyingKey::try_from(pubkey) else {
+        return false;
+    };
+    let Ok(signature) = ed25519_dalek::Signature::from_slice(signature) else {
+        return false;
+    };
+    key.verify_strict(digest, &signature).is_ok()
+}
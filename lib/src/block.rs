@@ -0,0 +1,107 @@
+//! Block proofs, for operators who hold a whole prefix rather than one address.
+//!
+//! A plain proof says one IP is clear, so an operator with a /24 would need 256 of them.
+//! With a block, the guest instead checks that no excluded range meets the prefix of
+//! `prefix_len` bits holding the proven address, and commits `BlockPublicValuesStruct`.
+//!
+//! The block itself stays private: the guest commits `SHA-256(domain, block, salt)` under
+//! a 32-byte salt the operator chooses, and `SHA-256(salt)` as the scope, as for
+//! nullifiers. Whoever the operator hands the salt and the block to recomputes the
+//! commitment to check which block the proof is about. An IPv4 /24 has only 2^24
+//! candidates, so the commitment hides the block only from those without the salt.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{commitment::hash_address, ip::IpV4, nullifier::scope, policy::Address, BlockValues};
+
+/// Domain tag prefixed to the input of `commitment`.
+const BLOCK_DOMAIN: &[u8] = b"zkip-block-v1";
+
+/// The block a proof covers: the prefix of `prefix_len` bits holding the single address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockInput {
+    /// At most 32 for an IPv4 address and 128 for an IPv6 one.
+    pub prefix_len: u8,
+    /// The operator's salt for `commitment`.
+    pub salt: [u8; 32],
+}
+
+/// First and last address of the IPv4 block of `prefix_len` bits holding `ip`, or `None`
+/// if `prefix_len` is over 32.
+pub fn bounds(ip: u32, prefix_len: u8) -> Option<(u32, u32)> {
+    if prefix_len > 32 {
+        return None;
+    }
+    let host = u32::MAX.checked_shr(prefix_len.into()).unwrap_or(0);
+    Some((ip & !host, ip | host))
+}
+
+/// `bounds` for IPv6, or `None` if `prefix_len` is over 128.
+pub fn bounds_v6(ip: u128, prefix_len: u8) -> Option<(u128, u128)> {
+    if prefix_len > 128 {
+        return None;
+    }
+    let host = u128::MAX.checked_shr(prefix_len.into()).unwrap_or(0);
+    Some((ip & !host, ip | host))
+}
+
+/// True if no range of `ranges` meets `start..=end`.
+pub fn disjoint<T: Copy + Ord>(start: T, end: T, ranges: &[(T, T)]) -> bool {
+    ranges
+        .iter()
+        .all(|&(range_start, range_end)| range_end < start || end < range_start)
+}
+
+/// Whether the block of `prefix_len` bits holding `ip` meets none of `ranges` (for IPv4)
+/// or `ranges_v6` (for IPv6), or `None` if `prefix_len` is longer than the address.
+pub fn clear(
+    ip: Address,
+    prefix_len: u8,
+    ranges: &[(u32, u32)],
+    ranges_v6: &[(u128, u128)],
+) -> Option<bool> {
+    Some(match ip {
+        Address::V4(IpV4(ip)) => {
+            let (start, end) = bounds(ip, prefix_len)?;
+            disjoint(start, end, ranges)
+        }
+        Address::V6(ip) => {
+            let (start, end) = bounds_v6(ip, prefix_len)?;
+            disjoint(start, end, ranges_v6)
+        }
+    })
+}
+
+/// SHA-256 over a domain tag, the address family (4 or 6), the block's big-endian first
+/// address, `prefix_len` and the salt. Panics if `prefix_len` is longer than `ip`.
+pub fn commitment(ip: Address, prefix_len: u8, salt: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(BLOCK_DOMAIN);
+    let network = match ip {
+        Address::V4(IpV4(ip)) => {
+            let (network, _) = bounds(ip, prefix_len).expect("prefix fits the address");
+            Address::V4(IpV4(network))
+        }
+        Address::V6(ip) => {
+            let (network, _) = bounds_v6(ip, prefix_len).expect("prefix fits the address");
+            Address::V6(network)
+        }
+    };
+    hash_address(&mut hasher, network);
+    hasher.update([prefix_len]);
+    hasher.update(salt);
+    hasher.finalize().into()
+}
+
+impl BlockInput {
+    /// What a block proof about the block holding `ip` commits besides its result.
+    pub fn values(&self, ip: Address) -> BlockValues {
+        BlockValues {
+            prefix_len: self.prefix_len,
+            is_v6: matches!(ip, Address::V6(_)),
+            scope: scope(&self.salt),
+            commitment: commitment(ip, self.prefix_len, &self.salt),
+        }
+    }
+}
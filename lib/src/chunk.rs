@@ -0,0 +1,190 @@
+//! Chunked proofs, for exclusion sets too large for one execution.
+//!
+//! A single proof reads every range of every excluded country, and large sets such as
+//! US, CN and RU push its cycle count past what one execution holds. The host instead
+//! splits each country's IPv4 and IPv6 ranges into chunks of `CHUNK_SIZE` ranges and
+//! proves each with the chunk guest, which checks the IP against that chunk alone and
+//! commits `ChunkPublicValuesStruct`. The aggregation guest verifies every chunk proof
+//! with SP1's proof composition, checks with `aggregate` that they are about the same IP
+//! and cover each country's ranges in order, and commits one exclusion claim.
+//!
+//! `CHUNK_SIZE` is a power of two and every chunk but a family's last is full, so each
+//! chunk's root is a subtree of that family's range tree (see `merkle`) and the aggregate
+//! commits the same `dataset_root` a single proof over the ranges would.
+//!
+//! A chunk commits the hash of the IP, which anyone can brute-force for IPv4. Chunk proofs
+//! stay with the prover; only the aggregate is published.
+
+use alloc::vec::Vec;
+use alloy_sol_types::sol;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    all_excluded,
+    commitment::hash_address,
+    ip::IpV4,
+    merkle::{country_leaf, leaves_root, ranges_root, ranges_root_v6},
+    policy::Address,
+    range_coverage,
+};
+
+/// Ranges per chunk; a power of two.
+pub const CHUNK_SIZE: usize = 1 << 13;
+
+/// Domain tag prefixed to the input of `ip_commitment`.
+const CHUNK_DOMAIN: &[u8] = b"zkip-chunk-v1";
+
+sol! {
+   /// Public values of one chunk proof, read by the aggregation guest.
+   struct ChunkPublicValuesStruct{
+    bytes32 ip_commitment;        // ip_commitment of the IP checked
+    uint16 country;               // ISO 3166-1 numeric code of the ranges' country
+    bool is_v6;                   // the chunk holds IPv6 ranges
+    uint32 index;                 // position among the country's chunks of that family
+    uint32 range_count;
+    uint64 covered_ipv4;          // IPv4 addresses the chunk's ranges cover
+    bytes32 root;                 // merkle root over the chunk's ranges
+    bool is_excluded;             // the IP is in none of the chunk's ranges
+   }
+}
+
+/// What the chunk guest reads, next to the chunk's packed ranges.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkInput {
+    pub ip: Address,
+    pub country: u16,
+    /// The packed ranges are IPv6 (`pack_ranges_v6`) rather than IPv4 (`pack_ranges`).
+    pub is_v6: bool,
+    pub index: u32,
+}
+
+/// What the aggregation guest reads, next to one compressed proof per chunk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AggregationInput {
+    /// Digest of the chunk guest's vkey (`HashableKey::hash_u32`).
+    pub chunk_vkey: [u32; 8],
+    pub ip: Address,
+    pub timestamp: u64,
+    pub excluded_countries: Vec<u16>,
+    /// `(IPv4, IPv6)` chunks per country, in the order of `excluded_countries`.
+    pub chunk_counts: Vec<(u32, u32)>,
+    /// Each chunk's public values: each country's IPv4 chunks, then its IPv6 ones.
+    pub chunks: Vec<Vec<u8>>,
+}
+
+/// What the aggregation guest commits besides the inputs it was given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aggregate {
+    pub is_excluded: bool,
+    pub dataset_root: [u8; 32],
+    pub range_count: u32,
+    pub covered_ipv4: u64,
+}
+
+/// SHA-256 over a domain tag, the address family (4 or 6) and the big-endian address.
+pub fn ip_commitment(ip: Address) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(CHUNK_DOMAIN);
+    hash_address(&mut hasher, ip);
+    hasher.finalize().into()
+}
+
+/// What the chunk guest commits for `input`, with `ranges` its IPv4 ranges and `ranges_v6`
+/// its IPv6 ones; the family it doesn't hold is empty.
+pub fn chunk_values(
+    input: &ChunkInput,
+    ranges: &[(u32, u32)],
+    ranges_v6: &[(u128, u128)],
+) -> ChunkPublicValuesStruct {
+    // An address is in none of the other family's ranges
+    let is_excluded = match input.ip {
+        Address::V4(IpV4(ip)) => all_excluded(&[ip], ranges),
+        Address::V6(ip) => all_excluded(&[ip], ranges_v6),
+    };
+    let (range_count, root) = if input.is_v6 {
+        (ranges_v6.len(), ranges_root_v6(ranges_v6))
+    } else {
+        (ranges.len(), ranges_root(ranges))
+    };
+    let (_, covered_ipv4) = range_coverage(ranges, ranges_v6);
+    ChunkPublicValuesStruct {
+        ip_commitment: ip_commitment(input.ip).into(),
+        country: input.country,
+        is_v6: input.is_v6,
+        index: input.index,
+        range_count: range_count as u32,
+        covered_ipv4,
+        root: root.into(),
+        is_excluded,
+    }
+}
+
+/// Combine the chunks of `excluded_countries`, `chunk_counts[i]` of them per country, into
+/// the claim they make about `ip`. Panics unless every chunk is about `ip` and the
+/// country, family and position it takes, with every chunk but a family's last full.
+pub fn aggregate(
+    ip: Address,
+    excluded_countries: &[u16],
+    chunk_counts: &[(u32, u32)],
+    chunks: &[ChunkPublicValuesStruct],
+) -> Aggregate {
+    assert_eq!(
+        chunk_counts.len(),
+        excluded_countries.len(),
+        "one chunk count per country"
+    );
+    let commitment = ip_commitment(ip);
+    let mut rest = chunks;
+    let mut take = |code: u16, is_v6: bool, count: u32| {
+        assert!(
+            count as usize <= rest.len(),
+            "chunk counts exceed the chunks"
+        );
+        let (family, tail) = rest.split_at(count as usize);
+        rest = tail;
+        for (index, chunk) in family.iter().enumerate() {
+            assert_eq!(
+                chunk.ip_commitment.0, commitment,
+                "chunks must be about one IP"
+            );
+            assert!(
+                chunk.country == code && chunk.is_v6 == is_v6 && chunk.index as usize == index,
+                "chunks must come in order"
+            );
+            let last = index + 1 == family.len();
+            let size = chunk.range_count as usize;
+            assert!(
+                size == CHUNK_SIZE || (last && (1..=CHUNK_SIZE).contains(&size)),
+                "only a family's last chunk may be short"
+            );
+        }
+        family
+    };
+    let mut aggregate = Aggregate {
+        is_excluded: true,
+        dataset_root: [0; 32],
+        range_count: 0,
+        covered_ipv4: 0,
+    };
+    let mut country_leaves = Vec::with_capacity(excluded_countries.len());
+    for (&code, &(count, count_v6)) in excluded_countries.iter().zip(chunk_counts) {
+        let mut roots = [[0; 32]; 2];
+        for (root, family) in roots
+            .iter_mut()
+            .zip([take(code, false, count), take(code, true, count_v6)])
+        {
+            let chunk_roots: Vec<[u8; 32]> = family.iter().map(|chunk| chunk.root.0).collect();
+            *root = leaves_root(&chunk_roots);
+            for chunk in family {
+                aggregate.is_excluded &= chunk.is_excluded;
+                aggregate.range_count += chunk.range_count;
+                aggregate.covered_ipv4 += chunk.covered_ipv4;
+            }
+        }
+        country_leaves.push(country_leaf(code, &roots[0], &roots[1]));
+    }
+    assert!(rest.is_empty(), "chunk counts do not cover every chunk");
+    aggregate.dataset_root = leaves_root(&country_leaves);
+    aggregate
+}
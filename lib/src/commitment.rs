@@ -0,0 +1,85 @@
+//! Salted commitments to an address, and the check verifiers run on them.
+//!
+//! The hashes the guests take over an address (attestation digests, nullifiers, chunk IP
+//! commitments and block commitments) all write it as `hash_address` does: the family
+//! byte, 4 or 6, then the big-endian address. `salted` is the plain commitment to one
+//! address, `SHA-256(domain, ip, salt)`; `prefix::commitment` and `block::commitment`
+//! commit to the address's network instead.
+//!
+//! Whoever holds the salt and the address opens any of them with `verify_commitment`,
+//! given which kind it is, so hosts, guests and external verifiers recompute commitments
+//! with the same code. An IPv4 address has only 2^32 candidates, so a commitment hides it
+//! only from those without the salt.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{block, ip::IpV4, policy::Address, prefix};
+
+/// Domain tag prefixed to the input of `salted`.
+const SALTED_DOMAIN: &[u8] = b"zkip-commitment-v1";
+
+/// What a salted commitment binds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CommitmentKind {
+    /// The address itself (`salted`).
+    Address,
+    /// Its /24, or /48 for IPv6 (`prefix::commitment`).
+    Prefix,
+    /// Its block of `prefix_len` bits (`block::commitment`).
+    Block(u8),
+}
+
+/// Writes `ip` as its family (4 or 6) and its big-endian bits.
+pub(crate) fn hash_address(hasher: &mut Sha256, ip: Address) {
+    match ip {
+        Address::V4(IpV4(ip)) => {
+            hasher.update([4]);
+            hasher.update(ip.to_be_bytes());
+        }
+        Address::V6(ip) => {
+            hasher.update([6]);
+            hasher.update(ip.to_be_bytes());
+        }
+    }
+}
+
+/// SHA-256 over a domain tag, the address family (4 or 6), the big-endian address and the
+/// salt.
+pub fn salted(ip: Address, salt: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(SALTED_DOMAIN);
+    hash_address(&mut hasher, ip);
+    hasher.update(salt);
+    hasher.finalize().into()
+}
+
+/// The `kind` commitment to `ip` under `salt`, or `None` if a block's prefix is longer
+/// than the address.
+pub fn commit(kind: CommitmentKind, ip: Address, salt: &[u8; 32]) -> Option<[u8; 32]> {
+    Some(match kind {
+        CommitmentKind::Address => salted(ip, salt),
+        CommitmentKind::Prefix => prefix::commitment(ip, salt),
+        CommitmentKind::Block(prefix_len) => {
+            let fits = match ip {
+                Address::V4(IpV4(ip)) => block::bounds(ip, prefix_len).is_some(),
+                Address::V6(ip) => block::bounds_v6(ip, prefix_len).is_some(),
+            };
+            if !fits {
+                return None;
+            }
+            block::commitment(ip, prefix_len, salt)
+        }
+    })
+}
+
+/// True if `commitment` is the `kind` commitment to `ip` under `salt`. A block prefix
+/// longer than the address opens nothing.
+pub fn verify_commitment(
+    commitment: &[u8; 32],
+    kind: CommitmentKind,
+    ip: Address,
+    salt: &[u8; 32],
+) -> bool {
+    commit(kind, ip, salt).is_some_and(|expected| expected == *commitment)
+}
@@ -0,0 +1,32 @@
+//! Companion proofs, for claims that join an IP's location to another SP1 proof.
+//!
+//! A verifier may want more than where an IP is, such as that its holder passed a zk
+//! passport or age check. Rather than verifying two proofs and trusting that they belong
+//! together, the prover hands the guest the other proof as a companion: the guest verifies
+//! it with SP1's proof composition, then commits its own public values wrapped with the
+//! companion's vkey and the SHA-256 of its public values. One on-chain verification then
+//! covers both, and the verifier checks the companion's public values against the digest.
+//!
+//! The guest reads nothing from the companion, so any SP1 program's proof will do; a
+//! verifier must pin the vkey it expects.
+
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// A companion proof's statement; the proof itself goes to the guest next to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompanionInput {
+    /// Digest of the companion program's vkey (`HashableKey::hash_u32`).
+    pub vkey: [u32; 8],
+    /// The companion proof's public values.
+    pub public_values: Vec<u8>,
+}
+
+impl CompanionInput {
+    /// The SHA-256 digest SP1 verifies the companion's public values against, and the
+    /// guest commits.
+    pub fn public_values_digest(&self) -> [u8; 32] {
+        Sha256::digest(&self.public_values).into()
+    }
+}
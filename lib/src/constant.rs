@@ -0,0 +1,134 @@
+//! Constant-work membership checks for the `constant-work` guest.
+//!
+//! The other guests stop at the first range that decides the result, so the cycle count,
+//! which the proving service sees, hints at where the private IP sits in the range list.
+//! These functions visit every range for every address and count matches instead of
+//! returning early, so the work depends on how many addresses and ranges there are, never
+//! on their values. The address family still shows, since IPv4 and IPv6 addresses are
+//! checked against their own ranges.
+
+use crate::ip::IpV4;
+use crate::merkle::split_groups;
+use crate::policy::{Address, PolicyExpr, RangeGroup};
+use crate::{RESERVED_RANGES, RESERVED_RANGES_V6};
+use alloc::vec::Vec;
+
+/// Number of `ranges` containing `ip`, after visiting all of them.
+pub fn hits<T: Copy + Ord>(ip: T, ranges: &[(T, T)]) -> usize {
+    ranges
+        .iter()
+        .map(|&(start, end)| usize::from((start <= ip) & (ip <= end)))
+        .sum()
+}
+
+/// Same result as `crate::all_excluded`.
+pub fn all_excluded<T: Copy + Ord>(ips: &[T], excluded_ranges: &[(T, T)]) -> bool {
+    ips.iter()
+        .map(|&ip| hits(ip, excluded_ranges))
+        .sum::<usize>()
+        == 0
+}
+
+/// Same result as `crate::clear_count`.
+pub fn clear_count<T: Copy + Ord>(ips: &[T], excluded_ranges: &[(T, T)]) -> u32 {
+    ips.iter()
+        .map(|&ip| u32::from(hits(ip, excluded_ranges) == 0))
+        .sum()
+}
+
+/// Same result as `crate::all_included`.
+pub fn all_included<T: Copy + Ord>(ips: &[T], included_ranges: &[(T, T)]) -> bool {
+    ips.iter()
+        .map(|&ip| usize::from(hits(ip, included_ranges) > 0))
+        .sum::<usize>()
+        == ips.len()
+}
+
+/// Same result as `crate::all_public`.
+pub fn all_public(ips: &[u32], ips_v6: &[u128]) -> bool {
+    all_excluded(ips, RESERVED_RANGES) & all_excluded(ips_v6, RESERVED_RANGES_V6)
+}
+
+/// Same result as `crate::country_results`.
+pub fn country_results(
+    ips: &[u32],
+    ips_v6: &[u128],
+    excluded_ranges: &[(u32, u32)],
+    excluded_ranges_v6: &[(u128, u128)],
+    range_counts: &[(u32, u32)],
+) -> Vec<bool> {
+    let groups = split_groups(
+        excluded_ranges,
+        range_counts.iter().map(|&(count, _)| count),
+    );
+    let groups_v6 = split_groups(
+        excluded_ranges_v6,
+        range_counts.iter().map(|&(_, count)| count),
+    );
+    groups
+        .into_iter()
+        .zip(groups_v6)
+        .map(|(group, group_v6)| all_excluded(ips, group) & all_excluded(ips_v6, group_v6))
+        .collect()
+}
+
+/// Same result as `crate::disclosed_code`, after checking the IP against every country.
+pub fn disclosed_code(
+    granularity: u8,
+    ip: u32,
+    excluded_countries: &[u16],
+    excluded_ranges: &[(u32, u32)],
+    range_counts: &[(u32, u32)],
+) -> Option<u16> {
+    let groups = split_groups(
+        excluded_ranges,
+        range_counts.iter().map(|&(count, _)| count),
+    );
+    let hit: Vec<bool> = groups.iter().map(|group| hits(ip, group) > 0).collect();
+    let (&numeric, _) = excluded_countries.iter().zip(hit).find(|&(_, hit)| hit)?;
+    crate::granularity_code(granularity, numeric)
+}
+
+/// Same result as `PolicyExpr::evaluate`, evaluating every sub-expression of `All` and
+/// `Any` instead of stopping at the first that decides them.
+pub fn evaluate(
+    expr: &PolicyExpr,
+    ip: Address,
+    countries: &[RangeGroup<u16>],
+    asns: &[RangeGroup<u32>],
+) -> bool {
+    match expr {
+        PolicyExpr::Country(code) => {
+            let group = countries.iter().find(|(key, ..)| key == code);
+            contains(ip, group.expect("policy country without ranges"))
+        }
+        PolicyExpr::Cidr(ranges, ranges_v6) => contains(ip, &((), ranges, ranges_v6)),
+        PolicyExpr::Asn(asn) => {
+            let group = asns.iter().find(|(key, ..)| key == asn);
+            contains(ip, group.expect("policy ASN without ranges"))
+        }
+        PolicyExpr::All(exprs) => held(exprs, ip, countries, asns) == exprs.len(),
+        PolicyExpr::Any(exprs) => held(exprs, ip, countries, asns) > 0,
+        PolicyExpr::Not(expr) => !evaluate(expr, ip, countries, asns),
+    }
+}
+
+/// Number of `exprs` that hold, after evaluating all of them.
+fn held(
+    exprs: &[PolicyExpr],
+    ip: Address,
+    countries: &[RangeGroup<u16>],
+    asns: &[RangeGroup<u32>],
+) -> usize {
+    exprs
+        .iter()
+        .map(|expr| usize::from(evaluate(expr, ip, countries, asns)))
+        .sum()
+}
+
+fn contains<K>(ip: Address, (_, ranges, ranges_v6): &RangeGroup<K>) -> bool {
+    match ip {
+        Address::V4(IpV4(ip)) => hits(ip, ranges) > 0,
+        Address::V6(ip) => hits(ip, ranges_v6) > 0,
+    }
+}
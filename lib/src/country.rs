@@ -0,0 +1,644 @@
+//! ISO 3166-1 country codes, for hosts and library consumers without `data/countries.csv`.
+//!
+//! Proofs commit numeric codes (`excluded_countries`), while users and the GeoIP databases
+//! name countries by alpha-2 code. `CountryCode` holds a numeric code known to the table
+//! below, which mirrors the CSV, and converts to and from the alpha-2 and alpha-3 codes.
+
+use alloc::string::ToString;
+use core::fmt;
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ZkipError;
+
+/// `(numeric, alpha-2, alpha-3, English short name)` of every country in
+/// `data/countries.csv`, in its order.
+const COUNTRIES: &[(u16, &str, &str, &str)] = &[
+    (4, "AF", "AFG", "Afghanistan"),
+    (248, "AX", "ALA", "Åland Islands"),
+    (8, "AL", "ALB", "Albania"),
+    (12, "DZ", "DZA", "Algeria"),
+    (16, "AS", "ASM", "American Samoa"),
+    (20, "AD", "AND", "Andorra"),
+    (24, "AO", "AGO", "Angola"),
+    (660, "AI", "AIA", "Anguilla"),
+    (10, "AQ", "ATA", "Antarctica"),
+    (28, "AG", "ATG", "Antigua and Barbuda"),
+    (32, "AR", "ARG", "Argentina"),
+    (51, "AM", "ARM", "Armenia"),
+    (533, "AW", "ABW", "Aruba"),
+    (36, "AU", "AUS", "Australia"),
+    (40, "AT", "AUT", "Austria"),
+    (31, "AZ", "AZE", "Azerbaijan"),
+    (44, "BS", "BHS", "Bahamas"),
+    (48, "BH", "BHR", "Bahrain"),
+    (50, "BD", "BGD", "Bangladesh"),
+    (52, "BB", "BRB", "Barbados"),
+    (112, "BY", "BLR", "Belarus"),
+    (56, "BE", "BEL", "Belgium"),
+    (84, "BZ", "BLZ", "Belize"),
+    (204, "BJ", "BEN", "Benin"),
+    (60, "BM", "BMU", "Bermuda"),
+    (64, "BT", "BTN", "Bhutan"),
+    (68, "BO", "BOL", "Bolivia, Plurinational State of"),
+    (535, "BQ", "BES", "Bonaire, Sint Eustatius and Saba"),
+    (70, "BA", "BIH", "Bosnia and Herzegovina"),
+    (72, "BW", "BWA", "Botswana"),
+    (74, "BV", "BVT", "Bouvet Island"),
+    (76, "BR", "BRA", "Brazil"),
+    (86, "IO", "IOT", "British Indian Ocean Territory"),
+    (96, "BN", "BRN", "Brunei Darussalam"),
+    (100, "BG", "BGR", "Bulgaria"),
+    (854, "BF", "BFA", "Burkina Faso"),
+    (108, "BI", "BDI", "Burundi"),
+    (132, "CV", "CPV", "Cabo Verde"),
+    (116, "KH", "KHM", "Cambodia"),
+    (120, "CM", "CMR", "Cameroon"),
+    (124, "CA", "CAN", "Canada"),
+    (136, "KY", "CYM", "Cayman Islands"),
+    (140, "CF", "CAF", "Central African Republic"),
+    (148, "TD", "TCD", "Chad"),
+    (152, "CL", "CHL", "Chile"),
+    (156, "CN", "CHN", "China"),
+    (162, "CX", "CXR", "Christmas Island"),
+    (166, "CC", "CCK", "Cocos (Keeling) Islands"),
+    (170, "CO", "COL", "Colombia"),
+    (174, "KM", "COM", "Comoros"),
+    (178, "CG", "COG", "Congo"),
+    (180, "CD", "COD", "Congo, Democratic Republic of the"),
+    (184, "CK", "COK", "Cook Islands"),
+    (188, "CR", "CRI", "Costa Rica"),
+    (384, "CI", "CIV", "Côte d'Ivoire"),
+    (191, "HR", "HRV", "Croatia"),
+    (192, "CU", "CUB", "Cuba"),
+    (531, "CW", "CUW", "Curaçao"),
+    (196, "CY", "CYP", "Cyprus"),
+    (203, "CZ", "CZE", "Czechia"),
+    (208, "DK", "DNK", "Denmark"),
+    (262, "DJ", "DJI", "Djibouti"),
+    (212, "DM", "DMA", "Dominica"),
+    (214, "DO", "DOM", "Dominican Republic"),
+    (218, "EC", "ECU", "Ecuador"),
+    (818, "EG", "EGY", "Egypt"),
+    (222, "SV", "SLV", "El Salvador"),
+    (226, "GQ", "GNQ", "Equatorial Guinea"),
+    (232, "ER", "ERI", "Eritrea"),
+    (233, "EE", "EST", "Estonia"),
+    (748, "SZ", "SWZ", "Eswatini"),
+    (231, "ET", "ETH", "Ethiopia"),
+    (238, "FK", "FLK", "Falkland Islands (Malvinas)"),
+    (234, "FO", "FRO", "Faroe Islands"),
+    (242, "FJ", "FJI", "Fiji"),
+    (246, "FI", "FIN", "Finland"),
+    (250, "FR", "FRA", "France"),
+    (254, "GF", "GUF", "French Guiana"),
+    (258, "PF", "PYF", "French Polynesia"),
+    (260, "TF", "ATF", "French Southern Territories"),
+    (266, "GA", "GAB", "Gabon"),
+    (270, "GM", "GMB", "Gambia"),
+    (268, "GE", "GEO", "Georgia"),
+    (276, "DE", "DEU", "Germany"),
+    (288, "GH", "GHA", "Ghana"),
+    (292, "GI", "GIB", "Gibraltar"),
+    (300, "GR", "GRC", "Greece"),
+    (304, "GL", "GRL", "Greenland"),
+    (308, "GD", "GRD", "Grenada"),
+    (312, "GP", "GLP", "Guadeloupe"),
+    (316, "GU", "GUM", "Guam"),
+    (320, "GT", "GTM", "Guatemala"),
+    (831, "GG", "GGY", "Guernsey"),
+    (324, "GN", "GIN", "Guinea"),
+    (624, "GW", "GNB", "Guinea-Bissau"),
+    (328, "GY", "GUY", "Guyana"),
+    (332, "HT", "HTI", "Haiti"),
+    (334, "HM", "HMD", "Heard Island and McDonald Islands"),
+    (336, "VA", "VAT", "Holy See"),
+    (340, "HN", "HND", "Honduras"),
+    (344, "HK", "HKG", "Hong Kong"),
+    (348, "HU", "HUN", "Hungary"),
+    (352, "IS", "ISL", "Iceland"),
+    (356, "IN", "IND", "India"),
+    (360, "ID", "IDN", "Indonesia"),
+    (364, "IR", "IRN", "Iran, Islamic Republic of"),
+    (368, "IQ", "IRQ", "Iraq"),
+    (372, "IE", "IRL", "Ireland"),
+    (833, "IM", "IMN", "Isle of Man"),
+    (376, "IL", "ISR", "Israel"),
+    (380, "IT", "ITA", "Italy"),
+    (388, "JM", "JAM", "Jamaica"),
+    (392, "JP", "JPN", "Japan"),
+    (832, "JE", "JEY", "Jersey"),
+    (400, "JO", "JOR", "Jordan"),
+    (398, "KZ", "KAZ", "Kazakhstan"),
+    (404, "KE", "KEN", "Kenya"),
+    (296, "KI", "KIR", "Kiribati"),
+    (408, "KP", "PRK", "Korea, Democratic People's Republic of"),
+    (410, "KR", "KOR", "Korea, Republic of"),
+    (414, "KW", "KWT", "Kuwait"),
+    (417, "KG", "KGZ", "Kyrgyzstan"),
+    (418, "LA", "LAO", "Lao People's Democratic Republic"),
+    (428, "LV", "LVA", "Latvia"),
+    (422, "LB", "LBN", "Lebanon"),
+    (426, "LS", "LSO", "Lesotho"),
+    (430, "LR", "LBR", "Liberia"),
+    (434, "LY", "LBY", "Libya"),
+    (438, "LI", "LIE", "Liechtenstein"),
+    (440, "LT", "LTU", "Lithuania"),
+    (442, "LU", "LUX", "Luxembourg"),
+    (446, "MO", "MAC", "Macao"),
+    (450, "MG", "MDG", "Madagascar"),
+    (454, "MW", "MWI", "Malawi"),
+    (458, "MY", "MYS", "Malaysia"),
+    (462, "MV", "MDV", "Maldives"),
+    (466, "ML", "MLI", "Mali"),
+    (470, "MT", "MLT", "Malta"),
+    (584, "MH", "MHL", "Marshall Islands"),
+    (474, "MQ", "MTQ", "Martinique"),
+    (478, "MR", "MRT", "Mauritania"),
+    (480, "MU", "MUS", "Mauritius"),
+    (175, "YT", "MYT", "Mayotte"),
+    (484, "MX", "MEX", "Mexico"),
+    (583, "FM", "FSM", "Micronesia, Federated States of"),
+    (498, "MD", "MDA", "Moldova, Republic of"),
+    (492, "MC", "MCO", "Monaco"),
+    (496, "MN", "MNG", "Mongolia"),
+    (499, "ME", "MNE", "Montenegro"),
+    (500, "MS", "MSR", "Montserrat"),
+    (504, "MA", "MAR", "Morocco"),
+    (508, "MZ", "MOZ", "Mozambique"),
+    (104, "MM", "MMR", "Myanmar"),
+    (516, "NA", "NAM", "Namibia"),
+    (520, "NR", "NRU", "Nauru"),
+    (524, "NP", "NPL", "Nepal"),
+    (528, "NL", "NLD", "Netherlands, Kingdom of the"),
+    (540, "NC", "NCL", "New Caledonia"),
+    (554, "NZ", "NZL", "New Zealand"),
+    (558, "NI", "NIC", "Nicaragua"),
+    (562, "NE", "NER", "Niger"),
+    (566, "NG", "NGA", "Nigeria"),
+    (570, "NU", "NIU", "Niue"),
+    (574, "NF", "NFK", "Norfolk Island"),
+    (807, "MK", "MKD", "North Macedonia"),
+    (580, "MP", "MNP", "Northern Mariana Islands"),
+    (578, "NO", "NOR", "Norway"),
+    (512, "OM", "OMN", "Oman"),
+    (586, "PK", "PAK", "Pakistan"),
+    (585, "PW", "PLW", "Palau"),
+    (275, "PS", "PSE", "Palestine, State of"),
+    (591, "PA", "PAN", "Panama"),
+    (598, "PG", "PNG", "Papua New Guinea"),
+    (600, "PY", "PRY", "Paraguay"),
+    (604, "PE", "PER", "Peru"),
+    (608, "PH", "PHL", "Philippines"),
+    (612, "PN", "PCN", "Pitcairn"),
+    (616, "PL", "POL", "Poland"),
+    (620, "PT", "PRT", "Portugal"),
+    (630, "PR", "PRI", "Puerto Rico"),
+    (634, "QA", "QAT", "Qatar"),
+    (638, "RE", "REU", "Réunion"),
+    (642, "RO", "ROU", "Romania"),
+    (643, "RU", "RUS", "Russian Federation"),
+    (646, "RW", "RWA", "Rwanda"),
+    (652, "BL", "BLM", "Saint Barthélemy"),
+    (
+        654,
+        "SH",
+        "SHN",
+        "Saint Helena, Ascension and Tristan da Cunha",
+    ),
+    (659, "KN", "KNA", "Saint Kitts and Nevis"),
+    (662, "LC", "LCA", "Saint Lucia"),
+    (663, "MF", "MAF", "Saint Martin (French part)"),
+    (666, "PM", "SPM", "Saint Pierre and Miquelon"),
+    (670, "VC", "VCT", "Saint Vincent and the Grenadines"),
+    (882, "WS", "WSM", "Samoa"),
+    (674, "SM", "SMR", "San Marino"),
+    (678, "ST", "STP", "Sao Tome and Principe"),
+    (682, "SA", "SAU", "Saudi Arabia"),
+    (686, "SN", "SEN", "Senegal"),
+    (688, "RS", "SRB", "Serbia"),
+    (690, "SC", "SYC", "Seychelles"),
+    (694, "SL", "SLE", "Sierra Leone"),
+    (702, "SG", "SGP", "Singapore"),
+    (534, "SX", "SXM", "Sint Maarten (Dutch part)"),
+    (703, "SK", "SVK", "Slovakia"),
+    (705, "SI", "SVN", "Slovenia"),
+    (90, "SB", "SLB", "Solomon Islands"),
+    (706, "SO", "SOM", "Somalia"),
+    (710, "ZA", "ZAF", "South Africa"),
+    (
+        239,
+        "GS",
+        "SGS",
+        "South Georgia and the South Sandwich Islands",
+    ),
+    (728, "SS", "SSD", "South Sudan"),
+    (724, "ES", "ESP", "Spain"),
+    (144, "LK", "LKA", "Sri Lanka"),
+    (729, "SD", "SDN", "Sudan"),
+    (740, "SR", "SUR", "Suriname"),
+    (744, "SJ", "SJM", "Svalbard and Jan Mayen"),
+    (752, "SE", "SWE", "Sweden"),
+    (756, "CH", "CHE", "Switzerland"),
+    (760, "SY", "SYR", "Syrian Arab Republic"),
+    (158, "TW", "TWN", "Taiwan, Province of China"),
+    (762, "TJ", "TJK", "Tajikistan"),
+    (834, "TZ", "TZA", "Tanzania, United Republic of"),
+    (764, "TH", "THA", "Thailand"),
+    (626, "TL", "TLS", "Timor-Leste"),
+    (768, "TG", "TGO", "Togo"),
+    (772, "TK", "TKL", "Tokelau"),
+    (776, "TO", "TON", "Tonga"),
+    (780, "TT", "TTO", "Trinidad and Tobago"),
+    (788, "TN", "TUN", "Tunisia"),
+    (792, "TR", "TUR", "Türkiye"),
+    (795, "TM", "TKM", "Turkmenistan"),
+    (796, "TC", "TCA", "Turks and Caicos Islands"),
+    (798, "TV", "TUV", "Tuvalu"),
+    (800, "UG", "UGA", "Uganda"),
+    (804, "UA", "UKR", "Ukraine"),
+    (784, "AE", "ARE", "United Arab Emirates"),
+    (
+        826,
+        "GB",
+        "GBR",
+        "United Kingdom of Great Britain and Northern Ireland",
+    ),
+    (840, "US", "USA", "United States of America"),
+    (581, "UM", "UMI", "United States Minor Outlying Islands"),
+    (858, "UY", "URY", "Uruguay"),
+    (860, "UZ", "UZB", "Uzbekistan"),
+    (548, "VU", "VUT", "Vanuatu"),
+    (862, "VE", "VEN", "Venezuela, Bolivarian Republic of"),
+    (704, "VN", "VNM", "Viet Nam"),
+    (92, "VG", "VGB", "Virgin Islands (British)"),
+    (850, "VI", "VIR", "Virgin Islands (U.S.)"),
+    (876, "WF", "WLF", "Wallis and Futuna"),
+    (732, "EH", "ESH", "Western Sahara"),
+    (887, "YE", "YEM", "Yemen"),
+    (894, "ZM", "ZMB", "Zambia"),
+    (716, "ZW", "ZWE", "Zimbabwe"),
+];
+
+/// `(numeric, M49 region, M49 sub-region)` of every country in `data/countries.csv` that
+/// has a region, in its order. Antarctica and Taiwan have none.
+const REGIONS: &[(u16, u16, u16)] = &[
+    (4, 142, 34),
+    (248, 150, 154),
+    (8, 150, 39),
+    (12, 2, 15),
+    (16, 9, 61),
+    (20, 150, 39),
+    (24, 2, 202),
+    (660, 19, 419),
+    (28, 19, 419),
+    (32, 19, 419),
+    (51, 142, 145),
+    (533, 19, 419),
+    (36, 9, 53),
+    (40, 150, 155),
+    (31, 142, 145),
+    (44, 19, 419),
+    (48, 142, 145),
+    (50, 142, 34),
+    (52, 19, 419),
+    (112, 150, 151),
+    (56, 150, 155),
+    (84, 19, 419),
+    (204, 2, 202),
+    (60, 19, 21),
+    (64, 142, 34),
+    (68, 19, 419),
+    (535, 19, 419),
+    (70, 150, 39),
+    (72, 2, 202),
+    (74, 19, 419),
+    (76, 19, 419),
+    (86, 2, 202),
+    (96, 142, 35),
+    (100, 150, 151),
+    (854, 2, 202),
+    (108, 2, 202),
+    (132, 2, 202),
+    (116, 142, 35),
+    (120, 2, 202),
+    (124, 19, 21),
+    (136, 19, 419),
+    (140, 2, 202),
+    (148, 2, 202),
+    (152, 19, 419),
+    (156, 142, 30),
+    (162, 9, 53),
+    (166, 9, 53),
+    (170, 19, 419),
+    (174, 2, 202),
+    (178, 2, 202),
+    (180, 2, 202),
+    (184, 9, 61),
+    (188, 19, 419),
+    (384, 2, 202),
+    (191, 150, 39),
+    (192, 19, 419),
+    (531, 19, 419),
+    (196, 142, 145),
+    (203, 150, 151),
+    (208, 150, 154),
+    (262, 2, 202),
+    (212, 19, 419),
+    (214, 19, 419),
+    (218, 19, 419),
+    (818, 2, 15),
+    (222, 19, 419),
+    (226, 2, 202),
+    (232, 2, 202),
+    (233, 150, 154),
+    (748, 2, 202),
+    (231, 2, 202),
+    (238, 19, 419),
+    (234, 150, 154),
+    (242, 9, 54),
+    (246, 150, 154),
+    (250, 150, 155),
+    (254, 19, 419),
+    (258, 9, 61),
+    (260, 2, 202),
+    (266, 2, 202),
+    (270, 2, 202),
+    (268, 142, 145),
+    (276, 150, 155),
+    (288, 2, 202),
+    (292, 150, 39),
+    (300, 150, 39),
+    (304, 19, 21),
+    (308, 19, 419),
+    (312, 19, 419),
+    (316, 9, 57),
+    (320, 19, 419),
+    (831, 150, 154),
+    (324, 2, 202),
+    (624, 2, 202),
+    (328, 19, 419),
+    (332, 19, 419),
+    (334, 9, 53),
+    (336, 150, 39),
+    (340, 19, 419),
+    (344, 142, 30),
+    (348, 150, 151),
+    (352, 150, 154),
+    (356, 142, 34),
+    (360, 142, 35),
+    (364, 142, 34),
+    (368, 142, 145),
+    (372, 150, 154),
+    (833, 150, 154),
+    (376, 142, 145),
+    (380, 150, 39),
+    (388, 19, 419),
+    (392, 142, 30),
+    (832, 150, 154),
+    (400, 142, 145),
+    (398, 142, 143),
+    (404, 2, 202),
+    (296, 9, 57),
+    (408, 142, 30),
+    (410, 142, 30),
+    (414, 142, 145),
+    (417, 142, 143),
+    (418, 142, 35),
+    (428, 150, 154),
+    (422, 142, 145),
+    (426, 2, 202),
+    (430, 2, 202),
+    (434, 2, 15),
+    (438, 150, 155),
+    (440, 150, 154),
+    (442, 150, 155),
+    (446, 142, 30),
+    (450, 2, 202),
+    (454, 2, 202),
+    (458, 142, 35),
+    (462, 142, 34),
+    (466, 2, 202),
+    (470, 150, 39),
+    (584, 9, 57),
+    (474, 19, 419),
+    (478, 2, 202),
+    (480, 2, 202),
+    (175, 2, 202),
+    (484, 19, 419),
+    (583, 9, 57),
+    (498, 150, 151),
+    (492, 150, 155),
+    (496, 142, 30),
+    (499, 150, 39),
+    (500, 19, 419),
+    (504, 2, 15),
+    (508, 2, 202),
+    (104, 142, 35),
+    (516, 2, 202),
+    (520, 9, 57),
+    (524, 142, 34),
+    (528, 150, 155),
+    (540, 9, 54),
+    (554, 9, 53),
+    (558, 19, 419),
+    (562, 2, 202),
+    (566, 2, 202),
+    (570, 9, 61),
+    (574, 9, 53),
+    (807, 150, 39),
+    (580, 9, 57),
+    (578, 150, 154),
+    (512, 142, 145),
+    (586, 142, 34),
+    (585, 9, 57),
+    (275, 142, 145),
+    (591, 19, 419),
+    (598, 9, 54),
+    (600, 19, 419),
+    (604, 19, 419),
+    (608, 142, 35),
+    (612, 9, 61),
+    (616, 150, 151),
+    (620, 150, 39),
+    (630, 19, 419),
+    (634, 142, 145),
+    (638, 2, 202),
+    (642, 150, 151),
+    (643, 150, 151),
+    (646, 2, 202),
+    (652, 19, 419),
+    (654, 2, 202),
+    (659, 19, 419),
+    (662, 19, 419),
+    (663, 19, 419),
+    (666, 19, 21),
+    (670, 19, 419),
+    (882, 9, 61),
+    (674, 150, 39),
+    (678, 2, 202),
+    (682, 142, 145),
+    (686, 2, 202),
+    (688, 150, 39),
+    (690, 2, 202),
+    (694, 2, 202),
+    (702, 142, 35),
+    (534, 19, 419),
+    (703, 150, 151),
+    (705, 150, 39),
+    (90, 9, 54),
+    (706, 2, 202),
+    (710, 2, 202),
+    (239, 19, 419),
+    (728, 2, 202),
+    (724, 150, 39),
+    (144, 142, 34),
+    (729, 2, 15),
+    (740, 19, 419),
+    (744, 150, 154),
+    (752, 150, 154),
+    (756, 150, 155),
+    (760, 142, 145),
+    (762, 142, 143),
+    (834, 2, 202),
+    (764, 142, 35),
+    (626, 142, 35),
+    (768, 2, 202),
+    (772, 9, 61),
+    (776, 9, 61),
+    (780, 19, 419),
+    (788, 2, 15),
+    (792, 142, 145),
+    (795, 142, 143),
+    (796, 19, 419),
+    (798, 9, 61),
+    (800, 2, 202),
+    (804, 150, 151),
+    (784, 142, 145),
+    (826, 150, 154),
+    (840, 19, 21),
+    (581, 9, 57),
+    (858, 19, 419),
+    (860, 142, 143),
+    (548, 9, 54),
+    (862, 19, 419),
+    (704, 142, 35),
+    (92, 19, 419),
+    (850, 19, 419),
+    (876, 9, 61),
+    (732, 2, 15),
+    (887, 142, 145),
+    (894, 2, 202),
+    (716, 2, 202),
+];
+
+/// A country known to the ISO 3166-1 table, by its numeric code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u16", into = "u16")]
+pub struct CountryCode(u16);
+
+impl CountryCode {
+    /// The country with numeric code `numeric`, if the table has one.
+    pub fn from_numeric(numeric: u16) -> Option<Self> {
+        Self::find(|&(code, _, _, _)| code == numeric)
+    }
+
+    /// The country with alpha-2 code `alpha2`, in any case.
+    pub fn from_alpha2(alpha2: &str) -> Option<Self> {
+        Self::find(|&(_, code, _, _)| code.eq_ignore_ascii_case(alpha2))
+    }
+
+    /// The country with alpha-3 code `alpha3`, in any case.
+    pub fn from_alpha3(alpha3: &str) -> Option<Self> {
+        Self::find(|&(_, _, code, _)| code.eq_ignore_ascii_case(alpha3))
+    }
+
+    /// Every country in the table.
+    pub fn all() -> impl Iterator<Item = Self> {
+        COUNTRIES.iter().map(|&(numeric, _, _, _)| Self(numeric))
+    }
+
+    pub fn numeric(self) -> u16 {
+        self.0
+    }
+
+    pub fn alpha2(self) -> &'static str {
+        self.entry().1
+    }
+
+    pub fn alpha3(self) -> &'static str {
+        self.entry().2
+    }
+
+    /// English short name, as in the ISO table (`United States of America`).
+    pub fn name(self) -> &'static str {
+        self.entry().3
+    }
+
+    /// UN M49 region (continent), e.g. 150 = Europe, if the country has one.
+    pub fn region(self) -> Option<u16> {
+        self.regions().map(|&(_, region, _)| region)
+    }
+
+    /// UN M49 sub-region, e.g. 155 = Western Europe, if the country has one.
+    pub fn sub_region(self) -> Option<u16> {
+        self.regions().map(|&(_, _, sub_region)| sub_region)
+    }
+
+    fn find(matches: impl Fn(&(u16, &str, &str, &str)) -> bool) -> Option<Self> {
+        COUNTRIES
+            .iter()
+            .find(|entry| matches(entry))
+            .map(|&(numeric, _, _, _)| Self(numeric))
+    }
+
+    fn entry(self) -> &'static (u16, &'static str, &'static str, &'static str) {
+        COUNTRIES
+            .iter()
+            .find(|&&(numeric, _, _, _)| numeric == self.0)
+            .expect("only built from the table")
+    }
+
+    fn regions(self) -> Option<&'static (u16, u16, u16)> {
+        REGIONS.iter().find(|&&(numeric, _, _)| numeric == self.0)
+    }
+}
+
+impl TryFrom<u16> for CountryCode {
+    type Error = ZkipError;
+
+    fn try_from(numeric: u16) -> Result<Self, Self::Error> {
+        Self::from_numeric(numeric).ok_or_else(|| ZkipError::UnknownCountry(numeric.to_string()))
+    }
+}
+
+impl From<CountryCode> for u16 {
+    fn from(code: CountryCode) -> Self {
+        code.0
+    }
+}
+
+/// Parses an alpha-2 or alpha-3 code in any case, or a numeric code (`250` or `004`).
+impl FromStr for CountryCode {
+    type Err = ZkipError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        let code = code.trim();
+        let found = match code.len() {
+            2 => Self::from_alpha2(code),
+            3 if code.bytes().all(|b| b.is_ascii_digit()) => {
+                code.parse().ok().and_then(Self::from_numeric)
+            }
+            3 => Self::from_alpha3(code),
+            _ => code.parse().ok().and_then(Self::from_numeric),
+        };
+        found.ok_or_else(|| ZkipError::UnknownCountry(code.to_string()))
+    }
+}
+
+/// The alpha-2 code.
+impl fmt::Display for CountryCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.alpha2())
+    }
+}
@@ -0,0 +1,51 @@
+//! Errors of the lib's fallible functions.
+//!
+//! Hosts and FFI layers match on the variant instead of the message, which may change.
+
+use alloc::string::String;
+use core::net::AddrParseError;
+use core::num::ParseIntError;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ZkipError {
+    /// An IPv4 address in none of the forms `ip_to_u32` accepts; the message says which
+    /// form was expected.
+    #[error("Invalid IP format: {0}")]
+    InvalidIp(&'static str),
+    /// Octet `index` (1 to 4) of a dotted-quad IPv4 address isn't a number from 0 to 255.
+    #[error("Invalid octet {index} of a dotted-quad IP")]
+    InvalidOctet {
+        index: usize,
+        #[source]
+        source: ParseIntError,
+    },
+    #[error("Invalid IPv6 address")]
+    InvalidIpv6(#[from] AddrParseError),
+    /// A CIDR block with a prefix longer than 32 bits or host bits set, or none after `/`.
+    #[error("Invalid CIDR block: {0}")]
+    InvalidCidr(&'static str),
+    /// Line of a GeoIP database (counted from 1) that isn't `start,end,CC` with
+    /// `start <= end` (see `geo::GeoDb`).
+    #[error("Invalid GeoIP database row on line {0}")]
+    InvalidGeoDbRow(usize),
+    /// Bytes that aren't a valid `.zkranges` file, or ranges `rangefile::write` can't
+    /// store; the message says which check failed.
+    #[error("Invalid range file: {0}")]
+    InvalidRangeFile(&'static str),
+    /// SSZ or borsh bytes that aren't `values::PublicValues`; the message says why.
+    #[error("Invalid public values encoding: {0}")]
+    InvalidEncoding(&'static str),
+    /// A range whose start is after its end (see `range::Ipv4Range`).
+    #[error("Range start {start} is after end {end}")]
+    InvalidRange { start: u32, end: u32 },
+    /// A country code `country::CountryCode` doesn't know.
+    #[error("Unknown country code: {0}")]
+    UnknownCountry(String),
+    /// Bytes `decode_public_values` reads as none of the committed layouts, or
+    /// `values::PublicValues` as anything but the plain one.
+    #[error("Failed to decode public values")]
+    UnknownPublicValues,
+    /// Plain public values committing a `PUBLIC_VALUES_VERSION` newer than this build's.
+    #[error("Unsupported public values version {0}")]
+    UnsupportedPublicValuesVersion(u16),
+}
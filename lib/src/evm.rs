@@ -0,0 +1,1281 @@
+//! The ABI layouts proofs commit, and their encoding and decoding.
+//!
+//! Guests commit their public values as Solidity ABI structs, so the verifier contracts
+//! can read them. The layouts come from alloy's `sol!`, behind the default `evm` feature;
+//! without it the crate keeps the range logic, merkle trees and input types, for
+//! verifiers that never read ABI bytes. Everything here is re-exported at the crate root.
+
+use alloc::vec::Vec;
+use alloy_sol_types::{sol, SolType};
+
+use crate::{
+    chunk, error::ZkipError, policy, publisher, snapshot, AggregationValues, AnchorInput,
+    AttestationValues, BlockValues, CompanionValues, DenylistValues, NullifierValues, PolicyValues,
+    PrefixValues, PublisherValues, RenewalValues, TimeBeaconValues, ValidityWindow,
+    DOMAIN_SEPARATOR, MODE_BLOCK, MODE_CHALLENGED, MODE_COMBINED, MODE_COMPOSED, MODE_EXCLUSION,
+    MODE_INCLUSION, MODE_POLICY, MODE_RENEWAL, MODE_THRESHOLD, MODE_WINDOWED,
+    PUBLIC_VALUES_VERSION,
+};
+
+sol! {
+   struct PublicValuesStruct{
+    bool is_excluded;
+    uint64 timestamp;             // uint32 before; the encoding of a uint32 time is the same
+    uint16[] excluded_countries;  // ISO 3166-1 numeric codes (840=US, 250=FR, etc.)
+    bytes32 dataset_root;         // merkle::dataset_root of the ranges checked
+    uint32 range_count;           // IPv4 and IPv6 ranges checked
+    uint64 covered_ipv4;          // IPv4 addresses those ranges cover
+    bool is_public_ip;            // the IP is in none of RESERVED_RANGES(_V6)
+    bytes32 ranges_digest;        // ranges_digest of the ranges as the guest read them
+    uint32 nullifier_epoch;       // the nullifier's epoch; zero without a nullifier
+    bytes32 nullifier_scope;      // nullifier::scope of the consumer's salt; zero without one
+    bytes32 nullifier;            // nullifier::nullifier of the IP; zero without one
+    uint16 version;               // PUBLIC_VALUES_VERSION of the program that made the proof
+    bytes32 domain_separator;     // always DOMAIN_SEPARATOR
+    bytes32 snapshot_source;      // snapshot::source_id of the snapshot's URL; zero if unknown
+    uint64 snapshot_date;         // when the snapshot was published; zero if unknown
+    bytes32 snapshot_hash;        // snapshot::content_hash of its files; zero if unknown
+    bytes32 policy_id;            // policy::policy_id of excluded_countries
+    bytes32 app_context;          // the integrator's context, committed as-is; zero if none
+    bytes32 prefix_scope;         // nullifier::scope of the prefix salt; zero without one
+    bytes32 prefix_commitment;    // prefix::commitment of the IP's network; zero without one
+   }
+
+   /// Public values when the prover opts into coarse location disclosure.
+   struct DisclosedPublicValuesStruct{
+    bool is_excluded;
+    uint64 timestamp;
+    uint16[] excluded_countries;
+    uint8 granularity;            // GRANULARITY_REGION, _SUB_REGION or _COUNTRY
+    uint16 region;                // UN M49 code of the IP's region, sub-region or country
+    bytes32 dataset_root;
+   }
+
+   /// Public values of a batch proof over several addresses (a domain's A records, or a
+   /// fleet's egress IPs).
+   struct BatchPublicValuesStruct{
+    bool is_excluded;             // true only if every address is clear
+    uint64 timestamp;
+    uint16[] excluded_countries;
+    uint32 address_count;
+    bytes32 dataset_root;
+    uint32 clear_count;           // addresses in none of the excluded countries
+   }
+
+   /// Public values of a batch proof over an address inventory an organization published
+   /// the root of: every address committed by `inventory_root` was checked.
+   struct InventoryPublicValuesStruct{
+    uint8 mode;                   // MODE_EXCLUSION
+    bool is_excluded;             // true only if every address is clear
+    uint64 timestamp;
+    uint16[] excluded_countries;
+    uint32 address_count;
+    bytes32 dataset_root;
+    bytes32 inventory_root;       // merkle::inventory_root of the sorted addresses
+   }
+
+   /// Public values of a threshold proof: at least `threshold` of `address_count` private
+   /// addresses are in none of `excluded_countries`, without saying which or how many.
+   struct ThresholdPublicValuesStruct{
+    uint8 mode;                   // MODE_THRESHOLD
+    bool is_met;
+    uint64 timestamp;
+    uint16[] excluded_countries;
+    uint32 threshold;
+    uint32 address_count;
+    bytes32 dataset_root;
+   }
+
+   /// Public values of a block proof (see `block`): no address of a private prefix of
+   /// `prefix_len` bits is in any of `excluded_countries`.
+   struct BlockPublicValuesStruct{
+    uint8 mode;                   // MODE_BLOCK
+    bool is_clear;
+    uint64 timestamp;
+    uint16[] excluded_countries;
+    uint8 prefix_len;
+    bool is_v6;
+    bytes32 dataset_root;
+    bytes32 block_scope;          // nullifier::scope of the salt
+    bytes32 block_commitment;     // block::commitment of the prefix
+   }
+
+   /// Public values when the prover anchors the proof to a recent block.
+   struct AnchoredPublicValuesStruct{
+    bool is_excluded;
+    uint64 timestamp;
+    uint16[] excluded_countries;
+    uint8 granularity;            // 0 when no region is disclosed
+    uint16 region;
+    uint64 anchor_block_number;
+    bytes32 anchor_block_hash;    // verifiers check blockhash(anchor_block_number)
+    bytes32 dataset_root;
+   }
+
+   /// Public values of an inclusion proof: the IP is in one of `included_countries`.
+   struct InclusionPublicValuesStruct{
+    uint8 mode;                   // MODE_INCLUSION
+    bool is_included;
+    uint64 timestamp;
+    uint16[] included_countries;
+    bytes32 dataset_root;         // merkle::dataset_root of the included countries' ranges
+   }
+
+   /// Public values of a combined proof: the IP is in one of `included_countries` and in
+   /// none of `excluded_countries`, checked in the same execution.
+   struct CombinedPublicValuesStruct{
+    uint8 mode;                   // MODE_COMBINED
+    bool is_included;
+    bool is_excluded;
+    uint64 timestamp;
+    uint16[] included_countries;
+    uint16[] excluded_countries;
+    bytes32 dataset_root;         // of the included countries' ranges
+    bytes32 denylist_root;        // of the excluded countries' ranges
+   }
+
+   /// Public values of an exclusion proof that also reports each country on its own.
+   struct CountryResultsPublicValuesStruct{
+    uint8 mode;                   // MODE_EXCLUSION
+    bool is_excluded;             // true only if every entry of country_results is
+    uint64 timestamp;
+    uint16[] excluded_countries;
+    bool[] country_results;       // country_results[i]: the IP is not in excluded_countries[i]
+    bytes32 dataset_root;
+   }
+
+   /// Public values of an exclusion proof from adjacency witnesses (see `gap`), which
+   /// never sees the full range lists and so commits no range count, coverage or digest.
+   struct GapPublicValuesStruct{
+    uint8 mode;                   // MODE_EXCLUSION
+    bool is_excluded;             // always true: an IP in a country has no gap witness
+    uint64 timestamp;
+    uint16[] excluded_countries;
+    bytes32 dataset_root;         // the same root the full lists give
+   }
+
+   /// Public values of an exclusion proof about an attester-signed IP (see `attest`), or
+   /// one a notarised response named (see `webproof`).
+   struct AttestedPublicValuesStruct{
+    uint8 mode;                   // MODE_EXCLUSION
+    bool is_excluded;
+    uint64 timestamp;
+    uint16[] excluded_countries;
+    bytes32 dataset_root;
+    bytes attester_pubkey;        // secp256k1 (33 bytes) or Ed25519 (32) key that signed the IP
+    bytes32 attestation_nonce;
+    uint64 attested_at;           // when the attester saw the IP
+   }
+
+   /// Public values of an exclusion proof over a publisher-signed snapshot (see
+   /// `publisher`).
+   struct SignedSnapshotPublicValuesStruct{
+    uint8 mode;                   // MODE_EXCLUSION
+    bool is_excluded;
+    uint64 timestamp;
+    uint16[] excluded_countries;
+    bytes32 dataset_root;
+    bytes32 publisher_pubkey;     // Ed25519 key that signed the snapshot
+    bytes32 snapshot_root;        // root over every country of the snapshot
+    uint64 published_at;          // when the publisher signed it
+    uint64 max_db_age;            // timestamp - published_at is at most this, in seconds
+   }
+
+   /// Public values of an exclusion proof whose timestamp a Roughtime server signed (see
+   /// `roughtime`).
+   struct TimedPublicValuesStruct{
+    uint8 mode;                   // MODE_EXCLUSION
+    bool is_excluded;
+    uint64 timestamp;             // the server's midpoint, rounded down
+    uint16[] excluded_countries;
+    bytes32 dataset_root;
+    bytes32 time_server_pubkey;   // Ed25519 long-term key of the Roughtime server
+    uint32 time_radius;           // the server's uncertainty, in seconds
+   }
+
+   /// Public values of an exclusion proof aggregated from chunk proofs (see `chunk`).
+   struct AggregatedPublicValuesStruct{
+    uint8 mode;                   // MODE_EXCLUSION
+    bool is_excluded;
+    uint64 timestamp;
+    uint16[] excluded_countries;
+    bytes32 dataset_root;         // the same root a single proof over the ranges commits
+    uint32 range_count;
+    uint64 covered_ipv4;
+    bytes32 chunk_vkey;           // renew::vkey_bytes of the chunk guest
+    uint32 chunk_count;
+   }
+
+   /// Public values of a renewal (see `renew`): an earlier proof's public values, unchanged,
+   /// under a new timestamp.
+   struct RenewedPublicValuesStruct{
+    uint8 mode;                   // MODE_RENEWAL
+    uint64 timestamp;             // when the proof was renewed
+    bytes32 previous_vkey;        // renew::vkey_bytes of the program that made the earlier proof
+    uint64 previous_timestamp;    // the earlier proof's timestamp
+    bytes previous_public_values;
+   }
+
+   /// Public values of a proof with a companion (see `compose`): the guest's own public
+   /// values, unchanged, next to the companion proof it verified.
+   struct ComposedPublicValuesStruct{
+    uint8 mode;                   // MODE_COMPOSED
+    bytes32 companion_vkey;       // renew::vkey_bytes of the companion program
+    bytes32 companion_digest;     // SHA-256 of the companion's public values
+    bytes public_values;
+   }
+
+   /// Public values bound to a verifier's challenge: the guest's own public values,
+   /// unchanged, next to the challenge the prover was given.
+   struct ChallengedPublicValuesStruct{
+    uint8 mode;                   // MODE_CHALLENGED
+    bytes32 challenge;
+    bytes public_values;
+   }
+
+   /// Public values with a validity window: the guest's own public values, unchanged,
+   /// valid from their timestamp until `not_after`.
+   struct WindowedPublicValuesStruct{
+    uint8 mode;                   // MODE_WINDOWED
+    uint64 not_before;            // the wrapped claim's timestamp
+    uint64 not_after;             // last second the claim may be accepted
+    bytes public_values;
+   }
+
+   /// Public values of a policy proof: the IP satisfies the policy expression hashed in
+   /// `policy_hash`, over the ranges of the `countries` and ASNs it names.
+   struct PolicyPublicValuesStruct{
+    uint8 mode;                   // MODE_POLICY
+    bool is_allowed;
+    uint64 timestamp;
+    uint16[] countries;           // every country the policy names
+    bytes32 policy_hash;          // policy::PolicyExpr::hash
+    bytes32 dataset_root;         // of the named countries' ranges
+    bytes32 asn_root;             // merkle::asn_root of the named ASNs' ranges
+   }
+
+   /// The layout committed before the dataset root was, still decoded for older proofs.
+   struct LegacyPublicValuesStruct{
+    bool is_excluded;
+    uint64 timestamp;
+    uint16[] excluded_countries;
+   }
+
+}
+
+/// Public values of any mode. For inclusion and combined proofs, `values.is_excluded`
+/// holds `is_included` and `values.excluded_countries` the included countries; for policy
+/// proofs, `is_allowed` and the countries the policy names; for block proofs, `is_clear`.
+pub struct DecodedPublicValues {
+    pub values: PublicValuesStruct,
+    /// `MODE_EXCLUSION`, `MODE_INCLUSION`, `MODE_COMBINED`, `MODE_POLICY`, `MODE_THRESHOLD`
+    /// or `MODE_BLOCK`.
+    pub mode: u8,
+    /// The denylist checked alongside the included countries, for combined proofs.
+    pub denylist: Option<DenylistValues>,
+    pub policy: Option<PolicyValues>,
+    /// Per-country results aligned with `excluded_countries`, if committed.
+    pub country_results: Option<Vec<bool>>,
+    /// `(range_count, covered_ipv4)`, for layouts that commit them. `values` then holds
+    /// them too; otherwise its fields read as zero.
+    pub coverage: Option<(u32, u64)>,
+    /// Whether the IP is outside reserved space, for layouts that commit it. The other
+    /// current layouts can only be proven for public addresses; `values` reads false.
+    pub is_public_ip: Option<bool>,
+    /// `ranges_digest` of the ranges checked, for layouts that commit it.
+    pub ranges_digest: Option<[u8; 32]>,
+    /// The IP's nullifier, for layouts that commit one.
+    pub nullifier: Option<NullifierValues>,
+    /// `PUBLIC_VALUES_VERSION` of the program that made the proof, for layouts that commit
+    /// it.
+    pub version: Option<u16>,
+    /// What the prover reported about its snapshot, for layouts that commit it.
+    pub snapshot: Option<snapshot::SnapshotMetadata>,
+    /// `policy::policy_id` of `excluded_countries`, for layouts that commit it.
+    pub policy_id: Option<[u8; 32]>,
+    /// The integrator's context, zero if none, for layouts that commit it.
+    pub app_context: Option<[u8; 32]>,
+    /// The IP's network prefix commitment, for layouts that commit one.
+    pub prefix: Option<PrefixValues>,
+    /// Disclosed `(granularity, region)`, if any.
+    pub disclosed: Option<(u8, u16)>,
+    pub anchor: Option<AnchorInput>,
+    /// Number of addresses checked, for batch proofs.
+    pub address_count: Option<u32>,
+    /// How many of them are clear, for batch proofs.
+    pub clear_count: Option<u32>,
+    /// Root of the addresses checked, for inventory proofs.
+    pub inventory_root: Option<[u8; 32]>,
+    /// How many addresses must be clear, for threshold proofs.
+    pub threshold: Option<u32>,
+    /// The prefix checked, for block proofs.
+    pub block: Option<BlockValues>,
+    /// Proven from adjacency witnesses (`GapPublicValuesStruct`).
+    pub gap: bool,
+    /// Who signed the IP, for attested proofs.
+    pub attestation: Option<AttestationValues>,
+    /// Who signed the snapshot the ranges came from, for signed-snapshot proofs.
+    pub publisher: Option<PublisherValues>,
+    /// Who signed the timestamp, for timed proofs.
+    pub time_beacon: Option<TimeBeaconValues>,
+    /// The chunk proofs, for aggregated proofs.
+    pub aggregation: Option<AggregationValues>,
+    /// The earlier proof, for renewals. The other fields are its public values, with
+    /// `values.timestamp` the renewal's.
+    pub renewal: Option<RenewalValues>,
+    /// The companion proof, for composed proofs.
+    pub companion: Option<CompanionValues>,
+    /// The verifier's challenge, for challenged proofs.
+    pub challenge: Option<[u8; 32]>,
+    /// When the claim may be accepted, for windowed proofs.
+    pub validity: Option<ValidityWindow>,
+    /// Decoded from a layout that predates `dataset_root`, which then reads as zero.
+    pub legacy: bool,
+}
+
+impl DecodedPublicValues {
+    fn new(is_excluded: bool, timestamp: u64, excluded_countries: Vec<u16>) -> Self {
+        Self {
+            values: PublicValuesStruct {
+                is_excluded,
+                timestamp,
+                excluded_countries,
+                dataset_root: Default::default(),
+                range_count: 0,
+                covered_ipv4: 0,
+                is_public_ip: false,
+                ranges_digest: Default::default(),
+                nullifier_epoch: 0,
+                nullifier_scope: Default::default(),
+                nullifier: Default::default(),
+                version: 0,
+                domain_separator: Default::default(),
+                snapshot_source: Default::default(),
+                snapshot_date: 0,
+                snapshot_hash: Default::default(),
+                policy_id: Default::default(),
+                app_context: Default::default(),
+                prefix_scope: Default::default(),
+                prefix_commitment: Default::default(),
+            },
+            mode: MODE_EXCLUSION,
+            denylist: None,
+            policy: None,
+            country_results: None,
+            coverage: None,
+            is_public_ip: None,
+            ranges_digest: None,
+            nullifier: None,
+            version: None,
+            snapshot: None,
+            policy_id: None,
+            app_context: None,
+            prefix: None,
+            disclosed: None,
+            anchor: None,
+            address_count: None,
+            clear_count: None,
+            inventory_root: None,
+            threshold: None,
+            block: None,
+            gap: false,
+            attestation: None,
+            publisher: None,
+            time_beacon: None,
+            aggregation: None,
+            renewal: None,
+            companion: None,
+            challenge: None,
+            validity: None,
+            legacy: true,
+        }
+    }
+
+    fn with_root(mut self, dataset_root: [u8; 32]) -> Self {
+        self.values.dataset_root = dataset_root.into();
+        self.legacy = false;
+        self
+    }
+
+    fn with_coverage(mut self, range_count: u32, covered_ipv4: u64) -> Self {
+        self.values.range_count = range_count;
+        self.values.covered_ipv4 = covered_ipv4;
+        self.coverage = Some((range_count, covered_ipv4));
+        self
+    }
+}
+
+/// Encode the public values for any mode, with the `(granularity, region)` the guest
+/// derived with `disclosed_code`, if any. `coverage` is the `range_coverage` of the ranges checked; only the plain layout commits
+/// it, `is_public_ip`, `ranges_digest`, a `nullifier` and the `snapshot` metadata, each
+/// zero when `None`, the `policy::policy_id` of `excluded_countries`, and the `app_context`
+/// and `prefix`, each zero when `None`.
+#[allow(clippy::too_many_arguments)]
+pub fn encode_public_values(
+    is_excluded: bool,
+    is_public_ip: bool,
+    timestamp: u64,
+    excluded_countries: Vec<u16>,
+    disclosed: Option<(u8, u16)>,
+    anchor: Option<AnchorInput>,
+    dataset_root: [u8; 32],
+    (range_count, covered_ipv4): (u32, u64),
+    ranges_digest: [u8; 32],
+    nullifier: Option<NullifierValues>,
+    snapshot: Option<snapshot::SnapshotMetadata>,
+    app_context: Option<[u8; 32]>,
+    prefix: Option<PrefixValues>,
+) -> Vec<u8> {
+    assert!(
+        nullifier.is_none() || (disclosed.is_none() && anchor.is_none()),
+        "only the plain layout commits a nullifier"
+    );
+    assert!(
+        app_context.is_none() || (disclosed.is_none() && anchor.is_none()),
+        "only the plain layout commits an app context"
+    );
+    assert!(
+        prefix.is_none() || (disclosed.is_none() && anchor.is_none()),
+        "only the plain layout commits a network prefix"
+    );
+    let dataset_root = dataset_root.into();
+    match (disclosed, anchor) {
+        (None, None) => {
+            let (nullifier_epoch, nullifier_scope, nullifier) =
+                nullifier.map_or((0, [0; 32], [0; 32]), |n| (n.epoch, n.scope, n.nullifier));
+            let snapshot = snapshot.unwrap_or(snapshot::SnapshotMetadata {
+                source: [0; 32],
+                published_at: 0,
+                hash: [0; 32],
+            });
+            let (prefix_scope, prefix_commitment) =
+                prefix.map_or(([0; 32], [0; 32]), |p| (p.scope, p.commitment));
+            let policy_id = policy::policy_id(&excluded_countries).into();
+            PublicValuesStruct::abi_encode(&PublicValuesStruct {
+                is_excluded,
+                timestamp,
+                excluded_countries,
+                dataset_root,
+                range_count,
+                covered_ipv4,
+                is_public_ip,
+                ranges_digest: ranges_digest.into(),
+                nullifier_epoch,
+                nullifier_scope: nullifier_scope.into(),
+                nullifier: nullifier.into(),
+                version: PUBLIC_VALUES_VERSION,
+                domain_separator: DOMAIN_SEPARATOR.into(),
+                snapshot_source: snapshot.source.into(),
+                snapshot_date: snapshot.published_at,
+                snapshot_hash: snapshot.hash.into(),
+                policy_id,
+                app_context: app_context.unwrap_or_default().into(),
+                prefix_scope: prefix_scope.into(),
+                prefix_commitment: prefix_commitment.into(),
+            })
+        }
+        (Some((granularity, region)), None) => {
+            DisclosedPublicValuesStruct::abi_encode(&DisclosedPublicValuesStruct {
+                is_excluded,
+                timestamp,
+                excluded_countries,
+                granularity,
+                region,
+                dataset_root,
+            })
+        }
+        (disclosed, Some((number, hash))) => {
+            let (granularity, region) = disclosed.unwrap_or((0, 0));
+            AnchoredPublicValuesStruct::abi_encode(&AnchoredPublicValuesStruct {
+                is_excluded,
+                timestamp,
+                excluded_countries,
+                granularity,
+                region,
+                anchor_block_number: number,
+                anchor_block_hash: hash.into(),
+                dataset_root,
+            })
+        }
+    }
+}
+
+/// Encode the public values of a batch proof over `address_count` addresses, `clear_count`
+/// of them clear.
+pub fn encode_batch_public_values(
+    is_excluded: bool,
+    timestamp: u64,
+    excluded_countries: Vec<u16>,
+    address_count: u32,
+    dataset_root: [u8; 32],
+    clear_count: u32,
+) -> Vec<u8> {
+    BatchPublicValuesStruct::abi_encode(&BatchPublicValuesStruct {
+        is_excluded,
+        timestamp,
+        excluded_countries,
+        address_count,
+        dataset_root: dataset_root.into(),
+        clear_count,
+    })
+}
+
+/// Encode the public values of a batch proof over the `address_count` addresses of an
+/// inventory with root `inventory_root`.
+pub fn encode_inventory_public_values(
+    is_excluded: bool,
+    timestamp: u64,
+    excluded_countries: Vec<u16>,
+    address_count: u32,
+    dataset_root: [u8; 32],
+    inventory_root: [u8; 32],
+) -> Vec<u8> {
+    InventoryPublicValuesStruct::abi_encode(&InventoryPublicValuesStruct {
+        mode: MODE_EXCLUSION,
+        is_excluded,
+        timestamp,
+        excluded_countries,
+        address_count,
+        dataset_root: dataset_root.into(),
+        inventory_root: inventory_root.into(),
+    })
+}
+
+/// Encode the public values of a threshold proof: whether at least `threshold` of
+/// `address_count` addresses are clear.
+pub fn encode_threshold_public_values(
+    is_met: bool,
+    timestamp: u64,
+    excluded_countries: Vec<u16>,
+    threshold: u32,
+    address_count: u32,
+    dataset_root: [u8; 32],
+) -> Vec<u8> {
+    ThresholdPublicValuesStruct::abi_encode(&ThresholdPublicValuesStruct {
+        mode: MODE_THRESHOLD,
+        is_met,
+        timestamp,
+        excluded_countries,
+        threshold,
+        address_count,
+        dataset_root: dataset_root.into(),
+    })
+}
+
+/// Encode the public values of a block proof: whether the prefix `block` describes is
+/// clear.
+pub fn encode_block_public_values(
+    is_clear: bool,
+    timestamp: u64,
+    excluded_countries: Vec<u16>,
+    dataset_root: [u8; 32],
+    block: BlockValues,
+) -> Vec<u8> {
+    BlockPublicValuesStruct::abi_encode(&BlockPublicValuesStruct {
+        mode: MODE_BLOCK,
+        is_clear,
+        timestamp,
+        excluded_countries,
+        prefix_len: block.prefix_len,
+        is_v6: block.is_v6,
+        dataset_root: dataset_root.into(),
+        block_scope: block.scope.into(),
+        block_commitment: block.commitment.into(),
+    })
+}
+
+/// Encode the public values of an inclusion proof over `included_countries`.
+pub fn encode_inclusion_public_values(
+    is_included: bool,
+    timestamp: u64,
+    included_countries: Vec<u16>,
+    dataset_root: [u8; 32],
+) -> Vec<u8> {
+    InclusionPublicValuesStruct::abi_encode(&InclusionPublicValuesStruct {
+        mode: MODE_INCLUSION,
+        is_included,
+        timestamp,
+        included_countries,
+        dataset_root: dataset_root.into(),
+    })
+}
+
+/// Encode the public values of a combined proof over `included_countries` and a denylist
+/// of `excluded_countries`.
+pub fn encode_combined_public_values(
+    is_included: bool,
+    is_excluded: bool,
+    timestamp: u64,
+    included_countries: Vec<u16>,
+    excluded_countries: Vec<u16>,
+    dataset_root: [u8; 32],
+    denylist_root: [u8; 32],
+) -> Vec<u8> {
+    CombinedPublicValuesStruct::abi_encode(&CombinedPublicValuesStruct {
+        mode: MODE_COMBINED,
+        is_included,
+        is_excluded,
+        timestamp,
+        included_countries,
+        excluded_countries,
+        dataset_root: dataset_root.into(),
+        denylist_root: denylist_root.into(),
+    })
+}
+
+/// Encode the public values of an exclusion proof with one result per excluded country.
+pub fn encode_country_results_public_values(
+    timestamp: u64,
+    excluded_countries: Vec<u16>,
+    country_results: Vec<bool>,
+    dataset_root: [u8; 32],
+) -> Vec<u8> {
+    assert_eq!(
+        country_results.len(),
+        excluded_countries.len(),
+        "one result per excluded country"
+    );
+    CountryResultsPublicValuesStruct::abi_encode(&CountryResultsPublicValuesStruct {
+        mode: MODE_EXCLUSION,
+        is_excluded: country_results.iter().all(|&cleared| cleared),
+        timestamp,
+        excluded_countries,
+        country_results,
+        dataset_root: dataset_root.into(),
+    })
+}
+
+/// Encode the public values of an exclusion proof from adjacency witnesses, whose
+/// `dataset_root` is `gap::gap_root`.
+pub fn encode_gap_public_values(
+    timestamp: u64,
+    excluded_countries: Vec<u16>,
+    dataset_root: [u8; 32],
+) -> Vec<u8> {
+    GapPublicValuesStruct::abi_encode(&GapPublicValuesStruct {
+        mode: MODE_EXCLUSION,
+        is_excluded: true,
+        timestamp,
+        excluded_countries,
+        dataset_root: dataset_root.into(),
+    })
+}
+
+/// Encode the public values of an exclusion proof about an IP `attestation` vouches for,
+/// from `attest::AttestationInput::values` or `webproof::WebProofInput::values`.
+pub fn encode_attested_public_values(
+    is_excluded: bool,
+    timestamp: u64,
+    excluded_countries: Vec<u16>,
+    dataset_root: [u8; 32],
+    attestation: &AttestationValues,
+) -> Vec<u8> {
+    AttestedPublicValuesStruct::abi_encode(&AttestedPublicValuesStruct {
+        mode: MODE_EXCLUSION,
+        is_excluded,
+        timestamp,
+        excluded_countries,
+        dataset_root: dataset_root.into(),
+        attester_pubkey: attestation.pubkey.clone().into(),
+        attestation_nonce: attestation.nonce.into(),
+        attested_at: attestation.attested_at,
+    })
+}
+
+/// Encode the public values of an exclusion proof over the snapshot `snapshot` signs.
+pub fn encode_signed_snapshot_public_values(
+    is_excluded: bool,
+    timestamp: u64,
+    excluded_countries: Vec<u16>,
+    dataset_root: [u8; 32],
+    snapshot: &publisher::SnapshotSignature,
+) -> Vec<u8> {
+    SignedSnapshotPublicValuesStruct::abi_encode(&SignedSnapshotPublicValuesStruct {
+        mode: MODE_EXCLUSION,
+        is_excluded,
+        timestamp,
+        excluded_countries,
+        dataset_root: dataset_root.into(),
+        publisher_pubkey: snapshot.pubkey.into(),
+        snapshot_root: snapshot.snapshot_root.into(),
+        published_at: snapshot.published_at,
+        max_db_age: snapshot.max_age,
+    })
+}
+
+/// Encode the public values of an exclusion proof whose `timestamp` the Roughtime server
+/// with long-term key `time_server_pubkey` signed, give or take `time_radius` seconds.
+pub fn encode_timed_public_values(
+    is_excluded: bool,
+    timestamp: u64,
+    excluded_countries: Vec<u16>,
+    dataset_root: [u8; 32],
+    time_server_pubkey: [u8; 32],
+    time_radius: u32,
+) -> Vec<u8> {
+    TimedPublicValuesStruct::abi_encode(&TimedPublicValuesStruct {
+        mode: MODE_EXCLUSION,
+        is_excluded,
+        timestamp,
+        excluded_countries,
+        dataset_root: dataset_root.into(),
+        time_server_pubkey: time_server_pubkey.into(),
+        time_radius,
+    })
+}
+
+/// Encode the public values of an exclusion proof aggregated from `chunk_count` proofs of
+/// the chunk guest with vkey `chunk_vkey` (see `chunk`).
+pub fn encode_aggregated_public_values(
+    timestamp: u64,
+    excluded_countries: Vec<u16>,
+    aggregate: chunk::Aggregate,
+    chunk_vkey: [u8; 32],
+    chunk_count: u32,
+) -> Vec<u8> {
+    AggregatedPublicValuesStruct::abi_encode(&AggregatedPublicValuesStruct {
+        mode: MODE_EXCLUSION,
+        is_excluded: aggregate.is_excluded,
+        timestamp,
+        excluded_countries,
+        dataset_root: aggregate.dataset_root.into(),
+        range_count: aggregate.range_count,
+        covered_ipv4: aggregate.covered_ipv4,
+        chunk_vkey: chunk_vkey.into(),
+        chunk_count,
+    })
+}
+
+/// Encode the public values of a renewal of the proof with `previous_public_values`.
+pub fn encode_renewed_public_values(
+    timestamp: u64,
+    previous_vkey: [u8; 32],
+    previous_timestamp: u64,
+    previous_public_values: Vec<u8>,
+) -> Vec<u8> {
+    RenewedPublicValuesStruct::abi_encode(&RenewedPublicValuesStruct {
+        mode: MODE_RENEWAL,
+        timestamp,
+        previous_vkey: previous_vkey.into(),
+        previous_timestamp,
+        previous_public_values: previous_public_values.into(),
+    })
+}
+
+/// Encode `public_values` joined to a companion proof.
+pub fn encode_composed_public_values(
+    public_values: Vec<u8>,
+    companion_vkey: [u8; 32],
+    companion_digest: [u8; 32],
+) -> Vec<u8> {
+    ComposedPublicValuesStruct::abi_encode(&ComposedPublicValuesStruct {
+        mode: MODE_COMPOSED,
+        companion_vkey: companion_vkey.into(),
+        companion_digest: companion_digest.into(),
+        public_values: public_values.into(),
+    })
+}
+
+/// Encode `public_values` bound to a verifier's `challenge`.
+pub fn encode_challenged_public_values(public_values: Vec<u8>, challenge: [u8; 32]) -> Vec<u8> {
+    ChallengedPublicValuesStruct::abi_encode(&ChallengedPublicValuesStruct {
+        mode: MODE_CHALLENGED,
+        challenge: challenge.into(),
+        public_values: public_values.into(),
+    })
+}
+
+/// Encode `public_values` valid within `window`, which must start at their timestamp.
+pub fn encode_windowed_public_values(public_values: Vec<u8>, window: ValidityWindow) -> Vec<u8> {
+    WindowedPublicValuesStruct::abi_encode(&WindowedPublicValuesStruct {
+        mode: MODE_WINDOWED,
+        not_before: window.not_before,
+        not_after: window.not_after,
+        public_values: public_values.into(),
+    })
+}
+
+/// Encode the public values of a policy proof.
+pub fn encode_policy_public_values(
+    is_allowed: bool,
+    timestamp: u64,
+    countries: Vec<u16>,
+    policy_hash: [u8; 32],
+    dataset_root: [u8; 32],
+    asn_root: [u8; 32],
+) -> Vec<u8> {
+    PolicyPublicValuesStruct::abi_encode(&PolicyPublicValuesStruct {
+        mode: MODE_POLICY,
+        is_allowed,
+        timestamp,
+        countries,
+        policy_hash: policy_hash.into(),
+        dataset_root: dataset_root.into(),
+        asn_root: asn_root.into(),
+    })
+}
+
+impl From<CountryResultsPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: CountryResultsPublicValuesStruct) -> Self {
+        Self {
+            country_results: Some(v.country_results),
+            ..Self::new(v.is_excluded, v.timestamp, v.excluded_countries)
+        }
+        .with_root(v.dataset_root.0)
+    }
+}
+
+impl From<PolicyPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: PolicyPublicValuesStruct) -> Self {
+        Self {
+            mode: v.mode,
+            policy: Some(PolicyValues {
+                policy_hash: v.policy_hash.0,
+                asn_root: v.asn_root.0,
+            }),
+            ..Self::new(v.is_allowed, v.timestamp, v.countries)
+        }
+        .with_root(v.dataset_root.0)
+    }
+}
+
+impl From<CombinedPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: CombinedPublicValuesStruct) -> Self {
+        Self {
+            mode: v.mode,
+            denylist: Some(DenylistValues {
+                is_excluded: v.is_excluded,
+                excluded_countries: v.excluded_countries,
+                dataset_root: v.denylist_root.0,
+            }),
+            ..Self::new(v.is_included, v.timestamp, v.included_countries)
+        }
+        .with_root(v.dataset_root.0)
+    }
+}
+
+impl From<InclusionPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: InclusionPublicValuesStruct) -> Self {
+        Self {
+            mode: v.mode,
+            ..Self::new(v.is_included, v.timestamp, v.included_countries)
+        }
+        .with_root(v.dataset_root.0)
+    }
+}
+
+impl From<GapPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: GapPublicValuesStruct) -> Self {
+        Self {
+            mode: v.mode,
+            gap: true,
+            ..Self::new(v.is_excluded, v.timestamp, v.excluded_countries)
+        }
+        .with_root(v.dataset_root.0)
+    }
+}
+
+impl From<AttestedPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: AttestedPublicValuesStruct) -> Self {
+        Self {
+            mode: v.mode,
+            attestation: Some(AttestationValues {
+                pubkey: v.attester_pubkey.to_vec(),
+                nonce: v.attestation_nonce.0,
+                attested_at: v.attested_at,
+            }),
+            ..Self::new(v.is_excluded, v.timestamp, v.excluded_countries)
+        }
+        .with_root(v.dataset_root.0)
+    }
+}
+
+impl From<SignedSnapshotPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: SignedSnapshotPublicValuesStruct) -> Self {
+        Self {
+            mode: v.mode,
+            publisher: Some(PublisherValues {
+                pubkey: v.publisher_pubkey.0,
+                snapshot_root: v.snapshot_root.0,
+                published_at: v.published_at,
+                max_age: v.max_db_age,
+            }),
+            ..Self::new(v.is_excluded, v.timestamp, v.excluded_countries)
+        }
+        .with_root(v.dataset_root.0)
+    }
+}
+
+impl From<TimedPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: TimedPublicValuesStruct) -> Self {
+        Self {
+            mode: v.mode,
+            time_beacon: Some(TimeBeaconValues {
+                pubkey: v.time_server_pubkey.0,
+                radius: v.time_radius,
+            }),
+            ..Self::new(v.is_excluded, v.timestamp, v.excluded_countries)
+        }
+        .with_root(v.dataset_root.0)
+    }
+}
+
+impl From<AggregatedPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: AggregatedPublicValuesStruct) -> Self {
+        Self {
+            mode: v.mode,
+            aggregation: Some(AggregationValues {
+                chunk_vkey: v.chunk_vkey.0,
+                chunk_count: v.chunk_count,
+            }),
+            ..Self::new(v.is_excluded, v.timestamp, v.excluded_countries)
+        }
+        .with_root(v.dataset_root.0)
+        .with_coverage(v.range_count, v.covered_ipv4)
+    }
+}
+
+impl From<AnchoredPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: AnchoredPublicValuesStruct) -> Self {
+        Self {
+            disclosed: (v.granularity != 0).then_some((v.granularity, v.region)),
+            anchor: Some((v.anchor_block_number, v.anchor_block_hash.0)),
+            ..Self::new(v.is_excluded, v.timestamp, v.excluded_countries)
+        }
+        .with_root(v.dataset_root.0)
+    }
+}
+
+impl From<DisclosedPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: DisclosedPublicValuesStruct) -> Self {
+        Self {
+            disclosed: Some((v.granularity, v.region)),
+            ..Self::new(v.is_excluded, v.timestamp, v.excluded_countries)
+        }
+        .with_root(v.dataset_root.0)
+    }
+}
+
+impl From<BatchPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: BatchPublicValuesStruct) -> Self {
+        Self {
+            address_count: Some(v.address_count),
+            clear_count: Some(v.clear_count),
+            ..Self::new(v.is_excluded, v.timestamp, v.excluded_countries)
+        }
+        .with_root(v.dataset_root.0)
+    }
+}
+
+impl From<InventoryPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: InventoryPublicValuesStruct) -> Self {
+        Self {
+            mode: v.mode,
+            address_count: Some(v.address_count),
+            inventory_root: Some(v.inventory_root.0),
+            ..Self::new(v.is_excluded, v.timestamp, v.excluded_countries)
+        }
+        .with_root(v.dataset_root.0)
+    }
+}
+
+impl From<ThresholdPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: ThresholdPublicValuesStruct) -> Self {
+        Self {
+            mode: v.mode,
+            address_count: Some(v.address_count),
+            threshold: Some(v.threshold),
+            ..Self::new(v.is_met, v.timestamp, v.excluded_countries)
+        }
+        .with_root(v.dataset_root.0)
+    }
+}
+
+impl From<BlockPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: BlockPublicValuesStruct) -> Self {
+        Self {
+            mode: v.mode,
+            block: Some(BlockValues {
+                prefix_len: v.prefix_len,
+                is_v6: v.is_v6,
+                scope: v.block_scope.0,
+                commitment: v.block_commitment.0,
+            }),
+            ..Self::new(v.is_clear, v.timestamp, v.excluded_countries)
+        }
+        .with_root(v.dataset_root.0)
+    }
+}
+
+impl From<PublicValuesStruct> for DecodedPublicValues {
+    fn from(v: PublicValuesStruct) -> Self {
+        let mut decoded = Self::new(v.is_excluded, v.timestamp, v.excluded_countries)
+            .with_root(v.dataset_root.0)
+            .with_coverage(v.range_count, v.covered_ipv4);
+        decoded.values.is_public_ip = v.is_public_ip;
+        decoded.is_public_ip = Some(v.is_public_ip);
+        decoded.values.ranges_digest = v.ranges_digest;
+        decoded.ranges_digest = Some(v.ranges_digest.0);
+        decoded.values.nullifier_epoch = v.nullifier_epoch;
+        decoded.values.nullifier_scope = v.nullifier_scope;
+        decoded.values.nullifier = v.nullifier;
+        decoded.nullifier = Some(NullifierValues {
+            epoch: v.nullifier_epoch,
+            scope: v.nullifier_scope.0,
+            nullifier: v.nullifier.0,
+        });
+        decoded.values.version = v.version;
+        decoded.values.domain_separator = v.domain_separator;
+        decoded.version = Some(v.version);
+        decoded.values.snapshot_source = v.snapshot_source;
+        decoded.values.snapshot_date = v.snapshot_date;
+        decoded.values.snapshot_hash = v.snapshot_hash;
+        decoded.snapshot = Some(snapshot::SnapshotMetadata {
+            source: v.snapshot_source.0,
+            published_at: v.snapshot_date,
+            hash: v.snapshot_hash.0,
+        });
+        decoded.values.policy_id = v.policy_id;
+        decoded.policy_id = Some(v.policy_id.0);
+        decoded.values.app_context = v.app_context;
+        decoded.app_context = Some(v.app_context.0);
+        decoded.values.prefix_scope = v.prefix_scope;
+        decoded.values.prefix_commitment = v.prefix_commitment;
+        decoded.prefix = Some(PrefixValues {
+            scope: v.prefix_scope.0,
+            commitment: v.prefix_commitment.0,
+        });
+        decoded
+    }
+}
+
+impl From<LegacyPublicValuesStruct> for DecodedPublicValues {
+    fn from(v: LegacyPublicValuesStruct) -> Self {
+        Self::new(v.is_excluded, v.timestamp, v.excluded_countries)
+    }
+}
+
+/// Decode `bytes` as layout `T` if they are exactly its canonical encoding.
+fn decode_layout<T>(bytes: &[u8]) -> Option<DecodedPublicValues>
+where
+    T: SolType<RustType = T> + alloy_sol_types::SolValue + Into<DecodedPublicValues>,
+{
+    let value = <T as SolType>::abi_decode(bytes).ok()?;
+    (alloy_sol_types::SolValue::abi_encode(&value) == bytes).then(|| value.into())
+}
+
+/// Decode `bytes` as a renewal if they are exactly its canonical encoding, of public
+/// values that decode themselves and are neither a renewal nor windowed.
+fn decode_renewal(bytes: &[u8]) -> Option<DecodedPublicValues> {
+    let renewed = RenewedPublicValuesStruct::abi_decode(bytes).ok()?;
+    if renewed.mode != MODE_RENEWAL || RenewedPublicValuesStruct::abi_encode(&renewed) != bytes {
+        return None;
+    }
+    let mut decoded = decode_public_values(&renewed.previous_public_values).ok()?;
+    if decoded.renewal.is_some() || decoded.validity.is_some() {
+        return None;
+    }
+    decoded.values.timestamp = renewed.timestamp;
+    decoded.renewal = Some(RenewalValues {
+        previous_vkey: renewed.previous_vkey.0,
+        previous_timestamp: renewed.previous_timestamp,
+    });
+    Some(decoded)
+}
+
+/// Decode `bytes` as a composed proof if they are exactly its canonical encoding, of public
+/// values that decode themselves and are neither composed nor a renewal.
+fn decode_composed(bytes: &[u8]) -> Option<DecodedPublicValues> {
+    let composed = ComposedPublicValuesStruct::abi_decode(bytes).ok()?;
+    if composed.mode != MODE_COMPOSED || ComposedPublicValuesStruct::abi_encode(&composed) != bytes
+    {
+        return None;
+    }
+    let mut decoded = decode_public_values(&composed.public_values).ok()?;
+    if decoded.companion.is_some() || decoded.renewal.is_some() {
+        return None;
+    }
+    decoded.companion = Some(CompanionValues {
+        vkey: composed.companion_vkey.0,
+        public_values_digest: composed.companion_digest.0,
+    });
+    Some(decoded)
+}
+
+/// Decode `bytes` as a challenged proof if they are exactly its canonical encoding, of
+/// public values that decode themselves and are neither challenged, composed nor a renewal.
+fn decode_challenged(bytes: &[u8]) -> Option<DecodedPublicValues> {
+    let challenged = ChallengedPublicValuesStruct::abi_decode(bytes).ok()?;
+    if challenged.mode != MODE_CHALLENGED
+        || ChallengedPublicValuesStruct::abi_encode(&challenged) != bytes
+    {
+        return None;
+    }
+    let mut decoded = decode_public_values(&challenged.public_values).ok()?;
+    if decoded.challenge.is_some() || decoded.companion.is_some() || decoded.renewal.is_some() {
+        return None;
+    }
+    decoded.challenge = Some(challenged.challenge.0);
+    Some(decoded)
+}
+
+/// Decode `bytes` as a windowed proof if they are exactly its canonical encoding, of
+/// public values that decode themselves, wrap nothing and carry `not_before` as their
+/// timestamp, with `not_before <= not_after`.
+fn decode_windowed(bytes: &[u8]) -> Option<DecodedPublicValues> {
+    let windowed = WindowedPublicValuesStruct::abi_decode(bytes).ok()?;
+    if windowed.mode != MODE_WINDOWED
+        || WindowedPublicValuesStruct::abi_encode(&windowed) != bytes
+        || windowed.not_before > windowed.not_after
+    {
+        return None;
+    }
+    let mut decoded = decode_public_values(&windowed.public_values).ok()?;
+    if decoded.validity.is_some()
+        || decoded.challenge.is_some()
+        || decoded.companion.is_some()
+        || decoded.renewal.is_some()
+        || decoded.values.timestamp != windowed.not_before
+    {
+        return None;
+    }
+    decoded.validity = Some(ValidityWindow {
+        not_before: windowed.not_before,
+        not_after: windowed.not_after,
+    });
+    Some(decoded)
+}
+
+/// Head word `index` of a layout's ABI `bytes`, if they have one. Every layout has a
+/// dynamic field, so its encoding starts with an offset word, which this skips.
+fn head_word(bytes: &[u8], index: usize) -> Option<&[u8]> {
+    bytes.get((index + 1) * 32..(index + 2) * 32)
+}
+
+/// Head word `index` of a layout's ABI `bytes`, if it holds an integer below 2^16.
+fn small_word(bytes: &[u8], index: usize) -> Option<u16> {
+    let (high, low) = head_word(bytes, index)?.split_at(30);
+    high.iter()
+        .all(|&byte| byte == 0)
+        .then(|| u16::from_be_bytes([low[0], low[1]]))
+}
+
+/// Decode public values from any mode. The first word picks the layout: every layout with a
+/// `mode` starts with it, and the others with `is_excluded`, so a word of 0 or 1 is
+/// `MODE_EXCLUSION`, `MODE_INCLUSION` or one of those. Plain public values, told apart by
+/// `DOMAIN_SEPARATOR` in their thirteenth head word, must commit this build's
+/// `PUBLIC_VALUES_VERSION`; a newer one fails with `UnsupportedPublicValuesVersion`. Any
+/// other bytes must be exactly the canonical encoding of one layout of their mode.
+///
+/// Some layouts are also checked for consistency. A threshold proof may not need more
+/// addresses than it counts, and a block proof may not have a prefix longer than its
+/// family. The per-country layout must carry one result per country that agrees with
+/// `is_excluded`, the gap layout must say `is_excluded`, and the attested layout must carry
+/// a 33-byte secp256k1 or 32-byte Ed25519 key. The aggregated layout must count at least
+/// one chunk. A batch must count at most `address_count` clear addresses, and say
+/// `is_excluded` only if all of them are. Renewals, composed, challenged and windowed
+/// proofs decode as the claim they wrap.
+pub fn decode_public_values(bytes: &[u8]) -> Result<DecodedPublicValues, ZkipError> {
+    let mode = small_word(bytes, 0).and_then(|word| u8::try_from(word).ok());
+    let decoded = match mode.ok_or(ZkipError::UnknownPublicValues)? {
+        MODE_RENEWAL => decode_renewal(bytes),
+        MODE_COMPOSED => decode_composed(bytes),
+        MODE_CHALLENGED => decode_challenged(bytes),
+        MODE_WINDOWED => decode_windowed(bytes),
+        MODE_POLICY => decode_layout::<PolicyPublicValuesStruct>(bytes),
+        MODE_THRESHOLD => decode_layout::<ThresholdPublicValuesStruct>(bytes)
+            .filter(|decoded| decoded.threshold <= decoded.address_count),
+        MODE_BLOCK => decode_layout::<BlockPublicValuesStruct>(bytes).filter(|decoded| {
+            let block = decoded.block.as_ref();
+            block.is_some_and(|block| block.prefix_len <= if block.is_v6 { 128 } else { 32 })
+        }),
+        MODE_COMBINED => decode_layout::<CombinedPublicValuesStruct>(bytes),
+        MODE_EXCLUSION | MODE_INCLUSION if head_word(bytes, 12) == Some(&DOMAIN_SEPARATOR[..]) => {
+            return decode_plain(bytes);
+        }
+        MODE_EXCLUSION | MODE_INCLUSION => decode_unique(bytes),
+        _ => None,
+    };
+    decoded.ok_or(ZkipError::UnknownPublicValues)
+}
+
+/// Decode `bytes`, which carry `DOMAIN_SEPARATOR` where the plain layout does, as the plain
+/// layout of this build's `PUBLIC_VALUES_VERSION`, whose `version` is its twelfth head word.
+fn decode_plain(bytes: &[u8]) -> Result<DecodedPublicValues, ZkipError> {
+    match small_word(bytes, 11) {
+        Some(PUBLIC_VALUES_VERSION) => {
+            decode_layout::<PublicValuesStruct>(bytes).ok_or(ZkipError::UnknownPublicValues)
+        }
+        Some(version) if version > PUBLIC_VALUES_VERSION => {
+            Err(ZkipError::UnsupportedPublicValuesVersion(version))
+        }
+        _ => Err(ZkipError::UnknownPublicValues),
+    }
+}
+
+/// Decodes `bytes` as one layout, if they are exactly its canonical encoding.
+type LayoutDecoder = fn(&[u8]) -> Option<DecodedPublicValues>;
+
+/// Decode `bytes`, whose first word is 0 or 1, as the one inclusion, exclusion or modeless
+/// layout they are exactly the canonical encoding of. Bytes matching several are rejected
+/// rather than read as whichever is tried first.
+fn decode_unique(bytes: &[u8]) -> Option<DecodedPublicValues> {
+    let layouts: [LayoutDecoder; 12] = [
+        |bytes| {
+            decode_layout::<InclusionPublicValuesStruct>(bytes)
+                .filter(|decoded| decoded.mode == MODE_INCLUSION)
+        },
+        |bytes| {
+            decode_layout::<CountryResultsPublicValuesStruct>(bytes).filter(|decoded| {
+                let results = decoded.country_results.as_deref().unwrap_or_default();
+                decoded.mode == MODE_EXCLUSION
+                    && results.len() == decoded.values.excluded_countries.len()
+                    && results.iter().all(|&cleared| cleared) == decoded.values.is_excluded
+            })
+        },
+        |bytes| {
+            decode_layout::<GapPublicValuesStruct>(bytes)
+                .filter(|decoded| decoded.mode == MODE_EXCLUSION && decoded.values.is_excluded)
+        },
+        |bytes| {
+            decode_layout::<AttestedPublicValuesStruct>(bytes).filter(|decoded| {
+                let attestation = decoded.attestation.as_ref();
+                decoded.mode == MODE_EXCLUSION
+                    && attestation
+                        .is_some_and(|attestation| matches!(attestation.pubkey.len(), 32 | 33))
+            })
+        },
+        |bytes| {
+            decode_layout::<SignedSnapshotPublicValuesStruct>(bytes)
+                .filter(|decoded| decoded.mode == MODE_EXCLUSION)
+        },
+        |bytes| {
+            decode_layout::<TimedPublicValuesStruct>(bytes)
+                .filter(|decoded| decoded.mode == MODE_EXCLUSION)
+        },
+        |bytes| {
+            decode_layout::<AggregatedPublicValuesStruct>(bytes).filter(|decoded| {
+                let aggregation = decoded.aggregation.as_ref();
+                decoded.mode == MODE_EXCLUSION
+                    && aggregation.is_some_and(|aggregation| aggregation.chunk_count > 0)
+            })
+        },
+        |bytes| {
+            decode_layout::<InventoryPublicValuesStruct>(bytes)
+                .filter(|decoded| decoded.mode == MODE_EXCLUSION)
+        },
+        decode_layout::<AnchoredPublicValuesStruct>,
+        decode_layout::<DisclosedPublicValuesStruct>,
+        |bytes| {
+            decode_layout::<BatchPublicValuesStruct>(bytes).filter(|decoded| {
+                let (address_count, clear_count) = (decoded.address_count, decoded.clear_count);
+                clear_count <= address_count
+                    && decoded.values.is_excluded == (clear_count == address_count)
+            })
+        },
+        decode_layout::<LegacyPublicValuesStruct>,
+    ];
+    let mut matches = layouts.iter().filter_map(|decode| decode(bytes));
+    let decoded = matches.next()?;
+    matches.next().is_none().then_some(decoded)
+}
@@ -0,0 +1,102 @@
+//! Exclusion from adjacency witnesses, for the `--gap-witness` mode.
+//!
+//! Each country's ranges are sorted by start and disjoint, so an IP in none of them falls
+//! in a gap between two ranges that sit next to each other in that country's range tree:
+//! the last range starting below it and the first starting above it. Instead of every
+//! range, the host sends those two leaves and their Merkle paths (see
+//! `merkle::merkle_path`), plus the root of the other address family's ranges. The guest
+//! checks the IP lies strictly between them, rebuilds the country's roots from the paths
+//! and commits the same dataset root the full lists would give. Guest input shrinks from
+//! every range to O(log n) hashes per country.
+//!
+//! Adjacency only proves a gap if the tree was built from sorted, disjoint ranges, which
+//! nothing inside a gap proof can check. The committed root is only meaningful when a
+//! verifier pins it to a snapshot built by the host tooling, which sorts and merges them.
+
+use crate::merkle::{adjacency_root, country_set_root, RangeLeaf, RangeProof, RangeTree};
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// One side of a gap: the range's index among its country's ranges, the range, and its
+/// Merkle path.
+pub type GapEnd<T> = RangeProof<T>;
+
+/// Proof that the IP is outside one country, in the IP's address family.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GapWitness<T> {
+    /// Number of the country's ranges in this family.
+    pub leaf_count: u32,
+    /// The last range starting below the IP, unless the IP is below every range.
+    pub left: Option<GapEnd<T>>,
+    /// The first range starting above the IP, unless the IP is above every range.
+    pub right: Option<GapEnd<T>>,
+    /// Root of the country's ranges in the other family, taken as given.
+    pub other_root: [u8; 32],
+}
+
+/// One witness per excluded country, in order, in the family of the single address proven.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GapInput {
+    V4(Vec<GapWitness<u32>>),
+    V6(Vec<GapWitness<u128>>),
+}
+
+/// An address family whose ranges can be witnessed.
+pub trait GapFamily: RangeLeaf {
+    /// `(root, root_v6)` from this family's root and the other one's.
+    fn roots(root: [u8; 32], other_root: [u8; 32]) -> ([u8; 32], [u8; 32]);
+}
+
+impl GapFamily for u32 {
+    fn roots(root: [u8; 32], other_root: [u8; 32]) -> ([u8; 32], [u8; 32]) {
+        (root, other_root)
+    }
+}
+
+impl GapFamily for u128 {
+    fn roots(root: [u8; 32], other_root: [u8; 32]) -> ([u8; 32], [u8; 32]) {
+        (other_root, root)
+    }
+}
+
+impl<T: GapFamily> GapWitness<T> {
+    /// Witness that `ip` is outside the sorted `ranges`, or `None` if one contains it.
+    pub fn new(ip: T, ranges: &[(T, T)], other_root: [u8; 32]) -> Option<Self> {
+        let (left, right) = RangeTree::new(ranges).prove_adjacent(ip)?;
+        Some(Self {
+            leaf_count: ranges.len() as u32,
+            left,
+            right,
+            other_root,
+        })
+    }
+
+    /// The country's `(root, root_v6)` if the witness shows `ip` in a gap of its ranges.
+    pub fn roots(&self, ip: T) -> Option<([u8; 32], [u8; 32])> {
+        let root = adjacency_root(ip, self.leaf_count, self.left.as_ref(), self.right.as_ref())?;
+        Some(T::roots(root, self.other_root))
+    }
+}
+
+/// The dataset root over `excluded_countries` if `witnesses` show `ip` outside each of them,
+/// in order, and at least one of them has ranges in the IP's family.
+pub fn gap_root<T: GapFamily>(
+    ip: T,
+    excluded_countries: &[u16],
+    witnesses: &[GapWitness<T>],
+) -> Option<[u8; 32]> {
+    if witnesses.len() != excluded_countries.len()
+        || witnesses.iter().all(|witness| witness.leaf_count == 0)
+    {
+        return None;
+    }
+    let countries = excluded_countries
+        .iter()
+        .zip(witnesses)
+        .map(|(&code, witness)| {
+            let (root, root_v6) = witness.roots(ip)?;
+            Some((code, root, root_v6))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(country_set_root(&countries))
+}
@@ -0,0 +1,198 @@
+//! Local country lookups over the GeoIP data the prover uses.
+//!
+//! Applications embedding zkip can tell a user which country their IP appears to be in
+//! before proving, for instance that an exclusion of FR will fail. `GeoDb` parses the
+//! same `start,end,CC` CSV files the host caches in `data/`, or the `.zkranges` files it
+//! caches per country, once (no file access, so it works without std). `find_country`
+//! then looks an address up by binary search, and `ranges_for` groups the ranges of a
+//! country list as the guests read them. Clones share the rows, so one database can serve
+//! every thread of a server.
+
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+use core::str::FromStr;
+
+use crate::country::CountryCode;
+use crate::error::ZkipError;
+use crate::ip::IpV4;
+use crate::policy::Address;
+use crate::rangefile::{self, RangeFile};
+use crate::snapshot::SnapshotMetadata;
+
+/// Rows of the IPv4 and IPv6 GeoIP databases, sorted by start address, and the snapshot
+/// they came from if known. Cloning is cheap: clones share the rows.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GeoDb {
+    rows: Arc<Rows>,
+    metadata: Option<SnapshotMetadata>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Rows {
+    ranges: Vec<(u32, u32, CountryCode)>,
+    ranges_v6: Vec<(u128, u128, CountryCode)>,
+}
+
+/// Ranges of a country list, each country's in turn, as `ZkipInput` and the range trees
+/// take them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CountryRanges {
+    pub ranges: Vec<(u32, u32)>,
+    pub ranges_v6: Vec<(u128, u128)>,
+    /// `(IPv4, IPv6)` range counts per country, in order.
+    pub counts: Vec<(u32, u32)>,
+}
+
+impl GeoDb {
+    /// Parse the IPv4 database and, if given, the IPv6 one: one `start,end,CC` row per
+    /// line, with addresses as decimal integers. Rows of codes outside the ISO table are
+    /// skipped, as the host skips them. Fails with `ZkipError::InvalidGeoDbRow` on the
+    /// first malformed row.
+    pub fn from_csv(csv: &str, csv_v6: Option<&str>) -> Result<Self, ZkipError> {
+        let ranges = parse_rows(csv)?;
+        let ranges_v6 = match csv_v6 {
+            Some(csv_v6) => parse_rows(csv_v6)?,
+            None => Vec::new(),
+        };
+        Ok(Self::from_rows(ranges, ranges_v6))
+    }
+
+    /// Build the database from range files (`rangefile`), one per country and address
+    /// family, as the host caches them. Fails with `ZkipError::InvalidRangeFile` on the
+    /// first file `RangeFile::parse` rejects.
+    pub fn from_range_files<'a>(
+        files: impl IntoIterator<Item = (CountryCode, &'a [u8])>,
+    ) -> Result<Self, ZkipError> {
+        let mut ranges = Vec::new();
+        let mut ranges_v6 = Vec::new();
+        for (country, bytes) in files {
+            match rangefile::family(bytes) {
+                Some(6) => {
+                    let file = RangeFile::<u128>::parse(bytes)?;
+                    ranges_v6.extend(file.iter().map(|(start, end)| (start, end, country)));
+                }
+                _ => {
+                    let file = RangeFile::<u32>::parse(bytes)?;
+                    ranges.extend(file.iter().map(|(start, end)| (start, end, country)));
+                }
+            }
+        }
+        Ok(Self::from_rows(ranges, ranges_v6))
+    }
+
+    fn from_rows(
+        mut ranges: Vec<(u32, u32, CountryCode)>,
+        mut ranges_v6: Vec<(u128, u128, CountryCode)>,
+    ) -> Self {
+        ranges.sort_unstable_by_key(|&(start, _, _)| start);
+        ranges_v6.sort_unstable_by_key(|&(start, _, _)| start);
+        Self {
+            rows: Arc::new(Rows { ranges, ranges_v6 }),
+            metadata: None,
+        }
+    }
+
+    /// The database, recording the snapshot it was loaded from.
+    pub fn with_metadata(mut self, metadata: SnapshotMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// The snapshot the database was loaded from, if the loader recorded it.
+    pub fn metadata(&self) -> Option<SnapshotMetadata> {
+        self.metadata
+    }
+
+    /// Number of IPv4 and IPv6 rows.
+    pub fn len(&self) -> usize {
+        self.rows.ranges.len() + self.rows.ranges_v6.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.ranges.is_empty() && self.rows.ranges_v6.is_empty()
+    }
+
+    /// The country the database places `ip` in, or `None` if no row holds it, as for
+    /// reserved and unallocated addresses.
+    pub fn find_country(&self, ip: Address) -> Option<CountryCode> {
+        match ip {
+            Address::V4(IpV4(ip)) => lookup(ip, &self.rows.ranges),
+            Address::V6(ip) => lookup(ip, &self.rows.ranges_v6),
+        }
+    }
+
+    /// The ranges of `countries`, in that order, each country's sorted and deduplicated as
+    /// the host loads them. Countries listed twice get their ranges twice, like their codes
+    /// in the public values.
+    pub fn ranges_for(&self, countries: &[CountryCode]) -> CountryRanges {
+        let by_country = group(&self.rows.ranges, countries);
+        let by_country_v6 = group(&self.rows.ranges_v6, countries);
+        let mut grouped = CountryRanges::default();
+        for country in countries {
+            let group = by_country.get(country).map_or(&[][..], Vec::as_slice);
+            let group_v6 = by_country_v6.get(country).map_or(&[][..], Vec::as_slice);
+            grouped
+                .counts
+                .push((group.len() as u32, group_v6.len() as u32));
+            grouped.ranges.extend_from_slice(group);
+            grouped.ranges_v6.extend_from_slice(group_v6);
+        }
+        grouped
+    }
+}
+
+/// The sorted rows of `countries`, deduplicated, by country.
+fn group<T: Copy + Ord>(
+    rows: &[(T, T, CountryCode)],
+    countries: &[CountryCode],
+) -> BTreeMap<CountryCode, Vec<(T, T)>> {
+    let mut by_country: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for &(start, end, country) in rows {
+        if countries.contains(&country) {
+            by_country.entry(country).or_default().push((start, end));
+        }
+    }
+    for ranges in by_country.values_mut() {
+        ranges.sort_unstable();
+        ranges.dedup();
+    }
+    by_country
+}
+
+/// The rows of one database, unsorted.
+fn parse_rows<T: FromStr + Ord>(csv: &str) -> Result<Vec<(T, T, CountryCode)>, ZkipError> {
+    let mut rows = Vec::new();
+    for (index, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let invalid = ZkipError::InvalidGeoDbRow(index + 1);
+        let mut fields = line.split(',');
+        let (Some(start), Some(end), Some(country)) = (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(invalid);
+        };
+        let (Ok(start), Ok(end)) = (start.trim().parse::<T>(), end.trim().parse::<T>()) else {
+            return Err(invalid);
+        };
+        if start > end {
+            return Err(invalid);
+        }
+        if let Some(country) = CountryCode::from_alpha2(country.trim()) {
+            rows.push((start, end, country));
+        }
+    }
+    Ok(rows)
+}
+
+/// The row of sorted `ranges` holding `ip`, if any.
+fn lookup<T: Copy + Ord>(ip: T, ranges: &[(T, T, CountryCode)]) -> Option<CountryCode> {
+    let after = ranges.partition_point(|&(start, _, _)| start <= ip);
+    let &(_, end, country) = ranges.get(after.checked_sub(1)?)?;
+    (ip <= end).then_some(country)
+}
+
+/// The country `db` places `ip` in (see `GeoDb::find_country`).
+pub fn find_country(ip: Address, db: &GeoDb) -> Option<CountryCode> {
+    db.find_country(ip)
+}
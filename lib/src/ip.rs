@@ -0,0 +1,55 @@
+//! A distinct type for single IPv4 addresses.
+//!
+//! Addresses, counts and range bounds are all `u32`s, so swapping two
+//! arguments or fields still compiles, and the guest would still prove something about the
+//! wrong value. `IpV4` wraps an address wherever one address is meant: in `policy::Address`,
+//! `ZkipInput::ips` and the lookups of `range`. It serializes as the bare `u32`, so inputs
+//! keep their encoding. Range bounds stay `u32`s, as the guests and range trees take them.
+
+use core::fmt;
+use core::net::Ipv4Addr;
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ZkipError, ip_to_u32};
+
+/// One IPv4 address, as the big-endian `u32` of its octets.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct IpV4(pub u32);
+
+impl From<Ipv4Addr> for IpV4 {
+    fn from(ip: Ipv4Addr) -> Self {
+        Self(ip.into())
+    }
+}
+
+impl From<IpV4> for Ipv4Addr {
+    fn from(ip: IpV4) -> Self {
+        Ipv4Addr::from(ip.0)
+    }
+}
+
+impl From<IpV4> for u32 {
+    fn from(ip: IpV4) -> Self {
+        ip.0
+    }
+}
+
+/// Parses any form `ip_to_u32` accepts.
+impl FromStr for IpV4 {
+    type Err = ZkipError;
+
+    fn from_str(ip: &str) -> Result<Self, Self::Err> {
+        ip_to_u32(ip).map(Self)
+    }
+}
+
+/// Dotted quad, e.g. `8.8.8.8`.
+impl fmt::Display for IpV4 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Ipv4Addr::from(self.0).fmt(f)
+    }
+}
@@ -1,48 +1,663 @@
-use alloy_sol_types::sol;
-use anyhow::Context;
+#![cfg_attr(not(feature = "std"), no_std)]
 
-sol! {
-   struct PublicValuesStruct{
-    bool is_excluded;
-    uint32 timestamp;
-    uint16[] excluded_countries;  // ISO 3166-1 numeric codes (840=US, 250=FR, etc.)
-   }
+extern crate alloc;
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::ZkipError;
+pub use crate::ip::IpV4;
+
+pub mod attest;
+pub mod block;
+#[cfg(feature = "evm")]
+pub mod chunk;
+pub mod commitment;
+pub mod compose;
+pub mod constant;
+pub mod country;
+pub mod error;
+#[cfg(feature = "evm")]
+mod evm;
+pub mod gap;
+pub mod geo;
+pub mod ip;
+pub mod merkle;
+pub mod nullifier;
+pub mod policy;
+pub mod prefix;
+pub mod publisher;
+pub mod range;
+pub mod rangefile;
+pub mod renew;
+pub mod roughtime;
+pub mod snapshot;
+pub mod values;
+pub mod webproof;
+
+#[cfg(feature = "evm")]
+pub use evm::*;
+
+/// The IP is in none of the committed countries: the layouts without a mode word,
+/// `CountryResultsPublicValuesStruct`, `GapPublicValuesStruct`,
+/// `AttestedPublicValuesStruct`, `SignedSnapshotPublicValuesStruct`,
+/// `TimedPublicValuesStruct`, `AggregatedPublicValuesStruct` and
+/// `InventoryPublicValuesStruct`.
+pub const MODE_EXCLUSION: u8 = 0;
+/// The IP is in one of the committed countries (`InclusionPublicValuesStruct`).
+pub const MODE_INCLUSION: u8 = 1;
+/// The IP is in one of the included countries and none of the excluded ones
+/// (`CombinedPublicValuesStruct`).
+pub const MODE_COMBINED: u8 = 2;
+/// The IP satisfies a policy expression (`PolicyPublicValuesStruct`).
+pub const MODE_POLICY: u8 = 3;
+/// An earlier proof's claim, renewed (`RenewedPublicValuesStruct`). Decoding reports the
+/// earlier proof's mode instead, with `DecodedPublicValues::renewal` set.
+pub const MODE_RENEWAL: u8 = 4;
+/// At least `threshold` of a batch's addresses are in none of the committed countries
+/// (`ThresholdPublicValuesStruct`).
+pub const MODE_THRESHOLD: u8 = 5;
+/// A claim joined to a companion proof (`ComposedPublicValuesStruct`). Decoding reports
+/// the wrapped claim's mode instead, with `DecodedPublicValues::companion` set.
+pub const MODE_COMPOSED: u8 = 6;
+/// Version of the plain layout the guest commits, bumped whenever its fields or their
+/// meaning change. Verifiers should reject versions they don't know, as
+/// `decode_public_values` does. Version 2 added the snapshot metadata, version 3 the policy
+/// ID, version 4 the application context, version 5 the network prefix and version 6
+/// widened `timestamp` and `snapshot_date` to 64 bits.
+pub const PUBLIC_VALUES_VERSION: u16 = 6;
+/// Committed by every plain proof, so its public values can't be mistaken for another
+/// program's that happen to share the layout: SHA-256 of `zkip.PublicValuesStruct`.
+pub const DOMAIN_SEPARATOR: [u8; 32] = [
+    0x73, 0xcf, 0x0c, 0x1c, 0xdc, 0x0f, 0x00, 0x23, 0x09, 0x00, 0x47, 0x44, 0x5e, 0x42, 0x26, 0xd3,
+    0xe9, 0xa2, 0x75, 0x52, 0xdb, 0x2c, 0x56, 0xb4, 0x54, 0x3c, 0xb4, 0xf0, 0x8f, 0x4f, 0xce, 0x61,
+];
+/// A claim bound to a verifier's challenge (`ChallengedPublicValuesStruct`). Decoding
+/// reports the wrapped claim's mode instead, with `DecodedPublicValues::challenge` set.
+pub const MODE_CHALLENGED: u8 = 7;
+/// A claim with a validity window (`WindowedPublicValuesStruct`). Decoding reports the
+/// wrapped claim's mode instead, with `DecodedPublicValues::validity` set.
+pub const MODE_WINDOWED: u8 = 8;
+/// A whole prefix is in none of the committed countries (`BlockPublicValuesStruct`).
+pub const MODE_BLOCK: u8 = 9;
+
+/// Reserved IPv4 space, as inclusive `(start, end)` ranges: addresses that say nothing about
+/// where their holder is, so excluding a country from them proves nothing.
+pub const RESERVED_RANGES: &[(u32, u32)] = &[
+    (0x0000_0000, 0x00ff_ffff), // 0.0.0.0/8 "this network"
+    (0x0a00_0000, 0x0aff_ffff), // 10.0.0.0/8 private (RFC 1918)
+    (0x6440_0000, 0x647f_ffff), // 100.64.0.0/10 carrier-grade NAT (RFC 6598)
+    (0x7f00_0000, 0x7fff_ffff), // 127.0.0.0/8 loopback
+    (0xa9fe_0000, 0xa9fe_ffff), // 169.254.0.0/16 link-local
+    (0xac10_0000, 0xac1f_ffff), // 172.16.0.0/12 private (RFC 1918)
+    (0xc000_0000, 0xc000_00ff), // 192.0.0.0/24 IETF protocol assignments
+    (0xc000_0200, 0xc000_02ff), // 192.0.2.0/24 documentation
+    (0xc0a8_0000, 0xc0a8_ffff), // 192.168.0.0/16 private (RFC 1918)
+    (0xc612_0000, 0xc613_ffff), // 198.18.0.0/15 benchmarking
+    (0xc633_6400, 0xc633_64ff), // 198.51.100.0/24 documentation
+    (0xcb00_7100, 0xcb00_71ff), // 203.0.113.0/24 documentation
+    (0xe000_0000, 0xffff_ffff), // 224.0.0.0/3 multicast, reserved and broadcast
+];
+
+/// Reserved IPv6 space, like `RESERVED_RANGES`. IPv4-mapped addresses count as reserved:
+/// prove the IPv4 address itself instead.
+pub const RESERVED_RANGES_V6: &[(u128, u128)] = &[
+    (0, 1),                                           // ::/127 unspecified and loopback
+    (0xffff << 32, (0x1_0000 << 32) - 1),             // ::ffff:0:0/96 IPv4-mapped
+    (0x0100 << 112, (0x0100 << 112) + (1 << 64) - 1), // 100::/64 discard
+    (0x2001_0db8 << 96, (0x2001_0db9 << 96) - 1),     // 2001:db8::/32 documentation
+    (0xfc00 << 112, (0xfe00 << 112) - 1),             // fc00::/7 unique local
+    (0xfe80 << 112, (0xff00 << 112) - 1),             // fe80::/9 link-local and site-local
+    (0xff00 << 112, u128::MAX),                       // ff00::/8 multicast
+];
+
+/// Domain tag prefixed to the input of `ranges_digest`.
+const RANGES_DIGEST_DOMAIN: &[u8] = b"zkip-ranges-digest-v1";
+
+/// Disclose the UN M49 region (continent), e.g. 150 = Europe.
+pub const GRANULARITY_REGION: u8 = 1;
+/// Disclose the UN M49 sub-region, e.g. 155 = Western Europe.
+pub const GRANULARITY_SUB_REGION: u8 = 2;
+/// Disclose the country itself, as its ISO 3166-1 numeric code (the same as its M49
+/// code), e.g. 250 = France.
+pub const GRANULARITY_COUNTRY: u8 = 3;
+
+/// Disclosure input: the granularity to disclose. The guest derives the region itself, from
+/// the excluded country whose ranges contain the IP (`disclosed_code`).
+pub type DisclosureInput = u8;
+
+/// Public freshness anchor: `(block_number, block_hash)` of a recent chain block.
+pub type AnchorInput = (u64, [u8; 32]);
+
+/// Denylist of a combined proof: `(excluded_countries, ranges, ranges_v6, range_counts)`,
+/// grouped by country like the shared ranges.
+pub type DenylistInput = (
+    Vec<u16>,
+    Vec<(u32, u32)>,
+    Vec<(u128, u128)>,
+    Vec<(u32, u32)>,
+);
+
+/// Policy of a policy proof: `(expr, asns, ranges, ranges_v6, range_counts)`, with the ranges
+/// of each ASN the expression names grouped like the shared ranges.
+pub type PolicyInput = (
+    policy::PolicyExpr,
+    Vec<u32>,
+    Vec<(u32, u32)>,
+    Vec<(u128, u128)>,
+    Vec<(u32, u32)>,
+);
+
+/// Everything the guest reads through serde, written by the host in one
+/// `SP1Stdin::write`, so the two cannot disagree on the order of separate reads. The shared
+/// IPv4 and IPv6 ranges follow it as two packed `read_vec` buffers (see `pack_ranges`),
+/// each country's ranges in turn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZkipInput {
+    /// One address, or every address of a batch. Unless `batch`, `ips` and `ips_v6` hold
+    /// exactly one address between them.
+    pub ips: Vec<IpV4>,
+    pub ips_v6: Vec<u128>,
+    pub excluded_countries: Vec<u16>,
+    /// How many of the shared ranges belong to each excluded country, as (IPv4, IPv6).
+    pub range_counts: Vec<(u32, u32)>,
+    pub timestamp: u64,
+    /// Optional coarse location disclosure, by granularity.
+    pub disclosure: Option<DisclosureInput>,
+    /// Optional freshness anchor (recent block number and hash), committed as-is.
+    pub anchor: Option<AnchorInput>,
+    /// Commit the number of addresses checked instead of any per-address data.
+    pub batch: bool,
+    /// With `batch`, also commit `merkle::inventory_root` of the addresses, which must be
+    /// sorted and distinct in each family.
+    pub inventory: bool,
+    /// With `batch`, commit whether at least this many addresses are clear instead of how
+    /// many are.
+    pub threshold: Option<u32>,
+    /// Claim the IP is in one of the countries instead of none of them.
+    pub include: bool,
+    /// Countries the IP must also be outside of, for combined proofs.
+    pub denylist: Option<DenylistInput>,
+    /// An expression over the countries above and the ASN ranges here, for policy proofs.
+    pub policy: Option<PolicyInput>,
+    /// Also commit, for each excluded country, whether the IP is outside it.
+    pub per_country: bool,
+    /// Prove exclusion from these witnesses instead of the shared ranges, which are empty.
+    pub gap: Option<gap::GapInput>,
+    /// An attester's signature over the single address, checked and committed.
+    pub attestation: Option<attest::AttestationInput>,
+    /// A notary's signature over a response naming the single address, checked and
+    /// committed as an attestation.
+    pub web_proof: Option<webproof::WebProofInput>,
+    /// A publisher's signature over a snapshot holding the excluded countries' ranges.
+    pub snapshot_signature: Option<publisher::SnapshotSignature>,
+    /// A Roughtime server's reply whose signed time must be `timestamp`.
+    pub time_beacon: Option<roughtime::RoughtimeResponse>,
+    /// Another SP1 proof's statement, verified and committed next to the result.
+    pub companion: Option<compose::CompanionInput>,
+    /// A consumer's epoch and salt, for a plain proof to commit the IP's nullifier.
+    pub nullifier: Option<nullifier::NullifierInput>,
+    /// A verifier's session challenge, committed as-is around the result.
+    pub challenge: Option<[u8; 32]>,
+    /// Commit a validity window from `timestamp` to this many seconds after it.
+    pub valid_for: Option<u32>,
+    /// What the host knows of the snapshot the ranges came from, for a plain proof to
+    /// commit as-is.
+    pub snapshot: Option<snapshot::SnapshotMetadata>,
+    /// An integrator's opaque context, such as a dapp ID or order hash, for a plain proof to
+    /// commit as-is.
+    pub app_context: Option<[u8; 32]>,
+    /// A consumer's salt, for a plain proof to commit the IP's network prefix.
+    pub prefix_salt: Option<[u8; 32]>,
+    /// Claim the whole prefix holding the single address is clear, instead of the address.
+    pub block: Option<block::BlockInput>,
+}
+
+/// The policy committed by a policy proof.
+pub struct PolicyValues {
+    pub policy_hash: [u8; 32],
+    pub asn_root: [u8; 32],
+}
+
+/// The attester of an attested proof.
+pub struct AttestationValues {
+    /// SEC1-compressed secp256k1 (33 bytes) or Ed25519 (32 bytes) public key.
+    pub pubkey: Vec<u8>,
+    pub nonce: [u8; 32],
+    pub attested_at: u64,
+}
+
+/// The publisher of a signed-snapshot proof.
+pub struct PublisherValues {
+    /// Ed25519 public key.
+    pub pubkey: [u8; 32],
+    pub snapshot_root: [u8; 32],
+    pub published_at: u64,
+    /// Bound on `timestamp - published_at`, in seconds.
+    pub max_age: u64,
+}
+
+/// The time server of a timed proof.
+pub struct TimeBeaconValues {
+    /// Ed25519 long-term public key.
+    pub pubkey: [u8; 32],
+    /// The server's uncertainty about `timestamp`, in seconds.
+    pub radius: u32,
+}
+
+/// The nullifier of a plain proof (see `nullifier`); all zero if the prover made none.
+pub struct NullifierValues {
+    pub epoch: u32,
+    /// `nullifier::scope` of the salt.
+    pub scope: [u8; 32],
+    pub nullifier: [u8; 32],
+}
+
+/// The network prefix commitment of a plain proof (see `prefix`); all zero if the prover
+/// made none.
+pub struct PrefixValues {
+    /// `nullifier::scope` of the salt.
+    pub scope: [u8; 32],
+    pub commitment: [u8; 32],
+}
+
+/// The private prefix of a block proof (see `block`).
+pub struct BlockValues {
+    pub prefix_len: u8,
+    pub is_v6: bool,
+    /// `nullifier::scope` of the salt.
+    pub scope: [u8; 32],
+    pub commitment: [u8; 32],
+}
+
+/// The chunk proofs of an aggregated proof.
+pub struct AggregationValues {
+    /// `renew::vkey_bytes` of the chunk guest.
+    pub chunk_vkey: [u8; 32],
+    pub chunk_count: u32,
+}
+
+/// The earlier proof of a renewal.
+pub struct RenewalValues {
+    /// `renew::vkey_bytes` of the program that made it.
+    pub previous_vkey: [u8; 32],
+    pub previous_timestamp: u64,
+}
+
+/// The companion proof a claim was joined to.
+pub struct CompanionValues {
+    /// `renew::vkey_bytes` of the companion program.
+    pub vkey: [u8; 32],
+    /// SHA-256 of the companion's public values.
+    pub public_values_digest: [u8; 32],
+}
+
+/// The validity window of a windowed proof, in Unix seconds, both ends inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidityWindow {
+    pub not_before: u64,
+    pub not_after: u64,
+}
+
+impl ValidityWindow {
+    /// The window from `timestamp` to `valid_for` seconds after it, if that fits a `u64`.
+    pub fn starting_at(timestamp: u64, valid_for: u32) -> Option<Self> {
+        Some(Self {
+            not_before: timestamp,
+            not_after: timestamp.checked_add(valid_for.into())?,
+        })
+    }
+
+    /// Whether `now` falls within the window.
+    pub fn contains(&self, now: u64) -> bool {
+        self.not_before <= now && now <= self.not_after
+    }
+}
+
+/// The exclusion half of a combined proof.
+pub struct DenylistValues {
+    pub is_excluded: bool,
+    pub excluded_countries: Vec<u16>,
+    pub dataset_root: [u8; 32],
+}
+
+/// Pack IPv4 ranges for `sp1_zkvm::io::read_vec`: each range's start, then its end,
+/// little-endian. Reading the bytes and unpacking them costs the guest far fewer cycles
+/// than deserializing a `Vec<(u32, u32)>` through serde.
+pub fn pack_ranges(ranges: &[(u32, u32)]) -> Vec<u8> {
+    ranges
+        .iter()
+        .flat_map(|&(start, end)| start.to_le_bytes().into_iter().chain(end.to_le_bytes()))
+        .collect()
+}
+
+/// Inverse of `pack_ranges`. Panics unless `bytes` holds whole ranges.
+pub fn unpack_ranges(bytes: &[u8]) -> Vec<(u32, u32)> {
+    assert_eq!(bytes.len() % 8, 0, "packed IPv4 ranges take 8 bytes each");
+    bytes
+        .chunks(8)
+        .map(|chunk| {
+            let (start, end) = chunk.split_at(4);
+            (
+                u32::from_le_bytes(start.try_into().unwrap()),
+                u32::from_le_bytes(end.try_into().unwrap()),
+            )
+        })
+        .collect()
+}
+
+/// Pack IPv6 ranges like `pack_ranges`, 32 bytes each.
+pub fn pack_ranges_v6(ranges: &[(u128, u128)]) -> Vec<u8> {
+    ranges
+        .iter()
+        .flat_map(|&(start, end)| start.to_le_bytes().into_iter().chain(end.to_le_bytes()))
+        .collect()
+}
+
+/// Inverse of `pack_ranges_v6`. Panics unless `bytes` holds whole ranges.
+pub fn unpack_ranges_v6(bytes: &[u8]) -> Vec<(u128, u128)> {
+    assert_eq!(bytes.len() % 32, 0, "packed IPv6 ranges take 32 bytes each");
+    bytes
+        .chunks(32)
+        .map(|chunk| {
+            let (start, end) = chunk.split_at(16);
+            (
+                u128::from_le_bytes(start.try_into().unwrap()),
+                u128::from_le_bytes(end.try_into().unwrap()),
+            )
+        })
+        .collect()
+}
+
+/// Flat digest of the shared ranges as the guest reads them: SHA-256 over a domain tag, the
+/// country count and codes, each country's `(IPv4, IPv6)` range counts, then the packed
+/// IPv4 and IPv6 ranges, each prefixed with its byte length. All integers are big-endian
+/// except the packed ranges, which are hashed as `pack_ranges` lays them out. Cheaper than
+/// `merkle::dataset_root`, but only a verifier holding the exact same country list in the
+/// same order can recompute it.
+pub fn ranges_digest(
+    countries: &[u16],
+    range_counts: &[(u32, u32)],
+    packed: &[u8],
+    packed_v6: &[u8],
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(RANGES_DIGEST_DOMAIN);
+    hasher.update((countries.len() as u32).to_be_bytes());
+    for code in countries {
+        hasher.update(code.to_be_bytes());
+    }
+    for (count, count_v6) in range_counts {
+        hasher.update(count.to_be_bytes());
+        hasher.update(count_v6.to_be_bytes());
+    }
+    hasher.update((packed.len() as u32).to_be_bytes());
+    hasher.update(packed);
+    hasher.update((packed_v6.len() as u32).to_be_bytes());
+    hasher.update(packed_v6);
+    hasher.finalize().into()
 }
 
 /// Check if an IP address is excluded from the specified country ranges.
 /// Returns true if IP is NOT in any excluded range (user is clear).
 /// Returns false if IP IS in an excluded range (user is from blocked country).
-pub fn is_excluded(ip: u32, excluded_ranges: Vec<(u32, u32)>) -> bool {
-    for (start, end) in excluded_ranges {
-        if ip >= start && ip <= end {
-            return false;
-        }
+pub fn is_excluded(ip: IpV4, excluded_ranges: &[range::Ipv4Range]) -> bool {
+    !excluded_ranges.iter().any(|range| range.contains(ip))
+}
+
+/// `is_excluded` for each of `ips` against `ranges`, in order (see `are_excluded_iter`).
+pub fn are_excluded(ips: &[IpV4], ranges: &range::RangeSet) -> Vec<bool> {
+    are_excluded_iter(ips.iter().copied(), ranges).collect()
+}
+
+/// `is_excluded` for each address `ips` yields, against `ranges`, lazily. Each lookup is a
+/// binary search; while the addresses ascend it starts from the previous one's range, so
+/// sorted batches cost O(n log m) at worst and far less when they cluster.
+pub fn are_excluded_iter<'a>(
+    ips: impl IntoIterator<Item = IpV4> + 'a,
+    ranges: &'a range::RangeSet,
+) -> impl Iterator<Item = bool> + 'a {
+    let ranges = ranges.ranges();
+    let mut last = None;
+    ips.into_iter().map(move |ip| {
+        // First range not ending before `ip`; every range before `from` does
+        let from = match last {
+            Some((previous, index)) if previous <= ip => index,
+            _ => 0,
+        };
+        let index = from + ranges[from..].partition_point(|range| range.end() < ip.0);
+        last = Some((ip, index));
+        !ranges.get(index).is_some_and(|range| range.contains(ip))
+    })
+}
+
+/// True if no address in `ips` falls in any excluded range. Works for IPv4 (`u32`) and
+/// IPv6 (`u128`) addresses alike.
+pub fn all_excluded<T: Copy + Ord>(ips: &[T], excluded_ranges: &[(T, T)]) -> bool {
+    ips.iter().all(|&ip| {
+        excluded_ranges
+            .iter()
+            .all(|&(start, end)| ip < start || ip > end)
+    })
+}
+
+/// Number of addresses in `ips` outside every excluded range.
+pub fn clear_count<T: Copy + Ord>(ips: &[T], excluded_ranges: &[(T, T)]) -> u32 {
+    ips.iter()
+        .filter(|&&ip| all_excluded(&[ip], excluded_ranges))
+        .count() as u32
+}
+
+/// Per-country results for `excluded_countries` grouped by `range_counts` like
+/// `merkle::dataset_root`: entry `i` is true if no address is in country `i`'s ranges.
+pub fn country_results(
+    ips: &[u32],
+    ips_v6: &[u128],
+    excluded_ranges: &[(u32, u32)],
+    excluded_ranges_v6: &[(u128, u128)],
+    range_counts: &[(u32, u32)],
+) -> Vec<bool> {
+    let groups = merkle::split_groups(
+        excluded_ranges,
+        range_counts.iter().map(|&(count, _)| count),
+    );
+    let groups_v6 = merkle::split_groups(
+        excluded_ranges_v6,
+        range_counts.iter().map(|&(_, count)| count),
+    );
+    groups
+        .into_iter()
+        .zip(groups_v6)
+        .map(|(group, group_v6)| all_excluded(ips, group) && all_excluded(ips_v6, group_v6))
+        .collect()
+}
+
+/// M49 code of the region, sub-region or country `granularity` discloses for `ip`: that of
+/// the first of `excluded_countries` whose ranges, grouped by `range_counts` like
+/// `merkle::dataset_root`, contain it. `None` if none does, or the country has no region.
+pub fn disclosed_code(
+    granularity: u8,
+    ip: u32,
+    excluded_countries: &[u16],
+    excluded_ranges: &[(u32, u32)],
+    range_counts: &[(u32, u32)],
+) -> Option<u16> {
+    let groups = merkle::split_groups(
+        excluded_ranges,
+        range_counts.iter().map(|&(count, _)| count),
+    );
+    let (&numeric, _) = excluded_countries
+        .iter()
+        .zip(groups)
+        .find(|(_, group)| !all_excluded(&[ip], group))?;
+    granularity_code(granularity, numeric)
+}
+
+/// M49 code `granularity` discloses for the country with numeric code `numeric`.
+pub(crate) fn granularity_code(granularity: u8, numeric: u16) -> Option<u16> {
+    let country = country::CountryCode::from_numeric(numeric)?;
+    match granularity {
+        GRANULARITY_REGION => country.region(),
+        GRANULARITY_SUB_REGION => country.sub_region(),
+        GRANULARITY_COUNTRY => Some(numeric),
+        _ => None,
     }
-    true
 }
 
-/// Parse an IP address string (e.g., "8.8.8.8") to a u32.
-pub fn ip_to_u32(ip_str: &str) -> anyhow::Result<u32> {
+/// Number of ranges checked and IPv4 addresses they cover, as the plain layout commits them.
+/// IPv6 ranges count towards the ranges only; their sizes would not fit.
+pub fn range_coverage(ranges: &[(u32, u32)], ranges_v6: &[(u128, u128)]) -> (u32, u64) {
+    let covered_ipv4 = ranges
+        .iter()
+        .map(|&(start, end)| u64::from(end.saturating_sub(start)) + 1)
+        .sum();
+    ((ranges.len() + ranges_v6.len()) as u32, covered_ipv4)
+}
+
+/// True if no address falls in `RESERVED_RANGES` or `RESERVED_RANGES_V6`.
+pub fn all_public(ips: &[u32], ips_v6: &[u128]) -> bool {
+    all_excluded(ips, RESERVED_RANGES) && all_excluded(ips_v6, RESERVED_RANGES_V6)
+}
+
+/// True if every address in `ips` falls in some range, the claim of an inclusion proof.
+pub fn all_included<T: Copy + Ord>(ips: &[T], included_ranges: &[(T, T)]) -> bool {
+    ips.iter().all(|&ip| {
+        included_ranges
+            .iter()
+            .any(|&(start, end)| start <= ip && ip <= end)
+    })
+}
+
+/// True if a proof has something to check: at least one country, and at least one range
+/// among them. With neither, every IP would read as excluded.
+pub fn non_vacuous(countries: &[u16], ranges: &[(u32, u32)], ranges_v6: &[(u128, u128)]) -> bool {
+    !countries.is_empty() && !(ranges.is_empty() && ranges_v6.is_empty())
+}
+
+/// Check that an inclusion set is a real anonymity set: no country repeats, and each has
+/// at least one range in `range_counts`, so its length is the number of places the IP
+/// could be.
+pub fn inclusion_set_valid(included_countries: &[u16], range_counts: &[(u32, u32)]) -> bool {
+    included_countries
+        .iter()
+        .enumerate()
+        .all(|(i, code)| !included_countries[..i].contains(code))
+        && range_counts
+            .iter()
+            .all(|&(count, count_v6)| count > 0 || count_v6 > 0)
+}
+
+/// Check that ranges are well-formed, sorted by start and pairwise disjoint, as
+/// `is_excluded_sorted` requires.
+pub fn ranges_sorted<T: Ord>(ranges: &[(T, T)]) -> bool {
+    ranges.iter().all(|(start, end)| start <= end)
+        && ranges.windows(2).all(|pair| pair[0].1 < pair[1].0)
+}
+
+/// Check that each family's addresses are sorted and distinct, so an inventory has one
+/// `merkle::inventory_root`.
+pub fn addresses_sorted(ips: &[u32], ips_v6: &[u128]) -> bool {
+    ips.windows(2).all(|pair| pair[0] < pair[1]) && ips_v6.windows(2).all(|pair| pair[0] < pair[1])
+}
+
+/// Check `ranges_sorted` for each country's ranges, grouped by `range_counts` like
+/// `merkle::dataset_root`. Ranges of different countries may overlap.
+pub fn groups_sorted(
+    ranges: &[(u32, u32)],
+    ranges_v6: &[(u128, u128)],
+    range_counts: &[(u32, u32)],
+) -> bool {
+    let groups = merkle::split_groups(ranges, range_counts.iter().map(|&(count, _)| count));
+    let groups_v6 = merkle::split_groups(ranges_v6, range_counts.iter().map(|&(_, count)| count));
+    groups.iter().all(|group| ranges_sorted(group))
+        && groups_v6.iter().all(|group| ranges_sorted(group))
+}
+
+/// Same result as `is_excluded`, by binary search over ranges that satisfy `ranges_sorted`.
+pub fn is_excluded_sorted<T: Copy + Ord>(ip: T, excluded_ranges: &[(T, T)]) -> bool {
+    let idx = excluded_ranges.partition_point(|(start, _)| *start <= ip);
+    idx == 0 || excluded_ranges[idx - 1].1 < ip
+}
+
+/// Parse an IPv4 address to a u32. Accepts dotted-quad (`8.8.8.8`), decimal integer
+/// (`134744072`) and `0x`-prefixed hex (`0x08080808`) forms. Dotted quads go through
+/// `Ipv4Addr`'s parser, which rejects octets with leading zeros or signs.
+pub fn ip_to_u32(ip_str: &str) -> Result<u32, ZkipError> {
+    let ip_str = ip_str.trim();
+    if let Some(hex) = ip_str
+        .strip_prefix("0x")
+        .or_else(|| ip_str.strip_prefix("0X"))
+    {
+        if hex.is_empty() || hex.len() > 8 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ZkipError::InvalidIp(
+                "expected 0x followed by 1 to 8 hex digits",
+            ));
+        }
+        return Ok(u32::from_str_radix(hex, 16).expect("checked hex digits"));
+    }
+    if !ip_str.contains('.') {
+        if ip_str.is_empty() || !ip_str.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ZkipError::InvalidIp(
+                "expected dotted-quad, integer or 0x hex",
+            ));
+        }
+        return ip_str
+            .parse::<u32>()
+            .map_err(|_| ZkipError::InvalidIp("expected an integer from 0 to 4294967295"));
+    }
+
     let parts: Vec<&str> = ip_str.split('.').collect();
     if parts.len() != 4 {
-        anyhow::bail!("Invalid IP format: expected 4 octets");
+        return Err(ZkipError::InvalidIp("expected 4 octets"));
     }
+    if let Ok(ip) = ip_str.parse::<Ipv4Addr>() {
+        return Ok(ip.into());
+    }
+    // Name the octet the standard parser rejected, if it is out of range
+    for (index, part) in parts.into_iter().enumerate() {
+        part.parse::<u8>()
+            .map_err(|source| ZkipError::InvalidOctet {
+                index: index + 1,
+                source,
+            })?;
+    }
+    Err(ZkipError::InvalidIp(
+        "expected decimal octets without signs or leading zeros",
+    ))
+}
 
-    let a: u8 = parts[0].parse().context("Invalid first octet")?;
-    let b: u8 = parts[1].parse().context("Invalid second octet")?;
-    let c: u8 = parts[2].parse().context("Invalid third octet")?;
-    let d: u8 = parts[3].parse().context("Invalid fourth octet")?;
-
-    Ok((a as u32) << 24 | (b as u32) << 16 | (c as u32) << 8 | (d as u32))
+/// `ip_to_u32` as an `Ipv4Addr`.
+pub fn parse_ipv4(ip_str: &str) -> Result<Ipv4Addr, ZkipError> {
+    ip_to_u32(ip_str).map(Ipv4Addr::from)
 }
 
 /// Convert a u32 IP back to dotted string format for display.
 pub fn u32_to_ip(ip: u32) -> String {
-    format!(
-        "{}.{}.{}.{}",
-        (ip >> 24) & 0xFF,
-        (ip >> 16) & 0xFF,
-        (ip >> 8) & 0xFF,
-        ip & 0xFF
-    )
+    Ipv4Addr::from(ip).to_string()
+}
+
+/// Parse an IPv6 address in any RFC 4291 text form (`2001:db8::1`, `::ffff:8.8.8.8`) to a
+/// u128.
+pub fn ipv6_to_u128(ip_str: &str) -> Result<u128, ZkipError> {
+    let addr: Ipv6Addr = ip_str.trim().parse()?;
+    Ok(u128::from(addr))
+}
+
+/// Convert a u128 IP back to its canonical IPv6 text form for display.
+pub fn u128_to_ipv6(ip: u128) -> String {
+    Ipv6Addr::from(ip).to_string()
+}
+
+/// Parse an IPv4 address in any form `ip_to_u32` accepts, or an IPv6 address (anything
+/// containing `:`).
+pub fn parse_ip(ip_str: &str) -> Result<IpAddr, ZkipError> {
+    if ip_str.contains(':') {
+        return Ok(IpAddr::V6(Ipv6Addr::from(ipv6_to_u128(ip_str)?)));
+    }
+    Ok(IpAddr::V4(Ipv4Addr::from(ip_to_u32(ip_str)?)))
 }
@@ -0,0 +1,572 @@
+//! Canonical commitment to the GeoIP ranges of a country set.
+//!
+//! Range leaves are `SHA-256(0x00 || start || end)` with big-endian `u32`s for IPv4 ranges
+//! and `SHA-256(0x02 || start || end)` with big-endian `u128`s for IPv6 ranges, in the
+//! order given (callers sort by start first). Inner nodes are
+//! `SHA-256(0x01 || left || right)`; an odd node at the end of a level moves up unchanged.
+//! The root of no leaves is all zeroes.
+//!
+//! Each country's IPv4 and IPv6 ranges get their own roots, bound to its ISO 3166-1
+//! numeric code by a country leaf `SHA-256(0x03 || code || root || root_v6)`. The tree over
+//! the country leaves, in the order of `excluded_countries`, is the dataset root the guest
+//! commits. Omitting any range of a claimed country changes that country's root, so a
+//! verifier holding a snapshot's per-country roots can check the ranges were complete.
+//! Policy proofs commit ASN ranges the same way, with ASN leaves
+//! `SHA-256(0x04 || asn || root || root_v6)` and a big-endian `u32` ASN.
+//! Inventory proofs commit a list of addresses instead: address leaves are
+//! `SHA-256(0x05 || ip)` for big-endian IPv4 addresses and `SHA-256(0x06 || ip)` for IPv6
+//! ones, IPv4 first, each family sorted ascending.
+//! Host tooling publishes these roots, so the scheme must not change without a new
+//! version of the commitment.
+//!
+//! The host builds witnesses with `MerkleTree` and `RangeTree`: membership proofs of single
+//! ranges, and adjacency proofs of the two ranges around a gap (see `gap`). The guests and
+//! outside verifiers check them with `range_proof_root`, `verify_range` and
+//! `adjacency_root`, so both sides hash through the functions here.
+
+use alloc::{vec, vec::Vec};
+use sha2::{Digest, Sha256};
+
+/// Root of an empty range set.
+pub const EMPTY_ROOT: [u8; 32] = [0; 32];
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+const LEAF_V6_PREFIX: u8 = 0x02;
+const COUNTRY_PREFIX: u8 = 0x03;
+const ASN_PREFIX: u8 = 0x04;
+const ADDRESS_PREFIX: u8 = 0x05;
+const ADDRESS_V6_PREFIX: u8 = 0x06;
+
+pub fn leaf_hash((start, end): (u32, u32)) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(start.to_be_bytes());
+    hasher.update(end.to_be_bytes());
+    hasher.finalize().into()
+}
+
+pub fn leaf_hash_v6((start, end): (u128, u128)) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_V6_PREFIX]);
+    hasher.update(start.to_be_bytes());
+    hasher.update(end.to_be_bytes());
+    hasher.finalize().into()
+}
+
+pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+pub fn country_leaf(code: u16, root: &[u8; 32], root_v6: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([COUNTRY_PREFIX]);
+    hasher.update(code.to_be_bytes());
+    hasher.update(root);
+    hasher.update(root_v6);
+    hasher.finalize().into()
+}
+
+pub fn asn_leaf(asn: u32, root: &[u8; 32], root_v6: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([ASN_PREFIX]);
+    hasher.update(asn.to_be_bytes());
+    hasher.update(root);
+    hasher.update(root_v6);
+    hasher.finalize().into()
+}
+
+pub fn address_leaf(ip: u32) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([ADDRESS_PREFIX]);
+    hasher.update(ip.to_be_bytes());
+    hasher.finalize().into()
+}
+
+pub fn address_leaf_v6(ip: u128) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([ADDRESS_V6_PREFIX]);
+    hasher.update(ip.to_be_bytes());
+    hasher.finalize().into()
+}
+
+/// Merkle root over an address inventory: `ips` then `ips_v6`, in the given order (callers
+/// sort each family first).
+pub fn inventory_root(ips: &[u32], ips_v6: &[u128]) -> [u8; 32] {
+    let leaves = ips.iter().map(|&ip| address_leaf(ip));
+    root_of(
+        leaves
+            .chain(ips_v6.iter().map(|&ip| address_leaf_v6(ip)))
+            .collect(),
+    )
+}
+
+/// Merkle root over IPv4 `ranges` in the given order.
+pub fn ranges_root(ranges: &[(u32, u32)]) -> [u8; 32] {
+    root_of(ranges.iter().map(|&range| leaf_hash(range)).collect())
+}
+
+/// Merkle root over IPv6 `ranges` in the given order.
+pub fn ranges_root_v6(ranges: &[(u128, u128)]) -> [u8; 32] {
+    root_of(ranges.iter().map(|&range| leaf_hash_v6(range)).collect())
+}
+
+/// Root over `(code, root, root_v6)` per country, in the given order.
+pub fn country_set_root(countries: &[(u16, [u8; 32], [u8; 32])]) -> [u8; 32] {
+    root_of(
+        countries
+            .iter()
+            .map(|(code, root, root_v6)| country_leaf(*code, root, root_v6))
+            .collect(),
+    )
+}
+
+/// Split `ranges` into consecutive groups of `counts` ranges, checking they add up.
+pub fn split_groups<T>(ranges: &[T], counts: impl IntoIterator<Item = u32>) -> Vec<&[T]> {
+    let mut rest = ranges;
+    let groups = counts
+        .into_iter()
+        .map(|count| {
+            assert!(
+                count as usize <= rest.len(),
+                "range counts exceed the ranges"
+            );
+            let (group, tail) = rest.split_at(count as usize);
+            rest = tail;
+            group
+        })
+        .collect();
+    assert!(rest.is_empty(), "range counts do not cover every range");
+    groups
+}
+
+/// The root the guest commits: `ranges` and `ranges_v6` hold each country's ranges in
+/// turn, `range_counts[i]` of them (IPv4, IPv6) for `excluded_countries[i]`.
+pub fn dataset_root(
+    excluded_countries: &[u16],
+    ranges: &[(u32, u32)],
+    ranges_v6: &[(u128, u128)],
+    range_counts: &[(u32, u32)],
+) -> [u8; 32] {
+    leaves_root(&country_leaves(
+        excluded_countries,
+        ranges,
+        ranges_v6,
+        range_counts,
+    ))
+}
+
+/// The country leaves under `dataset_root`, one per excluded country in order.
+pub fn country_leaves(
+    excluded_countries: &[u16],
+    ranges: &[(u32, u32)],
+    ranges_v6: &[(u128, u128)],
+    range_counts: &[(u32, u32)],
+) -> Vec<[u8; 32]> {
+    assert_eq!(
+        range_counts.len(),
+        excluded_countries.len(),
+        "one range count per country"
+    );
+    let groups = split_groups(ranges, range_counts.iter().map(|&(count, _)| count));
+    let groups_v6 = split_groups(ranges_v6, range_counts.iter().map(|&(_, count)| count));
+    excluded_countries
+        .iter()
+        .zip(groups.into_iter().zip(groups_v6))
+        .map(|(&code, (group, group_v6))| {
+            country_leaf(code, &ranges_root(group), &ranges_root_v6(group_v6))
+        })
+        .collect()
+}
+
+/// Root over `leaves` in the given order, such as `country_leaves`.
+pub fn leaves_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    root_of(leaves.to_vec())
+}
+
+/// Like `dataset_root`, over the ranges of each of `asns` in turn.
+pub fn asn_root(
+    asns: &[u32],
+    ranges: &[(u32, u32)],
+    ranges_v6: &[(u128, u128)],
+    range_counts: &[(u32, u32)],
+) -> [u8; 32] {
+    assert_eq!(range_counts.len(), asns.len(), "one range count per ASN");
+    let groups = split_groups(ranges, range_counts.iter().map(|&(count, _)| count));
+    let groups_v6 = split_groups(ranges_v6, range_counts.iter().map(|&(_, count)| count));
+    root_of(
+        asns.iter()
+            .zip(groups.into_iter().zip(groups_v6))
+            .map(|(&asn, (group, group_v6))| {
+                asn_leaf(asn, &ranges_root(group), &ranges_root_v6(group_v6))
+            })
+            .collect(),
+    )
+}
+
+/// Siblings from leaf `index` of `leaves` up to their root, skipping levels where the
+/// node is the odd one at the end and moves up unchanged.
+pub fn merkle_path(leaves: &[[u8; 32]], index: usize) -> Vec<[u8; 32]> {
+    MerkleTree::new(leaves.to_vec()).path(index)
+}
+
+/// Root of a tree of `leaf_count` leaves with `leaf` at `index`, from its `merkle_path`.
+/// `None` if the path has the wrong length for that position.
+pub fn root_from_path(
+    leaf: [u8; 32],
+    mut index: u32,
+    mut leaf_count: u32,
+    path: &[[u8; 32]],
+) -> Option<[u8; 32]> {
+    if index >= leaf_count {
+        return None;
+    }
+    let mut node = leaf;
+    let mut siblings = path.iter();
+    while leaf_count > 1 {
+        if index % 2 == 1 {
+            node = node_hash(siblings.next()?, &node);
+        } else if index + 1 < leaf_count {
+            node = node_hash(&node, siblings.next()?);
+        }
+        index /= 2;
+        leaf_count = leaf_count.div_ceil(2);
+    }
+    siblings.next().is_none().then_some(node)
+}
+
+/// Every level of a Merkle tree, leaves first, for hosts that need the paths of many
+/// leaves without rebuilding the tree for each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree {
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: Vec<[u8; 32]>) -> Self {
+        let mut levels = vec![leaves];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let next = next_level(level);
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    pub fn leaves(&self) -> &[[u8; 32]] {
+        &self.levels[0]
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.leaves().len()
+    }
+
+    /// The root, `EMPTY_ROOT` without leaves.
+    pub fn root(&self) -> [u8; 32] {
+        self.levels[self.levels.len() - 1]
+            .first()
+            .copied()
+            .unwrap_or(EMPTY_ROOT)
+    }
+
+    /// The `merkle_path` of leaf `index`.
+    pub fn path(&self, mut index: usize) -> Vec<[u8; 32]> {
+        assert!(index < self.leaf_count(), "leaf index out of range");
+        let mut path = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                path.push(*sibling);
+            }
+            index /= 2;
+        }
+        path
+    }
+}
+
+/// An address family of range trees: IPv4 ranges hash with `leaf_hash`, IPv6 ones with
+/// `leaf_hash_v6`.
+pub trait RangeLeaf: Copy + Ord {
+    fn leaf(range: (Self, Self)) -> [u8; 32];
+}
+
+impl RangeLeaf for u32 {
+    fn leaf(range: (u32, u32)) -> [u8; 32] {
+        leaf_hash(range)
+    }
+}
+
+impl RangeLeaf for u128 {
+    fn leaf(range: (u128, u128)) -> [u8; 32] {
+        leaf_hash_v6(range)
+    }
+}
+
+/// A range's index in its tree, the range, and its Merkle path.
+pub type RangeProof<T> = (u32, (T, T), Vec<[u8; 32]>);
+
+/// The ranges either side of a gap, each missing at its end of the tree.
+pub type AdjacencyProof<T> = (Option<RangeProof<T>>, Option<RangeProof<T>>);
+
+/// The tree over one family's ranges, for the host to prove ranges in it and gaps between
+/// them. Its root is `ranges_root` or `ranges_root_v6` of the same ranges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeTree<'a, T> {
+    ranges: &'a [(T, T)],
+    tree: MerkleTree,
+}
+
+impl<'a, T: RangeLeaf> RangeTree<'a, T> {
+    /// The tree over `ranges`, in the given order (callers sort by start first).
+    pub fn new(ranges: &'a [(T, T)]) -> Self {
+        let tree = MerkleTree::new(ranges.iter().map(|&range| T::leaf(range)).collect());
+        Self { ranges, tree }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.tree.root()
+    }
+
+    /// Proof that range `index` is in the tree (see `range_proof_root`).
+    pub fn prove(&self, index: usize) -> RangeProof<T> {
+        (index as u32, self.ranges[index], self.tree.path(index))
+    }
+
+    /// Proofs of the ranges around `ip`: the last starting below it, unless it is below
+    /// every range, and the first starting above it, unless it is above every range.
+    /// `None` if a range contains `ip` (see `adjacency_root`).
+    pub fn prove_adjacent(&self, ip: T) -> Option<AdjacencyProof<T>> {
+        let index = self.ranges.partition_point(|&(start, _)| start <= ip);
+        if index > 0 && ip <= self.ranges[index - 1].1 {
+            return None;
+        }
+        let left = index.checked_sub(1).map(|index| self.prove(index));
+        let right = (index < self.ranges.len()).then(|| self.prove(index));
+        Some((left, right))
+    }
+}
+
+/// Root of a range tree of `leaf_count` leaves holding `proof`'s range at its index, or
+/// `None` if the path has the wrong length for that position.
+pub fn range_proof_root<T: RangeLeaf>(
+    (index, range, path): &RangeProof<T>,
+    leaf_count: u32,
+) -> Option<[u8; 32]> {
+    root_from_path(T::leaf(*range), *index, leaf_count, path)
+}
+
+/// True if `proof` shows its range is in the tree of `leaf_count` ranges with `root`.
+pub fn verify_range<T: RangeLeaf>(proof: &RangeProof<T>, leaf_count: u32, root: &[u8; 32]) -> bool {
+    range_proof_root(proof, leaf_count) == Some(*root)
+}
+
+/// Root of a range tree of `leaf_count` leaves if `left` and `right`, from
+/// `RangeTree::prove_adjacent`, are neighbours in it with `ip` strictly between them. At
+/// either end of the tree, the missing side must be the first or last range. Adjacency only
+/// shows `ip` is in no range if the tree was built from sorted, disjoint ranges, which the
+/// root must be trusted for.
+pub fn adjacency_root<T: RangeLeaf>(
+    ip: T,
+    leaf_count: u32,
+    left: Option<&RangeProof<T>>,
+    right: Option<&RangeProof<T>>,
+) -> Option<[u8; 32]> {
+    Some(match (left, right) {
+        (None, None) if leaf_count == 0 => EMPTY_ROOT,
+        (Some(left @ (index, (_, end), _)), None) if index + 1 == leaf_count && *end < ip => {
+            range_proof_root(left, leaf_count)?
+        }
+        (None, Some(right @ (0, (start, _), _))) if ip < *start => {
+            range_proof_root(right, leaf_count)?
+        }
+        (Some(left @ (left_index, (_, end), _)), Some(right @ (right_index, (start, _), _)))
+            if *right_index == left_index + 1 && *end < ip && ip < *start =>
+        {
+            let root = range_proof_root(left, leaf_count)?;
+            (range_proof_root(right, leaf_count)? == root).then_some(root)?
+        }
+        _ => return None,
+    })
+}
+
+fn root_of(mut level: Vec<[u8; 32]>) -> [u8; 32] {
+    if level.is_empty() {
+        return EMPTY_ROOT;
+    }
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [single] => *single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(count: u8) -> Vec<[u8; 32]> {
+        (0..count).map(|i| [i; 32]).collect()
+    }
+
+    #[test]
+    fn leaf_hashes_follow_the_documented_layout() {
+        let mut preimage = vec![0x00];
+        preimage.extend_from_slice(&[1, 2, 3, 4, 0xff, 0xff, 0xff, 0xfe]);
+        assert_eq!(
+            leaf_hash((0x0102_0304, 0xffff_fffe)),
+            <[u8; 32]>::from(Sha256::digest(&preimage))
+        );
+        let mut preimage = vec![0x01];
+        preimage.extend_from_slice(&[1; 32]);
+        preimage.extend_from_slice(&[2; 32]);
+        assert_eq!(
+            node_hash(&[1; 32], &[2; 32]),
+            <[u8; 32]>::from(Sha256::digest(&preimage))
+        );
+    }
+
+    #[test]
+    fn odd_nodes_move_up_unchanged() {
+        let [a, b, c, d, e] = leaves(5).try_into().unwrap();
+        assert_eq!(MerkleTree::new(vec![]).root(), EMPTY_ROOT);
+        assert_eq!(MerkleTree::new(vec![a]).root(), a);
+        assert_eq!(leaves_root(&[a, b, c]), node_hash(&node_hash(&a, &b), &c));
+        let left = node_hash(&node_hash(&a, &b), &node_hash(&c, &d));
+        assert_eq!(leaves_root(&[a, b, c, d, e]), node_hash(&left, &e));
+        assert_eq!(
+            MerkleTree::new(vec![a, b, c, d, e]).root(),
+            node_hash(&left, &e)
+        );
+    }
+
+    #[test]
+    fn paths_skip_levels_where_the_node_is_promoted() {
+        let all = leaves(5);
+        let tree = MerkleTree::new(all.clone());
+        // The last leaf of 5 has no sibling until the top
+        let top = node_hash(&node_hash(&all[0], &all[1]), &node_hash(&all[2], &all[3]));
+        assert_eq!(tree.path(4), vec![top]);
+        assert_eq!(
+            tree.path(2),
+            vec![all[3], node_hash(&all[0], &all[1]), all[4]]
+        );
+        assert!(MerkleTree::new(leaves(1)).path(0).is_empty());
+    }
+
+    #[test]
+    fn every_path_leads_to_the_root() {
+        for count in 1..=17 {
+            let all = leaves(count);
+            let tree = MerkleTree::new(all.clone());
+            let count = u32::from(count);
+            for (index, leaf) in all.iter().enumerate() {
+                let path = merkle_path(&all, index);
+                assert_eq!(path, tree.path(index));
+                let index = index as u32;
+                assert_eq!(
+                    root_from_path(*leaf, index, count, &path),
+                    Some(tree.root())
+                );
+                assert_ne!(
+                    root_from_path([0xee; 32], index, count, &path),
+                    Some(tree.root())
+                );
+                let mut longer = path.clone();
+                longer.push([0; 32]);
+                assert_eq!(root_from_path(*leaf, index, count, &longer), None);
+            }
+            assert_eq!(root_from_path(all[0], count, count, &[]), None);
+        }
+    }
+
+    #[test]
+    fn last_leaf_path_depends_on_the_leaf_count() {
+        // Leaf 4 is promoted in a tree of 5 but paired with leaf 5 in a tree of 6
+        let six = MerkleTree::new(leaves(6));
+        let path = six.path(4);
+        assert_eq!(root_from_path([4; 32], 4, 6, &path), Some(six.root()));
+        assert_eq!(root_from_path([4; 32], 4, 5, &path), None);
+        let five = MerkleTree::new(leaves(5));
+        assert_eq!(root_from_path([4; 32], 4, 6, &five.path(4)), None);
+    }
+
+    const RANGES: [(u32, u32); 3] = [(10, 19), (30, 39), (50, u32::MAX)];
+
+    #[test]
+    fn range_tree_proves_membership() {
+        let tree = RangeTree::new(&RANGES);
+        assert_eq!(tree.root(), ranges_root(&RANGES));
+        for index in 0..RANGES.len() {
+            let proof = tree.prove(index);
+            assert!(verify_range(&proof, 3, &tree.root()));
+            let (index, (start, end), path) = proof;
+            assert!(!verify_range(
+                &(index, (start + 1, end), path),
+                3,
+                &tree.root()
+            ));
+        }
+        // The last range is promoted, so its path is too short for a tree of 4
+        assert!(!verify_range(&tree.prove(2), 4, &tree.root()));
+
+        let ranges_v6 = [(1u128, 2), (u128::MAX - 1, u128::MAX)];
+        let tree_v6 = RangeTree::new(&ranges_v6);
+        assert_eq!(tree_v6.root(), ranges_root_v6(&ranges_v6));
+        assert!(verify_range(&tree_v6.prove(1), 2, &tree_v6.root()));
+    }
+
+    #[test]
+    fn range_tree_proves_gaps() {
+        let tree = RangeTree::new(&RANGES);
+        let root = Some(tree.root());
+        assert_eq!(tree.prove_adjacent(10), None);
+        assert_eq!(tree.prove_adjacent(u32::MAX), None);
+
+        for ip in [0, 9, 20, 29, 40, 49] {
+            let (left, right) = tree.prove_adjacent(ip).unwrap();
+            assert_eq!(adjacency_root(ip, 3, left.as_ref(), right.as_ref()), root);
+            // A proof for one gap doesn't show another
+            assert_eq!(adjacency_root(15, 3, left.as_ref(), right.as_ref()), None);
+        }
+
+        let (_, right) = tree.prove_adjacent(0).unwrap();
+        assert!(right.is_some());
+        assert_eq!(adjacency_root(0, 3, None, right.as_ref()), root);
+        // The left end must be the first range, the right end the last
+        let middle = tree.prove(1);
+        assert_eq!(adjacency_root(25, 3, None, Some(&middle)), None);
+        assert_eq!(adjacency_root(45, 3, Some(&middle), None), None);
+        // Non-neighbours leave a range out of the gap
+        let (first, last) = (tree.prove(0), tree.prove(2));
+        assert_eq!(adjacency_root(25, 3, Some(&first), Some(&last)), None);
+    }
+
+    #[test]
+    fn gaps_at_the_top_and_in_empty_trees() {
+        let ranges = [(0u32, 9)];
+        let tree = RangeTree::new(&ranges);
+        let (left, right) = tree.prove_adjacent(u32::MAX).unwrap();
+        assert!(right.is_none());
+        assert_eq!(
+            adjacency_root(u32::MAX, 1, left.as_ref(), None),
+            Some(tree.root())
+        );
+
+        let empty = RangeTree::<u32>::new(&[]);
+        assert_eq!(empty.prove_adjacent(5), Some((None, None)));
+        assert_eq!(adjacency_root(5u32, 0, None, None), Some(EMPTY_ROOT));
+        assert_eq!(adjacency_root(5u32, 1, None, None), None);
+    }
+}
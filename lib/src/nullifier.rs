@@ -0,0 +1,55 @@
+//! Nullifiers, for consumers that accept one proof per IP per epoch.
+//!
+//! A plain proof says nothing that ties it to its IP, so one clean address could whitelist
+//! any number of accounts. With a nullifier, the guest also commits
+//! `SHA-256(domain, ip, epoch, salt)`: the same IP proven for the same epoch and salt
+//! always gives the same nullifier, which a consumer records and refuses to see twice.
+//!
+//! The salt is the consumer's, handed to its provers. The guest commits `SHA-256(salt)` as
+//! the scope, so a consumer can check the salt is its own without publishing it. An IPv4
+//! nullifier can be brute-forced in 2^32 hashes by anyone holding the salt, so the salt
+//! should be kept from everyone but the consumer's own provers.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{commitment::hash_address, policy::Address, NullifierValues};
+
+/// Domain tag prefixed to the input of `nullifier`.
+const NULLIFIER_DOMAIN: &[u8] = b"zkip-nullifier-v1";
+
+/// What the guest needs to derive a nullifier for the proven address.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NullifierInput {
+    /// The consumer's epoch, e.g. a day number; the guest commits it as-is.
+    pub epoch: u32,
+    /// The consumer's secret salt.
+    pub salt: [u8; 32],
+}
+
+/// SHA-256 over a domain tag, the address family (4 or 6), the big-endian address, the
+/// big-endian epoch and the salt.
+pub fn nullifier(ip: Address, epoch: u32, salt: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(NULLIFIER_DOMAIN);
+    hash_address(&mut hasher, ip);
+    hasher.update(epoch.to_be_bytes());
+    hasher.update(salt);
+    hasher.finalize().into()
+}
+
+/// The committed scope of `salt`: its plain SHA-256, for a contract to hold as a constant.
+pub fn scope(salt: &[u8; 32]) -> [u8; 32] {
+    Sha256::digest(salt).into()
+}
+
+impl NullifierInput {
+    /// What a proof about `ip` commits.
+    pub fn values(&self, ip: Address) -> NullifierValues {
+        NullifierValues {
+            epoch: self.epoch,
+            scope: scope(&self.salt),
+            nullifier: nullifier(ip, self.epoch, &self.salt),
+        }
+    }
+}
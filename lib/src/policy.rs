@@ -0,0 +1,284 @@
+//! Policy expressions evaluated in the guest.
+//!
+//! A policy combines country, CIDR and ASN checks with AND, OR and NOT. The guest evaluates
+//! it against the private IP and commits its hash, so a verifier learns that a known rule
+//! held without learning the IP. Country and ASN membership come from GeoIP and ASN ranges
+//! the host supplies and the guest commits roots of (see `merkle`); CIDR blocks are part
+//! of the expression, so its hash covers them.
+
+use alloc::{boxed::Box, collections::BTreeSet, vec::Vec};
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::ZkipError;
+use crate::ip::IpV4;
+use crate::is_excluded_sorted;
+use crate::merkle::split_groups;
+use crate::parse_ip;
+
+/// Domain tag prefixed to the canonical expression encoding before hashing.
+const POLICY_EXPR_DOMAIN: &[u8] = b"zkip-policy-expr-v1";
+/// Domain tag prefixed to the canonical exclusion list before hashing.
+const POLICY_ID_DOMAIN: &[u8] = b"zkip-policy-v1";
+
+const TAG_COUNTRY: u8 = 0x01;
+const TAG_CIDR: u8 = 0x02;
+const TAG_ASN: u8 = 0x03;
+const TAG_ALL: u8 = 0x04;
+const TAG_ANY: u8 = 0x05;
+const TAG_NOT: u8 = 0x06;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PolicyExpr {
+    /// The IP is in this country (ISO 3166-1 numeric code).
+    Country(u16),
+    /// The IP is in one of these inclusive ranges: IPv4 blocks, then IPv6 blocks.
+    Cidr(Vec<(u32, u32)>, Vec<(u128, u128)>),
+    /// The IP is announced by this autonomous system.
+    Asn(u32),
+    /// Every sub-expression holds (true when empty).
+    All(Vec<PolicyExpr>),
+    /// At least one sub-expression holds (false when empty).
+    Any(Vec<PolicyExpr>),
+    Not(Box<PolicyExpr>),
+}
+
+/// The address a policy is evaluated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Address {
+    V4(IpV4),
+    V6(u128),
+}
+
+impl From<Ipv4Addr> for Address {
+    fn from(ip: Ipv4Addr) -> Self {
+        Self::V4(ip.into())
+    }
+}
+
+impl From<Ipv6Addr> for Address {
+    fn from(ip: Ipv6Addr) -> Self {
+        Self::V6(ip.into())
+    }
+}
+
+impl From<IpAddr> for Address {
+    fn from(ip: IpAddr) -> Self {
+        match ip {
+            IpAddr::V4(ip) => ip.into(),
+            IpAddr::V6(ip) => ip.into(),
+        }
+    }
+}
+
+impl From<Address> for IpAddr {
+    fn from(ip: Address) -> Self {
+        match ip {
+            Address::V4(IpV4(ip)) => IpAddr::V4(ip.into()),
+            Address::V6(ip) => IpAddr::V6(ip.into()),
+        }
+    }
+}
+
+/// Parses any form `parse_ip` accepts.
+impl TryFrom<&str> for Address {
+    type Error = ZkipError;
+
+    fn try_from(ip: &str) -> Result<Self, Self::Error> {
+        parse_ip(ip).map(Into::into)
+    }
+}
+
+/// Ranges of one country or ASN: `(key, IPv4 ranges, IPv6 ranges)`.
+pub type RangeGroup<'a, K> = (K, &'a [(u32, u32)], &'a [(u128, u128)]);
+
+/// Canonical ID of an exclusion list: SHA-256 over a domain tag followed by the sorted,
+/// deduplicated numeric country codes as big-endian `u16`s. Independent of ordering and
+/// duplicates, so equivalent lists share an ID; plain proofs commit it, and registries key
+/// their roots by it.
+pub fn policy_id(countries: &[u16]) -> [u8; 32] {
+    let canonical: BTreeSet<u16> = countries.iter().copied().collect();
+    let mut hasher = Sha256::new();
+    hasher.update(POLICY_ID_DOMAIN);
+    for code in canonical {
+        hasher.update(code.to_be_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// Pair `keys` with their ranges, `range_counts[i]` of them (IPv4, IPv6) for `keys[i]`.
+pub fn range_groups<'a, K: Copy>(
+    keys: &[K],
+    ranges: &'a [(u32, u32)],
+    ranges_v6: &'a [(u128, u128)],
+    range_counts: &[(u32, u32)],
+) -> Vec<RangeGroup<'a, K>> {
+    assert_eq!(range_counts.len(), keys.len(), "one range count per key");
+    let groups = split_groups(ranges, range_counts.iter().map(|&(count, _)| count));
+    let groups_v6 = split_groups(ranges_v6, range_counts.iter().map(|&(_, count)| count));
+    keys.iter()
+        .zip(groups.into_iter().zip(groups_v6))
+        .map(|(&key, (group, group_v6))| (key, group, group_v6))
+        .collect()
+}
+
+impl PolicyExpr {
+    /// Countries the expression names, sorted and deduplicated.
+    pub fn countries(&self) -> Vec<u16> {
+        let mut countries = Vec::new();
+        self.visit(&mut |expr| {
+            if let Self::Country(code) = expr {
+                countries.push(*code);
+            }
+        });
+        countries.sort_unstable();
+        countries.dedup();
+        countries
+    }
+
+    /// ASNs the expression names, sorted and deduplicated.
+    pub fn asns(&self) -> Vec<u32> {
+        let mut asns = Vec::new();
+        self.visit(&mut |expr| {
+            if let Self::Asn(asn) = expr {
+                asns.push(*asn);
+            }
+        });
+        asns.sort_unstable();
+        asns.dedup();
+        asns
+    }
+
+    fn visit(&self, f: &mut impl FnMut(&Self)) {
+        f(self);
+        match self {
+            Self::All(exprs) | Self::Any(exprs) => exprs.iter().for_each(|expr| expr.visit(f)),
+            Self::Not(expr) => expr.visit(f),
+            Self::Country(_) | Self::Cidr(..) | Self::Asn(_) => {}
+        }
+    }
+
+    /// Evaluate the expression for `ip`, with the ranges of every country and ASN it names.
+    /// Panics if one is missing, since treating it as empty would flip any `Not` above it.
+    pub fn evaluate(
+        &self,
+        ip: Address,
+        countries: &[RangeGroup<u16>],
+        asns: &[RangeGroup<u32>],
+    ) -> bool {
+        self.evaluate_with(ip, countries, asns, false)
+    }
+
+    /// Same result as `evaluate`, looking the IP up in each country's and ASN's ranges by
+    /// binary search, for groups that satisfy `crate::ranges_sorted`. CIDR blocks are still
+    /// scanned, since nothing checks their order.
+    pub fn evaluate_sorted(
+        &self,
+        ip: Address,
+        countries: &[RangeGroup<u16>],
+        asns: &[RangeGroup<u32>],
+    ) -> bool {
+        self.evaluate_with(ip, countries, asns, true)
+    }
+
+    fn evaluate_with(
+        &self,
+        ip: Address,
+        countries: &[RangeGroup<u16>],
+        asns: &[RangeGroup<u32>],
+        sorted: bool,
+    ) -> bool {
+        let evaluate = |expr: &Self| expr.evaluate_with(ip, countries, asns, sorted);
+        match self {
+            Self::Country(code) => {
+                let group = countries.iter().find(|(key, ..)| key == code);
+                lookup(ip, group.expect("policy country without ranges"), sorted)
+            }
+            Self::Cidr(ranges, ranges_v6) => contains(ip, &((), ranges, ranges_v6)),
+            Self::Asn(asn) => {
+                let group = asns.iter().find(|(key, ..)| key == asn);
+                lookup(ip, group.expect("policy ASN without ranges"), sorted)
+            }
+            Self::All(exprs) => exprs.iter().all(evaluate),
+            Self::Any(exprs) => exprs.iter().any(evaluate),
+            Self::Not(expr) => !evaluate(expr),
+        }
+    }
+
+    /// Canonical hash: SHA-256 over a domain tag and the expression encoded depth-first,
+    /// one tag byte per node followed by its big-endian operands. Lists are prefixed with
+    /// their `u32` length.
+    pub fn hash(&self) -> [u8; 32] {
+        let mut bytes = POLICY_EXPR_DOMAIN.to_vec();
+        self.encode(&mut bytes);
+        Sha256::digest(&bytes).into()
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Country(code) => {
+                out.push(TAG_COUNTRY);
+                out.extend(code.to_be_bytes());
+            }
+            Self::Cidr(ranges, ranges_v6) => {
+                out.push(TAG_CIDR);
+                out.extend((ranges.len() as u32).to_be_bytes());
+                for (start, end) in ranges {
+                    out.extend(start.to_be_bytes());
+                    out.extend(end.to_be_bytes());
+                }
+                out.extend((ranges_v6.len() as u32).to_be_bytes());
+                for (start, end) in ranges_v6 {
+                    out.extend(start.to_be_bytes());
+                    out.extend(end.to_be_bytes());
+                }
+            }
+            Self::Asn(asn) => {
+                out.push(TAG_ASN);
+                out.extend(asn.to_be_bytes());
+            }
+            Self::All(exprs) => {
+                out.push(TAG_ALL);
+                encode_list(exprs, out);
+            }
+            Self::Any(exprs) => {
+                out.push(TAG_ANY);
+                encode_list(exprs, out);
+            }
+            Self::Not(expr) => {
+                out.push(TAG_NOT);
+                expr.encode(out);
+            }
+        }
+    }
+}
+
+fn encode_list(exprs: &[PolicyExpr], out: &mut Vec<u8>) {
+    out.extend((exprs.len() as u32).to_be_bytes());
+    exprs.iter().for_each(|expr| expr.encode(out));
+}
+
+fn lookup<K>(ip: Address, group: &RangeGroup<K>, sorted: bool) -> bool {
+    if sorted {
+        contains_sorted(ip, group)
+    } else {
+        contains(ip, group)
+    }
+}
+
+fn contains_sorted<K>(ip: Address, (_, ranges, ranges_v6): &RangeGroup<K>) -> bool {
+    match ip {
+        Address::V4(IpV4(ip)) => !is_excluded_sorted(ip, ranges),
+        Address::V6(ip) => !is_excluded_sorted(ip, ranges_v6),
+    }
+}
+
+fn contains<K>(ip: Address, (_, ranges, ranges_v6): &RangeGroup<K>) -> bool {
+    match ip {
+        Address::V4(IpV4(ip)) => ranges.iter().any(|&(start, end)| start <= ip && ip <= end),
+        Address::V6(ip) => ranges_v6
+            .iter()
+            .any(|&(start, end)| start <= ip && ip <= end),
+    }
+}
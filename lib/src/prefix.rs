@@ -0,0 +1,45 @@
+//! Salted network prefixes, for consumers that look for many accounts behind one network.
+//!
+//! A nullifier ties a proof to its exact IP, but a sybil farm rotates addresses within the
+//! networks it rents. With a prefix salt, the guest also commits
+//! `SHA-256(domain, prefix, salt)` of the IP's /24, or /48 for IPv6: every address in the
+//! same network gives the same commitment, which a consumer can count across accounts
+//! without learning the network.
+//!
+//! The salt is the consumer's, handed to its provers, and `SHA-256(salt)` is committed as
+//! the scope, as for nullifiers. An IPv4 /24 has only 2^24 candidates, so the commitment
+//! hides the network only from those without the salt.
+
+use sha2::{Digest, Sha256};
+
+use crate::{ip::IpV4, nullifier::scope, policy::Address, PrefixValues};
+
+/// Domain tag prefixed to the input of `commitment`.
+const PREFIX_DOMAIN: &[u8] = b"zkip-prefix-v1";
+
+/// SHA-256 over a domain tag, the address family (4 or 6), the big-endian /24 (`ip >> 8`)
+/// or /48 (`ip >> 80`) prefix and the salt.
+pub fn commitment(ip: Address, salt: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(PREFIX_DOMAIN);
+    match ip {
+        Address::V4(IpV4(ip)) => {
+            hasher.update([4]);
+            hasher.update((ip >> 8).to_be_bytes());
+        }
+        Address::V6(ip) => {
+            hasher.update([6]);
+            hasher.update((ip >> 80).to_be_bytes());
+        }
+    }
+    hasher.update(salt);
+    hasher.finalize().into()
+}
+
+/// What a proof about `ip` commits for `salt`.
+pub fn values(ip: Address, salt: &[u8; 32]) -> PrefixValues {
+    PrefixValues {
+        scope: scope(salt),
+        commitment: commitment(ip, salt),
+    }
+}
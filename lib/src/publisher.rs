@@ -0,0 +1,80 @@
+//! Publisher-signed snapshots, so verifiers trust one key instead of every host's download.
+//!
+//! A publisher, such as the zkip maintainers, runs `zkip db root --sign` over a snapshot's
+//! countries and signs `snapshot_message` of their root (`merkle::country_set_root`) and the
+//! time it published them, with an Ed25519 key. Every country leaf of a proof's dataset
+//! root is also a leaf of that tree, so the guest checks a Merkle path from each leaf it
+//! computed to the signed root, verifies the signature with SP1's patched
+//! `curve25519-dalek`, and commits the publisher's key, the snapshot root and the time. A
+//! verifier that trusts the key then knows the ranges were the published ones, for any
+//! subset of the snapshot's countries, without pinning a root per country set.
+//!
+//! Ranges get reassigned between countries over time, so the prover also picks a
+//! `max_age`. The guest checks the snapshot was signed at most that long before the proof's
+//! timestamp and commits it, so a years-old snapshot can't pass for a current one.
+
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+use crate::merkle::root_from_path;
+
+/// Domain tag prefixed to the signed snapshot message.
+const SNAPSHOT_DOMAIN: &[u8] = b"zkip-snapshot-v2";
+
+/// A publisher's signature over a snapshot, and where the proof's countries sit in it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotSignature {
+    /// Ed25519 public key of the publisher.
+    pub pubkey: [u8; 32],
+    /// Ed25519 signature over `snapshot_message`, 64 bytes.
+    pub signature: Vec<u8>,
+    /// Root over every country of the snapshot, in the publisher's order.
+    pub snapshot_root: [u8; 32],
+    /// Number of countries in the snapshot.
+    pub country_count: u32,
+    /// When the publisher signed the snapshot, in Unix seconds.
+    pub published_at: u64,
+    /// Largest accepted `timestamp - published_at`, in seconds.
+    pub max_age: u64,
+    /// Each excluded country's index among the snapshot's countries and its Merkle path,
+    /// in the order of `excluded_countries`.
+    pub paths: Vec<(u32, Vec<[u8; 32]>)>,
+}
+
+/// The message a publisher signs: a domain tag, the snapshot root and the big-endian time.
+pub fn snapshot_message(snapshot_root: &[u8; 32], published_at: u64) -> Vec<u8> {
+    let mut message = SNAPSHOT_DOMAIN.to_vec();
+    message.extend(snapshot_root);
+    message.extend(published_at.to_be_bytes());
+    message
+}
+
+impl SnapshotSignature {
+    /// Whether each of `country_leaves` is in the signed snapshot at its path and the
+    /// signature is the publisher's. Malformed keys and signatures fail, as do
+    /// non-canonical or small-order ones (`verify_strict`).
+    pub fn verify(&self, country_leaves: &[[u8; 32]]) -> bool {
+        let in_snapshot = self.paths.len() == country_leaves.len()
+            && country_leaves
+                .iter()
+                .zip(&self.paths)
+                .all(|(&leaf, (index, path))| {
+                    root_from_path(leaf, *index, self.country_count, path)
+                        == Some(self.snapshot_root)
+                });
+        let Ok(key) = ed25519_dalek::VerifyingKey::from_bytes(&self.pubkey) else {
+            return false;
+        };
+        let Ok(signature) = ed25519_dalek::Signature::from_slice(&self.signature) else {
+            return false;
+        };
+        let message = snapshot_message(&self.snapshot_root, self.published_at);
+        in_snapshot && key.verify_strict(&message, &signature).is_ok()
+    }
+
+    /// Whether the snapshot was signed at most `max_age` before `timestamp`. One signed
+    /// after `timestamp`, from clock skew, counts as new.
+    pub fn fresh(&self, timestamp: u64) -> bool {
+        timestamp.saturating_sub(self.published_at) <= self.max_age
+    }
+}
@@ -0,0 +1,546 @@
+//! Inclusive IPv4 ranges, as the GeoIP databases and CIDR blocks describe them.
+//!
+//! The guests read ranges as packed `(start, end)` pairs (see `pack_ranges`), and the range
+//! trees hash them as such, so those APIs keep taking tuples. `Ipv4Range` is for the host
+//! side, where ranges come from files and user input: it can only hold `start <= end`, and
+//! serializes as the same `(start, end)` pair. `RangeSet` holds a normalized list of them,
+//! for membership checks against ranges of any origin. `Ipv4Cidr` is a CIDR block, for
+//! blocklists and firewall-style inputs, and any range splits into a minimal list of them.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::net::Ipv4Addr;
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ZkipError;
+use crate::{ip::IpV4, ip_to_u32};
+
+/// The addresses from `start` to `end`, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "(u32, u32)", into = "(u32, u32)")]
+pub struct Ipv4Range {
+    start: u32,
+    end: u32,
+}
+
+impl Ipv4Range {
+    /// The range from `start` to `end`, or `ZkipError::InvalidRange` if `start` is after
+    /// `end`.
+    pub fn new(start: u32, end: u32) -> Result<Self, ZkipError> {
+        if start > end {
+            return Err(ZkipError::InvalidRange { start, end });
+        }
+        Ok(Self { start, end })
+    }
+
+    /// The single address `ip`.
+    pub fn single(IpV4(ip): IpV4) -> Self {
+        Self { start: ip, end: ip }
+    }
+
+    /// The CIDR block of `prefix_len` bits holding `ip`, or `None` if `prefix_len` is over 32.
+    /// Host bits of `ip` are ignored.
+    pub fn from_cidr(IpV4(ip): IpV4, prefix_len: u8) -> Option<Self> {
+        let (start, end) = crate::block::bounds(ip, prefix_len)?;
+        Some(Self { start, end })
+    }
+
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+
+    pub fn end(&self) -> u32 {
+        self.end
+    }
+
+    /// Number of addresses in the range, at most 2^32.
+    pub fn len(&self) -> u64 {
+        u64::from(self.end - self.start) + 1
+    }
+
+    /// Always false: a range holds at least one address.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn contains(&self, IpV4(ip): IpV4) -> bool {
+        self.start <= ip && ip <= self.end
+    }
+
+    /// True if every address of `other` is in this range.
+    pub fn contains_range(&self, other: &Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    /// True if the ranges share an address.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// The range covering both, if they overlap or one ends right before the other starts.
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        let (first, second) = if self.start <= other.start {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        if first
+            .end
+            .checked_add(1)
+            .is_some_and(|next| next < second.start)
+        {
+            return None;
+        }
+        Some(Self {
+            start: first.start,
+            end: first.end.max(second.end),
+        })
+    }
+
+    /// The fewest CIDR blocks covering exactly this range, in address order.
+    pub fn to_cidrs(&self) -> Vec<Ipv4Cidr> {
+        let mut cidrs = Vec::new();
+        let end = u64::from(self.end);
+        let mut start = u64::from(self.start);
+        while start <= end {
+            // The largest block aligned at `start` that doesn't pass `end`
+            let aligned = start.trailing_zeros().min(32);
+            let fits = (end - start + 1).ilog2();
+            let host_bits = aligned.min(fits);
+            cidrs.push(Ipv4Cidr {
+                network: start as u32,
+                prefix_len: (32 - host_bits) as u8,
+            });
+            start += 1 << host_bits;
+        }
+        cidrs
+    }
+
+    /// `ranges` sorted by start, with overlapping and adjacent ranges merged, so the result
+    /// covers the same addresses with pairwise disjoint ranges and satisfies `ranges_sorted`.
+    pub fn merge(ranges: impl IntoIterator<Item = Self>) -> Vec<Self> {
+        let mut ranges: Vec<Self> = ranges.into_iter().collect();
+        ranges.sort_unstable();
+        let mut merged: Vec<Self> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            if let Some(last) = merged.last_mut() {
+                if let Some(union) = last.union(&range) {
+                    *last = union;
+                    continue;
+                }
+            }
+            merged.push(range);
+        }
+        merged
+    }
+}
+
+impl TryFrom<(u32, u32)> for Ipv4Range {
+    type Error = ZkipError;
+
+    fn try_from((start, end): (u32, u32)) -> Result<Self, Self::Error> {
+        Self::new(start, end)
+    }
+}
+
+impl From<Ipv4Range> for (u32, u32) {
+    fn from(range: Ipv4Range) -> Self {
+        (range.start, range.end)
+    }
+}
+
+impl fmt::Display for Ipv4Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            Ipv4Addr::from(self.start),
+            Ipv4Addr::from(self.end)
+        )
+    }
+}
+
+/// Sorted, pairwise disjoint ranges with no two adjacent, so each address set has one
+/// representation. Lookups are by binary search.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "Vec<Ipv4Range>", into = "Vec<Ipv4Range>")]
+pub struct RangeSet {
+    ranges: Vec<Ipv4Range>,
+}
+
+impl RangeSet {
+    /// The set of addresses in any of `ranges`: sorted, deduplicated and with overlapping
+    /// and adjacent ranges coalesced (see `Ipv4Range::merge`).
+    pub fn new(ranges: impl IntoIterator<Item = Ipv4Range>) -> Self {
+        Self {
+            ranges: Ipv4Range::merge(ranges),
+        }
+    }
+
+    /// The set of `(start, end)` pairs. Fails on the first with `start` after `end`.
+    pub fn from_pairs(pairs: &[(u32, u32)]) -> Result<Self, ZkipError> {
+        let ranges = pairs
+            .iter()
+            .map(|&pair| Ipv4Range::try_from(pair))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(ranges))
+    }
+
+    /// True if some range holds `ip`, in O(log n).
+    pub fn contains(&self, ip: IpV4) -> bool {
+        let after = self.ranges.partition_point(|range| range.start() <= ip.0);
+        after > 0 && self.ranges[after - 1].contains(ip)
+    }
+
+    /// The normalized ranges, sorted by start.
+    pub fn ranges(&self) -> &[Ipv4Range] {
+        &self.ranges
+    }
+
+    /// Number of normalized ranges, not addresses (see `address_count`).
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of addresses in the set, at most 2^32.
+    pub fn address_count(&self) -> u64 {
+        self.ranges.iter().map(Ipv4Range::len).sum()
+    }
+
+    /// Every IPv4 address in no range of the set.
+    pub fn complement(&self) -> Self {
+        let mut ranges = Vec::with_capacity(self.ranges.len() + 1);
+        // First address past the ranges so far, or `None` once they reach the top
+        let mut next = Some(0);
+        for range in &self.ranges {
+            if let Some(start) = next.filter(|&start| start < range.start()) {
+                ranges.push(Ipv4Range {
+                    start,
+                    end: range.start() - 1,
+                });
+            }
+            next = range.end().checked_add(1);
+        }
+        if let Some(start) = next {
+            ranges.push(Ipv4Range {
+                start,
+                end: u32::MAX,
+            });
+        }
+        Self { ranges }
+    }
+
+    /// The fewest CIDR blocks covering exactly the set, in address order.
+    pub fn to_cidrs(&self) -> Vec<Ipv4Cidr> {
+        self.ranges.iter().flat_map(Ipv4Range::to_cidrs).collect()
+    }
+
+    /// The normalized ranges as `(start, end)` pairs, as the lib's tuple APIs take them.
+    pub fn to_pairs(&self) -> Vec<(u32, u32)> {
+        self.ranges.iter().copied().map(Into::into).collect()
+    }
+}
+
+impl From<Vec<Ipv4Range>> for RangeSet {
+    fn from(ranges: Vec<Ipv4Range>) -> Self {
+        Self::new(ranges)
+    }
+}
+
+impl From<RangeSet> for Vec<Ipv4Range> {
+    fn from(set: RangeSet) -> Self {
+        set.ranges
+    }
+}
+
+impl FromIterator<Ipv4Range> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Ipv4Range>>(ranges: I) -> Self {
+        Self::new(ranges)
+    }
+}
+
+/// An IPv4 CIDR block: the addresses sharing the first `prefix_len` bits of `network`,
+/// whose other bits are zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "(u32, u8)", into = "(u32, u8)")]
+pub struct Ipv4Cidr {
+    network: u32,
+    prefix_len: u8,
+}
+
+impl Ipv4Cidr {
+    /// The block of `prefix_len` bits at `network`. Fails with `ZkipError::InvalidCidr` if
+    /// `prefix_len` is over 32 or `network` has bits set past it.
+    pub fn new(network: u32, prefix_len: u8) -> Result<Self, ZkipError> {
+        let range = Ipv4Range::from_cidr(IpV4(network), prefix_len)
+            .ok_or(ZkipError::InvalidCidr("prefix longer than 32 bits"))?;
+        if range.start != network {
+            return Err(ZkipError::InvalidCidr("host bits set past the prefix"));
+        }
+        Ok(Self {
+            network,
+            prefix_len,
+        })
+    }
+
+    pub fn network(&self) -> u32 {
+        self.network
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// The addresses of the block.
+    pub fn range(&self) -> Ipv4Range {
+        Ipv4Range::from_cidr(IpV4(self.network), self.prefix_len).expect("checked by new")
+    }
+}
+
+/// Parses `a.b.c.d/len`, or a lone address as a /32. The address takes any form
+/// `ip_to_u32` accepts.
+impl FromStr for Ipv4Cidr {
+    type Err = ZkipError;
+
+    fn from_str(block: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix_len) = match block.trim().split_once('/') {
+            Some((addr, prefix_len)) => {
+                let prefix_len = prefix_len
+                    .parse()
+                    .map_err(|_| ZkipError::InvalidCidr("expected a prefix length after /"))?;
+                (addr, prefix_len)
+            }
+            None => (block, 32),
+        };
+        Self::new(ip_to_u32(addr)?, prefix_len)
+    }
+}
+
+impl TryFrom<(u32, u8)> for Ipv4Cidr {
+    type Error = ZkipError;
+
+    fn try_from((network, prefix_len): (u32, u8)) -> Result<Self, Self::Error> {
+        Self::new(network, prefix_len)
+    }
+}
+
+impl From<Ipv4Cidr> for (u32, u8) {
+    fn from(cidr: Ipv4Cidr) -> Self {
+        (cidr.network, cidr.prefix_len)
+    }
+}
+
+impl From<Ipv4Cidr> for Ipv4Range {
+    fn from(cidr: Ipv4Cidr) -> Self {
+        cidr.range()
+    }
+}
+
+impl fmt::Display for Ipv4Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", Ipv4Addr::from(self.network), self.prefix_len)
+    }
+}
+
+/// The range of a CIDR block such as `1.2.3.0/24` (see `Ipv4Cidr`). Fails if the block has
+/// host bits set.
+pub fn parse_cidr(block: &str) -> Result<Ipv4Range, ZkipError> {
+    block.parse::<Ipv4Cidr>().map(|cidr| cidr.range())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec};
+
+    fn range(start: u32, end: u32) -> Ipv4Range {
+        Ipv4Range::new(start, end).unwrap()
+    }
+
+    #[test]
+    fn new_rejects_reversed_bounds() {
+        assert_eq!(
+            Ipv4Range::new(2, 1),
+            Err(ZkipError::InvalidRange { start: 2, end: 1 })
+        );
+        assert_eq!(range(7, 7), Ipv4Range::single(IpV4(7)));
+    }
+
+    #[test]
+    fn len_counts_the_whole_space() {
+        assert_eq!(range(0, u32::MAX).len(), 1 << 32);
+        assert_eq!(range(u32::MAX, u32::MAX).len(), 1);
+    }
+
+    #[test]
+    fn contains_includes_both_ends() {
+        let top = range(u32::MAX - 1, u32::MAX);
+        assert!(top.contains(IpV4(u32::MAX)));
+        assert!(top.contains(IpV4(u32::MAX - 1)));
+        assert!(!top.contains(IpV4(u32::MAX - 2)));
+        assert!(range(0, 0).contains(IpV4(0)));
+        assert!(range(0, u32::MAX).contains_range(&range(u32::MAX, u32::MAX)));
+    }
+
+    #[test]
+    fn union_joins_adjacent_ranges_only() {
+        assert_eq!(range(0, 9).union(&range(10, 20)), Some(range(0, 20)));
+        assert_eq!(range(10, 20).union(&range(0, 9)), Some(range(0, 20)));
+        assert_eq!(range(0, 9).union(&range(11, 20)), None);
+        assert_eq!(range(0, 30).union(&range(10, 20)), Some(range(0, 30)));
+    }
+
+    #[test]
+    fn union_does_not_overflow_at_the_top() {
+        let top = range(u32::MAX, u32::MAX);
+        assert_eq!(range(5, u32::MAX - 1).union(&top), Some(range(5, u32::MAX)));
+        assert_eq!(range(5, u32::MAX).union(&top), Some(range(5, u32::MAX)));
+        assert_eq!(range(0, 0).union(&top), None);
+    }
+
+    #[test]
+    fn merge_sorts_and_coalesces() {
+        let merged = Ipv4Range::merge([
+            range(u32::MAX, u32::MAX),
+            range(20, 30),
+            range(0, 9),
+            range(10, 12),
+            range(25, 40),
+            range(u32::MAX - 10, u32::MAX - 1),
+        ]);
+        assert_eq!(
+            merged,
+            vec![range(0, 12), range(20, 40), range(u32::MAX - 10, u32::MAX)]
+        );
+        assert!(Ipv4Range::merge([]).is_empty());
+    }
+
+    #[test]
+    fn complement_of_empty_set_is_everything() {
+        let all = RangeSet::default().complement();
+        assert_eq!(all.ranges(), &[range(0, u32::MAX)]);
+        assert_eq!(all.address_count(), 1 << 32);
+    }
+
+    #[test]
+    fn complement_of_everything_is_empty() {
+        let all = RangeSet::new([range(0, u32::MAX)]);
+        assert!(all.complement().is_empty());
+        assert_eq!(all.complement().complement(), all);
+    }
+
+    #[test]
+    fn complement_handles_ranges_touching_the_ends() {
+        let set = RangeSet::new([range(0, 9), range(20, 29), range(u32::MAX - 9, u32::MAX)]);
+        assert_eq!(
+            set.complement().ranges(),
+            &[range(10, 19), range(30, u32::MAX - 10)]
+        );
+
+        let inner = RangeSet::new([range(1, u32::MAX - 1)]);
+        assert_eq!(
+            inner.complement().ranges(),
+            &[range(0, 0), range(u32::MAX, u32::MAX)]
+        );
+        assert_eq!(inner.complement().complement(), inner);
+    }
+
+    #[test]
+    fn complement_partitions_the_space() {
+        let set = RangeSet::from_pairs(&[(5, 5), (100, 200), (150, 300)]).unwrap();
+        let complement = set.complement();
+        assert_eq!(set.address_count() + complement.address_count(), 1 << 32);
+        for ip in [0, 4, 5, 6, 99, 100, 300, 301, u32::MAX] {
+            assert_ne!(set.contains(IpV4(ip)), complement.contains(IpV4(ip)));
+        }
+    }
+
+    fn cidr(block: &str) -> Ipv4Cidr {
+        block.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_cidr_blocks() {
+        assert_eq!(cidr("10.0.0.0/8").range(), range(0x0a00_0000, 0x0aff_ffff));
+        assert_eq!(cidr(" 1.2.3.4 ").range(), range(0x0102_0304, 0x0102_0304));
+        assert_eq!(cidr("0.0.0.0/0").range(), range(0, u32::MAX));
+        assert_eq!(
+            parse_cidr("192.168.1.0/24").unwrap(),
+            cidr("192.168.1.0/24").range()
+        );
+        assert_eq!(cidr("172.16.0.0/12").to_string(), "172.16.0.0/12");
+    }
+
+    #[test]
+    fn rejects_malformed_cidr_blocks() {
+        assert_eq!(
+            "10.0.0.1/8".parse::<Ipv4Cidr>(),
+            Err(ZkipError::InvalidCidr("host bits set past the prefix"))
+        );
+        assert_eq!(
+            "10.0.0.0/33".parse::<Ipv4Cidr>(),
+            Err(ZkipError::InvalidCidr("prefix longer than 32 bits"))
+        );
+        assert_eq!(
+            "10.0.0.0/".parse::<Ipv4Cidr>(),
+            Err(ZkipError::InvalidCidr("expected a prefix length after /"))
+        );
+        assert!("10.0.0/8x".parse::<Ipv4Cidr>().is_err());
+    }
+
+    #[test]
+    fn to_cidrs_splits_minimally() {
+        assert_eq!(range(0, u32::MAX).to_cidrs(), vec![cidr("0.0.0.0/0")]);
+        assert_eq!(
+            range(u32::MAX, u32::MAX).to_cidrs(),
+            vec![cidr("255.255.255.255/32")]
+        );
+        // 10.0.0.1-10.0.0.6 needs a /32, a /31 at .2, a /31 at .4 and a /32 at .6
+        assert_eq!(
+            range(0x0a00_0001, 0x0a00_0006).to_cidrs(),
+            vec![
+                cidr("10.0.0.1/32"),
+                cidr("10.0.0.2/31"),
+                cidr("10.0.0.4/31"),
+                cidr("10.0.0.6/32"),
+            ]
+        );
+        // The worst case: every prefix length from /32 up to /1 and back down
+        assert_eq!(range(1, u32::MAX - 1).to_cidrs().len(), 62);
+    }
+
+    #[test]
+    fn to_cidrs_round_trips() {
+        for (start, end) in [
+            (0, 0),
+            (1, 254),
+            (0x0a00_0000, 0x0aff_ffff),
+            (0x0102_0300, 0x0102_05ff),
+            (1, u32::MAX - 1),
+            (u32::MAX - 1000, u32::MAX),
+        ] {
+            let cidrs = range(start, end).to_cidrs();
+            assert!(cidrs
+                .windows(2)
+                .all(|pair| pair[0].range().end() + 1 == pair[1].range().start()));
+            assert_eq!(cidrs.first().unwrap().network(), start);
+            assert_eq!(cidrs.last().unwrap().range().end(), end);
+            let merged = RangeSet::new(cidrs.iter().map(Ipv4Cidr::range));
+            assert_eq!(merged.ranges(), &[range(start, end)]);
+            for block in &cidrs {
+                assert_eq!(&block.to_string().parse::<Ipv4Cidr>().unwrap(), block);
+            }
+        }
+
+        let set = RangeSet::new([range(0, 9), range(u32::MAX - 3, u32::MAX)]);
+        assert_eq!(
+            RangeSet::new(set.to_cidrs().into_iter().map(Ipv4Range::from)),
+            set
+        );
+    }
+}
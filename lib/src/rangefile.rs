@@ -0,0 +1,332 @@
+//! `.zkranges` files: one address family's ranges in a compact, checksummed binary form.
+//!
+//! The host caches each country's ranges from the CSV databases in this form, so it parses
+//! a database once per download rather than on every run. The body is the ranges exactly
+//! as `pack_ranges` and `pack_ranges_v6` lay them out for the guests, and `RangeFile`
+//! reads them in place, so a guest can take a file's bytes as input without copying or
+//! deserializing them.
+//!
+//! | Bytes    | Field                                                  |
+//! | -------- | ------------------------------------------------------ |
+//! | 0..4     | `MAGIC`                                                |
+//! | 4        | `VERSION`                                              |
+//! | 5        | address family, 4 or 6                                 |
+//! | 6..10    | number of ranges, little-endian `u32`                  |
+//! | 10..42   | SHA-256 of the body                                    |
+//! | 42..     | body: the packed ranges, sorted and disjoint           |
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use sha2::{Digest, Sha256};
+
+use crate::{error::ZkipError, pack_ranges, pack_ranges_v6, ranges_sorted};
+
+/// First bytes of every `.zkranges` file.
+pub const MAGIC: [u8; 4] = *b"ZKRG";
+
+/// Format version `write` produces and `RangeFile::parse` reads.
+pub const VERSION: u8 = 1;
+
+/// File extension of range files, without the dot.
+pub const EXTENSION: &str = "zkranges";
+
+/// Bytes before the body.
+const HEADER_LEN: usize = 42;
+
+/// An address type ranges files hold: `u32` for IPv4, `u128` for IPv6.
+pub trait RangeAddress: Copy + Ord {
+    /// The family byte of the header, 4 or 6.
+    const FAMILY: u8;
+    /// Bytes per address in the body.
+    const WIDTH: usize;
+
+    /// Reads one little-endian address from `WIDTH` bytes.
+    fn read_le(bytes: &[u8]) -> Self;
+
+    /// The body for `ranges`.
+    fn pack(ranges: &[(Self, Self)]) -> Vec<u8>;
+}
+
+impl RangeAddress for u32 {
+    const FAMILY: u8 = 4;
+    const WIDTH: usize = 4;
+
+    fn read_le(bytes: &[u8]) -> Self {
+        u32::from_le_bytes(bytes.try_into().expect("an IPv4 address takes 4 bytes"))
+    }
+
+    fn pack(ranges: &[(Self, Self)]) -> Vec<u8> {
+        pack_ranges(ranges)
+    }
+}
+
+impl RangeAddress for u128 {
+    const FAMILY: u8 = 6;
+    const WIDTH: usize = 16;
+
+    fn read_le(bytes: &[u8]) -> Self {
+        u128::from_le_bytes(bytes.try_into().expect("an IPv6 address takes 16 bytes"))
+    }
+
+    fn pack(ranges: &[(Self, Self)]) -> Vec<u8> {
+        pack_ranges_v6(ranges)
+    }
+}
+
+/// The range file holding `ranges`. Fails with `ZkipError::InvalidRangeFile` unless they
+/// satisfy `ranges_sorted`.
+pub fn write<T: RangeAddress>(ranges: &[(T, T)]) -> Result<Vec<u8>, ZkipError> {
+    if !ranges_sorted(ranges) {
+        return Err(ZkipError::InvalidRangeFile(
+            "ranges must be sorted and disjoint",
+        ));
+    }
+    let count =
+        u32::try_from(ranges.len()).map_err(|_| ZkipError::InvalidRangeFile("too many ranges"))?;
+    let body = T::pack(ranges);
+    let mut bytes = Vec::with_capacity(HEADER_LEN + body.len());
+    bytes.extend_from_slice(&MAGIC);
+    bytes.push(VERSION);
+    bytes.push(T::FAMILY);
+    bytes.extend_from_slice(&count.to_le_bytes());
+    bytes.extend_from_slice(&Sha256::digest(&body));
+    bytes.extend_from_slice(&body);
+    Ok(bytes)
+}
+
+/// The address family of the range file `bytes`, 4 or 6, read from its header without
+/// checking the rest. `None` if it doesn't start with `MAGIC`.
+pub fn family(bytes: &[u8]) -> Option<u8> {
+    (bytes.get(..4)? == MAGIC).then_some(*bytes.get(5)?)
+}
+
+/// The ranges of a range file, read in place from its bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeFile<'a, T> {
+    body: &'a [u8],
+    family: PhantomData<T>,
+}
+
+impl<'a, T: RangeAddress> RangeFile<'a, T> {
+    /// The ranges of the file `bytes`. Fails with `ZkipError::InvalidRangeFile` unless it
+    /// has the magic, version and family `T` expects, its checksum matches, and its ranges
+    /// are sorted and disjoint.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, ZkipError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(ZkipError::InvalidRangeFile("truncated header"));
+        }
+        let (header, body) = bytes.split_at(HEADER_LEN);
+        if header[..4] != MAGIC {
+            return Err(ZkipError::InvalidRangeFile("not a range file"));
+        }
+        if header[4] != VERSION {
+            return Err(ZkipError::InvalidRangeFile("unsupported version"));
+        }
+        if header[5] != T::FAMILY {
+            return Err(ZkipError::InvalidRangeFile("wrong address family"));
+        }
+        let count = u32::from_le_bytes(header[6..10].try_into().unwrap()) as usize;
+        if count.checked_mul(2 * T::WIDTH) != Some(body.len()) {
+            return Err(ZkipError::InvalidRangeFile(
+                "body length doesn't match the range count",
+            ));
+        }
+        if Sha256::digest(body)[..] != header[10..] {
+            return Err(ZkipError::InvalidRangeFile("checksum mismatch"));
+        }
+        let file = Self {
+            body,
+            family: PhantomData,
+        };
+        let mut previous_end = None;
+        for (start, end) in file.iter() {
+            if start > end || previous_end.is_some_and(|previous| previous >= start) {
+                return Err(ZkipError::InvalidRangeFile(
+                    "ranges must be sorted and disjoint",
+                ));
+            }
+            previous_end = Some(end);
+        }
+        Ok(file)
+    }
+
+    pub fn len(&self) -> usize {
+        self.body.len() / (2 * T::WIDTH)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.body.is_empty()
+    }
+
+    /// Range `index`, in order.
+    pub fn get(&self, index: usize) -> Option<(T, T)> {
+        let offset = index.checked_mul(2 * T::WIDTH)?;
+        let range = self.body.get(offset..offset + 2 * T::WIDTH)?;
+        let (start, end) = range.split_at(T::WIDTH);
+        Some((T::read_le(start), T::read_le(end)))
+    }
+
+    /// The ranges in order, decoded one at a time.
+    pub fn iter(&self) -> impl Iterator<Item = (T, T)> + 'a {
+        self.body.chunks_exact(2 * T::WIDTH).map(|range| {
+            let (start, end) = range.split_at(T::WIDTH);
+            (T::read_le(start), T::read_le(end))
+        })
+    }
+
+    /// The ranges as `(start, end)` pairs, as the lib's tuple APIs take them.
+    pub fn to_vec(&self) -> Vec<(T, T)> {
+        self.iter().collect()
+    }
+
+    /// The body: the ranges as `pack_ranges` or `pack_ranges_v6` packs them, to hand to a
+    /// guest as is.
+    pub fn packed(&self) -> &'a [u8] {
+        self.body
+    }
+
+    /// True if some range holds `ip`, by binary search over the bytes.
+    pub fn contains(&self, ip: T) -> bool {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let (start, _) = self.get(mid).expect("index in bounds");
+            if start <= ip {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low > 0 && self.get(low - 1).is_some_and(|(_, end)| ip <= end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    const V4: [(u32, u32); 3] = [(0, 9), (100, 200), (u32::MAX - 1, u32::MAX)];
+
+    fn error(message: &'static str) -> Result<RangeFile<'static, u32>, ZkipError> {
+        Err(ZkipError::InvalidRangeFile(message))
+    }
+
+    #[test]
+    fn writes_the_documented_header() {
+        let bytes = write(&V4).unwrap();
+        assert_eq!(&bytes[..4], b"ZKRG");
+        assert_eq!(bytes[4], VERSION);
+        assert_eq!(bytes[5], 4);
+        assert_eq!(bytes[6..10], 3u32.to_le_bytes());
+        assert_eq!(bytes[10..42], Sha256::digest(&bytes[42..])[..]);
+        assert_eq!(&bytes[42..], &pack_ranges(&V4)[..]);
+        assert_eq!(family(&bytes), Some(4));
+        assert_eq!(family(b"ZKR"), None);
+    }
+
+    #[test]
+    fn round_trips_ipv4() {
+        let bytes = write(&V4).unwrap();
+        let file = RangeFile::<u32>::parse(&bytes).unwrap();
+        assert_eq!(file.len(), 3);
+        assert_eq!(file.to_vec(), V4);
+        assert_eq!(file.get(2), Some((u32::MAX - 1, u32::MAX)));
+        assert_eq!(file.get(3), None);
+        for (ip, held) in [
+            (0, true),
+            (10, false),
+            (150, true),
+            (201, false),
+            (u32::MAX, true),
+        ] {
+            assert_eq!(file.contains(ip), held, "{}", ip);
+        }
+    }
+
+    #[test]
+    fn round_trips_ipv6_and_empty_files() {
+        let ranges = [(1u128, 2), (u128::MAX, u128::MAX)];
+        let bytes = write(&ranges).unwrap();
+        assert_eq!(family(&bytes), Some(6));
+        assert_eq!(RangeFile::<u128>::parse(&bytes).unwrap().to_vec(), ranges);
+        assert_eq!(
+            RangeFile::<u32>::parse(&bytes),
+            error("wrong address family")
+        );
+
+        let empty = write::<u32>(&[]).unwrap();
+        let file = RangeFile::<u32>::parse(&empty).unwrap();
+        assert!(file.is_empty());
+        assert!(!file.contains(0));
+    }
+
+    #[test]
+    fn write_rejects_unsorted_ranges() {
+        assert_eq!(
+            write(&[(10u32, 20), (5, 6)]),
+            Err(ZkipError::InvalidRangeFile(
+                "ranges must be sorted and disjoint"
+            ))
+        );
+        assert!(write(&[(10u32, 20), (20, 30)]).is_err());
+    }
+
+    #[test]
+    fn rejects_corrupted_files() {
+        let bytes = write(&V4).unwrap();
+
+        assert_eq!(RangeFile::parse(&bytes[..41]), error("truncated header"));
+
+        let mut bad = bytes.clone();
+        bad[0] = b'X';
+        assert_eq!(RangeFile::parse(&bad), error("not a range file"));
+
+        let mut bad = bytes.clone();
+        bad[4] = VERSION + 1;
+        assert_eq!(RangeFile::parse(&bad), error("unsupported version"));
+
+        let mut bad = bytes.clone();
+        bad[6] = 4;
+        assert_eq!(
+            RangeFile::parse(&bad),
+            error("body length doesn't match the range count")
+        );
+        assert_eq!(
+            RangeFile::parse(&bytes[..bytes.len() - 1]),
+            error("body length doesn't match the range count")
+        );
+
+        let mut bad = bytes.clone();
+        *bad.last_mut().unwrap() ^= 1;
+        assert_eq!(RangeFile::parse(&bad), error("checksum mismatch"));
+
+        let mut bad = bytes.clone();
+        bad[20] ^= 0x80;
+        assert_eq!(RangeFile::parse(&bad), error("checksum mismatch"));
+    }
+
+    #[test]
+    fn rejects_unsorted_bodies_with_a_valid_checksum() {
+        for ranges in [
+            vec![(10u32, 20), (5, 6)],
+            vec![(10, 20), (20, 30)],
+            vec![(7, 3)],
+        ] {
+            let body = pack_ranges(&ranges);
+            let mut bytes = Vec::new();
+            bytes.extend_from_slice(&MAGIC);
+            bytes.push(VERSION);
+            bytes.push(4);
+            bytes.extend_from_slice(&(ranges.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&Sha256::digest(&body));
+            bytes.extend_from_slice(&body);
+            assert_eq!(
+                RangeFile::<u32>::parse(&bytes),
+                Err(ZkipError::InvalidRangeFile(
+                    "ranges must be sorted and disjoint"
+                ))
+            );
+        }
+    }
+}
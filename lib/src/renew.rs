@@ -0,0 +1,35 @@
+//! Renewals, for re-committing an earlier proof's claim under a new timestamp.
+//!
+//! A proof says where an IP was at its `timestamp`, and verifiers often want a recent one.
+//! Proving again reads every range of every country, while the renewal guest only reads
+//! the earlier proof: it verifies it with SP1's proof composition, decodes its public
+//! values and commits them unchanged next to a new timestamp, the old one and the vkey of
+//! the program that made it. Nothing about the IP is checked again, so a renewal claims
+//! only that someone holding a valid proof from `previous_timestamp` renewed it at
+//! `timestamp`.
+//!
+//! Renewals wrap the original proof, never another renewal, so the committed vkey is
+//! always a zkip program's and a verifier checks it against the same registry.
+
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// What the renewal guest reads, next to the earlier proof itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenewalInput {
+    /// Digest of the vkey that made the earlier proof (`HashableKey::hash_u32`).
+    pub vkey: [u32; 8],
+    /// The earlier proof's public values.
+    pub public_values: Vec<u8>,
+    /// The new timestamp, in Unix seconds.
+    pub timestamp: u64,
+}
+
+/// A vkey digest as committed: its words, big-endian.
+pub fn vkey_bytes(vkey: &[u32; 8]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (chunk, word) in bytes.chunks_mut(4).zip(vkey) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    bytes
+}
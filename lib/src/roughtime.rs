@@ -0,0 +1,178 @@
+//! Roughtime responses, so the committed timestamp is a time server's and not the host's.
+//!
+//! Without one, `timestamp` is whatever the prover's clock said. With `--time-source
+//! roughtime` the host sends a Roughtime server a random nonce, and the server signs the
+//! time together with a Merkle root over the nonces it answered. The guest checks the
+//! server's long-term Ed25519 key delegated to the key that signed the time, that the time
+//! falls in the delegation's window, and that the nonce is in the signed tree. It then
+//! commits the time as `timestamp`, with the long-term key and the server's uncertainty
+//! radius, so a verifier that trusts the key knows the proof was made after that time.
+//!
+//! This speaks the original Roughtime protocol: SHA-512 tree hashes and times in
+//! microseconds. RFC 3161 timestamps need CMS, ASN.1 and RSA in the guest and are not
+//! supported.
+
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+
+/// Prefixed by the long-term key to the delegation it signs.
+const DELEGATION_CONTEXT: &[u8] = b"RoughTime v1 delegation signature--\0";
+/// Prefixed by the delegated key to the response it signs.
+const RESPONSE_CONTEXT: &[u8] = b"RoughTime v1 response signature\0";
+
+/// Nonce size, and the size of each tree hash.
+pub const NONCE_SIZE: usize = 64;
+
+/// Smallest request a server answers, so replies are never larger than requests.
+const REQUEST_SIZE: usize = 1024;
+
+/// A Roughtime server's reply to the host's nonce: read by the guest, committed without
+/// the reply.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoughtimeResponse {
+    /// Long-term Ed25519 public key of the server.
+    pub pubkey: [u8; 32],
+    /// The nonce the host sent, 64 bytes.
+    pub nonce: Vec<u8>,
+    /// The server's reply, as received.
+    pub reply: Vec<u8>,
+}
+
+/// The time a server signed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignedTime {
+    /// Midpoint of the server's estimate, in Unix seconds, rounded down.
+    pub timestamp: u64,
+    /// The server's uncertainty either side of it, in seconds, rounded up.
+    pub radius: u32,
+}
+
+/// A request for `nonce`, padded to the size servers require.
+pub fn request(nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    let padding = REQUEST_SIZE - 16 - NONCE_SIZE;
+    let mut message = Vec::with_capacity(REQUEST_SIZE);
+    message.extend(2u32.to_le_bytes());
+    message.extend((NONCE_SIZE as u32).to_le_bytes());
+    message.extend(b"NONC");
+    message.extend(b"PAD\xff");
+    message.extend(nonce);
+    message.resize(message.len() + padding, 0);
+    message
+}
+
+impl RoughtimeResponse {
+    /// The time the server signed for this nonce, if the delegation and response
+    /// signatures verify, the time is within the delegation's window and the nonce is in
+    /// the signed tree. Non-canonical or small-order signatures fail (`verify_strict`).
+    pub fn verify(&self) -> Option<SignedTime> {
+        let signed_response = field(&self.reply, b"SREP")?;
+        let certificate = field(&self.reply, b"CERT")?;
+        let delegation = field(certificate, b"DELE")?;
+        let delegated_key = field(delegation, b"PUBK")?;
+        let delegated = verify_signature(
+            &self.pubkey,
+            field(certificate, b"SIG\0")?,
+            DELEGATION_CONTEXT,
+            delegation,
+        );
+        let signed = verify_signature(
+            delegated_key,
+            field(&self.reply, b"SIG\0")?,
+            RESPONSE_CONTEXT,
+            signed_response,
+        );
+
+        let midpoint = u64_field(signed_response, b"MIDP")?;
+        let radius = u32_field(signed_response, b"RADI")?;
+        let in_window = u64_field(delegation, b"MINT")? <= midpoint
+            && midpoint <= u64_field(delegation, b"MAXT")?;
+        let index = u32_field(&self.reply, b"INDX")?;
+        let root = tree_root(&self.nonce, index, field(&self.reply, b"PATH")?)?;
+        let answered = field(signed_response, b"ROOT")? == root.as_slice();
+
+        (delegated && signed && in_window && answered).then_some(SignedTime {
+            timestamp: midpoint / 1_000_000,
+            radius: radius.div_ceil(1_000_000),
+        })
+    }
+}
+
+/// The value of `tag` in a Roughtime message: a tag count, the end offset of each value
+/// but the last, the tags in increasing order, then the values.
+fn field<'a>(message: &'a [u8], tag: &[u8; 4]) -> Option<&'a [u8]> {
+    let word = |index: usize| {
+        let bytes = message.get(4 * index..4 * index + 4)?;
+        Some(u32::from_le_bytes(bytes.try_into().ok()?))
+    };
+    let count = word(0)? as usize;
+    let values = message.get(8usize.checked_mul(count)?..)?;
+    let mut previous = None;
+    for index in 0..count {
+        let current = word(count + index)?;
+        if previous.is_some_and(|previous| previous >= current) {
+            return None;
+        }
+        previous = Some(current);
+        if current.to_le_bytes() == *tag {
+            let start = if index == 0 { 0 } else { word(index)? as usize };
+            let end = if index + 1 == count {
+                values.len()
+            } else {
+                word(index + 1)? as usize
+            };
+            return values.get(start..end);
+        }
+    }
+    None
+}
+
+fn u32_field(message: &[u8], tag: &[u8; 4]) -> Option<u32> {
+    Some(u32::from_le_bytes(field(message, tag)?.try_into().ok()?))
+}
+
+fn u64_field(message: &[u8], tag: &[u8; 4]) -> Option<u64> {
+    Some(u64::from_le_bytes(field(message, tag)?.try_into().ok()?))
+}
+
+fn verify_signature(pubkey: &[u8], signature: &[u8], context: &[u8], message: &[u8]) -> bool {
+    let Ok(key) = ed25519_dalek::VerifyingKey::try_from(pubkey) else {
+        return false;
+    };
+    let Ok(signature) = ed25519_dalek::Signature::from_slice(signature) else {
+        return false;
+    };
+    key.verify_strict(&[context, message].concat(), &signature)
+        .is_ok()
+}
+
+/// Root of the server's tree from the nonce's leaf at `index` and its `path` of sibling
+/// hashes, bottom up. Leaves hash `0x00 || nonce` and nodes `0x01 || left || right`.
+fn tree_root(nonce: &[u8], index: u32, path: &[u8]) -> Option<[u8; NONCE_SIZE]> {
+    let levels = path.len() / NONCE_SIZE;
+    if nonce.len() != NONCE_SIZE
+        || levels * NONCE_SIZE != path.len()
+        || index.checked_shr(levels as u32).unwrap_or(0) != 0
+    {
+        return None;
+    }
+    let mut node: [u8; NONCE_SIZE] = Sha512::new()
+        .chain_update([0])
+        .chain_update(nonce)
+        .finalize()
+        .into();
+    for (level, sibling) in path.chunks(NONCE_SIZE).enumerate() {
+        let (left, right) = if index.checked_shr(level as u32).unwrap_or(0) & 1 == 0 {
+            (node.as_slice(), sibling)
+        } else {
+            (sibling, node.as_slice())
+        };
+        node = Sha512::new()
+            .chain_update([1])
+            .chain_update(left)
+            .chain_update(right)
+            .finalize()
+            .into();
+    }
+    Some(node)
+}
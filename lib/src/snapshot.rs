@@ -0,0 +1,42 @@
+//! Snapshot metadata, for verifiers that only accept proofs over known database versions.
+//!
+//! The dataset root and ranges digest say which ranges a proof checked, but a verifier
+//! needs the snapshot itself to recompute them. A plain proof also commits where its
+//! snapshot came from, when it was published and a hash of its files, so a verifier can
+//! compare them against the snapshots it accepts before doing anything else.
+//!
+//! The guest only sees the ranges, not the files, so it commits this metadata as the host
+//! reports it. It's bound to the ranges only through a verifier who recomputes the ranges
+//! digest from the snapshot it names.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Domain tag prefixed to the input of `content_hash`.
+const SNAPSHOT_DOMAIN: &[u8] = b"zkip-snapshot-v1";
+
+/// What a plain proof commits about its snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotMetadata {
+    /// `source_id` of the IPv4 snapshot's URL.
+    pub source: [u8; 32],
+    /// When the snapshot was published, in Unix seconds.
+    pub published_at: u64,
+    /// `content_hash` of the IPv4 and IPv6 snapshot files.
+    pub hash: [u8; 32],
+}
+
+/// The committed identifier of a snapshot source: the plain SHA-256 of its URL.
+pub fn source_id(url: &str) -> [u8; 32] {
+    Sha256::digest(url.as_bytes()).into()
+}
+
+/// SHA-256 over a domain tag and the SHA-256 of the IPv4 and IPv6 snapshot files, as a
+/// `db root` manifest records them.
+pub fn content_hash(sha256: &[u8; 32], sha256_v6: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(SNAPSHOT_DOMAIN);
+    hasher.update(sha256);
+    hasher.update(sha256_v6);
+    hasher.finalize().into()
+}
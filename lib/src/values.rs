@@ -0,0 +1,446 @@
+//! Public values of a plain proof as a plain Rust struct, and their encodings.
+//!
+//! `PublicValuesStruct` is generated by `sol!` and holds alloy types such as `FixedBytes`,
+//! so a verifier reading it would depend on alloy too. `PublicValues` holds the same
+//! fields with byte arrays and integers, derives serde, and converts from and to both the
+//! generated struct and the ABI bytes a proof commits. `encode` and `decode` do the latter
+//! as plain functions, so verifiers don't import `SolType` to read a proof.
+//!
+//! Consumers on chains and light clients that don't speak the Solidity ABI can re-encode
+//! the values as SSZ (`encode_ssz`), or as borsh with the `borsh` feature
+//! (`encode_borsh`). Neither needs the `evm` feature; only reading the committed ABI
+//! bytes does.
+
+use alloc::vec::Vec;
+#[cfg(feature = "evm")]
+use alloy_sol_types::SolType;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ZkipError;
+#[cfg(feature = "evm")]
+use crate::{PublicValuesStruct, DOMAIN_SEPARATOR};
+
+/// Most entries of `excluded_countries` an SSZ encoding holds: the limit of its
+/// `List[uint16, N]`.
+pub const SSZ_MAX_COUNTRIES: usize = 1024;
+
+/// Bytes of the fixed-size part of the SSZ encoding: each field in order, little-endian,
+/// with a 4-byte offset in place of `excluded_countries`.
+const SSZ_FIXED_LEN: usize =
+    1 + 8 + 4 + 32 + 4 + 8 + 1 + 32 + 4 + 32 + 32 + 2 + 32 + 32 + 8 + 32 + 32 + 32 + 32 + 32;
+
+/// The fields of `PublicValuesStruct`, documented there.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+pub struct PublicValues {
+    pub is_excluded: bool,
+    pub timestamp: u64,
+    /// ISO 3166-1 numeric codes.
+    pub excluded_countries: Vec<u16>,
+    pub dataset_root: [u8; 32],
+    pub range_count: u32,
+    pub covered_ipv4: u64,
+    pub is_public_ip: bool,
+    pub ranges_digest: [u8; 32],
+    pub nullifier_epoch: u32,
+    pub nullifier_scope: [u8; 32],
+    pub nullifier: [u8; 32],
+    pub version: u16,
+    pub domain_separator: [u8; 32],
+    pub snapshot_source: [u8; 32],
+    pub snapshot_date: u64,
+    pub snapshot_hash: [u8; 32],
+    pub policy_id: [u8; 32],
+    pub app_context: [u8; 32],
+    pub prefix_scope: [u8; 32],
+    pub prefix_commitment: [u8; 32],
+}
+
+#[cfg(feature = "evm")]
+impl PublicValues {
+    /// The ABI encoding of `PublicValuesStruct`, as the plain guest commits it.
+    pub fn to_abi_bytes(&self) -> Vec<u8> {
+        encode(self)
+    }
+}
+
+#[cfg(feature = "evm")]
+/// The ABI encoding of `values`, as the plain guest commits it.
+pub fn encode(values: &PublicValues) -> Vec<u8> {
+    PublicValuesStruct::abi_encode(&PublicValuesStruct::from(values.clone()))
+}
+
+#[cfg(feature = "evm")]
+/// The public values of a plain proof. Fails with `ZkipError::UnknownPublicValues` unless
+/// `bytes` are exactly the ABI encoding of a `PublicValuesStruct` with `DOMAIN_SEPARATOR`.
+/// Use `decode_public_values` for proofs of other modes.
+pub fn decode(bytes: &[u8]) -> Result<PublicValues, ZkipError> {
+    let values = PublicValuesStruct::abi_decode(bytes)
+        .ok()
+        .filter(|values| {
+            values.domain_separator == DOMAIN_SEPARATOR
+                && PublicValuesStruct::abi_encode(values) == bytes
+        })
+        .ok_or(ZkipError::UnknownPublicValues)?;
+    Ok(values.into())
+}
+
+#[cfg(feature = "evm")]
+impl From<PublicValuesStruct> for PublicValues {
+    fn from(values: PublicValuesStruct) -> Self {
+        Self {
+            is_excluded: values.is_excluded,
+            timestamp: values.timestamp,
+            excluded_countries: values.excluded_countries,
+            dataset_root: values.dataset_root.0,
+            range_count: values.range_count,
+            covered_ipv4: values.covered_ipv4,
+            is_public_ip: values.is_public_ip,
+            ranges_digest: values.ranges_digest.0,
+            nullifier_epoch: values.nullifier_epoch,
+            nullifier_scope: values.nullifier_scope.0,
+            nullifier: values.nullifier.0,
+            version: values.version,
+            domain_separator: values.domain_separator.0,
+            snapshot_source: values.snapshot_source.0,
+            snapshot_date: values.snapshot_date,
+            snapshot_hash: values.snapshot_hash.0,
+            policy_id: values.policy_id.0,
+            app_context: values.app_context.0,
+            prefix_scope: values.prefix_scope.0,
+            prefix_commitment: values.prefix_commitment.0,
+        }
+    }
+}
+
+#[cfg(feature = "evm")]
+impl From<PublicValues> for PublicValuesStruct {
+    fn from(values: PublicValues) -> Self {
+        Self {
+            is_excluded: values.is_excluded,
+            timestamp: values.timestamp,
+            excluded_countries: values.excluded_countries,
+            dataset_root: values.dataset_root.into(),
+            range_count: values.range_count,
+            covered_ipv4: values.covered_ipv4,
+            is_public_ip: values.is_public_ip,
+            ranges_digest: values.ranges_digest.into(),
+            nullifier_epoch: values.nullifier_epoch,
+            nullifier_scope: values.nullifier_scope.into(),
+            nullifier: values.nullifier.into(),
+            version: values.version,
+            domain_separator: values.domain_separator.into(),
+            snapshot_source: values.snapshot_source.into(),
+            snapshot_date: values.snapshot_date,
+            snapshot_hash: values.snapshot_hash.into(),
+            policy_id: values.policy_id.into(),
+            app_context: values.app_context.into(),
+            prefix_scope: values.prefix_scope.into(),
+            prefix_commitment: values.prefix_commitment.into(),
+        }
+    }
+}
+
+#[cfg(feature = "evm")]
+/// Reads the plain layout only (see `decode`).
+impl TryFrom<&[u8]> for PublicValues {
+    type Error = ZkipError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        decode(bytes)
+    }
+}
+
+#[cfg(feature = "evm")]
+impl From<PublicValues> for Vec<u8> {
+    fn from(values: PublicValues) -> Self {
+        values.to_abi_bytes()
+    }
+}
+
+/// The SSZ encoding of `values`, as a container of its fields in order: `bool`s as one
+/// byte, integers little-endian, byte arrays as `Vector[byte, 32]` and
+/// `excluded_countries` as `List[uint16, SSZ_MAX_COUNTRIES]`. Panics if it holds more
+/// countries than that.
+pub fn encode_ssz(values: &PublicValues) -> Vec<u8> {
+    assert!(
+        values.excluded_countries.len() <= SSZ_MAX_COUNTRIES,
+        "at most SSZ_MAX_COUNTRIES countries"
+    );
+    let mut bytes = Vec::with_capacity(SSZ_FIXED_LEN + 2 * values.excluded_countries.len());
+    bytes.push(values.is_excluded.into());
+    bytes.extend_from_slice(&values.timestamp.to_le_bytes());
+    bytes.extend_from_slice(&(SSZ_FIXED_LEN as u32).to_le_bytes());
+    bytes.extend_from_slice(&values.dataset_root);
+    bytes.extend_from_slice(&values.range_count.to_le_bytes());
+    bytes.extend_from_slice(&values.covered_ipv4.to_le_bytes());
+    bytes.push(values.is_public_ip.into());
+    bytes.extend_from_slice(&values.ranges_digest);
+    bytes.extend_from_slice(&values.nullifier_epoch.to_le_bytes());
+    bytes.extend_from_slice(&values.nullifier_scope);
+    bytes.extend_from_slice(&values.nullifier);
+    bytes.extend_from_slice(&values.version.to_le_bytes());
+    bytes.extend_from_slice(&values.domain_separator);
+    bytes.extend_from_slice(&values.snapshot_source);
+    bytes.extend_from_slice(&values.snapshot_date.to_le_bytes());
+    bytes.extend_from_slice(&values.snapshot_hash);
+    bytes.extend_from_slice(&values.policy_id);
+    bytes.extend_from_slice(&values.app_context);
+    bytes.extend_from_slice(&values.prefix_scope);
+    bytes.extend_from_slice(&values.prefix_commitment);
+    for code in &values.excluded_countries {
+        bytes.extend_from_slice(&code.to_le_bytes());
+    }
+    bytes
+}
+
+/// Inverse of `encode_ssz`. Fails with `ZkipError::InvalidEncoding` unless `bytes` are
+/// exactly such an encoding, with `bool`s of 0 or 1 and the list right after the fixed
+/// part.
+pub fn decode_ssz(bytes: &[u8]) -> Result<PublicValues, ZkipError> {
+    if bytes.len() < SSZ_FIXED_LEN {
+        return Err(ZkipError::InvalidEncoding(
+            "SSZ shorter than its fixed part",
+        ));
+    }
+    let (fixed, list) = bytes.split_at(SSZ_FIXED_LEN);
+    let mut fields = SszFields(fixed);
+    let is_excluded = fields.bool()?;
+    let timestamp = u64::from_le_bytes(fields.take());
+    if u32::from_le_bytes(fields.take()) as usize != SSZ_FIXED_LEN {
+        return Err(ZkipError::InvalidEncoding(
+            "SSZ list offset must follow the fixed part",
+        ));
+    }
+    let dataset_root = fields.take();
+    let range_count = u32::from_le_bytes(fields.take());
+    let covered_ipv4 = u64::from_le_bytes(fields.take());
+    let is_public_ip = fields.bool()?;
+    let ranges_digest = fields.take();
+    let nullifier_epoch = u32::from_le_bytes(fields.take());
+    let nullifier_scope = fields.take();
+    let nullifier = fields.take();
+    let version = u16::from_le_bytes(fields.take());
+    let domain_separator = fields.take();
+    let snapshot_source = fields.take();
+    let snapshot_date = u64::from_le_bytes(fields.take());
+    let snapshot_hash = fields.take();
+    let policy_id = fields.take();
+    let app_context = fields.take();
+    let prefix_scope = fields.take();
+    let prefix_commitment = fields.take();
+    if list.len() % 2 != 0 || list.len() / 2 > SSZ_MAX_COUNTRIES {
+        return Err(ZkipError::InvalidEncoding(
+            "SSZ country list must hold whole uint16s, at most SSZ_MAX_COUNTRIES",
+        ));
+    }
+    let excluded_countries = list
+        .as_chunks()
+        .0
+        .iter()
+        .map(|&code| u16::from_le_bytes(code))
+        .collect();
+    Ok(PublicValues {
+        is_excluded,
+        timestamp,
+        excluded_countries,
+        dataset_root,
+        range_count,
+        covered_ipv4,
+        is_public_ip,
+        ranges_digest,
+        nullifier_epoch,
+        nullifier_scope,
+        nullifier,
+        version,
+        domain_separator,
+        snapshot_source,
+        snapshot_date,
+        snapshot_hash,
+        policy_id,
+        app_context,
+        prefix_scope,
+        prefix_commitment,
+    })
+}
+
+/// The fixed-size part of an SSZ encoding, read field by field.
+struct SszFields<'a>(&'a [u8]);
+
+impl SszFields<'_> {
+    /// The next `N` bytes. Panics past the end; `decode_ssz` checks the length first.
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let (field, rest) = self.0.split_at(N);
+        self.0 = rest;
+        field.try_into().expect("split at N bytes")
+    }
+
+    fn bool(&mut self) -> Result<bool, ZkipError> {
+        match self.take() {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            _ => Err(ZkipError::InvalidEncoding("SSZ bool must be 0 or 1")),
+        }
+    }
+}
+
+/// The borsh encoding of `values`: its fields in order as `PublicValues` derives it.
+#[cfg(feature = "borsh")]
+pub fn encode_borsh(values: &PublicValues) -> Vec<u8> {
+    borsh::to_vec(values).expect("writing to a Vec doesn't fail")
+}
+
+/// Inverse of `encode_borsh`. Fails with `ZkipError::InvalidEncoding` unless `bytes` are
+/// exactly such an encoding.
+#[cfg(feature = "borsh")]
+pub fn decode_borsh(bytes: &[u8]) -> Result<PublicValues, ZkipError> {
+    borsh::from_slice(bytes).map_err(|_| ZkipError::InvalidEncoding("not borsh public values"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn sample() -> PublicValues {
+        PublicValues {
+            is_excluded: true,
+            timestamp: 0x0102_0304_0506_0708,
+            excluded_countries: vec![643, 408, 364],
+            dataset_root: [0x11; 32],
+            range_count: 7,
+            covered_ipv4: 1 << 32,
+            is_public_ip: true,
+            ranges_digest: [0x22; 32],
+            nullifier_epoch: 19_000,
+            nullifier_scope: [0x33; 32],
+            nullifier: [0x44; 32],
+            version: 6,
+            domain_separator: [0x55; 32],
+            snapshot_source: [0x66; 32],
+            snapshot_date: 1_700_000_000,
+            snapshot_hash: [0x77; 32],
+            policy_id: [0x88; 32],
+            app_context: [0x99; 32],
+            prefix_scope: [0xaa; 32],
+            prefix_commitment: [0xbb; 32],
+        }
+    }
+
+    #[test]
+    fn ssz_round_trips() {
+        let values = sample();
+        assert_eq!(decode_ssz(&encode_ssz(&values)), Ok(values));
+
+        let empty = PublicValues {
+            excluded_countries: vec![],
+            ..sample()
+        };
+        assert_eq!(encode_ssz(&empty).len(), SSZ_FIXED_LEN);
+        assert_eq!(decode_ssz(&encode_ssz(&empty)), Ok(empty));
+    }
+
+    #[test]
+    fn ssz_known_vector() {
+        let bytes = encode_ssz(&sample());
+        assert_eq!(SSZ_FIXED_LEN, 392);
+        assert_eq!(bytes.len(), 392 + 6);
+        assert_eq!(bytes[..13], [1, 8, 7, 6, 5, 4, 3, 2, 1, 0x88, 0x01, 0, 0]);
+        assert_eq!(bytes[13..45], [0x11; 32]);
+        // range_count, then covered_ipv4 = 2^32
+        assert_eq!(bytes[45..57], [7, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(bytes[57], 1);
+        // version sits after the epoch, scope and nullifier
+        assert_eq!(bytes[58 + 32 + 4 + 64..][..2], [6, 0]);
+        assert_eq!(bytes[360..392], [0xbb; 32]);
+        // 643, 408, 364 as little-endian uint16s
+        assert_eq!(bytes[392..], [0x83, 0x02, 0x98, 0x01, 0x6c, 0x01]);
+    }
+
+    #[test]
+    fn ssz_rejects_malformed_input() {
+        let bytes = encode_ssz(&sample());
+        let invalid = |message| Err(ZkipError::InvalidEncoding(message));
+
+        assert_eq!(
+            decode_ssz(&bytes[..SSZ_FIXED_LEN - 1]),
+            invalid("SSZ shorter than its fixed part")
+        );
+
+        let mut bad = bytes.clone();
+        bad[0] = 2;
+        assert_eq!(decode_ssz(&bad), invalid("SSZ bool must be 0 or 1"));
+
+        let mut bad = bytes.clone();
+        bad[9] += 2;
+        assert_eq!(
+            decode_ssz(&bad),
+            invalid("SSZ list offset must follow the fixed part")
+        );
+
+        let countries_error =
+            invalid("SSZ country list must hold whole uint16s, at most SSZ_MAX_COUNTRIES");
+        assert_eq!(decode_ssz(&bytes[..bytes.len() - 1]), countries_error);
+        let mut long = encode_ssz(&PublicValues {
+            excluded_countries: vec![1; SSZ_MAX_COUNTRIES],
+            ..sample()
+        });
+        assert!(decode_ssz(&long).is_ok());
+        long.extend_from_slice(&[1, 0]);
+        assert_eq!(decode_ssz(&long), countries_error);
+    }
+
+    #[test]
+    #[should_panic(expected = "at most SSZ_MAX_COUNTRIES countries")]
+    fn ssz_encode_panics_past_the_list_limit() {
+        encode_ssz(&PublicValues {
+            excluded_countries: vec![1; SSZ_MAX_COUNTRIES + 1],
+            ..sample()
+        });
+    }
+
+    #[cfg(feature = "borsh")]
+    #[test]
+    fn borsh_round_trips() {
+        let values = sample();
+        assert_eq!(decode_borsh(&encode_borsh(&values)), Ok(values));
+
+        let mut trailing = encode_borsh(&sample());
+        trailing.push(0);
+        assert_eq!(
+            decode_borsh(&trailing),
+            Err(ZkipError::InvalidEncoding("not borsh public values"))
+        );
+    }
+
+    #[cfg(feature = "borsh")]
+    #[test]
+    fn borsh_known_vector() {
+        // The same little-endian fields as SSZ, with the country list inline behind a u32
+        // length where SSZ puts its offset
+        let ssz = encode_ssz(&sample());
+        let mut expected = ssz[..9].to_vec();
+        expected.extend_from_slice(&3u32.to_le_bytes());
+        expected.extend_from_slice(&ssz[SSZ_FIXED_LEN..]);
+        expected.extend_from_slice(&ssz[13..SSZ_FIXED_LEN]);
+        assert_eq!(encode_borsh(&sample()), expected);
+    }
+
+    #[cfg(feature = "evm")]
+    #[test]
+    fn abi_round_trips_with_the_domain_separator() {
+        let values = PublicValues {
+            domain_separator: DOMAIN_SEPARATOR,
+            ..sample()
+        };
+        let bytes = values.to_abi_bytes();
+        assert_eq!(decode(&bytes), Ok(values));
+        assert_eq!(
+            decode(&encode(&sample())),
+            Err(ZkipError::UnknownPublicValues)
+        );
+    }
+}
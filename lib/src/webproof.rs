@@ -0,0 +1,91 @@
+//! Notarised "what is my IP" responses, for proofs about the address a server observed.
+//!
+//! An attestation needs the attester's cooperation; a web proof only needs a notary. The
+//! user fetches a plain-text "what is my IP" endpoint, such as `api.ipify.org`, over
+//! TLSNotary or another zkTLS protocol. The notary, or a verifier that checked the
+//! resulting presentation, signs `statement_digest` of the server name and the revealed
+//! response body with a secp256k1 or Ed25519 key. The guest checks that signature, parses
+//! the body as an address and requires it to be the IP it proves, so the IP is the one the
+//! server saw rather than one the prover typed.
+//!
+//! The TLS transcript itself is checked by whoever signs the statement, outside the guest,
+//! so a verifier trusts the notary as it would in TLSNotary. The proof commits as an
+//! attested one (see `attest`): the notary's key as the attester, `server_id` of the server
+//! name as the nonce, and the notarisation time.
+
+use alloc::{string::String, vec::Vec};
+use core::net::IpAddr;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{attest::verify_signature, policy::Address, AttestationValues};
+
+/// Domain tag prefixed to the signed statement.
+const WEB_PROOF_DOMAIN: &[u8] = b"zkip-webproof-v2";
+
+/// Domain tag prefixed to the server name in `server_id`.
+const SERVER_ID_DOMAIN: &[u8] = b"zkip-webproof-server-v1";
+
+/// A notary's signature over one response: read by the guest, committed without the
+/// signature or the response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebProofInput {
+    /// Public key of the notary: SEC1-compressed secp256k1 (33 bytes) or Ed25519 (32).
+    pub notary_pubkey: Vec<u8>,
+    /// Over `statement_digest`, 64 bytes, as for attestations.
+    pub signature: Vec<u8>,
+    /// The TLS server name of the notarised session.
+    pub server_name: String,
+    /// The revealed HTTP response body: the client's address as text.
+    pub response: Vec<u8>,
+    /// When the session was notarised, in Unix seconds.
+    pub notarised_at: u64,
+}
+
+/// SHA-256 over a domain tag and the lowercase server name, committed as the nonce.
+pub fn server_id(server_name: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(SERVER_ID_DOMAIN);
+    hasher.update(server_name.to_ascii_lowercase());
+    hasher.finalize().into()
+}
+
+/// The message a notary signs: SHA-256 over a domain tag, `server_id`, the SHA-256 of the
+/// response body and the big-endian timestamp.
+pub fn statement_digest(server_name: &str, response: &[u8], notarised_at: u64) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(WEB_PROOF_DOMAIN);
+    hasher.update(server_id(server_name));
+    hasher.update(Sha256::digest(response));
+    hasher.update(notarised_at.to_be_bytes());
+    hasher.finalize().into()
+}
+
+/// The address a response body names: an IPv4 or IPv6 address, with surrounding
+/// whitespace, as plain-text endpoints return it.
+pub fn observed_ip(response: &[u8]) -> Option<Address> {
+    let text = core::str::from_utf8(response).ok()?;
+    match text.trim().parse().ok()? {
+        IpAddr::V4(ip) => Some(Address::V4(ip.into())),
+        IpAddr::V6(ip) => Some(Address::V6(ip.into())),
+    }
+}
+
+impl WebProofInput {
+    /// Whether the response names `ip` and the signature is the notary's over it. Keys and
+    /// signatures are checked as for attestations.
+    pub fn verify(&self, ip: Address) -> bool {
+        let digest = statement_digest(&self.server_name, &self.response, self.notarised_at);
+        observed_ip(&self.response) == Some(ip)
+            && verify_signature(&self.notary_pubkey, &self.signature, &digest)
+    }
+
+    /// What a proof commits for this web proof.
+    pub fn values(&self) -> AttestationValues {
+        AttestationValues {
+            pubkey: self.notary_pubkey.clone(),
+            nonce: server_id(&self.server_name),
+            attested_at: self.notarised_at,
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! zkip - aggregation
+//! Combines chunk proofs of an exclusion set too large for one execution into one
+//! exclusion claim, verifying each with SP1's proof composition (`zkip_lib::chunk`).
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use alloy_sol_types::SolType;
+use sha2::{Digest, Sha256};
+use zkip_lib::{
+    all_public,
+    chunk::{aggregate, AggregationInput, ChunkPublicValuesStruct},
+    encode_aggregated_public_values,
+    policy::Address,
+    renew::vkey_bytes,
+    IpV4,
+};
+
+pub fn main() {
+    let AggregationInput {
+        chunk_vkey,
+        ip,
+        timestamp,
+        excluded_countries,
+        chunk_counts,
+        chunks,
+    } = sp1_zkvm::io::read::<AggregationInput>();
+
+    // Like every layout other than the plain one, aggregated proofs need a public IP
+    let is_public_ip = match ip {
+        Address::V4(IpV4(ip)) => all_public(&[ip], &[]),
+        Address::V6(ip) => all_public(&[], &[ip]),
+    };
+    assert!(is_public_ip, "only plain proofs take a reserved address");
+
+    // Aborts the proof unless the host supplied a valid chunk proof committing exactly
+    // each of these public values
+    let chunks: Vec<ChunkPublicValuesStruct> = chunks
+        .iter()
+        .map(|public_values| {
+            let digest: [u8; 32] = Sha256::digest(public_values).into();
+            sp1_zkvm::lib::verify::verify_sp1_proof(&chunk_vkey, &digest);
+            ChunkPublicValuesStruct::abi_decode(public_values)
+                .expect("chunk proofs commit chunk public values")
+        })
+        .collect();
+
+    let aggregate = aggregate(ip, &excluded_countries, &chunk_counts, &chunks);
+    sp1_zkvm::io::commit_slice(&encode_aggregated_public_values(
+        timestamp,
+        excluded_countries,
+        aggregate,
+        vkey_bytes(&chunk_vkey),
+        chunks.len() as u32,
+    ));
+}
@@ -0,0 +1,25 @@
+//! zkip - chunk
+//! Checks the IP against one chunk of one country's ranges, for the aggregation guest to
+//! combine with the others (`zkip_lib::chunk`).
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use alloy_sol_types::SolType;
+use zkip_lib::{
+    chunk::{chunk_values, ChunkInput, ChunkPublicValuesStruct},
+    unpack_ranges, unpack_ranges_v6,
+};
+
+pub fn main() {
+    let input = sp1_zkvm::io::read::<ChunkInput>();
+    let packed = sp1_zkvm::io::read_vec();
+    let (ranges, ranges_v6) = if input.is_v6 {
+        (Vec::new(), unpack_ranges_v6(&packed))
+    } else {
+        (unpack_ranges(&packed), Vec::new())
+    };
+
+    let values = chunk_values(&input, &ranges, &ranges_v6);
+    sp1_zkvm::io::commit_slice(&ChunkPublicValuesStruct::abi_encode(&values));
+}
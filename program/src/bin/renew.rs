@@ -0,0 +1,47 @@
+//! zkip - renewal
+//! Re-commits an earlier zkip proof's public values under a new timestamp, verifying that
+//! proof with SP1's proof composition instead of reading any ranges (`zkip_lib::renew`).
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use sha2::{Digest, Sha256};
+use zkip_lib::{
+    decode_public_values, encode_renewed_public_values,
+    renew::{vkey_bytes, RenewalInput},
+};
+
+pub fn main() {
+    let RenewalInput {
+        vkey,
+        public_values,
+        timestamp,
+    } = sp1_zkvm::io::read::<RenewalInput>();
+
+    // Aborts the proof unless the host supplied a valid proof of `vkey` committing exactly
+    // these public values
+    let digest: [u8; 32] = Sha256::digest(&public_values).into();
+    sp1_zkvm::lib::verify::verify_sp1_proof(&vkey, &digest);
+
+    let previous = decode_public_values(&public_values).expect("earlier proof must be zkip's");
+    assert!(
+        previous.renewal.is_none(),
+        "renewals take the original proof, not a renewal"
+    );
+    assert!(
+        previous.validity.is_none(),
+        "windowed proofs expire; prove again instead"
+    );
+    let previous_timestamp = previous.values.timestamp;
+    assert!(
+        timestamp >= previous_timestamp,
+        "a renewal can't predate the proof it renews"
+    );
+
+    sp1_zkvm::io::commit_slice(&encode_renewed_public_values(
+        timestamp,
+        vkey_bytes(&vkey),
+        previous_timestamp,
+        public_values,
+    ));
+}
@@ -1,32 +1,583 @@
 //! zkip - Zero-knowledge IP location proof
-//! Proves an IP is NOT from specified countries without revealing the IP.
+//! Proves an IP is NOT from specified countries, or with inclusion that it is from one of
+//! them (optionally while in none of a denylist), or that it satisfies a policy
+//! expression, without revealing the IP.
 
 #![no_main]
 sp1_zkvm::entrypoint!(main);
 
-use alloy_sol_types::SolType;
-use zkip_lib::{is_excluded, PublicValuesStruct};
+use zkip_lib::{
+    addresses_sorted, all_excluded, all_included, all_public, block, clear_count,
+    compose::CompanionInput,
+    country_results, disclosed_code, encode_attested_public_values, encode_batch_public_values,
+    encode_block_public_values, encode_challenged_public_values, encode_combined_public_values,
+    encode_composed_public_values, encode_country_results_public_values, encode_gap_public_values,
+    encode_inclusion_public_values, encode_inventory_public_values, encode_policy_public_values,
+    encode_public_values, encode_signed_snapshot_public_values, encode_threshold_public_values,
+    encode_timed_public_values, encode_windowed_public_values,
+    gap::{gap_root, GapInput},
+    groups_sorted, inclusion_set_valid,
+    merkle::{asn_root, country_leaves, dataset_root, inventory_root, leaves_root},
+    non_vacuous,
+    policy::{range_groups, Address},
+    prefix, range_coverage, ranges_digest,
+    renew::vkey_bytes,
+    unpack_ranges, unpack_ranges_v6, IpV4, ValidityWindow, ZkipInput, RESERVED_RANGES,
+    RESERVED_RANGES_V6,
+};
 
 pub fn main() {
-    // Read private inputs
-    let ip = sp1_zkvm::io::read::<u32>();
-    let excluded_ranges = sp1_zkvm::io::read::<Vec<(u32, u32)>>();
+    // Read the inputs in one value, then the shared ranges. Ranges arrive packed
+    // (`zkip_lib::pack_ranges`), far cheaper to read than through serde.
+    let ZkipInput {
+        ips,
+        ips_v6,
+        excluded_countries,
+        range_counts,
+        timestamp,
+        disclosure,
+        anchor,
+        batch,
+        inventory,
+        threshold,
+        include,
+        denylist,
+        policy,
+        per_country,
+        gap,
+        attestation,
+        web_proof,
+        snapshot_signature,
+        time_beacon,
+        companion,
+        nullifier,
+        challenge,
+        valid_for,
+        snapshot,
+        app_context,
+        prefix_salt,
+        block,
+    } = sp1_zkvm::io::read::<ZkipInput>();
+    let packed = sp1_zkvm::io::read_vec();
+    let packed_v6 = sp1_zkvm::io::read_vec();
+    // The checks below compare addresses with the bare `u32` bounds of the ranges
+    let ips: Vec<u32> = ips.into_iter().map(u32::from).collect();
+    let excluded_ranges = unpack_ranges(&packed);
+    let excluded_ranges_v6 = unpack_ranges_v6(&packed_v6);
+    // The window starts at the committed timestamp, so the two can't disagree
+    let window = valid_for.map(|valid_for| {
+        ValidityWindow::starting_at(timestamp, valid_for)
+            .expect("validity window must end within a uint64")
+    });
 
-    // Read public inputs
-    let excluded_countries = sp1_zkvm::io::read::<Vec<u16>>();
-    let timestamp = sp1_zkvm::io::read::<u32>();
+    assert!(batch || !inventory, "inventory proofs are batch proofs");
+    assert!(
+        (batch && !inventory) || threshold.is_none(),
+        "threshold proofs are batch proofs without an inventory"
+    );
+    assert!(
+        include || denylist.is_none(),
+        "a denylist needs included countries"
+    );
+    assert!(
+        policy.is_none() || (!include && denylist.is_none()),
+        "policy proofs take no inclusion or denylist"
+    );
+    // A web proof commits as an attestation by its notary (`zkip_lib::webproof`)
+    assert!(
+        attestation.is_none() || web_proof.is_none(),
+        "a proof takes an attestation or a web proof, not both"
+    );
+    let attested = attestation.is_some() || web_proof.is_some();
+    assert!(
+        !attested
+            || (!include
+                && !batch
+                && !per_country
+                && policy.is_none()
+                && disclosure.is_none()
+                && anchor.is_none()
+                && gap.is_none()),
+        "attested proofs take a single excluded address only"
+    );
+    assert!(
+        snapshot_signature.is_none()
+            || (!include
+                && !batch
+                && !per_country
+                && policy.is_none()
+                && disclosure.is_none()
+                && anchor.is_none()
+                && gap.is_none()
+                && !attested),
+        "signed-snapshot proofs take a single excluded address only"
+    );
+    assert!(
+        time_beacon.is_none()
+            || (!include
+                && !batch
+                && !per_country
+                && policy.is_none()
+                && disclosure.is_none()
+                && anchor.is_none()
+                && gap.is_none()
+                && !attested
+                && snapshot_signature.is_none()),
+        "timed proofs take a single excluded address only"
+    );
+    assert!(
+        (nullifier.is_none() && app_context.is_none() && prefix_salt.is_none())
+            || (!include
+                && !batch
+                && !per_country
+                && policy.is_none()
+                && disclosure.is_none()
+                && anchor.is_none()
+                && gap.is_none()
+                && !attested
+                && snapshot_signature.is_none()
+                && time_beacon.is_none()),
+        "nullifier, app-context and prefix proofs take a single plain exclusion only"
+    );
+    assert!(
+        block.is_none()
+            || (!include
+                && !batch
+                && !per_country
+                && policy.is_none()
+                && disclosure.is_none()
+                && anchor.is_none()
+                && gap.is_none()
+                && !attested
+                && snapshot_signature.is_none()
+                && time_beacon.is_none()
+                && nullifier.is_none()
+                && app_context.is_none()
+                && prefix_salt.is_none()),
+        "block proofs take a single excluded address only"
+    );
 
-    // Check if IP is NOT in any excluded range
-    let is_excluded = is_excluded(ip, excluded_ranges);
+    // Gap proofs check one address against the two ranges around it in each country, from
+    // Merkle paths into the dataset root, instead of reading every range (`zkip_lib::gap`)
+    if let Some(gap) = gap {
+        assert!(
+            !include
+                && !batch
+                && !per_country
+                && policy.is_none()
+                && disclosure.is_none()
+                && anchor.is_none(),
+            "gap proofs take a single excluded address only"
+        );
+        assert!(
+            range_counts.is_empty() && packed.is_empty() && packed_v6.is_empty(),
+            "gap proofs take no shared ranges"
+        );
+        let dataset_root = match (gap, ips.as_slice(), ips_v6.as_slice()) {
+            (GapInput::V4(witnesses), &[ip], []) => gap_root(ip, &excluded_countries, &witnesses),
+            (GapInput::V6(witnesses), [], &[ip]) => gap_root(ip, &excluded_countries, &witnesses),
+            _ => panic!("gap witnesses must match the family of a single address"),
+        }
+        .expect("gap witnesses must show the IP outside every excluded country");
+        // The gap layout has no public-IP flag, so it requires one
+        assert!(
+            all_public(&ips, &ips_v6),
+            "gap proofs take public addresses only"
+        );
+        let bytes = encode_gap_public_values(timestamp, excluded_countries, dataset_root);
+        sp1_zkvm::io::commit_slice(&compose(bytes, window, challenge, companion));
+        return;
+    }
 
-    // Encode the public values of the program.
-    let bytes = PublicValuesStruct::abi_encode(&PublicValuesStruct {
-        is_excluded,
-        timestamp,
-        excluded_countries,
-    });
+    // Without countries or ranges every IP would pass, so such a proof would check nothing.
+    // Policy rules may name only CIDRs or ASNs, and pin their own contents by hash.
+    assert!(
+        policy.is_some() || non_vacuous(&excluded_countries, &excluded_ranges, &excluded_ranges_v6),
+        "proofs need at least one country and one range"
+    );
+
+    // Each country's ranges must be well-formed, sorted and disjoint, so malformed host input
+    // aborts the proof instead of skewing the result or the committed range count
+    assert!(
+        groups_sorted(&excluded_ranges, &excluded_ranges_v6, &range_counts),
+        "excluded ranges must be sorted and disjoint"
+    );
+
+    // Check that no IP is in any excluded range, or for inclusion that every IP is in one
+    let result = if include {
+        all_included(&ips, &excluded_ranges) && all_included(&ips_v6, &excluded_ranges_v6)
+    } else {
+        all_excluded(&ips, &excluded_ranges) && all_excluded(&ips_v6, &excluded_ranges_v6)
+    };
+
+    // Reserved addresses say nothing about location. Plain proofs commit whether the IP is
+    // public; every other layout requires it.
+    let is_public_ip = all_public(&ips, &ips_v6);
+    let plain = policy.is_none()
+        && !per_country
+        && !include
+        && !batch
+        && disclosure.is_none()
+        && anchor.is_none()
+        && !attested
+        && snapshot_signature.is_none()
+        && time_beacon.is_none()
+        && block.is_none();
+    assert!(
+        is_public_ip || plain,
+        "only plain proofs take a reserved address"
+    );
+
+    // Commit to each country's ranges, so verifiers can check they are a trusted dataset's
+    // complete ranges for the claimed countries
+    let country_leaves = country_leaves(
+        &excluded_countries,
+        &excluded_ranges,
+        &excluded_ranges_v6,
+        &range_counts,
+    );
+    let dataset_root = leaves_root(&country_leaves);
+
+    // Encode the public values of the program, with the region and anchor when requested,
+    // the address count for a batch, or the policy, per-country, inclusion or combined layout.
+    let bytes = if let Some((expr, asns, asn_ranges, asn_ranges_v6, asn_counts)) = policy {
+        assert!(
+            !batch && !per_country && disclosure.is_none() && anchor.is_none(),
+            "policy proofs take no batch, per-country results, disclosure or anchor"
+        );
+        assert_eq!(
+            ips.len() + ips_v6.len(),
+            1,
+            "single proofs take exactly one address"
+        );
+        // Every country and ASN the policy names must have its ranges here, and nothing else
+        assert!(
+            excluded_countries == expr.countries() && asns == expr.asns(),
+            "policy ranges must cover exactly the countries and ASNs it names"
+        );
+        let ip = match ips.first() {
+            Some(&ip) => Address::V4(IpV4(ip)),
+            None => Address::V6(ips_v6[0]),
+        };
+        let countries = range_groups(
+            &excluded_countries,
+            &excluded_ranges,
+            &excluded_ranges_v6,
+            &range_counts,
+        );
+        assert!(
+            groups_sorted(&asn_ranges, &asn_ranges_v6, &asn_counts),
+            "ASN ranges must be sorted and disjoint"
+        );
+        let asn_groups = range_groups(&asns, &asn_ranges, &asn_ranges_v6, &asn_counts);
+        let is_allowed = expr.evaluate(ip, &countries, &asn_groups);
+        encode_policy_public_values(
+            is_allowed,
+            timestamp,
+            excluded_countries,
+            expr.hash(),
+            dataset_root,
+            asn_root(&asns, &asn_ranges, &asn_ranges_v6, &asn_counts),
+        )
+    } else if per_country {
+        assert!(
+            !include && !batch && disclosure.is_none() && anchor.is_none(),
+            "per-country proofs take no inclusion, batch, disclosure or anchor"
+        );
+        assert_eq!(
+            ips.len() + ips_v6.len(),
+            1,
+            "single proofs take exactly one address"
+        );
+        let results = country_results(
+            &ips,
+            &ips_v6,
+            &excluded_ranges,
+            &excluded_ranges_v6,
+            &range_counts,
+        );
+        encode_country_results_public_values(timestamp, excluded_countries, results, dataset_root)
+    } else if include {
+        assert!(
+            !batch && disclosure.is_none() && anchor.is_none(),
+            "inclusion proofs take no batch, disclosure or anchor"
+        );
+        assert_eq!(
+            ips.len() + ips_v6.len(),
+            1,
+            "single proofs take exactly one address"
+        );
+        // Only the set is committed, so it must not be padded to look larger than it is
+        assert!(
+            inclusion_set_valid(&excluded_countries, &range_counts),
+            "inclusion countries must be distinct and have ranges"
+        );
+        match denylist {
+            None => {
+                encode_inclusion_public_values(result, timestamp, excluded_countries, dataset_root)
+            }
+            Some((denied_countries, denied_ranges, denied_ranges_v6, denied_counts)) => {
+                assert!(
+                    non_vacuous(&denied_countries, &denied_ranges, &denied_ranges_v6),
+                    "denylists need at least one country and one range"
+                );
+                assert!(
+                    groups_sorted(&denied_ranges, &denied_ranges_v6, &denied_counts),
+                    "denylist ranges must be sorted and disjoint"
+                );
+                let is_excluded =
+                    all_excluded(&ips, &denied_ranges) && all_excluded(&ips_v6, &denied_ranges_v6);
+                let denylist_root = dataset_root(
+                    &denied_countries,
+                    &denied_ranges,
+                    &denied_ranges_v6,
+                    &denied_counts,
+                );
+                encode_combined_public_values(
+                    result,
+                    is_excluded,
+                    timestamp,
+                    excluded_countries,
+                    denied_countries,
+                    dataset_root,
+                    denylist_root,
+                )
+            }
+        }
+    } else if batch {
+        assert!(
+            disclosure.is_none() && anchor.is_none(),
+            "batch proofs take no disclosure or anchor"
+        );
+        let address_count = (ips.len() + ips_v6.len()) as u32;
+        if inventory {
+            // The root only identifies the inventory if the order of its leaves is canonical
+            assert!(
+                addresses_sorted(&ips, &ips_v6),
+                "inventory addresses must be sorted and distinct"
+            );
+            encode_inventory_public_values(
+                result,
+                timestamp,
+                excluded_countries,
+                address_count,
+                dataset_root,
+                inventory_root(&ips, &ips_v6),
+            )
+        } else {
+            let clear_count =
+                clear_count(&ips, &excluded_ranges) + clear_count(&ips_v6, &excluded_ranges_v6);
+            match threshold {
+                Some(threshold) => {
+                    // A threshold of zero always holds, and one above the address count never does
+                    assert!(
+                        0 < threshold && threshold <= address_count,
+                        "thresholds must be between 1 and the address count"
+                    );
+                    encode_threshold_public_values(
+                        clear_count >= threshold,
+                        timestamp,
+                        excluded_countries,
+                        threshold,
+                        address_count,
+                        dataset_root,
+                    )
+                }
+                None => encode_batch_public_values(
+                    result,
+                    timestamp,
+                    excluded_countries,
+                    address_count,
+                    dataset_root,
+                    clear_count,
+                ),
+            }
+        }
+    } else if attested {
+        assert_eq!(
+            ips.len() + ips_v6.len(),
+            1,
+            "single proofs take exactly one address"
+        );
+        let ip = match ips.first() {
+            Some(&ip) => Address::V4(IpV4(ip)),
+            None => Address::V6(ips_v6[0]),
+        };
+        // The proven IP must be the one the attester saw, or the one the notarised response
+        // names
+        let attestation = match (attestation, web_proof) {
+            (Some(attestation), None) => {
+                assert!(
+                    attestation.verify(ip),
+                    "attestation must be a valid signature over the IP"
+                );
+                attestation.values()
+            }
+            (None, Some(web_proof)) => {
+                assert!(
+                    web_proof.verify(ip),
+                    "web proof must be a notarised response naming the IP"
+                );
+                web_proof.values()
+            }
+            _ => unreachable!("a proof takes an attestation or a web proof, not both"),
+        };
+        encode_attested_public_values(
+            result,
+            timestamp,
+            excluded_countries,
+            dataset_root,
+            &attestation,
+        )
+    } else if let Some(snapshot) = snapshot_signature {
+        assert_eq!(
+            ips.len() + ips_v6.len(),
+            1,
+            "single proofs take exactly one address"
+        );
+        // Every country's ranges must be the ones the publisher signed
+        assert!(
+            snapshot.verify(&country_leaves),
+            "snapshot signature must cover every excluded country's ranges"
+        );
+        // Ranges reassigned since an old snapshot would otherwise go unnoticed
+        assert!(
+            snapshot.fresh(timestamp),
+            "snapshot must be signed at most max_age before the timestamp"
+        );
+        encode_signed_snapshot_public_values(
+            result,
+            timestamp,
+            excluded_countries,
+            dataset_root,
+            &snapshot,
+        )
+    } else if let Some(beacon) = time_beacon {
+        assert_eq!(
+            ips.len() + ips_v6.len(),
+            1,
+            "single proofs take exactly one address"
+        );
+        // The committed time must be the one the server signed for the host's nonce
+        let time = beacon
+            .verify()
+            .expect("time beacon must be a valid Roughtime reply");
+        assert_eq!(
+            timestamp, time.timestamp,
+            "timestamp must be the time the server signed"
+        );
+        encode_timed_public_values(
+            result,
+            timestamp,
+            excluded_countries,
+            dataset_root,
+            beacon.pubkey,
+            time.radius,
+        )
+    } else if let Some(block) = block {
+        assert_eq!(
+            ips.len() + ips_v6.len(),
+            1,
+            "single proofs take exactly one address"
+        );
+        let ip = match ips.first() {
+            Some(&ip) => Address::V4(IpV4(ip)),
+            None => Address::V6(ips_v6[0]),
+        };
+        // The whole prefix must be clear, not just the address (`zkip_lib::block`)
+        let is_clear = block::clear(ip, block.prefix_len, &excluded_ranges, &excluded_ranges_v6)
+            .expect("block prefix must fit the address");
+        // The block layout has no public-IP flag, so no address of the prefix may be reserved
+        assert_eq!(
+            block::clear(ip, block.prefix_len, RESERVED_RANGES, RESERVED_RANGES_V6),
+            Some(true),
+            "block proofs take public prefixes only"
+        );
+        encode_block_public_values(
+            is_clear,
+            timestamp,
+            excluded_countries,
+            dataset_root,
+            block.values(ip),
+        )
+    } else {
+        assert_eq!(
+            ips.len() + ips_v6.len(),
+            1,
+            "single proofs take exactly one address"
+        );
+        assert!(
+            ips_v6.is_empty() || disclosure.is_none(),
+            "disclosure takes an IPv4 address"
+        );
+        let address = match ips.first() {
+            Some(&ip) => Address::V4(IpV4(ip)),
+            None => Address::V6(ips_v6[0]),
+        };
+        // Derived from the address the proof is about, so one IP gets one nullifier per
+        // epoch and salt, and one network one prefix commitment per salt
+        let nullifier = nullifier.map(|input| input.values(address));
+        let prefix = prefix_salt.map(|salt| prefix::values(address, &salt));
+        // Hashed as read, with SP1's SHA-256 precompile, so verifiers holding the same
+        // snapshot can recompute it without the Merkle scheme
+        let digest = ranges_digest(&excluded_countries, &range_counts, &packed, &packed_v6);
+        // Derived from the excluded country whose ranges contain the IP, the ranges hashed
+        // into `dataset_root`, so the host cannot name a region of its choosing
+        let disclosed = disclosure.map(|granularity| {
+            let code = disclosed_code(
+                granularity,
+                ips[0],
+                &excluded_countries,
+                &excluded_ranges,
+                &range_counts,
+            )
+            .expect("disclosure needs the IP in one of the countries");
+            (granularity, code)
+        });
+        encode_public_values(
+            result,
+            is_public_ip,
+            timestamp,
+            excluded_countries,
+            disclosed,
+            anchor,
+            dataset_root,
+            range_coverage(&excluded_ranges, &excluded_ranges_v6),
+            digest,
+            nullifier,
+            snapshot,
+            app_context,
+            prefix,
+        )
+    };
 
     // Commit to the public values of the program. The final proof will have a commitment to all the
     // bytes that were committed to.
-    sp1_zkvm::io::commit_slice(&bytes);
+    sp1_zkvm::io::commit_slice(&compose(bytes, window, challenge, companion));
+}
+
+/// Give `public_values` their validity window, bind them to the verifier's challenge, then
+/// join them to the companion proof (`zkip_lib::compose`), for each the host sent.
+fn compose(
+    public_values: Vec<u8>,
+    window: Option<ValidityWindow>,
+    challenge: Option<[u8; 32]>,
+    companion: Option<CompanionInput>,
+) -> Vec<u8> {
+    let public_values = match window {
+        Some(window) => encode_windowed_public_values(public_values, window),
+        None => public_values,
+    };
+    let public_values = match challenge {
+        Some(challenge) => encode_challenged_public_values(public_values, challenge),
+        None => public_values,
+    };
+    let Some(companion) = companion else {
+        return public_values;
+    };
+    // Aborts the proof unless the host supplied a valid proof of `vkey` committing exactly
+    // these public values
+    let digest = companion.public_values_digest();
+    sp1_zkvm::lib::verify::verify_sp1_proof(&companion.vkey, &digest);
+    encode_composed_public_values(public_values, vkey_bytes(&companion.vkey), digest)
 }
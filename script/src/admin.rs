@@ -0,0 +1,137 @@
+//! `zkip admin push-root`: publish snapshot roots to an on-chain registry.
+//!
+//! The registry maps a policy hash (see `policy::policy_hash`) to the range root of that
+//! country set and the SHA-256 of the snapshot it came from. Updates are restricted to
+//! holders of `ROOT_UPDATER_ROLE` (OpenZeppelin `AccessControl`); the sender's role is
+//! checked and the update simulated before anything is sent.
+
+use alloy::network::EthereumWallet;
+use alloy::primitives::{Address, Bytes, FixedBytes, TxHash};
+use alloy::providers::ProviderBuilder;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::sol;
+use anyhow::{bail, Context};
+
+use crate::i18n::Msg;
+use crate::tr;
+
+sol! {
+    #[sol(rpc)]
+    interface IZkipRootRegistry {
+        function ROOT_UPDATER_ROLE() external view returns (bytes32);
+        function hasRole(bytes32 role, address account) external view returns (bool);
+        function roots(bytes32 policyHash)
+            external
+            view
+            returns (bytes32 root, bytes32 snapshotSha256, uint64 updatedAt);
+        function setRoot(bytes32 policyHash, bytes32 root, bytes32 snapshotSha256) external;
+    }
+}
+
+/// What to publish for one country set.
+#[derive(Debug, Clone)]
+pub struct RootUpdate {
+    pub policy_hash: [u8; 32],
+    pub root: [u8; 32],
+    pub snapshot_sha256: [u8; 32],
+}
+
+#[derive(Debug, Clone)]
+pub enum PushOutcome {
+    /// The registry already holds this root.
+    Unchanged,
+    /// `--dry-run`: the update would succeed; nothing was sent.
+    DryRun {
+        from: Address,
+        calldata: Bytes,
+    },
+    Pushed {
+        from: Address,
+        tx: TxHash,
+    },
+}
+
+/// Check the sender's role, simulate `setRoot`, and send it unless `dry_run`.
+pub fn push_root(
+    rpc_url: &str,
+    contract: Address,
+    private_key: &str,
+    update: &RootUpdate,
+    dry_run: bool,
+) -> anyhow::Result<PushOutcome> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .with_context(|| tr!(Msg::RuntimeFailed))?
+        .block_on(push_root_async(
+            rpc_url,
+            contract,
+            private_key,
+            update,
+            dry_run,
+        ))
+}
+
+async fn push_root_async(
+    rpc_url: &str,
+    contract: Address,
+    private_key: &str,
+    update: &RootUpdate,
+    dry_run: bool,
+) -> anyhow::Result<PushOutcome> {
+    let signer: PrivateKeySigner = private_key
+        .trim()
+        .parse()
+        .with_context(|| tr!(Msg::InvalidAdminKey))?;
+    let from = signer.address();
+    let provider = ProviderBuilder::new()
+        .wallet(EthereumWallet::from(signer))
+        .connect_http(rpc_url.parse().with_context(|| tr!(Msg::InvalidRpcUrl))?);
+    let registry = IZkipRootRegistry::new(contract, &provider);
+
+    let policy_hash = FixedBytes::from(update.policy_hash);
+    let root = FixedBytes::from(update.root);
+    let current = registry
+        .roots(policy_hash)
+        .call()
+        .await
+        .with_context(|| tr!(Msg::ReadRootFailed))?;
+    if current.root == root && current.snapshotSha256 == update.snapshot_sha256 {
+        return Ok(PushOutcome::Unchanged);
+    }
+
+    let role = registry
+        .ROOT_UPDATER_ROLE()
+        .call()
+        .await
+        .with_context(|| tr!(Msg::NoUpdaterRole))?;
+    if !registry.hasRole(role, from).call().await? {
+        bail!(tr!(Msg::MissingUpdaterRole, from, contract));
+    }
+
+    let call = registry.setRoot(policy_hash, root, update.snapshot_sha256.into());
+    call.call()
+        .await
+        .with_context(|| tr!(Msg::SetRootWouldRevert))?;
+    if dry_run {
+        return Ok(PushOutcome::DryRun {
+            from,
+            calldata: call.calldata().clone(),
+        });
+    }
+
+    let receipt = call
+        .send()
+        .await
+        .with_context(|| tr!(Msg::SetRootSendFailed))?
+        .get_receipt()
+        .await
+        .with_context(|| tr!(Msg::SetRootReceiptFailed))?;
+    if !receipt.status() {
+        bail!(tr!(Msg::SetRootReverted, receipt.transaction_hash));
+    }
+    Ok(PushOutcome::Pushed {
+        from,
+        tx: receipt.transaction_hash,
+    })
+}
@@ -0,0 +1,97 @@
+//! Block anchors for `--anchor-block`.
+//!
+//! The host fetches the latest block from an Ethereum JSON-RPC endpoint and the guest
+//! commits its number and hash. An on-chain verifier checks
+//! `blockhash(anchor_block_number) == anchor_block_hash`, which only holds for the last
+//! 256 blocks, so the proof is known to be recent without trusting anyone's clock.
+//! `verify --anchor-rpc-url` runs the same check off-chain against an endpoint the verifier
+//! trusts.
+
+use anyhow::{bail, Context};
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::info;
+use zkip_lib::AnchorInput;
+
+use crate::i18n::Msg;
+use crate::tr;
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: Option<RpcBlock>,
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct RpcBlock {
+    number: String,
+    hash: String,
+}
+
+/// Blocks for which `blockhash` still answers, and the default `--max-anchor-age`.
+pub const BLOCKHASH_WINDOW: u64 = 256;
+
+/// Fetch the number and hash of the latest block from `rpc_url`.
+pub fn fetch_anchor(rpc_url: &str) -> anyhow::Result<AnchorInput> {
+    let anchor = fetch_block(rpc_url, "latest")?.with_context(|| tr!(Msg::RpcNoBlock))?;
+    info!(
+        block = anchor.0,
+        "{}",
+        tr!(
+            Msg::AnchorFetched,
+            anchor.0,
+            format!("0x{}", hex::encode(anchor.1))
+        )
+    );
+    Ok(anchor)
+}
+
+/// How many blocks `rpc_url`'s chain has added since `anchor`, or `None` if its block at
+/// that height has another hash (or does not exist yet).
+pub fn anchor_age(rpc_url: &str, (number, hash): AnchorInput) -> anyhow::Result<Option<u64>> {
+    let (latest, _) = fetch_block(rpc_url, "latest")?.with_context(|| tr!(Msg::RpcNoBlock))?;
+    let on_chain = fetch_block(rpc_url, &format!("0x{:x}", number))?;
+    Ok((on_chain == Some((number, hash))).then(|| latest.saturating_sub(number)))
+}
+
+/// Fetch the number and hash of the block at `tag` (a hex height or `latest`), or `None`
+/// if there is none.
+fn fetch_block(rpc_url: &str, tag: &str) -> anyhow::Result<Option<AnchorInput>> {
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "eth_getBlockByNumber",
+        "params": [tag, false],
+    });
+    let body = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()?
+        .post(rpc_url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(request.to_string())
+        .send()
+        .with_context(|| tr!(Msg::RpcUnreachable, rpc_url))?
+        .error_for_status()?
+        .text()?;
+
+    let response: RpcResponse =
+        serde_json::from_str(&body).with_context(|| tr!(Msg::RpcInvalidJson))?;
+    if let Some(error) = response.error {
+        bail!(tr!(Msg::RpcCallFailed, error));
+    }
+    response
+        .result
+        .map(|block| parse_block(&block.number, &block.hash))
+        .transpose()
+}
+
+/// Parse a JSON-RPC quantity and 32-byte hash.
+pub fn parse_block(number: &str, hash: &str) -> anyhow::Result<AnchorInput> {
+    let number = u64::from_str_radix(number.trim_start_matches("0x"), 16)
+        .with_context(|| tr!(Msg::InvalidBlockNumber, number))?;
+    let hash: [u8; 32] = hex::decode(hash.trim_start_matches("0x"))
+        .with_context(|| tr!(Msg::InvalidBlockHash, hash))?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!(tr!(Msg::BlockHashLength)))?;
+    Ok((number, hash))
+}
@@ -0,0 +1,108 @@
+//! Proof artifacts, written to one directory by `--output-dir`.
+//!
+//! `--save-proof` keeps the proof in one file for `zkip verify`. Pipelines that hand the
+//! pieces to different consumers (a contract call, an indexer, an archive) get each one in
+//! its own file instead: the proof, its raw public values, the vkey hash and a metadata
+//! JSON describing the run.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sp1_sdk::SP1ProofWithPublicValues;
+use std::fs;
+use std::path::Path;
+use tracing::info;
+use zkip_lib::country::CountryCode;
+
+use crate::audit::proof_id;
+use crate::i18n::Msg;
+use crate::snapshot::ExcludedRanges;
+use crate::timesource::ProofTime;
+use crate::tr;
+
+/// The proof, as `--save-proof` writes it, for `zkip verify`.
+pub const PROOF_FILE: &str = "proof.bin";
+/// The raw public values the guest committed.
+pub const PUBLIC_VALUES_FILE: &str = "public_values.bin";
+/// The `bytes32` hash of the verifying key, hex, on one line.
+pub const VKEY_FILE: &str = "vkey.txt";
+/// `ArtifactMetadata` as JSON.
+pub const METADATA_FILE: &str = "metadata.json";
+
+/// What `metadata.json` records about a proof and the run that made it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactMetadata {
+    /// Proof system: `core` or `compressed`.
+    pub proof_system: String,
+    pub variant: String,
+    /// `bytes32` hash of the verifying key, as in `vkey.txt`.
+    pub vkey: String,
+    /// SHA-256 of the serialized proof, hex, as the audit log records it.
+    pub proof_id: String,
+    /// `zkip_lib::ranges_digest` of the ranges the guest read, hex: the public part of its
+    /// input. The address is left out, since a hash of it could be brute-forced.
+    pub inputs_hash: String,
+    /// ISO 3166-1 numeric codes of the excluded countries, in the committed order.
+    pub excluded_countries: Vec<u16>,
+    /// The same countries by alpha-2 code.
+    pub countries: Vec<String>,
+    /// Timestamp committed in the public values.
+    pub timestamp: u64,
+    /// Where the timestamp came from, as the audit log records it.
+    pub time_source: String,
+}
+
+impl ArtifactMetadata {
+    pub fn new(
+        proof_system: &str,
+        variant: &str,
+        vkey: &str,
+        proof: &SP1ProofWithPublicValues,
+        excluded: &ExcludedRanges,
+        excluded_countries: &[u16],
+        proof_time: &ProofTime,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            proof_system: proof_system.to_string(),
+            variant: variant.to_string(),
+            vkey: vkey.to_string(),
+            proof_id: proof_id(proof)?,
+            inputs_hash: hex::encode(excluded.digest(excluded_countries)),
+            excluded_countries: excluded_countries.to_vec(),
+            countries: excluded_countries
+                .iter()
+                .map(|&code| match CountryCode::from_numeric(code) {
+                    Some(country) => country.alpha2().to_string(),
+                    None => code.to_string(),
+                })
+                .collect(),
+            timestamp: proof_time.timestamp,
+            time_source: proof_time.source.clone(),
+        })
+    }
+}
+
+/// Write `proof`, its public values, its vkey hash and `metadata` into `dir`, creating it
+/// if needed. Files already there are overwritten.
+pub fn write_artifacts(
+    dir: &Path,
+    proof: &SP1ProofWithPublicValues,
+    metadata: &ArtifactMetadata,
+) -> anyhow::Result<()> {
+    fs::create_dir_all(dir).with_context(|| tr!(Msg::CreateFailed, dir.display()))?;
+    let path = dir.join(PROOF_FILE);
+    proof
+        .save(&path)
+        .with_context(|| tr!(Msg::WriteFailed, path.display()))?;
+    let path = dir.join(PUBLIC_VALUES_FILE);
+    fs::write(&path, proof.public_values.as_slice())
+        .with_context(|| tr!(Msg::WriteFailed, path.display()))?;
+    let path = dir.join(VKEY_FILE);
+    fs::write(&path, format!("{}\n", metadata.vkey))
+        .with_context(|| tr!(Msg::WriteFailed, path.display()))?;
+    let path = dir.join(METADATA_FILE);
+    let json = serde_json::to_string_pretty(metadata)
+        .with_context(|| tr!(Msg::SerializeMetadataFailed))?;
+    fs::write(&path, json + "\n").with_context(|| tr!(Msg::WriteFailed, path.display()))?;
+    info!("{}", tr!(Msg::ArtifactsWritten, dir.display()));
+    Ok(())
+}
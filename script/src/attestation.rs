@@ -0,0 +1,114 @@
+//! Attestation files for `--attestation`, and web proofs for `--web-proof`.
+//!
+//! An attester, such as a website the user connected to, hands out its signature over the
+//! IP it saw (see `zkip_lib::attest`) as JSON:
+//!
+//! ```json
+//! {
+//!   "pubkey": "02a1...",
+//!   "signature": "3f0c...",
+//!   "nonce": "9b1e...",
+//!   "attested_at": 1718000000
+//! }
+//! ```
+//!
+//! `pubkey` is either a 33-byte SEC1-compressed secp256k1 key, with `signature` the 64-byte
+//! `r || s` ECDSA signature, or a 32-byte Ed25519 key, with `signature` the 64-byte Ed25519
+//! signature. `nonce` is 32 bytes. All are hex with an optional `0x` prefix.
+//!
+//! A notary, or a verifier that checked a TLSNotary presentation, hands out its signature
+//! over a "what is my IP" response (see `zkip_lib::webproof`) the same way:
+//!
+//! ```json
+//! {
+//!   "notary": "02a1...",
+//!   "signature": "3f0c...",
+//!   "server_name": "api.ipify.org",
+//!   "response": "203.0.113.7\n",
+//!   "notarised_at": 1718000000
+//! }
+//! ```
+//!
+//! `notary` and `signature` are as for attestations, and `response` is the revealed body.
+
+use anyhow::Context;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use zkip_lib::{attest::AttestationInput, webproof::WebProofInput};
+
+use crate::i18n::Msg;
+use crate::tr;
+
+#[derive(Debug, Deserialize)]
+struct AttestationFile {
+    pubkey: String,
+    signature: String,
+    nonce: String,
+    attested_at: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebProofFile {
+    notary: String,
+    signature: String,
+    server_name: String,
+    response: String,
+    notarised_at: u64,
+}
+
+fn decode_hex(value: &str, field: &str) -> anyhow::Result<Vec<u8>> {
+    hex::decode(value.trim().trim_start_matches("0x"))
+        .with_context(|| tr!(Msg::AttestationNotHex, field))
+}
+
+/// Read the attestation at `path`, checking field sizes but not the signature.
+pub fn load_attestation(path: &Path) -> anyhow::Result<AttestationInput> {
+    let content = fs::read_to_string(path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+    let file: AttestationFile =
+        serde_json::from_str(&content).with_context(|| tr!(Msg::ParseFailed, path.display()))?;
+    let pubkey = parse_attester(&file.pubkey)?;
+    let signature = decode_hex(&file.signature, "signature")?;
+    if signature.len() != 64 {
+        anyhow::bail!(tr!(Msg::AttestationSignatureLength));
+    }
+    let nonce = decode_hex(&file.nonce, "nonce")?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!(tr!(Msg::AttestationNonceLength)))?;
+    Ok(AttestationInput {
+        pubkey,
+        signature,
+        nonce,
+        attested_at: file.attested_at,
+    })
+}
+
+/// Parse a hex SEC1-compressed secp256k1 or Ed25519 public key, as attested proofs commit
+/// it.
+pub fn parse_attester(hex_key: &str) -> anyhow::Result<Vec<u8>> {
+    let key = decode_hex(hex_key, "public key")?;
+    let secp256k1 = key.len() == 33 && matches!(key[0], 0x02 | 0x03);
+    if !secp256k1 && key.len() != 32 {
+        anyhow::bail!(tr!(Msg::AttesterKeyLength));
+    }
+    Ok(key)
+}
+
+/// Read the web proof at `path`, checking field sizes but not the signature or response.
+pub fn load_web_proof(path: &Path) -> anyhow::Result<WebProofInput> {
+    let content = fs::read_to_string(path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+    let file: WebProofFile =
+        serde_json::from_str(&content).with_context(|| tr!(Msg::ParseFailed, path.display()))?;
+    let notary_pubkey = parse_attester(&file.notary)?;
+    let signature = decode_hex(&file.signature, "signature")?;
+    if signature.len() != 64 {
+        anyhow::bail!(tr!(Msg::WebProofSignatureLength));
+    }
+    Ok(WebProofInput {
+        notary_pubkey,
+        signature,
+        server_name: file.server_name,
+        response: file.response.into_bytes(),
+        notarised_at: file.notarised_at,
+    })
+}
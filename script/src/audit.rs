@@ -0,0 +1,210 @@
+//! Append-only, hash-chained audit log of generated proofs.
+//!
+//! One JSON object per line. Each entry carries the hash of the previous one, and its own
+//! hash is SHA-256 over `zkip-audit-v1`, the previous hash and the JSON of every other
+//! field. Editing, dropping or reordering an entry breaks the chain from that point on,
+//! which `zkip audit verify` reports.
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sp1_sdk::SP1ProofWithPublicValues;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::info;
+
+use crate::i18n::Msg;
+use crate::policy::policy_hash;
+use crate::timesource::ProofTime;
+use crate::tr;
+
+/// Domain tag prefixed to every entry before hashing.
+const AUDIT_HASH_DOMAIN: &[u8] = b"zkip-audit-v1";
+
+/// `prev_hash` of the first entry.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+pub fn default_audit_log_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../data/audit.jsonl")
+}
+
+/// SHA-256 of the serialized proof, hex.
+pub fn proof_id(proof: &SP1ProofWithPublicValues) -> anyhow::Result<String> {
+    let bytes = serde_json::to_vec(proof).with_context(|| tr!(Msg::SerializeProofFailed))?;
+    Ok(hex::encode(Sha256::digest(bytes)))
+}
+
+/// What a proof attests to, as recorded in the log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofRecord {
+    /// Proof system: `core`, `plonk` or `groth16`.
+    pub system: String,
+    pub variant: String,
+    pub vkey: String,
+    /// `policy_hash` of the excluded countries, hex.
+    pub policy_hash: String,
+    /// SHA-256 of the serialized proof, hex.
+    pub proof_id: String,
+    /// Timestamp committed in the public values.
+    pub proof_timestamp: u64,
+    /// Where the timestamp came from: `system` or `ntp:<servers>`. Absent in entries
+    /// written before it was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_source: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub seq: u64,
+    /// Unix time the entry was written.
+    pub recorded_at: u64,
+    #[serde(flatten)]
+    pub record: ProofRecord,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+/// The hashed part of an entry: everything except `hash`.
+#[derive(Serialize)]
+struct HashedFields<'a> {
+    seq: u64,
+    recorded_at: u64,
+    #[serde(flatten)]
+    record: &'a ProofRecord,
+}
+
+fn entry_hash(
+    prev_hash: &str,
+    seq: u64,
+    recorded_at: u64,
+    record: &ProofRecord,
+) -> anyhow::Result<String> {
+    let fields = HashedFields {
+        seq,
+        recorded_at,
+        record,
+    };
+    let mut hasher = Sha256::new();
+    hasher.update(AUDIT_HASH_DOMAIN);
+    hasher.update(prev_hash.as_bytes());
+    hasher.update(serde_json::to_vec(&fields).with_context(|| tr!(Msg::SerializeAuditFailed))?);
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn read_entries(path: &Path) -> anyhow::Result<Vec<(usize, String)>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+    Ok(content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| (i + 1, line.to_string()))
+        .collect())
+}
+
+/// Append `record` to the log at `path`, linking it to the last entry.
+pub fn append(path: &Path, record: ProofRecord, recorded_at: u64) -> anyhow::Result<AuditEntry> {
+    let last = match read_entries(path)?.last() {
+        Some((line, json)) => Some(
+            serde_json::from_str::<AuditEntry>(json)
+                .with_context(|| tr!(Msg::AuditParseFailed, line))?,
+        ),
+        None => None,
+    };
+    let (seq, prev_hash) = match last {
+        Some(entry) => (entry.seq + 1, entry.hash),
+        None => (0, GENESIS_HASH.to_string()),
+    };
+
+    let hash = entry_hash(&prev_hash, seq, recorded_at, &record)?;
+    let entry = AuditEntry {
+        seq,
+        recorded_at,
+        record,
+        prev_hash,
+        hash,
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| tr!(Msg::CreateFailed, parent.display()))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| tr!(Msg::OpenFailed, path.display()))?;
+    let line = serde_json::to_string(&entry).with_context(|| tr!(Msg::SerializeAuditFailed))?;
+    writeln!(file, "{}", line).with_context(|| tr!(Msg::WriteFailed, path.display()))?;
+    Ok(entry)
+}
+
+/// Append an entry for a freshly generated `proof`.
+pub fn record_proof(
+    path: &Path,
+    system: &str,
+    variant: &str,
+    vkey: &str,
+    excluded_countries: &[u16],
+    proof: &SP1ProofWithPublicValues,
+    proof_time: &ProofTime,
+) -> anyhow::Result<()> {
+    let record = ProofRecord {
+        system: system.to_string(),
+        variant: variant.to_string(),
+        vkey: vkey.to_string(),
+        policy_hash: hex::encode(policy_hash(excluded_countries)),
+        proof_id: proof_id(proof)?,
+        proof_timestamp: proof_time.timestamp,
+        time_source: Some(proof_time.source.clone()),
+    };
+    let recorded_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .with_context(|| tr!(Msg::ClockBeforeEpoch))?
+        .as_secs();
+    let entry = append(path, record, recorded_at)?;
+    info!("{}", tr!(Msg::AuditRecorded, entry.seq, path.display()));
+    Ok(())
+}
+
+/// Summary of an intact chain.
+#[derive(Debug, Clone)]
+pub struct ChainSummary {
+    pub entries: u64,
+    /// Hash of the last entry, or `GENESIS_HASH` for an empty log.
+    pub head: String,
+}
+
+/// Check every link of the log at `path`, failing at the first broken entry.
+pub fn verify_chain(path: &Path) -> anyhow::Result<ChainSummary> {
+    let mut prev_hash = GENESIS_HASH.to_string();
+    let mut expected_seq = 0;
+    for (line, json) in read_entries(path)? {
+        let entry: AuditEntry =
+            serde_json::from_str(&json).with_context(|| tr!(Msg::AuditEntryInvalid, line))?;
+        if entry.seq != expected_seq {
+            bail!(tr!(Msg::AuditSeqMismatch, line, expected_seq, entry.seq));
+        }
+        if entry.prev_hash != prev_hash {
+            bail!(tr!(Msg::AuditPrevHashMismatch, line));
+        }
+        let hash = entry_hash(
+            &entry.prev_hash,
+            entry.seq,
+            entry.recorded_at,
+            &entry.record,
+        )?;
+        if entry.hash != hash {
+            bail!(tr!(Msg::AuditHashMismatch, line));
+        }
+        prev_hash = entry.hash;
+        expected_seq += 1;
+    }
+    Ok(ChainSummary {
+        entries: expected_seq,
+        head: prev_hash,
+    })
+}
@@ -10,276 +10,421 @@
 //! RUST_LOG=info cargo run --release --bin evm -- --system plonk
 //! ```
 
-use alloy_sol_types::SolType;
-use anyhow::{bail, Context};
-use clap::{Parser, ValueEnum};
+use anyhow::Context;
+use clap::Parser;
 use serde::{Deserialize, Serialize};
-use sp1_sdk::{
-    include_elf, HashableKey, ProverClient, SP1ProofWithPublicValues, SP1Stdin, SP1VerifyingKey,
-};
-use std::collections::HashMap;
-use std::fs::{self, File};
-use std::io::{BufRead, BufReader, Write};
+use sp1_sdk::{HashableKey, ProverClient, SP1ProofWithPublicValues, SP1VerifyingKey};
 use std::path::PathBuf;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use zkip_lib::{ip_to_u32, PublicValuesStruct};
-
-/// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
-pub const ZKIP_ELF: &[u8] = include_elf!("zkip-program");
-
-const GEOIP_URL: &str = "https://cdn.jsdelivr.net/npm/@ip-location-db/geo-whois-asn-country/geo-whois-asn-country-ipv4-num.csv";
-const CACHE_MAX_AGE_DAYS: u32 = 30;
+use std::time::Instant;
+use tracing::{info, info_span};
+use zkip_lib::{decode_public_values, parse_ip, DecodedPublicValues, PublicValuesStruct};
+use zkip_script::anchor::fetch_anchor;
+use zkip_script::audit::{default_audit_log_path, proof_id, record_proof};
+use zkip_script::config::default_config_path;
+use zkip_script::countries::parse_excluded_countries;
+use zkip_script::disclosure::{resolve_disclosure, Disclosure};
+use zkip_script::e2e::ProofSystem;
+use zkip_script::error::{exit_with_error, ZkipCliError};
+use zkip_script::geoip::{
+    ensure_geoip_database, excluded_ranges, get_v6_cache_path, snapshot_metadata,
+};
+use zkip_script::history::{default_history_path, record_run, RunCost, RunLabel};
+use zkip_script::i18n::{resolve_lang, set_lang, Lang, Msg};
+use zkip_script::inputs::{build_stdin, split_addresses, SharedInputs};
+use zkip_script::lock::{default_lock_path, enforce_lock, ZkipLock};
+use zkip_script::logging::{init_logging, new_run_id, LogFormat};
+use zkip_script::store::{configured_store, ArtifactStore};
+use zkip_script::telemetry::{self, UsageEvent};
+use zkip_script::timesource::{ntp_servers, proof_time, TimeSource};
+use zkip_script::tr;
+use zkip_script::variants::ProgramVariant;
 
 /// The arguments for the EVM command.
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct EVMArgs {
-    /// IP address to test (e.g., "8.8.8.8")
+    /// IP address to test: IPv4 as "8.8.8.8", "134744072" or "0x08080808", or IPv6
     #[arg(long, default_value = "8.8.8.8")]
     ip: String,
 
-    /// Comma-separated country codes to exclude (e.g., "FR,US,DE")
+    /// Comma-separated country codes or names to exclude (e.g., "FR,US,Germany")
     #[arg(long, default_value = "FR")]
     exclude: String,
 
     #[arg(long, value_enum, default_value = "groth16")]
     system: ProofSystem,
 
+    /// Guest program variant
+    #[arg(long, value_enum, default_value = "linear")]
+    variant: ProgramVariant,
+
     /// Force refresh the GeoIP database
     #[arg(long)]
     refresh: bool,
-}
 
-/// Enum representing the available proof systems
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
-enum ProofSystem {
-    Plonk,
-    Groth16,
-}
+    /// Also commit the IP's UN M49 region or sub-region (the country stays hidden)
+    #[arg(long, value_enum)]
+    disclose: Option<Disclosure>,
 
-/// A fixture that can be used to test the verification of SP1 zkVM proofs inside Solidity.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct SP1ZkipProofFixture {
-    is_excluded: bool,
-    timestamp: u32,
-    excluded_countries: Vec<u16>,
-    vkey: String,
-    public_values: String,
-    proof: String,
-}
+    /// Rewrite zkip.lock with the current inputs instead of verifying against it
+    #[arg(long)]
+    update_lock: bool,
 
-fn get_cache_path() -> PathBuf {
-    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../data/ipv4-country.csv")
-}
+    /// Where the committed timestamp comes from
+    #[arg(long, value_enum, default_value = "system")]
+    time_source: TimeSource,
 
-fn is_cache_stale(path: &PathBuf) -> bool {
-    let Ok(metadata) = fs::metadata(path) else {
-        return true;
-    };
-    let Ok(modified) = metadata.modified() else {
-        return true;
-    };
-    let Ok(age) = SystemTime::now().duration_since(modified) else {
-        return true;
-    };
-    age > Duration::from_secs((CACHE_MAX_AGE_DAYS * 24 * 60 * 60) as u64)
-}
+    /// NTP server to cross-check the clock against (repeatable; with --time-source ntp)
+    #[arg(long = "ntp-server", value_delimiter = ',')]
+    ntp_servers: Vec<String>,
 
-fn fetch_geoip_database(path: &PathBuf) -> anyhow::Result<()> {
-    println!("Fetching GeoIP database from {}...", GEOIP_URL);
+    /// Largest tolerated clock skew, in seconds (with --time-source ntp)
+    #[arg(long, default_value_t = 2.0)]
+    max_clock_skew: f64,
 
-    let response = reqwest::blocking::get(GEOIP_URL)
-        .context("Failed to fetch GeoIP database")?;
-
-    if !response.status().is_success() {
-        bail!("HTTP error: {}", response.status());
-    }
+    /// Commit the latest block of --rpc-url as a freshness anchor
+    #[arg(long, requires = "rpc_url")]
+    anchor_block: bool,
 
-    let content = response.text().context("Failed to read response")?;
+    /// Ethereum JSON-RPC endpoint used by --anchor-block
+    #[arg(long, env = "ZKIP_RPC_URL")]
+    rpc_url: Option<String>,
 
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).context("Failed to create data directory")?;
-    }
+    /// Append-only audit log of generated proofs
+    #[arg(long, env = "ZKIP_AUDIT_LOG")]
+    audit_log: Option<PathBuf>,
 
-    let mut file = File::create(path).context("Failed to create cache file")?;
-    file.write_all(content.as_bytes()).context("Failed to write cache file")?;
+    /// Per-run cost history read by `zkip stats`
+    #[arg(long, env = "ZKIP_HISTORY")]
+    history: Option<PathBuf>,
 
-    println!("GeoIP database cached to {:?}", path);
-    Ok(())
-}
+    /// Operator settings, including the artifact store fixtures are copied to
+    #[arg(long, env = "ZKIP_CONFIG")]
+    config: Option<PathBuf>,
 
-fn ensure_geoip_database(refresh: bool) -> anyhow::Result<PathBuf> {
-    let path = get_cache_path();
-
-    if refresh || !path.exists() || is_cache_stale(&path) {
-        let reason = if refresh {
-            "refresh requested"
-        } else if !path.exists() {
-            "cache not found"
-        } else {
-            "cache older than 30 days"
-        };
-        println!("Updating GeoIP database ({})...", reason);
-
-        if let Err(e) = fetch_geoip_database(&path) {
-            if path.exists() {
-                eprintln!("Warning: Failed to fetch GeoIP database: {}. Using cached version.", e);
-            } else {
-                return Err(e);
-            }
-        }
-    }
+    /// Emit failures as a JSON envelope (code, kind, message, hint) on stderr
+    #[arg(long)]
+    json: bool,
 
-    Ok(path)
-}
+    /// Log line format
+    #[arg(long, value_enum, default_value = "text")]
+    log_format: LogFormat,
 
-/// Load country codes from CSV file.
-fn load_country_codes() -> anyhow::Result<HashMap<String, u16>> {
-    let csv_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../data/countries.csv");
-    let file = File::open(csv_path).context("Failed to open countries.csv")?;
-    let reader = BufReader::new(file);
+    /// Log filter (e.g. "debug", "zkip=trace"); defaults to RUST_LOG, then "info"
+    #[arg(long)]
+    log_level: Option<String>,
 
-    let mut codes = HashMap::new();
-    for (i, line) in reader.lines().enumerate() {
-        if i == 0 {
-            continue;
-        }
-        let line = line.context("Failed to read line")?;
-        let fields: Vec<&str> = line.split(',').collect();
-        if fields.len() >= 4 {
-            let alpha2 = fields[1].to_uppercase();
-            if let Ok(numeric) = fields[3].parse::<u16>() {
-                codes.insert(alpha2, numeric);
-            }
-        }
-    }
-    Ok(codes)
+    /// Output language; defaults to the system locale, then English
+    #[arg(long, value_enum, env = "ZKIP_LANG")]
+    lang: Option<Lang>,
 }
 
-/// Parse comma-separated country codes and resolve to numeric codes.
-fn parse_excluded_countries(exclude_arg: &str) -> anyhow::Result<(Vec<String>, Vec<u16>)> {
-    let country_codes = load_country_codes()?;
-    let mut alpha2_codes = Vec::new();
-    let mut numeric_codes = Vec::new();
-
-    for code in exclude_arg.split(',') {
-        let code = code.trim().to_uppercase();
-        if code.is_empty() {
-            continue;
-        }
-        match country_codes.get(&code) {
-            Some(&numeric) => {
-                alpha2_codes.push(code);
-                numeric_codes.push(numeric);
-            }
-            None => bail!("Unknown country code: {}", code),
-        }
-    }
-
-    if numeric_codes.is_empty() {
-        bail!("No valid country codes provided");
-    }
-
-    Ok((alpha2_codes, numeric_codes))
+/// A fixture that can be used to test the verification of SP1 zkVM proofs inside Solidity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SP1ZkipProofFixture {
+    is_excluded: bool,
+    timestamp: u64,
+    excluded_countries: Vec<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disclosure_granularity: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disclosed_region: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    anchor_block_number: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    anchor_block_hash: Option<String>,
+    /// Absent for legacy public values, which predate the dataset root.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dataset_root: Option<String>,
+    /// Only present for the plain layout, which commits the ranges checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    range_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    covered_ipv4: Option<u64>,
+    /// Only present for the plain layout; verifiers should reject `false`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_public_ip: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ranges_digest: Option<String>,
+    /// Only present for the plain layout; all zero unless the prover made a nullifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nullifier_epoch: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nullifier_scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nullifier: Option<String>,
+    /// Only present for the plain layout, from its version onwards.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public_values_version: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    domain_separator: Option<String>,
+    /// Only present for the plain layout from version 2.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snapshot_source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snapshot_date: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snapshot_hash: Option<String>,
+    /// Only present for the plain layout from version 3, and the app context from version 4.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    policy_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    app_context: Option<String>,
+    /// Only present for the plain layout from version 5.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prefix_scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prefix_commitment: Option<String>,
+    vkey: String,
+    public_values: String,
+    proof: String,
 }
 
-/// Load IPv4 ranges for specified countries from the GeoIP database.
-fn load_ip_ranges_for_countries(path: &PathBuf, country_codes: &[String]) -> anyhow::Result<Vec<(u32, u32)>> {
-    let file = File::open(path).context("Failed to open GeoIP database")?;
-    let reader = BufReader::new(file);
-
-    let mut ranges = Vec::new();
-    for line in reader.lines() {
-        let line = line.context("Failed to read line")?;
-        let fields: Vec<&str> = line.split(',').collect();
-        if fields.len() >= 3 {
-            let country = fields[2].to_uppercase();
-            if country_codes.contains(&country) {
-                let start: u32 = fields[0].parse().context("Invalid start IP")?;
-                let end: u32 = fields[1].parse().context("Invalid end IP")?;
-                ranges.push((start, end));
-            }
-        }
+fn main() {
+    let args = EVMArgs::parse();
+    let json = args.json;
+    set_lang(resolve_lang(args.lang));
+    if let Err(err) = init_logging(args.log_format, args.log_level.as_deref()) {
+        exit_with_error(err.context(ZkipCliError::InvalidArguments), json);
     }
 
-    Ok(ranges)
+    let _run = info_span!("run", run_id = %new_run_id()).entered();
+    let mut usage = UsageEvent::new(args.system.id());
+    let result = run(args);
+    usage.success = result.is_ok();
+    telemetry::report(&usage);
+    if let Err(err) = result {
+        exit_with_error(err, json);
+    }
 }
 
-fn main() -> anyhow::Result<()> {
-    sp1_sdk::utils::setup_logger();
-
-    let args = EVMArgs::parse();
+fn run(args: EVMArgs) -> anyhow::Result<()> {
+    let config = args.config.clone().unwrap_or_else(default_config_path);
+    let store = configured_store(&config).context(ZkipCliError::ArtifactStoreFailed)?;
 
     // Ensure GeoIP database is available and fresh
-    let geoip_path = ensure_geoip_database(args.refresh)?;
+    let geoip_path =
+        ensure_geoip_database(args.refresh).context(ZkipCliError::DatabaseUnavailable)?;
 
     let client = ProverClient::from_env();
-    let (pk, vk) = client.setup(ZKIP_ELF);
-
-    let ip = ip_to_u32(&args.ip).context("failed to parse IP address")?;
-    let (alpha2_codes, excluded_countries) = parse_excluded_countries(&args.exclude)?;
-
-    let excluded_ranges = load_ip_ranges_for_countries(&geoip_path, &alpha2_codes)?;
-    println!("Loaded {} IP ranges for {:?}", excluded_ranges.len(), alpha2_codes);
-
-    let timestamp: u32 = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .context("System clock is before Unix epoch")?
-        .as_secs() as u32;
-
-    let mut stdin = SP1Stdin::new();
-    stdin.write(&ip);
-    stdin.write(&excluded_ranges);
-    stdin.write(&excluded_countries);
-    stdin.write(&timestamp);
+    let (pk, vk) = client.setup(args.variant.elf());
+
+    let current = ZkipLock::current(&geoip_path, args.variant.id(), &vk.bytes32())?;
+    enforce_lock(&default_lock_path(), &current, args.update_lock)?;
+
+    let addr = parse_ip(&args.ip).context(ZkipCliError::InvalidIp)?;
+    let (ips, ips_v6) = split_addresses(&[addr]);
+    let (alpha2_codes, excluded_countries) =
+        parse_excluded_countries(&args.exclude).context(ZkipCliError::InvalidCountry)?;
+
+    let excluded = excluded_ranges(&geoip_path, &alpha2_codes, args.refresh)?;
+    let snapshot = snapshot_metadata(&geoip_path, &get_v6_cache_path())
+        .context(ZkipCliError::DatabaseUnavailable)?;
+
+    let servers = ntp_servers(&args.ntp_servers);
+    let proof_time = proof_time(args.time_source, &servers, args.max_clock_skew)
+        .context(ZkipCliError::ClockUntrusted)?;
+    let timestamp = proof_time.timestamp;
+
+    let disclosure = match (args.disclose, ips.first()) {
+        (Some(level), Some(&ip)) => Some(
+            resolve_disclosure(ip, level, &excluded_countries, &excluded)
+                .context(ZkipCliError::InvalidIp)?,
+        ),
+        (Some(_), None) => {
+            return Err(
+                anyhow::anyhow!(tr!(Msg::DiscloseIpv4Only)).context(ZkipCliError::InvalidIp)
+            );
+        }
+        (None, _) => None,
+    };
 
-    println!("IP: {} ({})", args.ip, ip);
-    println!("Excluded countries: {:?}", excluded_countries);
-    println!("Proof System: {:?}", args.system);
+    let anchor = match (args.anchor_block, &args.rpc_url) {
+        (true, Some(rpc_url)) => {
+            Some(fetch_anchor(rpc_url).context(ZkipCliError::AnchorUnavailable)?)
+        }
+        _ => None,
+    };
 
+    let stdin = build_stdin(&SharedInputs {
+        ips: &ips,
+        excluded_ranges: &excluded.ranges,
+        excluded_countries: &excluded_countries,
+        timestamp,
+        disclosure,
+        anchor,
+        batch: false,
+        inventory: false,
+        threshold: None,
+        ips_v6: &ips_v6,
+        excluded_ranges_v6: &excluded.ranges_v6,
+        range_counts: &excluded.counts,
+        include: false,
+        denylist: None,
+        policy: None,
+        per_country: false,
+        gap: None,
+        attestation: None,
+        web_proof: None,
+        snapshot_signature: None,
+        time_beacon: None,
+        companion: None,
+        nullifier: None,
+        challenge: None,
+        valid_for: None,
+        snapshot: Some(snapshot),
+        app_context: None,
+        prefix_salt: None,
+        block: None,
+    });
+
+    info!("{}", tr!(Msg::EvmIp, args.ip, addr));
+    info!(
+        "{}",
+        tr!(
+            Msg::EvmExcludedCountries,
+            format!("{:?}", excluded_countries)
+        )
+    );
+    info!("{}", tr!(Msg::EvmProofSystem, format!("{:?}", args.system)));
+
+    let _phase = info_span!("phase", phase = "prove", system = ?args.system).entered();
+    let started = Instant::now();
     let proof = match args.system {
         ProofSystem::Plonk => client.prove(&pk, &stdin).plonk().run(),
         ProofSystem::Groth16 => client.prove(&pk, &stdin).groth16().run(),
     }
-    .context("failed to generate proof")?;
+    .context(ZkipCliError::ProvingFailed)?;
+    let wall = started.elapsed();
+
+    let system = args.system.id();
+    let audit_log = args.audit_log.unwrap_or_else(default_audit_log_path);
+    record_proof(
+        &audit_log,
+        system,
+        args.variant.id(),
+        &vk.bytes32(),
+        &excluded_countries,
+        &proof,
+        &proof_time,
+    )?;
+    let history = args.history.unwrap_or_else(default_history_path);
+    let label = RunLabel {
+        mode: system,
+        variant: args.variant.id(),
+        vkey: &vk.bytes32(),
+        excluded_countries: &excluded_countries,
+        ranges: excluded.ranges.len() + excluded.ranges_v6.len(),
+        geoip_path: &geoip_path,
+    };
+    let cost = RunCost {
+        cycles: None,
+        wall,
+        proof: Some(&proof),
+    };
+    record_run(&history, label, cost)?;
 
-    create_proof_fixture(&proof, &vk, args.system);
+    let fixture = create_proof_fixture(&proof, &vk, args.system);
+    if let Some(store) = &store {
+        store_fixture(store.as_ref(), &proof, args.system, &fixture)?;
+    }
 
     Ok(())
 }
 
-/// Create a fixture for the given proof.
+/// Create a fixture for the given proof, returning its JSON.
 fn create_proof_fixture(
     proof: &SP1ProofWithPublicValues,
     vk: &SP1VerifyingKey,
     system: ProofSystem,
-) {
+) -> String {
     let bytes = proof.public_values.as_slice();
-    let PublicValuesStruct {
-        is_excluded,
-        timestamp,
-        excluded_countries,
-    } = PublicValuesStruct::abi_decode(bytes).unwrap();
+    let DecodedPublicValues {
+        values:
+            PublicValuesStruct {
+                is_excluded,
+                timestamp,
+                excluded_countries,
+                dataset_root,
+                domain_separator,
+                ..
+            },
+        disclosed,
+        anchor,
+        coverage,
+        is_public_ip,
+        ranges_digest,
+        nullifier,
+        version,
+        snapshot,
+        policy_id,
+        app_context,
+        prefix,
+        legacy,
+        ..
+    } = decode_public_values(bytes).unwrap();
 
     let fixture = SP1ZkipProofFixture {
         is_excluded,
         timestamp,
         excluded_countries,
+        disclosure_granularity: disclosed.map(|(granularity, _)| granularity),
+        disclosed_region: disclosed.map(|(_, region)| region),
+        anchor_block_number: anchor.map(|(number, _)| number),
+        anchor_block_hash: anchor.map(|(_, hash)| format!("0x{}", hex::encode(hash))),
+        dataset_root: (!legacy).then(|| format!("0x{}", hex::encode(dataset_root))),
+        range_count: coverage.map(|(range_count, _)| range_count),
+        covered_ipv4: coverage.map(|(_, covered_ipv4)| covered_ipv4),
+        is_public_ip,
+        ranges_digest: ranges_digest.map(|digest| format!("0x{}", hex::encode(digest))),
+        nullifier_epoch: nullifier.as_ref().map(|values| values.epoch),
+        nullifier_scope: nullifier
+            .as_ref()
+            .map(|values| format!("0x{}", hex::encode(values.scope))),
+        nullifier: nullifier.map(|values| format!("0x{}", hex::encode(values.nullifier))),
+        public_values_version: version,
+        domain_separator: version.map(|_| format!("0x{}", hex::encode(domain_separator))),
+        snapshot_source: snapshot.map(|snapshot| format!("0x{}", hex::encode(snapshot.source))),
+        snapshot_date: snapshot.map(|snapshot| snapshot.published_at),
+        snapshot_hash: snapshot.map(|snapshot| format!("0x{}", hex::encode(snapshot.hash))),
+        policy_id: policy_id.map(|id| format!("0x{}", hex::encode(id))),
+        app_context: app_context.map(|context| format!("0x{}", hex::encode(context))),
+        prefix_scope: prefix
+            .as_ref()
+            .map(|values| format!("0x{}", hex::encode(values.scope))),
+        prefix_commitment: prefix.map(|values| format!("0x{}", hex::encode(values.commitment))),
         vkey: vk.bytes32().to_string(),
         public_values: format!("0x{}", hex::encode(bytes)),
         proof: format!("0x{}", hex::encode(proof.bytes())),
     };
 
-    println!("Verification Key: {}", fixture.vkey);
-    println!("Public Values: {}", fixture.public_values);
-    println!("Proof Bytes: {}", fixture.proof);
+    info!("{}", tr!(Msg::VerificationKey, fixture.vkey));
+    info!("{}", tr!(Msg::PublicValues, fixture.public_values));
+    info!("{}", tr!(Msg::ProofBytes, fixture.proof));
 
     let fixture_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../contracts/src/fixtures");
     std::fs::create_dir_all(&fixture_path).expect("failed to create fixture path");
+    let json = serde_json::to_string_pretty(&fixture).unwrap();
     std::fs::write(
         fixture_path.join(format!("{:?}-fixture.json", system).to_lowercase()),
-        serde_json::to_string_pretty(&fixture).unwrap(),
+        &json,
     )
     .expect("failed to write fixture");
+    json
+}
+
+/// Copy the fixture to the artifact store, keyed by system and proof ID.
+fn store_fixture(
+    store: &dyn ArtifactStore,
+    proof: &SP1ProofWithPublicValues,
+    system: ProofSystem,
+    fixture: &str,
+) -> anyhow::Result<()> {
+    let key = format!("fixtures/{}/{}.json", system.id(), proof_id(proof)?);
+    let location = store
+        .put(&key, fixture.as_bytes())
+        .context(ZkipCliError::ArtifactStoreFailed)?;
+    info!("{}", tr!(Msg::ArtifactStored, key, location));
+    Ok(())
 }
@@ -0,0 +1,234 @@
+//! Fixture generator for Foundry's `vm.ffi`.
+//!
+//! Takes one JSON request, as the first argument or on stdin, and prints one JSON fixture
+//! on stdout. Nothing else is written to stdout, so Solidity tests can `vm.parseJson` it:
+//!
+//! ```solidity
+//! string[] memory cmd = new string[](2);
+//! cmd[0] = "zkip-ffi-prover";
+//! cmd[1] = '{"version":1,"mode":"mock","ip":"8.8.8.8","exclude":"FR"}';
+//! string memory fixture = string(vm.ffi(cmd));
+//! ```
+//!
+//! Request fields besides `ip` (IPv4 or IPv6): `version` (1), `mode` (`execute` or `mock`),
+//! `exclude`, `timestamp`, `variant`, `disclose`, and `geoip` and `geoip_v6` (CSV paths
+//! instead of the caches).
+//! Failures print the usual JSON error envelope on stderr and exit non-zero.
+
+use anyhow::Context;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{HashableKey, Prover, ProverClient};
+use std::io::Read;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use zkip_lib::{decode_public_values, parse_ip, DecodedPublicValues};
+use zkip_script::countries::parse_excluded_countries;
+use zkip_script::disclosure::{resolve_disclosure, Disclosure};
+use zkip_script::error::{exit_with_error, ZkipCliError};
+use zkip_script::geoip::{ensure_geoip_database, ensure_geoip_v6_database};
+use zkip_script::i18n::Msg;
+use zkip_script::inputs::{build_stdin, split_addresses, SharedInputs};
+use zkip_script::logging::{init_logging, LogFormat};
+use zkip_script::snapshot::ExcludedRanges;
+use zkip_script::tr;
+use zkip_script::variants::ProgramVariant;
+
+/// Version of the request/response contract; bump on any incompatible change.
+const FFI_VERSION: u32 = 1;
+
+#[derive(Copy, Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum Mode {
+    /// Execute only; `proof` is empty
+    #[default]
+    Execute,
+    /// Mock Groth16 proof, accepted by `SP1MockVerifier`
+    Mock,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FfiRequest {
+    version: u32,
+    #[serde(default)]
+    mode: Mode,
+    ip: String,
+    #[serde(default = "default_exclude")]
+    exclude: String,
+    timestamp: Option<u64>,
+    #[serde(default)]
+    variant: ProgramVariant,
+    disclose: Option<String>,
+    geoip: Option<PathBuf>,
+    geoip_v6: Option<PathBuf>,
+}
+
+fn default_exclude() -> String {
+    "FR".to_string()
+}
+
+/// Same field names as the `evm` binary's fixtures.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FfiFixture {
+    version: u32,
+    is_excluded: bool,
+    timestamp: u64,
+    excluded_countries: Vec<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disclosure_granularity: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disclosed_region: Option<u16>,
+    dataset_root: String,
+    vkey: String,
+    public_values: String,
+    proof: String,
+}
+
+fn main() {
+    // Logs go to stderr; keep them quiet so test output stays readable.
+    if let Err(err) = init_logging(LogFormat::Json, Some("warn")) {
+        exit_with_error(err.context(ZkipCliError::InvalidArguments), true);
+    }
+    match run() {
+        Ok(fixture) => println!(
+            "{}",
+            serde_json::to_string(&fixture).expect("fixture serializes")
+        ),
+        Err(err) => exit_with_error(err, true),
+    }
+}
+
+fn read_request() -> anyhow::Result<FfiRequest> {
+    let raw = match std::env::args().nth(1) {
+        Some(arg) => arg,
+        None => {
+            let mut buf = String::new();
+            std::io::stdin()
+                .read_to_string(&mut buf)
+                .with_context(|| tr!(Msg::FfiReadFailed))?;
+            buf
+        }
+    };
+    let request: FfiRequest =
+        serde_json::from_str(&raw).with_context(|| tr!(Msg::FfiInvalidRequest))?;
+    if request.version != FFI_VERSION {
+        anyhow::bail!(tr!(
+            Msg::FfiVersionUnsupported,
+            request.version,
+            FFI_VERSION
+        ));
+    }
+    Ok(request)
+}
+
+fn run() -> anyhow::Result<FfiFixture> {
+    let request = read_request().context(ZkipCliError::InvalidArguments)?;
+    let geoip_path = match &request.geoip {
+        Some(path) => path.clone(),
+        None => ensure_geoip_database(false).context(ZkipCliError::DatabaseUnavailable)?,
+    };
+
+    let addr = parse_ip(&request.ip).context(ZkipCliError::InvalidIp)?;
+    let (ips, ips_v6) = split_addresses(&[addr]);
+    let (alpha2_codes, excluded_countries) =
+        parse_excluded_countries(&request.exclude).context(ZkipCliError::InvalidCountry)?;
+    let geoip_v6_path = match &request.geoip_v6 {
+        Some(path) => path.clone(),
+        None => ensure_geoip_v6_database(false).context(ZkipCliError::DatabaseUnavailable)?,
+    };
+    let excluded = ExcludedRanges::load(&geoip_path, Some(&geoip_v6_path), &alpha2_codes)
+        .context(ZkipCliError::DatabaseCorrupted)?;
+    let timestamp = match request.timestamp {
+        Some(timestamp) => timestamp,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .with_context(|| tr!(Msg::ClockBeforeEpoch))?
+            .as_secs(),
+    };
+    let disclosure = match &request.disclose {
+        Some(level) => {
+            let level = Disclosure::from_str(level, true)
+                .map_err(|e| anyhow::anyhow!(e))
+                .context(ZkipCliError::InvalidArguments)?;
+            let &ip = ips
+                .first()
+                .with_context(|| tr!(Msg::FfiDiscloseIpv4Only))
+                .context(ZkipCliError::InvalidIp)?;
+            Some(
+                resolve_disclosure(ip, level, &excluded_countries, &excluded)
+                    .context(ZkipCliError::InvalidIp)?,
+            )
+        }
+        None => None,
+    };
+    let stdin = build_stdin(&SharedInputs {
+        ips: &ips,
+        excluded_ranges: &excluded.ranges,
+        excluded_countries: &excluded_countries,
+        timestamp,
+        disclosure,
+        anchor: None,
+        batch: false,
+        inventory: false,
+        threshold: None,
+        ips_v6: &ips_v6,
+        excluded_ranges_v6: &excluded.ranges_v6,
+        range_counts: &excluded.counts,
+        include: false,
+        denylist: None,
+        policy: None,
+        per_country: false,
+        gap: None,
+        attestation: None,
+        web_proof: None,
+        snapshot_signature: None,
+        time_beacon: None,
+        companion: None,
+        nullifier: None,
+        challenge: None,
+        valid_for: None,
+        snapshot: None,
+        app_context: None,
+        prefix_salt: None,
+        block: None,
+    });
+
+    let elf = request.variant.elf();
+    let prover = ProverClient::builder().mock().build();
+    let (pk, vk) = prover.setup(elf);
+    let (public_values, proof) = match request.mode {
+        Mode::Execute => {
+            let (output, _) = prover
+                .execute(elf, &stdin)
+                .run()
+                .context(ZkipCliError::ExecutionFailed)?;
+            (output.to_vec(), Vec::new())
+        }
+        Mode::Mock => {
+            let proof = prover
+                .prove(&pk, &stdin)
+                .groth16()
+                .run()
+                .context(ZkipCliError::ProvingFailed)?;
+            (proof.public_values.to_vec(), proof.bytes())
+        }
+    };
+
+    let DecodedPublicValues {
+        values, disclosed, ..
+    } = decode_public_values(&public_values).context(ZkipCliError::DecodeFailed)?;
+    Ok(FfiFixture {
+        version: FFI_VERSION,
+        is_excluded: values.is_excluded,
+        timestamp: values.timestamp,
+        excluded_countries: values.excluded_countries,
+        disclosure_granularity: disclosed.map(|(granularity, _)| granularity),
+        disclosed_region: disclosed.map(|(_, region)| region),
+        dataset_root: format!("0x{}", hex::encode(values.dataset_root)),
+        vkey: vk.bytes32(),
+        public_values: format!("0x{}", hex::encode(public_values)),
+        proof: format!("0x{}", hex::encode(proof)),
+    })
+}
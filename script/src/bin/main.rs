@@ -10,257 +10,4077 @@
 //! RUST_LOG=info cargo run --release -- --prove
 //! ```
 
-use alloy_sol_types::SolType;
-use anyhow::{bail, Context};
-use clap::Parser;
-use sp1_sdk::{include_elf, ProverClient, SP1Stdin};
-use std::collections::HashMap;
-use std::fs::{self, File};
-use std::io::{BufRead, BufReader, Write};
-use std::path::PathBuf;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use zkip_lib::{ip_to_u32, PublicValuesStruct};
-
-/// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
-pub const ZKIP_ELF: &[u8] = include_elf!("zkip-program");
-
-const GEOIP_URL: &str = "https://cdn.jsdelivr.net/npm/@ip-location-db/geo-whois-asn-country/geo-whois-asn-country-ipv4-num.csv";
-const CACHE_MAX_AGE_DAYS: u32 = 30;
+use alloy::primitives::Address as EthAddress;
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use ed25519_dalek::VerifyingKey;
+use sp1_sdk::{
+    install::try_install_circuit_artifacts, CpuProver, EnvProver, HashableKey, Prover,
+    ProverClient, SP1Proof, SP1ProofWithPublicValues, SP1ProverOpts, SP1Stdin, SP1VerifyingKey,
+};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tracing::{error, info, info_span, warn};
+use zkip_lib::{
+    attest::AttestationInput,
+    block::{self, BlockInput},
+    chunk::AggregationInput,
+    compose::CompanionInput,
+    decode_public_values,
+    error::ZkipError,
+    ip_to_u32,
+    merkle::{asn_root, inventory_root},
+    nullifier::NullifierInput,
+    parse_ip,
+    policy::{range_groups, Address, PolicyExpr},
+    prefix,
+    renew::{vkey_bytes, RenewalInput},
+    snapshot::SnapshotMetadata,
+    webproof::{server_id, WebProofInput},
+    AggregationValues, AnchorInput, AttestationValues, BlockValues, CompanionValues,
+    DecodedPublicValues, DenylistValues, NullifierValues, PolicyValues, PrefixValues,
+    PublicValuesStruct, PublisherValues, RenewalValues, TimeBeaconValues, ValidityWindow,
+    GRANULARITY_COUNTRY, MODE_BLOCK, MODE_COMBINED, MODE_EXCLUSION, MODE_INCLUSION, MODE_POLICY,
+    MODE_THRESHOLD, PUBLIC_VALUES_VERSION, RESERVED_RANGES, RESERVED_RANGES_V6,
+};
+use zkip_script::admin::{push_root, PushOutcome, RootUpdate};
+use zkip_script::anchor::{anchor_age, fetch_anchor, BLOCKHASH_WINDOW};
+use zkip_script::artifacts::{write_artifacts, ArtifactMetadata};
+use zkip_script::attestation::{load_attestation, load_web_proof, parse_attester};
+use zkip_script::audit::{default_audit_log_path, proof_id, record_proof, verify_chain};
+use zkip_script::bundle::{
+    is_bundle, BundleVerdict, ProofBundle, Rejection, EXTENSION as BUNDLE_EXTENSION,
+};
+use zkip_script::circuit::{circuit_stdin, ExternalCircuit, PolicyCircuit, SharedInputs};
+use zkip_script::config::default_config_path;
+use zkip_script::cosign::{default_envelope_path, CosignEnvelope, CosignPolicy};
+use zkip_script::countries::{load_country_codes, parse_excluded_countries};
+use zkip_script::disclosure::{region_name, resolve_disclosure, Disclosure};
+use zkip_script::domain::resolve_domain;
+use zkip_script::e2e::{
+    default_contracts_dir, forge_build, spawn_anvil, verify_onchain, ProofSystem,
+};
+use zkip_script::error::{exit_with_error, ZkipCliError};
+use zkip_script::explain::{explain, format_utc, parse_public_values};
+use zkip_script::fleet::load_ip_list;
+use zkip_script::geoip::{
+    allow_only, asn_ranges, cache_age, count_ranges_by_country, count_rows, ensure_geoip_database,
+    ensure_geoip_v6_database, excluded_ranges, fetch_upstream_checksum, get_cache_path,
+    get_v6_cache_path, load_geo_db, refresh_reason, sha256_file, snapshot_metadata,
+    use_embedded_snapshot, validate_csv, CACHE_MAX_AGE_DAYS, GEOIP_PROVIDER, GEOIP_URL,
+};
+use zkip_script::history::{
+    default_history_path, load as load_history, record_run, summarize, RunCost, RunLabel,
+};
+use zkip_script::i18n::{resolve_lang, set_lang, Lang, Msg};
+use zkip_script::inputs::{build_stdin, chunk_stdins, gap_input, split_addresses};
+use zkip_script::lock::{default_lock_path, enforce_lock, ZkipLock};
+use zkip_script::logging::{init_logging, new_run_id, LogFormat};
+use zkip_script::manifest::{
+    default_manifest_path, load_signing_key, parse_public_key, verify_elf_manifest, Artifact,
+    ManifestBody, ReleaseManifest,
+};
+use zkip_script::policy::{compile_rule, lint_policy, load_policy, policy_hash, Severity};
+use zkip_script::registry::{load_verifying_key, registry_dir, Registry};
+use zkip_script::replay::{replay_key, ReplayStore};
+use zkip_script::reproduce::build_guest_in_docker;
+use zkip_script::revocation::{default_revocation_path, revoke, RevocationKind, RevocationList};
+use zkip_script::selftest::{selftest_dataset_path, SELFTEST_TIMESTAMP, SELFTEST_VECTORS};
+use zkip_script::snapshot::{parse_publisher, root_manifest, ExcludedRanges, RootManifest};
+use zkip_script::store::{configured_store, put_file, ArtifactStore};
+use zkip_script::telemetry::{self, cycle_bucket, UsageEvent};
+use zkip_script::timesource::{
+    ntp_servers, parse_roughtime_key, proof_time, ProofTime, TimeSource,
+};
+use zkip_script::tr;
+use zkip_script::variants::{ProgramVariant, AGGREGATE_ELF, CHUNK_ELF, RENEW_ELF};
+
+/// `--max-db-age` is in days; proofs commit it in seconds.
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
 
 /// The arguments for the command.
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(long)]
     execute: bool,
 
     #[arg(long)]
     prove: bool,
 
-    /// IP address to test (e.g., "8.8.8.8")
+    /// IP address to test: IPv4 as "8.8.8.8", "134744072" or "0x08080808", or IPv6
     #[arg(long, default_value = "8.8.8.8")]
     ip: String,
 
-    /// Comma-separated country codes to exclude (e.g., "FR,US,DE")
-    #[arg(long, default_value = "FR")]
-    exclude: String,
+    /// Prove every address this host name resolves to, in one batch proof
+    #[arg(long, conflicts_with_all = ["ip", "disclose", "anchor_block"])]
+    domain: Option<String>,
+
+    /// With --domain, leave out the host's IPv6 addresses
+    #[arg(long, requires = "domain")]
+    skip_ipv6: bool,
+
+    /// Prove every address listed in this file, one per line, in one batch proof
+    #[arg(long, conflicts_with_all = ["ip", "domain", "disclose", "anchor_block"])]
+    ip_file: Option<PathBuf>,
+
+    /// With --ip-file, also commit the list's Merkle root (see `zkip inventory-root`)
+    #[arg(long, requires = "ip_file")]
+    inventory: bool,
+
+    /// With --ip-file, commit only whether at least this many addresses are clear
+    #[arg(long, requires = "ip_file", conflicts_with = "inventory")]
+    threshold: Option<u32>,
+
+    /// Comma-separated country codes or names to exclude (e.g., "FR,US,Germany"); FR if
+    /// unset. With --include, a denylist checked in the same proof
+    #[arg(long)]
+    exclude: Option<String>,
+
+    /// Prove the IP is in one of these countries instead (e.g., "DE" or "DE,AT,CH")
+    #[arg(long, conflicts_with_all = ["domain", "ip_file", "disclose", "anchor_block"])]
+    include: Option<String>,
+
+    /// Exclude every country but these (e.g., "SE,NO"), for an allowlist proved as an
+    /// exclusion
+    #[arg(long, conflicts_with_all = ["exclude", "include", "policy"])]
+    allow_only: Option<String>,
+
+    /// Prove the IP satisfies the `rule` of this policy file instead
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "domain", "ip_file", "exclude", "include", "disclose", "anchor_block"
+        ]
+    )]
+    policy: Option<PathBuf>,
+
+    /// Also commit whether the IP is outside each excluded country, not only all of them
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "domain", "ip_file", "include", "policy", "disclose", "anchor_block"
+        ]
+    )]
+    per_country: bool,
+
+    /// Prove exclusion from Merkle witnesses of the two ranges around the IP in each
+    /// country, instead of sending the guest every range
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "domain", "ip_file", "include", "policy", "per_country", "disclose", "anchor_block"
+        ]
+    )]
+    gap_witness: bool,
+
+    /// Attester-signed IP (JSON, see `zkip_script::attestation`); commit who signed it
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "domain", "ip_file", "include", "policy", "per_country", "disclose", "anchor_block",
+            "gap_witness"
+        ]
+    )]
+    attestation: Option<PathBuf>,
+
+    /// Notarised "what is my IP" response (JSON, see `zkip_script::attestation`); prove the
+    /// IP it names instead of --ip, and commit the notary and server
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "ip", "domain", "ip_file", "include", "policy", "per_country", "disclose",
+            "anchor_block", "gap_witness", "attestation", "signed_snapshot", "roughtime_server",
+            "nullifier_epoch", "app_context", "prefix_salt"
+        ]
+    )]
+    web_proof: Option<PathBuf>,
+
+    /// Prove the ranges in chunks (`zkip_lib::chunk`) and aggregate the chunk proofs into
+    /// one, for exclusion sets too large for one execution (with --prove)
+    #[arg(
+        long,
+        requires = "prove",
+        conflicts_with_all = [
+            "domain", "ip_file", "include", "policy", "per_country", "disclose", "anchor_block",
+            "gap_witness", "attestation", "web_proof", "signed_snapshot", "roughtime_server",
+            "nullifier_epoch", "app_context", "prefix_salt", "challenge", "valid_for",
+            "companion_proof", "elf"
+        ]
+    )]
+    chunked: bool,
+
+    /// Publisher-signed `zkip db root` manifest; prove the ranges are the ones it signed
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "domain", "ip_file", "include", "policy", "per_country", "disclose", "anchor_block",
+            "gap_witness", "attestation"
+        ]
+    )]
+    signed_snapshot: Option<PathBuf>,
+
+    /// Oldest --signed-snapshot the guest accepts, in days before the proof's timestamp
+    /// (defaults to the cache's 30 days)
+    #[arg(long, requires = "signed_snapshot")]
+    max_db_age: Option<u32>,
 
     /// Force refresh the GeoIP database
     #[arg(long)]
     refresh: bool,
+
+    /// Use the GeoIP snapshot published on this date (YYYY-MM-DD) embedded in the binary
+    /// instead of the downloaded database, without any fetch (needs the embedded-data
+    /// feature)
+    #[arg(long, value_name = "DATE", conflicts_with = "refresh")]
+    snapshot: Option<String>,
+
+    /// Also commit the IP's UN M49 region or sub-region, or its country (the IP stays
+    /// hidden)
+    #[arg(long, value_enum)]
+    disclose: Option<Disclosure>,
+
+    /// Rewrite zkip.lock with the current inputs instead of verifying against it
+    #[arg(long)]
+    update_lock: bool,
+
+    /// Load the guest ELF from this file instead of the embedded one
+    #[arg(long)]
+    elf: Option<PathBuf>,
+
+    /// Signed manifest for --elf (defaults to `<elf>.manifest.json`)
+    #[arg(long, requires = "elf")]
+    manifest: Option<PathBuf>,
+
+    /// Accept an --elf whose manifest is missing or does not verify
+    #[arg(long, requires = "elf")]
+    allow_unsigned: bool,
+
+    /// Write the proof to this file (with --prove) for later `zkip verify`; `-` for stdout
+    #[arg(long, visible_alias = "output")]
+    save_proof: Option<PathBuf>,
+
+    /// Also write the proof, its public values, vkey hash and a metadata JSON into this
+    /// directory (with --prove)
+    #[arg(long)]
+    output_dir: Option<PathBuf>,
+
+    /// Prove in compressed mode (with --prove), so the proof can be renewed by `zkip renew`;
+    /// always on with --companion-proof
+    #[arg(long)]
+    compressed: bool,
+
+    /// Another program's compressed SP1 proof to verify in the guest and commit next to the
+    /// result
+    #[arg(long, requires = "companion_elf")]
+    companion_proof: Option<PathBuf>,
+
+    /// ELF of the program that made --companion-proof
+    #[arg(long, requires = "companion_proof")]
+    companion_elf: Option<PathBuf>,
+
+    /// A verifier's 32-byte session challenge (hex) to commit around the result
+    #[arg(long)]
+    challenge: Option<String>,
+
+    /// Commit a validity window ending this many seconds after the proof's timestamp
+    #[arg(long)]
+    valid_for: Option<u32>,
+
+    /// Where the committed timestamp comes from
+    #[arg(long, value_enum, default_value = "system")]
+    time_source: TimeSource,
+
+    /// NTP server to cross-check the clock against (repeatable; with --time-source ntp)
+    #[arg(long = "ntp-server", value_delimiter = ',')]
+    ntp_servers: Vec<String>,
+
+    /// Largest tolerated clock skew, in seconds (with --time-source ntp)
+    #[arg(long, default_value_t = 2.0)]
+    max_clock_skew: f64,
+
+    /// Roughtime server whose signed time the guest commits, as host:port (with
+    /// --time-source roughtime)
+    #[arg(
+        long,
+        required_if_eq("time_source", "roughtime"),
+        conflicts_with_all = [
+            "domain", "ip_file", "include", "policy", "per_country", "disclose", "anchor_block",
+            "gap_witness", "attestation", "signed_snapshot"
+        ]
+    )]
+    roughtime_server: Option<String>,
+
+    /// Long-term Ed25519 key of --roughtime-server, base64 or hex
+    #[arg(long, required_if_eq("time_source", "roughtime"))]
+    roughtime_key: Option<String>,
+
+    /// Commit the latest block of --rpc-url as a freshness anchor
+    #[arg(long, requires = "rpc_url")]
+    anchor_block: bool,
+
+    /// Also commit the IP's nullifier for this epoch of the consumer's (with
+    /// --nullifier-salt)
+    #[arg(
+        long,
+        requires = "nullifier_salt",
+        conflicts_with_all = [
+            "domain", "ip_file", "include", "policy", "per_country", "disclose", "anchor_block",
+            "gap_witness", "attestation", "signed_snapshot", "roughtime_server"
+        ]
+    )]
+    nullifier_epoch: Option<u32>,
+
+    /// The consumer's secret 32-byte salt for --nullifier-epoch, as hex
+    #[arg(
+        long,
+        env = "ZKIP_NULLIFIER_SALT",
+        hide_env_values = true,
+        requires = "nullifier_epoch"
+    )]
+    nullifier_salt: Option<String>,
+
+    /// An integrator's 32-byte context (hex), such as a dapp ID or order hash, to commit in
+    /// a plain proof
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "domain", "ip_file", "include", "policy", "per_country", "disclose", "anchor_block",
+            "gap_witness", "attestation", "signed_snapshot", "roughtime_server"
+        ]
+    )]
+    app_context: Option<String>,
+
+    /// Also commit the IP's /24 (or IPv6 /48) under the consumer's secret 32-byte salt, as
+    /// hex
+    #[arg(
+        long,
+        env = "ZKIP_PREFIX_SALT",
+        hide_env_values = true,
+        conflicts_with_all = [
+            "domain", "ip_file", "include", "policy", "per_country", "disclose", "anchor_block",
+            "gap_witness", "attestation", "signed_snapshot", "roughtime_server"
+        ]
+    )]
+    prefix_salt: Option<String>,
+
+    /// Prove every address of the IP's prefix of this many bits clear, e.g. 24 for its /24,
+    /// committing the prefix under --block-salt instead of the IP
+    #[arg(
+        long,
+        value_name = "LEN",
+        requires = "block_salt",
+        conflicts_with_all = [
+            "domain", "ip_file", "include", "policy", "per_country", "disclose", "anchor_block",
+            "gap_witness", "attestation", "web_proof", "chunked", "signed_snapshot",
+            "roughtime_server", "nullifier_epoch", "app_context", "prefix_salt"
+        ]
+    )]
+    block: Option<u8>,
+
+    /// The operator's secret 32-byte salt to commit the --block prefix under, as hex
+    #[arg(
+        long,
+        env = "ZKIP_BLOCK_SALT",
+        hide_env_values = true,
+        requires = "block"
+    )]
+    block_salt: Option<String>,
+
+    /// Ethereum JSON-RPC endpoint used by --anchor-block
+    #[arg(long, env = "ZKIP_RPC_URL")]
+    rpc_url: Option<String>,
+
+    /// Guest program variant
+    #[arg(long, global = true, value_enum, default_value = "linear")]
+    variant: ProgramVariant,
+
+    /// Append-only audit log of generated proofs
+    #[arg(long, global = true, env = "ZKIP_AUDIT_LOG")]
+    audit_log: Option<PathBuf>,
+
+    /// Per-run cost history read by `zkip stats`
+    #[arg(long, global = true, env = "ZKIP_HISTORY")]
+    history: Option<PathBuf>,
+
+    /// Signed revocation list written by `zkip revoke` and checked by `zkip verify`
+    #[arg(long, global = true, env = "ZKIP_REVOCATIONS")]
+    revocations: Option<PathBuf>,
+
+    /// Operator settings, including the artifact store proofs and envelopes are copied to
+    #[arg(long, global = true, env = "ZKIP_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Emit failures as a JSON envelope (code, kind, message, hint) on stderr
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Log line format
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    log_format: LogFormat,
+
+    /// Log filter (e.g. "debug", "zkip=trace"); defaults to RUST_LOG, then "info"
+    #[arg(long, global = true)]
+    log_level: Option<String>,
+
+    /// Output language; defaults to the system locale, then English
+    #[arg(long, global = true, value_enum, env = "ZKIP_LANG")]
+    lang: Option<Lang>,
 }
 
-fn get_cache_path() -> PathBuf {
-    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../data/ipv4-country.csv")
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Inspect and maintain the cached GeoIP database
+    #[command(subcommand)]
+    Db(DbCommand),
+    /// Execute the guest against built-in known-answer vectors
+    Selftest {
+        /// Also generate and verify a mock proof
+        #[arg(long)]
+        mock_prove: bool,
+    },
+    /// Work with exclusion policy files
+    #[command(subcommand)]
+    Policy(PolicyCommand),
+    /// Check a saved proof against every non-revoked program version
+    Verify {
+        /// Proof file written by --save-proof, or `-` for stdin
+        proof: PathBuf,
+        /// Hex ed25519 key the revocation list must be signed with
+        #[arg(long, env = "ZKIP_REVOCATION_PUBKEY")]
+        revocation_key: Option<String>,
+        /// Reject proofs already accepted within --replay-ttl, recording them in this store
+        #[arg(long, env = "ZKIP_REPLAY_STORE")]
+        replay_store: Option<PathBuf>,
+        /// How long an accepted proof stays consumed, in seconds
+        #[arg(long, default_value_t = 86_400, requires = "replay_store")]
+        replay_ttl: u64,
+        /// Operator keys and threshold; require that many co-signatures on the proof
+        #[arg(long, env = "ZKIP_COSIGNERS")]
+        cosigners: Option<PathBuf>,
+        /// Co-signature envelope (defaults to `<proof>.cosign.json`)
+        #[arg(long, requires = "cosigners")]
+        envelope: Option<PathBuf>,
+        /// Require the proof to commit this dataset root (hex, as printed by `zkip db root`)
+        #[arg(long, env = "ZKIP_DATASET_ROOT")]
+        dataset_root: Option<String>,
+        /// Require the dataset root this `zkip db root` manifest gives the checked countries
+        #[arg(long, conflicts_with = "dataset_root")]
+        root_manifest: Option<PathBuf>,
+        /// Require a plain proof committing this ranges digest (hex, as printed by `zkip db root`)
+        #[arg(long, env = "ZKIP_RANGES_DIGEST")]
+        ranges_digest: Option<String>,
+        /// Require an inclusion proof hiding the IP's country among at least this many
+        #[arg(long)]
+        min_anonymity: Option<usize>,
+        /// Require a policy proof of this policy file's rule
+        #[arg(long)]
+        policy: Option<PathBuf>,
+        /// Require a proof about an IP this attester signed (hex secp256k1 or Ed25519 key)
+        #[arg(long, env = "ZKIP_ATTESTER")]
+        attester: Option<String>,
+        /// Require a web proof of this server's response (e.g. api.ipify.org), with
+        /// --attester pinning its notary
+        #[arg(long, env = "ZKIP_WEB_SERVER", requires = "attester")]
+        web_server: Option<String>,
+        /// Require ranges from a snapshot this publisher signed (hex Ed25519 key, or
+        /// `maintainer` for the release maintainer's)
+        #[arg(long, env = "ZKIP_PUBLISHER")]
+        publisher: Option<String>,
+        /// Require a signed-snapshot proof bounding the database's age to at most this many
+        /// days
+        #[arg(long)]
+        max_db_age: Option<u32>,
+        /// Require a timestamp this Roughtime server signed (its base64 or hex Ed25519 key)
+        #[arg(long, env = "ZKIP_TIME_SERVER")]
+        time_server: Option<String>,
+        /// Require an anchored proof whose block this Ethereum JSON-RPC endpoint still has
+        #[arg(long)]
+        anchor_rpc_url: Option<String>,
+        /// Oldest anchor block accepted, in blocks behind the latest (defaults to the 256
+        /// `blockhash` answers for)
+        #[arg(long, requires = "anchor_rpc_url")]
+        max_anchor_age: Option<u64>,
+        /// Require an inventory proof over the IP list with this root (hex, as printed by
+        /// `zkip inventory-root`)
+        #[arg(long, env = "ZKIP_INVENTORY_ROOT")]
+        inventory_root: Option<String>,
+        /// Require a proof joined to a companion proof of the program built as this ELF
+        #[arg(long)]
+        companion_elf: Option<PathBuf>,
+        /// Require a proof bound to this 32-byte challenge (hex), as issued for the session
+        #[arg(long)]
+        challenge: Option<String>,
+        /// Require a plain proof committing one of these snapshot hashes (hex, as printed
+        /// by `zkip db root`). Needs --ranges-digest, --dataset-root or --root-manifest
+        #[arg(
+            long = "snapshot-hash",
+            env = "ZKIP_SNAPSHOT_HASHES",
+            value_delimiter = ','
+        )]
+        snapshot_hashes: Vec<String>,
+        /// Require a plain proof committing this 32-byte app context (hex)
+        #[arg(long)]
+        app_context: Option<String>,
+        /// Require a `.zkip` bundle signed by one of these operators (hex Ed25519 keys)
+        #[arg(long = "operator", env = "ZKIP_OPERATORS", value_delimiter = ',')]
+        operators: Vec<String>,
+    },
+    /// Print the Merkle root of an IP list, for an organization to publish before proving
+    /// it with --ip-file --inventory
+    InventoryRoot {
+        /// One address per line, as for --ip-file
+        ip_file: PathBuf,
+    },
+    /// Re-commit a saved proof's claim under the current time, without its ranges
+    Renew {
+        /// Compressed proof file to renew (from --prove --compressed); `-` for stdin
+        proof: PathBuf,
+        /// Write the renewal to this file; `-` for stdout
+        #[arg(long, visible_alias = "output")]
+        save_proof: Option<PathBuf>,
+    },
+    /// Wrap a saved compressed proof in Groth16 or PLONK for an EVM verifier, apart from
+    /// proving
+    Wrap {
+        /// Compressed proof file to wrap (from --prove --compressed); `-` for stdin
+        proof: PathBuf,
+        #[arg(long, value_enum, default_value = "groth16")]
+        system: ProofSystem,
+        /// Write the wrapped proof to this file; `-` for stdout
+        #[arg(long, visible_alias = "output")]
+        save_proof: Option<PathBuf>,
+    },
+    /// Pack a saved proof with its public values, vkey, program version and snapshot
+    /// metadata into one `.zkip` file for a verifier
+    Bundle {
+        /// Proof file to bundle (from --save-proof, renew or wrap); `-` for stdin
+        proof: PathBuf,
+        /// Write the bundle to this file (default: the proof's path with a `.zkip`
+        /// extension, or `proof.zkip` for stdin)
+        #[arg(long, visible_alias = "output")]
+        save_bundle: Option<PathBuf>,
+        /// File holding the operator's hex-encoded 32-byte signing seed, to sign the bundle
+        /// with
+        #[arg(long, env = "ZKIP_OPERATOR_KEY")]
+        sign_key: Option<PathBuf>,
+    },
+    /// Check a `.zkip` bundle against the known program versions and print a JSON verdict
+    VerifyBundle {
+        /// Bundle file (from `zkip bundle`)
+        bundle: PathBuf,
+        /// Require a bundle signed by one of these operators (hex Ed25519 keys)
+        #[arg(long = "operator", env = "ZKIP_OPERATORS", value_delimiter = ',')]
+        operators: Vec<String>,
+    },
+    /// Add this operator's signature to a proof's co-signature envelope
+    Cosign {
+        /// Proof file to vouch for
+        proof: PathBuf,
+        /// File holding the hex-encoded 32-byte signing seed
+        #[arg(long)]
+        key: PathBuf,
+        /// Co-signature envelope (defaults to `<proof>.cosign.json`)
+        #[arg(long)]
+        envelope: Option<PathBuf>,
+    },
+    /// Add a proof ID (as recorded in the audit log) to the signed revocation list
+    Revoke {
+        /// Proof ID to revoke, or with --nullifier a nullifier
+        id: String,
+        /// Revoke every proof committing the nullifier ID, wrapped or not
+        #[arg(long)]
+        nullifier: bool,
+        /// File holding the hex-encoded 32-byte signing seed
+        #[arg(long)]
+        key: PathBuf,
+        /// Why the proof is revoked (e.g. "bad GeoIP snapshot")
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Manage the registry of released program versions and their vkeys
+    #[command(subcommand)]
+    Registry(RegistryCommand),
+    /// Sign release manifests for guest ELFs
+    #[command(subcommand)]
+    Manifest(ManifestCommand),
+    /// Inspect the proof audit log
+    #[command(subcommand)]
+    Audit(AuditCommand),
+    /// Decode raw public values (as attested on-chain) into readable fields
+    Explain {
+        /// ABI-encoded public values, hex (with or without 0x)
+        public_values: String,
+    },
+    /// Boot anvil, deploy the verifier and Zkip contracts, and check a fresh proof on-chain
+    E2e {
+        #[arg(long, value_enum, default_value = "groth16")]
+        system: ProofSystem,
+        /// Foundry project with the Zkip contract and the SP1 verifiers
+        #[arg(long)]
+        contracts: Option<PathBuf>,
+        /// Port for the local anvil node
+        #[arg(long, default_value_t = 8546)]
+        port: u16,
+        /// IPv4 or IPv6 address to prove
+        #[arg(long, default_value = "8.8.8.8")]
+        ip: String,
+        /// Comma-separated country codes to exclude
+        #[arg(long, default_value = "FR")]
+        exclude: String,
+    },
+    /// Summarize proving cost per mode, variant and country set from the run history
+    Stats,
+    /// Operator tasks against on-chain contracts
+    #[command(subcommand)]
+    Admin(AdminCommand),
+    /// Print the program verification key
+    Vkey {
+        /// Rebuild the guest in SP1's pinned docker image and check the vkey matches
+        #[arg(long)]
+        reproduce: bool,
+        /// vkey to compare against (defaults to the one embedded in this binary)
+        #[arg(long, requires = "reproduce")]
+        expected: Option<String>,
+    },
 }
 
-fn is_cache_stale(path: &PathBuf) -> bool {
-    let Ok(metadata) = fs::metadata(path) else {
-        return true;
-    };
-    let Ok(modified) = metadata.modified() else {
-        return true;
-    };
-    let Ok(age) = SystemTime::now().duration_since(modified) else {
-        return true;
-    };
-    age > Duration::from_secs((CACHE_MAX_AGE_DAYS * 24 * 60 * 60) as u64)
+#[derive(Subcommand, Debug)]
+enum PolicyCommand {
+    /// Validate a policy file and print its canonical hash
+    Lint {
+        /// Path to the policy YAML file
+        path: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum AdminCommand {
+    /// Publish the cached snapshot's root for a country set to the root registry contract
+    PushRoot {
+        /// Comma-separated country codes (e.g. "FR,US,DE")
+        countries: String,
+        /// Ethereum JSON-RPC endpoint
+        #[arg(long, env = "ZKIP_RPC_URL")]
+        rpc_url: String,
+        /// Root registry contract address
+        #[arg(long)]
+        contract: EthAddress,
+        /// Hex private key of an account holding ROOT_UPDATER_ROLE
+        #[arg(long, env = "ZKIP_ADMIN_PRIVATE_KEY", hide_env_values = true)]
+        private_key: String,
+        /// Check the role and simulate the update without sending it
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
-fn fetch_geoip_database(path: &PathBuf) -> anyhow::Result<()> {
-    println!("Fetching GeoIP database from {}...", GEOIP_URL);
+#[derive(Subcommand, Debug)]
+enum AuditCommand {
+    /// Check the hash chain of the audit log
+    Verify,
+}
+
+#[derive(Subcommand, Debug)]
+enum ManifestCommand {
+    /// Write `<elf>.manifest.json` with the ELF hash and vkey, signed with an ed25519 seed
+    Sign {
+        /// Guest ELF to sign
+        elf: PathBuf,
+        /// File holding the hex-encoded 32-byte signing seed
+        #[arg(long)]
+        key: PathBuf,
+        /// Release version (defaults to this crate's version)
+        #[arg(long)]
+        version: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum RegistryCommand {
+    /// Register the embedded program under a version
+    Add {
+        /// Version label (defaults to this crate's version)
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// List registered versions
+    List,
+    /// Stop accepting proofs from a version
+    Revoke {
+        /// Version to revoke
+        version: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum DbCommand {
+    /// Check the cache against the upstream checksum and validate its structure
+    Verify,
+    /// Show cache location, size, age, hash and whether the next run would refresh it
+    Status,
+    /// Print the Merkle root of a country set's ranges, for verifiers to pin
+    Root {
+        /// Comma-separated country codes (e.g. "FR,US,DE")
+        countries: String,
+        /// Also write a JSON manifest with the snapshot hashes and per-country roots
+        #[arg(long)]
+        manifest: Option<PathBuf>,
+        /// Sign the manifest's root with this hex-encoded 32-byte ed25519 seed, as a publisher
+        #[arg(long, requires = "manifest")]
+        sign: Option<PathBuf>,
+    },
+}
+
+/// Print the state of the cached GeoIP database without fetching or proving anything.
+fn db_status() -> anyhow::Result<()> {
+    let path = get_cache_path();
+    info!("{}", tr!(Msg::Provider, GEOIP_PROVIDER));
+    info!("{}", tr!(Msg::Source, GEOIP_URL));
+    info!("{}", tr!(Msg::Location, path.display()));
 
-    let response = reqwest::blocking::get(GEOIP_URL)
-        .context("Failed to fetch GeoIP database")?;
+    if path.exists() {
+        let size = std::fs::metadata(&path)
+            .with_context(|| tr!(Msg::GeoipMetadataFailed))?
+            .len();
+        let mib = format!("{:.1}", size as f64 / (1024.0 * 1024.0));
+        info!("{}", tr!(Msg::Size, size, mib));
+        match cache_age(&path) {
+            Some(age) => {
+                let hours = age.as_secs() / 3600;
+                info!("{}", tr!(Msg::Age, hours / 24, hours % 24));
+            }
+            None => info!("{}", tr!(Msg::AgeUnknown)),
+        }
+        info!("{}", tr!(Msg::Sha256, hex::encode(sha256_file(&path)?)));
+        info!("{}", tr!(Msg::Rows, count_rows(&path)?));
+    } else {
+        info!("{}", tr!(Msg::NotDownloaded));
+    }
 
-    if !response.status().is_success() {
-        bail!("HTTP error: {}", response.status());
+    match refresh_reason(&path, false) {
+        Some(reason) => info!("{}", tr!(Msg::NextRunRefresh, reason)),
+        None => info!("{}", tr!(Msg::NextRunCache)),
     }
+    Ok(())
+}
 
-    let content = response.text().context("Failed to read response")?;
+/// Print the dataset root the cached snapshots give `countries`, optionally writing the
+/// full manifest with per-country roots, signed with the seed at `sign`.
+fn db_root(countries: &str, manifest: Option<&Path>, sign: Option<&Path>) -> anyhow::Result<()> {
+    let path = get_cache_path();
+    let v6_path = get_v6_cache_path();
+    for cache in [&path, &v6_path] {
+        if !cache.exists() {
+            return Err(anyhow::anyhow!(tr!(Msg::DbNotFound, cache.display()))
+                .context(ZkipCliError::DatabaseUnavailable));
+        }
+    }
+    let (alpha2_codes, codes) =
+        parse_excluded_countries(countries).context(ZkipCliError::InvalidCountry)?;
+    let generated_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .with_context(|| tr!(Msg::ClockBeforeEpoch))?
+        .as_secs();
+    let mut roots = root_manifest(&path, &v6_path, &alpha2_codes, &codes, generated_at)
+        .context(ZkipCliError::DatabaseCorrupted)?;
+    if let Some(key) = sign {
+        let key = load_signing_key(key).context(ZkipCliError::InvalidArguments)?;
+        roots.sign(&key, generated_at)?;
+        let pubkey = hex::encode(key.verifying_key().to_bytes());
+        info!("{}", tr!(Msg::SnapshotSigned, roots.root, pubkey));
+    }
 
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).context("Failed to create data directory")?;
+    for country in &roots.countries {
+        info!(
+            "{}",
+            tr!(
+                Msg::CountryRoot,
+                country.country,
+                country.code,
+                country.ranges,
+                country.root,
+                country.ranges_v6,
+                country.root_v6
+            )
+        );
+    }
+    info!(
+        "{}",
+        tr!(
+            Msg::SetRoot,
+            format!("{:?}", roots.excluded_countries),
+            roots.ranges,
+            roots.ranges_v6,
+            roots.root
+        )
+    );
+    if let Some(digest) = &roots.ranges_digest {
+        info!("{}", tr!(Msg::RangesDigest, digest));
+    }
+    if let Some(hash) = &roots.snapshot_hash {
+        info!("{}", tr!(Msg::SnapshotHash, hash));
+    }
+    if let Some(manifest) = manifest {
+        roots.save(manifest)?;
+        info!("{}", tr!(Msg::RootManifestWritten, manifest.display()));
     }
+    println!("{}", roots.root);
+    Ok(())
+}
 
-    let mut file = File::create(path).context("Failed to create cache file")?;
-    file.write_all(content.as_bytes()).context("Failed to write cache file")?;
+/// Publish the cached snapshots' dataset root for `countries` to the registry at `contract`.
+fn admin_push_root(
+    countries: &str,
+    rpc_url: &str,
+    contract: EthAddress,
+    private_key: &str,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let path = get_cache_path();
+    let v6_path = get_v6_cache_path();
+    for cache in [&path, &v6_path] {
+        if !cache.exists() {
+            return Err(anyhow::anyhow!(tr!(Msg::DbNotFound, cache.display()))
+                .context(ZkipCliError::DatabaseUnavailable));
+        }
+    }
+    let (alpha2_codes, codes) =
+        parse_excluded_countries(countries).context(ZkipCliError::InvalidCountry)?;
+    let excluded = ExcludedRanges::load(&path, Some(&v6_path), &alpha2_codes)
+        .context(ZkipCliError::DatabaseCorrupted)?;
+    let update = RootUpdate {
+        policy_hash: policy_hash(&codes),
+        root: excluded.root(&codes),
+        snapshot_sha256: sha256_file(&path)?,
+    };
+    let root = format!("0x{}", hex::encode(update.root));
+    let policy = format!("0x{}", hex::encode(update.policy_hash));
 
-    println!("GeoIP database cached to {:?}", path);
+    match push_root(rpc_url, contract, private_key, &update, dry_run)
+        .context(ZkipCliError::RootPushFailed)?
+    {
+        PushOutcome::Unchanged => info!("{}", tr!(Msg::RootUnchanged, root, policy)),
+        PushOutcome::DryRun { from, calldata } => {
+            info!("{}", tr!(Msg::RootDryRun, from, calldata))
+        }
+        PushOutcome::Pushed { from, tx } => {
+            info!("{}", tr!(Msg::RootPushed, root, policy, from, tx))
+        }
+    }
     Ok(())
 }
 
-fn ensure_geoip_database(refresh: bool) -> anyhow::Result<PathBuf> {
+/// Verify the cached GeoIP database, failing if it is corrupted or does not match upstream.
+fn db_verify() -> anyhow::Result<()> {
     let path = get_cache_path();
+    if !path.exists() {
+        return Err(anyhow::anyhow!(tr!(Msg::DbNotFound, path.display()))
+            .context(ZkipCliError::DatabaseUnavailable));
+    }
+    info!("{}", tr!(Msg::Verifying, path.display()));
 
-    if refresh || !path.exists() || is_cache_stale(&path) {
-        let reason = if refresh {
-            "refresh requested"
-        } else if !path.exists() {
-            "cache not found"
-        } else {
-            "cache older than 30 days"
-        };
-        println!("Updating GeoIP database ({})...", reason);
+    let mut failed = false;
 
-        if let Err(e) = fetch_geoip_database(&path) {
-            if path.exists() {
-                eprintln!("Warning: Failed to fetch GeoIP database: {}. Using cached version.", e);
-            } else {
-                return Err(e);
-            }
+    let local = sha256_file(&path)?;
+    info!("{}", tr!(Msg::LocalSha256, hex::encode(local)));
+
+    let upstream = fetch_upstream_checksum().context(ZkipCliError::UpstreamUnavailable)?;
+    info!(
+        "{}",
+        tr!(
+            Msg::UpstreamSha256,
+            upstream.version,
+            hex::encode(upstream.sha256)
+        )
+    );
+    if local == upstream.sha256 {
+        info!("{}", tr!(Msg::ChecksumOk));
+    } else {
+        warn!("{}", tr!(Msg::ChecksumMismatch));
+        failed = true;
+    }
+
+    let report = validate_csv(&path)?;
+    info!("{}", tr!(Msg::Rows, report.rows));
+    if report.rows == 0 {
+        warn!("{}", tr!(Msg::StructureEmpty));
+        failed = true;
+    } else if report.issues.is_empty() {
+        info!("{}", tr!(Msg::StructureOk));
+    } else {
+        warn!("{}", tr!(Msg::StructureIssues, report.issues.len()));
+        for issue in report.issues.iter().take(20) {
+            warn!("  {}", issue);
         }
+        if report.issues.len() > 20 {
+            warn!("{}", tr!(Msg::MoreIssues, report.issues.len() - 20));
+        }
+        failed = true;
+    }
+
+    if failed {
+        return Err(
+            anyhow::anyhow!(tr!(Msg::DbVerifyFailed)).context(ZkipCliError::DatabaseCorrupted)
+        );
+    }
+    info!("{}", tr!(Msg::DbVerified));
+    Ok(())
+}
+
+/// Execute every guest variant on the bundled vectors and check the decoded public values.
+fn selftest(mock_prove: bool) -> anyhow::Result<()> {
+    let mut failures = 0;
+    for &variant in ProgramVariant::ALL {
+        info!("{}", tr!(Msg::SelftestVariant, variant.id()));
+        failures += selftest_variant(variant, mock_prove)?;
     }
 
-    Ok(path)
+    if failures > 0 {
+        return Err(anyhow::anyhow!(tr!(
+            Msg::SelftestFailedCount,
+            failures,
+            SELFTEST_VECTORS.len() * ProgramVariant::ALL.len()
+        ))
+        .context(ZkipCliError::SelftestFailed));
+    }
+    info!("{}", tr!(Msg::SelftestPassed));
+    Ok(())
 }
 
-/// Load country codes from CSV file.
-fn load_country_codes() -> anyhow::Result<HashMap<String, u16>> {
-    let csv_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../data/countries.csv");
-    let file = File::open(csv_path).context("Failed to open countries.csv")?;
-    let reader = BufReader::new(file);
+/// Run the selftest vectors against one variant, returning the number of failures.
+fn selftest_variant(variant: ProgramVariant, mock_prove: bool) -> anyhow::Result<usize> {
+    let client = ProverClient::builder().mock().build();
+    let dataset = selftest_dataset_path();
+    // Parsed once: every vector filters the same rows
+    let db = load_geo_db(&dataset, None).context(ZkipCliError::DatabaseCorrupted)?;
+
+    let mut failures = 0;
+    let mut first_stdin = None;
+    for vector in SELFTEST_VECTORS {
+        let ip = ip_to_u32(vector.ip).context(ZkipCliError::InvalidIp)?;
+        let (_, excluded_countries) =
+            parse_excluded_countries(vector.exclude).context(ZkipCliError::InvalidCountry)?;
+        let excluded = ExcludedRanges::from_geo_db(&db, &excluded_countries)
+            .context(ZkipCliError::InvalidCountry)?;
+        let disclosure = match vector.region {
+            Some(_) => Some(
+                resolve_disclosure(ip, Disclosure::Region, &excluded_countries, &excluded)
+                    .context(ZkipCliError::DatabaseCorrupted)?,
+            ),
+            None => None,
+        };
+        let stdin = build_stdin(&SharedInputs {
+            ips: &[ip],
+            excluded_ranges: &excluded.ranges,
+            excluded_countries: &excluded_countries,
+            timestamp: SELFTEST_TIMESTAMP,
+            disclosure,
+            anchor: None,
+            batch: false,
+            inventory: false,
+            threshold: None,
+            ips_v6: &[],
+            excluded_ranges_v6: &[],
+            range_counts: &excluded.counts,
+            include: false,
+            denylist: None,
+            policy: None,
+            per_country: false,
+            gap: None,
+            attestation: None,
+            web_proof: None,
+            snapshot_signature: None,
+            time_beacon: None,
+            companion: None,
+            nullifier: None,
+            challenge: None,
+            valid_for: None,
+            snapshot: None,
+            app_context: None,
+            prefix_salt: None,
+            block: None,
+        });
+
+        let (output, _) = client
+            .execute(variant.elf(), &stdin)
+            .run()
+            .context(ZkipCliError::ExecutionFailed)?;
+        let DecodedPublicValues {
+            values: decoded,
+            disclosed,
+            ..
+        } = decode_public_values(output.as_slice()).context(ZkipCliError::DecodeFailed)?;
 
-    let mut codes = HashMap::new();
-    for (i, line) in reader.lines().enumerate() {
-        if i == 0 {
-            continue;
+        let passed = decoded.is_excluded == vector.expected
+            && decoded.timestamp == SELFTEST_TIMESTAMP
+            && decoded.excluded_countries == excluded_countries
+            && decoded.dataset_root.0 == excluded.root(&excluded_countries)
+            && disclosed.map(|(_, region)| region) == vector.region;
+        let msg = if passed {
+            Msg::SelftestPass
+        } else {
+            Msg::SelftestFail
+        };
+        info!(
+            "{}",
+            tr!(
+                msg,
+                vector.ip,
+                vector.exclude,
+                decoded.is_excluded,
+                vector.expected
+            )
+        );
+        if !passed {
+            failures += 1;
         }
-        let line = line.context("Failed to read line")?;
-        let fields: Vec<&str> = line.split(',').collect();
-        if fields.len() >= 4 {
-            let alpha2 = fields[1].to_uppercase();
-            if let Ok(numeric) = fields[3].parse::<u16>() {
-                codes.insert(alpha2, numeric);
-            }
+        first_stdin.get_or_insert(stdin);
+    }
+
+    if mock_prove {
+        if let Some(stdin) = first_stdin {
+            let (pk, vk) = client.setup(variant.elf());
+            let proof = client
+                .prove(&pk, &stdin)
+                .run()
+                .context(ZkipCliError::ProvingFailed)?;
+            client
+                .verify(&proof, &vk)
+                .context(ZkipCliError::VerificationFailed)?;
+            info!("{}", tr!(Msg::MockProofOk));
         }
     }
-    Ok(codes)
+
+    Ok(failures)
 }
 
-/// Parse comma-separated country codes and resolve to numeric codes.
-fn parse_excluded_countries(exclude_arg: &str) -> anyhow::Result<(Vec<String>, Vec<u16>)> {
+/// Lint a policy file, failing if it has errors.
+fn policy_lint(path: &Path) -> anyhow::Result<()> {
+    let policy = load_policy(path).context(ZkipCliError::InvalidPolicy)?;
     let country_codes = load_country_codes()?;
-    let mut alpha2_codes = Vec::new();
-    let mut numeric_codes = Vec::new();
-
-    for code in exclude_arg.split(',') {
-        let code = code.trim().to_uppercase();
-        if code.is_empty() {
-            continue;
-        }
-        match country_codes.get(&code) {
-            Some(&numeric) => {
-                alpha2_codes.push(code);
-                numeric_codes.push(numeric);
-            }
-            None => bail!("Unknown country code: {}", code),
+
+    let cache = get_cache_path();
+    let range_counts = if cache.exists() {
+        Some(count_ranges_by_country(&cache).context(ZkipCliError::DatabaseCorrupted)?)
+    } else {
+        warn!("{}", tr!(Msg::PolicyRangeCountsSkipped));
+        None
+    };
+
+    let report = lint_policy(&policy, &country_codes, range_counts.as_ref());
+    for finding in &report.findings {
+        match finding.severity {
+            Severity::Error => error!("{}", finding.message),
+            Severity::Warning => warn!("{}", finding.message),
         }
     }
 
-    if numeric_codes.is_empty() {
-        bail!("No valid country codes provided");
+    let codes: Vec<&str> = report
+        .countries
+        .iter()
+        .map(|(code, _)| code.as_str())
+        .collect();
+    info!(
+        "{}",
+        tr!(Msg::PolicyCountries, codes.len(), codes.join(","))
+    );
+    info!("{}", tr!(Msg::PolicyHash, hex::encode(report.hash)));
+    if let Some(rule_hash) = report.rule_hash {
+        info!("{}", tr!(Msg::PolicyRuleHash, hex::encode(rule_hash)));
     }
 
-    Ok((alpha2_codes, numeric_codes))
+    if report.has_errors() {
+        let errors = report
+            .findings
+            .iter()
+            .filter(|f| f.severity == Severity::Error)
+            .count();
+        return Err(
+            anyhow::anyhow!(tr!(Msg::PolicyInvalid, errors)).context(ZkipCliError::InvalidPolicy)
+        );
+    }
+    info!("{}", tr!(Msg::PolicyOk));
+    Ok(())
 }
 
-/// Load IPv4 ranges for specified countries from the GeoIP database.
-fn load_ip_ranges_for_countries(path: &PathBuf, country_codes: &[String]) -> anyhow::Result<Vec<(u32, u32)>> {
-    let file = File::open(path).context("Failed to open GeoIP database")?;
-    let reader = BufReader::new(file);
+/// Register the vkey of every embedded variant under `version`.
+fn registry_add(version: Option<String>) -> anyhow::Result<()> {
+    let version = version.unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string());
+    let prover = ProverClient::builder().cpu().build();
 
-    let mut ranges = Vec::new();
-    for line in reader.lines() {
-        let line = line.context("Failed to read line")?;
-        let fields: Vec<&str> = line.split(',').collect();
-        if fields.len() >= 3 {
-            let country = fields[2].to_uppercase();
-            if country_codes.contains(&country) {
-                let start: u32 = fields[0].parse().context("Invalid start IP")?;
-                let end: u32 = fields[1].parse().context("Invalid end IP")?;
-                ranges.push((start, end));
-            }
+    let dir = registry_dir();
+    let mut registry = Registry::load(&dir)?;
+    for &variant in ProgramVariant::ALL {
+        let (_, vk) = prover.setup(variant.elf());
+        let vkey = vk.bytes32();
+        if registry
+            .add(&dir, &version, variant, &vkey, &vk)
+            .context(ZkipCliError::InvalidArguments)?
+        {
+            info!("{}", tr!(Msg::RegistryAdded, version, variant.id(), vkey));
+        } else {
+            info!("{}", tr!(Msg::RegistryUnchanged, version, variant.id()));
         }
     }
+    registry.save(&dir)
+}
 
-    Ok(ranges)
+fn registry_list() -> anyhow::Result<()> {
+    let dir = registry_dir();
+    let registry = Registry::load(&dir)?;
+    if registry.programs.is_empty() {
+        info!("{}", tr!(Msg::RegistryEmpty, dir.display()));
+    }
+    for entry in &registry.programs {
+        let msg = if entry.revoked {
+            Msg::RegistryEntryRevoked
+        } else {
+            Msg::RegistryEntry
+        };
+        info!(
+            "{}",
+            tr!(msg, entry.version, entry.variant.id(), entry.vkey)
+        );
+    }
+    Ok(())
 }
 
-fn main() -> anyhow::Result<()> {
-    sp1_sdk::utils::setup_logger();
-    dotenv::dotenv().ok();
+fn registry_revoke(version: &str) -> anyhow::Result<()> {
+    let dir = registry_dir();
+    let mut registry = Registry::load(&dir)?;
+    if registry
+        .revoke(version)
+        .context(ZkipCliError::InvalidArguments)?
+    {
+        registry.save(&dir)?;
+        info!("{}", tr!(Msg::RegistryRevoked, version));
+    } else {
+        info!("{}", tr!(Msg::RegistryAlreadyRevoked, version));
+    }
+    Ok(())
+}
 
-    let args = Args::parse();
+/// What `verify` requires of the public values, beyond a valid proof.
+struct Requirements<'a> {
+    dataset_root: Option<[u8; 32]>,
+    root_manifest: Option<&'a RootManifest>,
+    min_anonymity: Option<usize>,
+    policy_hash: Option<[u8; 32]>,
+    ranges_digest: Option<[u8; 32]>,
+    attester: Option<Vec<u8>>,
+    /// Server name of a web proof.
+    web_server: Option<&'a str>,
+    publisher: Option<[u8; 32]>,
+    /// In seconds.
+    max_db_age: Option<u64>,
+    time_server: Option<[u8; 32]>,
+    /// RPC endpoint and largest age in blocks.
+    anchor: Option<(&'a str, u64)>,
+    inventory_root: Option<[u8; 32]>,
+    /// `renew::vkey_bytes` of the companion program.
+    companion: Option<[u8; 32]>,
+    challenge: Option<[u8; 32]>,
+    /// Snapshot hashes accepted; any if empty.
+    snapshot_hashes: Vec<[u8; 32]>,
+    app_context: Option<[u8; 32]>,
+    /// Operators a bundle must be signed by one of; any, or none, if empty.
+    operators: Vec<VerifyingKey>,
+}
 
-    if args.execute == args.prove {
-        eprintln!("Error: You must specify either --execute or --prove");
-        std::process::exit(1);
+/// Verify a saved proof against the embedded program and every registered version,
+/// reporting which one produced it.
+fn verify(
+    path: &Path,
+    revocations: &Path,
+    revocation_key: Option<&str>,
+    replay: Option<(&Path, u64)>,
+    cosigners: Option<(&Path, PathBuf)>,
+    requirements: Requirements,
+) -> anyhow::Result<()> {
+    let Requirements {
+        dataset_root: expected_root,
+        root_manifest,
+        min_anonymity,
+        policy_hash: expected_policy,
+        ranges_digest: expected_digest,
+        attester: expected_attester,
+        web_server: expected_web_server,
+        publisher: expected_publisher,
+        max_db_age,
+        time_server: expected_time_server,
+        anchor: anchor_check,
+        inventory_root: expected_inventory,
+        companion: expected_companion,
+        challenge: expected_challenge,
+        snapshot_hashes,
+        app_context: expected_app_context,
+        operators,
+    } = requirements;
+    let proof = if is_bundle(path) {
+        let bundle = ProofBundle::load(path).context(ZkipCliError::InvalidArguments)?;
+        check_operator(&bundle, path, &operators)?;
+        bundle.proof().context(ZkipCliError::InvalidArguments)?
+    } else if !operators.is_empty() {
+        return Err(
+            anyhow::anyhow!(tr!(Msg::OperatorNeedsBundle, path.display()))
+                .context(ZkipCliError::OperatorSignatureInvalid),
+        );
+    } else {
+        load_proof(path).context(ZkipCliError::InvalidArguments)?
+    };
+    check_revocations(&proof, revocations, revocation_key)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .with_context(|| tr!(Msg::ClockBeforeEpoch))?
+        .as_secs();
+    let id = proof_id(&proof)?;
+    if let Some((policy, envelope)) = cosigners {
+        check_cosignatures(&id, policy, &envelope)?;
     }
+    let replay_store = match replay {
+        Some((store, ttl)) => {
+            let store = ReplayStore::open(store).context(ZkipCliError::InvalidArguments)?;
+            let key = replay_key(proof.public_values.as_slice());
+            if let Some(expires) = store.consumed_until(&key, now) {
+                return Err(anyhow::anyhow!(tr!(Msg::ProofReplayed, key, expires))
+                    .context(ZkipCliError::ProofReplayed));
+            }
+            Some((store, key, ttl))
+        }
+        None => None,
+    };
+    let prover = ProverClient::builder().cpu().build();
 
-    // Ensure GeoIP database is available and fresh
-    let geoip_path = ensure_geoip_database(args.refresh)?;
+    // An aggregate comes from the aggregation guest and names the chunk guest it verified,
+    // which must be this release's
+    let (_, aggregate_vk) = prover.setup(AGGREGATE_ELF);
+    if prover.verify(&proof, &aggregate_vk).is_ok() {
+        let (_, chunk_vk) = prover.setup(CHUNK_ELF);
+        let chunk_vkey = decode_public_values(proof.public_values.as_slice())
+            .ok()
+            .and_then(|decoded| decoded.aggregation)
+            .map(|aggregation| aggregation.chunk_vkey);
+        if chunk_vkey != Some(vkey_bytes(&chunk_vk.hash_u32())) {
+            let committed =
+                chunk_vkey.map_or("?".to_string(), |vkey| format!("0x{}", hex::encode(vkey)));
+            return Err(anyhow::anyhow!(tr!(Msg::ChunkProgramUnknown, committed))
+                .context(ZkipCliError::VerificationFailed));
+        }
+        info!("{}", tr!(Msg::VerifyAggregateOk, aggregate_vk.bytes32()));
+    } else {
+        let candidates = program_candidates(&prover)?;
+        // A renewal comes from the renewal guest and names the program of the proof it
+        // renews
+        let (_, renew_vk) = prover.setup(RENEW_ELF);
+        let found = if prover.verify(&proof, &renew_vk).is_ok() {
+            let previous = decode_public_values(proof.public_values.as_slice())
+                .ok()
+                .and_then(|decoded| decoded.renewal)
+                .map(|renewal| renewal.previous_vkey);
+            candidates
+                .into_iter()
+                .find(|(_, _, _, vk)| previous == Some(vkey_bytes(&vk.hash_u32())))
+        } else {
+            candidates
+                .into_iter()
+                .find(|(_, _, _, vk)| prover.verify(&proof, vk).is_ok())
+        };
+        let Some((version, variant, revoked, vk)) = found else {
+            return Err(
+                anyhow::anyhow!(tr!(Msg::VerifyNoMatch)).context(ZkipCliError::VerificationFailed)
+            );
+        };
+        if revoked {
+            return Err(
+                anyhow::anyhow!(tr!(Msg::VerifyRevoked, version, variant.id()))
+                    .context(ZkipCliError::RevokedVersion),
+            );
+        }
+        info!(
+            "{}",
+            tr!(Msg::VerifyOk, version, variant.id(), vk.bytes32())
+        );
+    }
 
-    let client = ProverClient::from_env();
+    let DecodedPublicValues {
+        values: decoded,
+        mode,
+        denylist,
+        policy,
+        country_results,
+        coverage,
+        is_public_ip,
+        ranges_digest,
+        nullifier,
+        version,
+        snapshot,
+        policy_id,
+        app_context,
+        prefix,
+        disclosed,
+        anchor,
+        address_count,
+        clear_count,
+        inventory_root,
+        threshold,
+        block,
+        gap,
+        attestation,
+        publisher,
+        time_beacon,
+        aggregation,
+        renewal,
+        companion,
+        challenge,
+        validity,
+        legacy,
+    } = decode_versioned(proof.public_values.as_slice())?;
+    log_result(mode, decoded.is_excluded);
+    info!("{}", tr!(Msg::Timestamp, decoded.timestamp));
+    log_renewal(renewal.as_ref());
+    info!(
+        "{}",
+        tr!(
+            Msg::CheckedCountries,
+            format!("{:?}", decoded.excluded_countries)
+        )
+    );
+    log_country_results(&decoded.excluded_countries, country_results.as_deref());
+    log_denylist(denylist.as_ref());
+    log_policy(policy.as_ref());
+    log_attestation(attestation.as_ref());
+    log_publisher(publisher.as_ref());
+    log_time_beacon(time_beacon.as_ref());
+    log_aggregation(aggregation.as_ref());
+    if let Some(expected) = expected_time_server {
+        let Some(time_beacon) = &time_beacon else {
+            return Err(
+                anyhow::anyhow!(tr!(Msg::NotTimedProof)).context(ZkipCliError::TimeBeaconInvalid)
+            );
+        };
+        if time_beacon.pubkey != expected {
+            return Err(anyhow::anyhow!(tr!(
+                Msg::TimeServerMismatch,
+                format!("0x{}", hex::encode(time_beacon.pubkey)),
+                format!("0x{}", hex::encode(expected))
+            ))
+            .context(ZkipCliError::TimeBeaconInvalid));
+        }
+    }
+    if let Some(expected) = expected_publisher {
+        let Some(publisher) = &publisher else {
+            return Err(anyhow::anyhow!(tr!(Msg::NotSignedSnapshotProof))
+                .context(ZkipCliError::SnapshotSignatureInvalid));
+        };
+        if publisher.pubkey != expected {
+            return Err(anyhow::anyhow!(tr!(
+                Msg::PublisherMismatch,
+                format!("0x{}", hex::encode(publisher.pubkey)),
+                format!("0x{}", hex::encode(expected))
+            ))
+            .context(ZkipCliError::SnapshotSignatureInvalid));
+        }
+    }
+    if let Some(max_db_age) = max_db_age {
+        let Some(publisher) = &publisher else {
+            return Err(
+                anyhow::anyhow!(tr!(Msg::DbAgeUnbounded)).context(ZkipCliError::DatabaseTooOld)
+            );
+        };
+        if publisher.max_age > max_db_age {
+            return Err(anyhow::anyhow!(tr!(
+                Msg::DbAgeTooLoose,
+                publisher.max_age / SECONDS_PER_DAY,
+                max_db_age / SECONDS_PER_DAY
+            ))
+            .context(ZkipCliError::DatabaseTooOld));
+        }
+    }
+    if let Some(expected) = expected_attester {
+        let Some(attestation) = &attestation else {
+            return Err(anyhow::anyhow!(tr!(Msg::NotAttestedProof))
+                .context(ZkipCliError::AttestationInvalid));
+        };
+        if attestation.pubkey != expected {
+            return Err(anyhow::anyhow!(tr!(
+                Msg::AttesterMismatch,
+                format!("0x{}", hex::encode(&attestation.pubkey)),
+                format!("0x{}", hex::encode(&expected))
+            ))
+            .context(ZkipCliError::AttestationInvalid));
+        }
+    }
+    if let Some(expected) = expected_web_server {
+        let nonce = attestation.as_ref().map(|attestation| attestation.nonce);
+        if nonce != Some(server_id(expected)) {
+            return Err(anyhow::anyhow!(tr!(Msg::NotWebProof, expected))
+                .context(ZkipCliError::AttestationInvalid));
+        }
+    }
+    if let Some(expected) = expected_policy {
+        let Some(policy) = &policy else {
+            return Err(
+                anyhow::anyhow!(tr!(Msg::NotPolicyProof)).context(ZkipCliError::PolicyMismatch)
+            );
+        };
+        if policy.policy_hash != expected {
+            return Err(anyhow::anyhow!(tr!(
+                Msg::PolicyMismatch,
+                format!("0x{}", hex::encode(policy.policy_hash)),
+                format!("0x{}", hex::encode(expected))
+            ))
+            .context(ZkipCliError::PolicyMismatch));
+        }
+    }
+    if let Some(min) = min_anonymity {
+        if mode != MODE_INCLUSION && mode != MODE_COMBINED {
+            return Err(anyhow::anyhow!(tr!(Msg::NotInclusionProof))
+                .context(ZkipCliError::AnonymitySetTooSmall));
+        }
+        let size = decoded.excluded_countries.len();
+        if size < min {
+            return Err(anyhow::anyhow!(tr!(Msg::AnonymitySetTooSmall, size, min))
+                .context(ZkipCliError::AnonymitySetTooSmall));
+        }
+    }
+    log_dataset_root(decoded.dataset_root.0, legacy);
+    log_coverage(coverage);
+    log_ranges_digest(ranges_digest);
+    log_nullifier(nullifier.as_ref());
+    log_version(version);
+    log_public_ip(is_public_ip);
+    log_gap(gap);
+    if is_public_ip == Some(false) {
+        return Err(anyhow::anyhow!(tr!(Msg::ReservedIpProof)).context(ZkipCliError::ReservedIp));
+    }
+    let expected_root = match root_manifest {
+        Some(manifest) => Some(
+            manifest
+                .expected_root(&decoded.excluded_countries)
+                .context(ZkipCliError::DatasetRootMismatch)?,
+        ),
+        None => expected_root,
+    };
+    if let Some(expected) = expected_root {
+        if legacy || decoded.dataset_root.0 != expected {
+            return Err(anyhow::anyhow!(tr!(
+                Msg::DatasetRootMismatch,
+                format!("0x{}", hex::encode(decoded.dataset_root)),
+                format!("0x{}", hex::encode(expected))
+            ))
+            .context(ZkipCliError::DatasetRootMismatch));
+        }
+    }
+    // A manifest pins a combined proof's denylist too; a single --dataset-root cannot
+    if let (Some(manifest), Some(denylist)) = (root_manifest, &denylist) {
+        let expected = manifest
+            .expected_root(&denylist.excluded_countries)
+            .context(ZkipCliError::DatasetRootMismatch)?;
+        if denylist.dataset_root != expected {
+            return Err(anyhow::anyhow!(tr!(
+                Msg::DenylistRootMismatch,
+                format!("0x{}", hex::encode(denylist.dataset_root)),
+                format!("0x{}", hex::encode(expected))
+            ))
+            .context(ZkipCliError::DatasetRootMismatch));
+        }
+    }
+    if let Some(expected) = expected_digest {
+        let Some(digest) = ranges_digest else {
+            return Err(anyhow::anyhow!(tr!(Msg::RangesDigestMissing))
+                .context(ZkipCliError::DatasetRootMismatch));
+        };
+        if digest != expected {
+            return Err(anyhow::anyhow!(tr!(
+                Msg::RangesDigestMismatch,
+                format!("0x{}", hex::encode(digest)),
+                format!("0x{}", hex::encode(expected))
+            ))
+            .context(ZkipCliError::DatasetRootMismatch));
+        }
+    }
+    log_snapshot(snapshot.as_ref());
+    log_policy_id(policy_id);
+    if !snapshot_hashes.is_empty() {
+        let hash = snapshot
+            .map(|snapshot| snapshot.hash)
+            .filter(|&hash| hash != [0; 32]);
+        if !hash.is_some_and(|hash| snapshot_hashes.contains(&hash)) {
+            let committed = hash.map_or("none".to_string(), |hash| {
+                format!("0x{}", hex::encode(hash))
+            });
+            let accepted = snapshot_hashes
+                .iter()
+                .map(|hash| format!("0x{}", hex::encode(hash)))
+                .collect::<Vec<_>>();
+            return Err(anyhow::anyhow!(tr!(
+                Msg::SnapshotHashMismatch,
+                committed,
+                accepted.join(", ")
+            ))
+            .context(ZkipCliError::DatasetRootMismatch));
+        }
+    }
+    log_prefix(prefix.as_ref());
+    log_block(block.as_ref());
+    log_app_context(app_context);
+    if let Some(expected) = expected_app_context {
+        let context = app_context.filter(|&context| context != [0; 32]);
+        if context != Some(expected) {
+            let committed = context.map_or("none".to_string(), |context| {
+                format!("0x{}", hex::encode(context))
+            });
+            return Err(anyhow::anyhow!(tr!(
+                Msg::AppContextMismatch,
+                committed,
+                format!("0x{}", hex::encode(expected))
+            ))
+            .context(ZkipCliError::AppContextMismatch));
+        }
+    }
+    log_disclosure(disclosed);
+    log_anchor(anchor);
+    if let Some((rpc_url, max_age)) = anchor_check {
+        let Some(anchor) = anchor else {
+            return Err(
+                anyhow::anyhow!(tr!(Msg::NotAnchoredProof)).context(ZkipCliError::AnchorStale)
+            );
+        };
+        let age = anchor_age(rpc_url, anchor).context(ZkipCliError::AnchorUnavailable)?;
+        match age {
+            None => {
+                return Err(anyhow::anyhow!(tr!(Msg::AnchorNotOnChain, anchor.0))
+                    .context(ZkipCliError::AnchorStale));
+            }
+            Some(age) if age > max_age => {
+                return Err(
+                    anyhow::anyhow!(tr!(Msg::AnchorTooOld, anchor.0, age, max_age))
+                        .context(ZkipCliError::AnchorStale),
+                );
+            }
+            Some(age) => info!(age, "{}", tr!(Msg::AnchorRecent, anchor.0, age)),
+        }
+    }
+    log_batch(address_count, clear_count);
+    log_threshold(threshold, address_count);
+    log_inventory(inventory_root);
+    if let Some(expected) = expected_inventory {
+        let Some(root) = inventory_root else {
+            return Err(anyhow::anyhow!(tr!(Msg::NotInventoryProof))
+                .context(ZkipCliError::InventoryMismatch));
+        };
+        if root != expected {
+            return Err(anyhow::anyhow!(tr!(
+                Msg::InventoryMismatch,
+                format!("0x{}", hex::encode(root)),
+                format!("0x{}", hex::encode(expected))
+            ))
+            .context(ZkipCliError::InventoryMismatch));
+        }
+    }
+    log_companion(companion.as_ref());
+    if let Some(expected) = expected_companion {
+        let Some(companion) = &companion else {
+            return Err(anyhow::anyhow!(tr!(Msg::NotComposedProof))
+                .context(ZkipCliError::CompanionMismatch));
+        };
+        if companion.vkey != expected {
+            return Err(anyhow::anyhow!(tr!(
+                Msg::CompanionMismatch,
+                format!("0x{}", hex::encode(companion.vkey)),
+                format!("0x{}", hex::encode(expected))
+            ))
+            .context(ZkipCliError::CompanionMismatch));
+        }
+    }
+    log_challenge(challenge);
+    if let Some(expected) = expected_challenge {
+        let Some(challenge) = challenge else {
+            return Err(anyhow::anyhow!(tr!(Msg::NotChallengedProof))
+                .context(ZkipCliError::ChallengeMismatch));
+        };
+        if challenge != expected {
+            return Err(anyhow::anyhow!(tr!(
+                Msg::ChallengeMismatch,
+                format!("0x{}", hex::encode(challenge)),
+                format!("0x{}", hex::encode(expected))
+            ))
+            .context(ZkipCliError::ChallengeMismatch));
+        }
+    }
+    log_validity(validity);
+    if let Some(validity) = validity {
+        if now < validity.not_before {
+            return Err(anyhow::anyhow!(tr!(
+                Msg::ProofNotYetValid,
+                format_utc(validity.not_before)
+            ))
+            .context(ZkipCliError::ProofExpired));
+        }
+        if !validity.contains(now) {
+            return Err(
+                anyhow::anyhow!(tr!(Msg::ProofExpired, format_utc(validity.not_after)))
+                    .context(ZkipCliError::ProofExpired),
+            );
+        }
+    }
 
-    let ip = ip_to_u32(&args.ip).context("failed to parse IP address")?;
-    let (alpha2_codes, excluded_countries) = parse_excluded_countries(&args.exclude)?;
+    if let Some((mut store, key, ttl)) = replay_store {
+        store
+            .consume(&key, now, ttl)
+            .context(ZkipCliError::ProofReplayed)?;
+    }
+    Ok(())
+}
 
-    let excluded_ranges = load_ip_ranges_for_countries(&geoip_path, &alpha2_codes)?;
-    println!("Loaded {} IP ranges for {:?}", excluded_ranges.len(), alpha2_codes);
+/// Every program a proof may come from: `(version, variant, revoked, vk)` for each
+/// registered version, and for each embedded variant not registered yet.
+fn program_candidates(
+    prover: &CpuProver,
+) -> anyhow::Result<Vec<(String, ProgramVariant, bool, SP1VerifyingKey)>> {
+    let dir = registry_dir();
+    let registry = Registry::load(&dir)?;
+    let mut candidates = Vec::new();
+    for &variant in ProgramVariant::ALL {
+        let (_, embedded) = prover.setup(variant.elf());
+        if registry.find_vkey(&embedded.bytes32()).is_none() {
+            let version = env!("CARGO_PKG_VERSION").to_string();
+            candidates.push((version, variant, false, embedded));
+        }
+    }
+    for entry in &registry.programs {
+        let vk = load_verifying_key(&dir, entry)?;
+        candidates.push((entry.version.clone(), entry.variant, entry.revoked, vk));
+    }
+    Ok(candidates)
+}
 
-    let timestamp: u32 = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .context("System clock is before Unix epoch")?
-        .as_secs() as u32;
+/// Prove `address` against `excluded`'s ranges one chunk at a time, then aggregate the
+/// chunk proofs into one (`zkip_lib::chunk`), compressed if `compressed`.
+fn prove_chunked(
+    client: &EnvProver,
+    address: Address,
+    timestamp: u64,
+    excluded_countries: &[u16],
+    excluded: &ExcludedRanges,
+    compressed: bool,
+) -> anyhow::Result<(SP1ProofWithPublicValues, SP1VerifyingKey)> {
+    let (stdins, chunk_counts) = chunk_stdins(address, excluded_countries, excluded);
+    let (chunk_pk, chunk_vk) = client.setup(CHUNK_ELF);
+    let mut chunks = Vec::with_capacity(stdins.len());
+    for (index, stdin) in stdins.iter().enumerate() {
+        info!("{}", tr!(Msg::ProvingChunk, index + 1, stdins.len()));
+        // The aggregation guest verifies chunk proofs, which takes them compressed
+        let chunk = client
+            .prove(&chunk_pk, stdin)
+            .compressed()
+            .run()
+            .context(ZkipCliError::ProvingFailed)?;
+        chunks.push(chunk);
+    }
 
     let mut stdin = SP1Stdin::new();
-    stdin.write(&ip);
-    stdin.write(&excluded_ranges);
-    stdin.write(&excluded_countries);
-    stdin.write(&timestamp);
-
-    println!(
-        "Testing IP: {} ({}) against excluded countries: {:?}",
-        args.ip, ip, excluded_countries
-    );
+    stdin.write(&AggregationInput {
+        chunk_vkey: chunk_vk.hash_u32(),
+        ip: address,
+        timestamp,
+        excluded_countries: excluded_countries.to_vec(),
+        chunk_counts,
+        chunks: chunks
+            .iter()
+            .map(|chunk| chunk.public_values.to_vec())
+            .collect(),
+    });
+    for chunk in &chunks {
+        if let SP1Proof::Compressed(inner) = &chunk.proof {
+            stdin.write_proof(*inner.clone(), chunk_vk.vk.clone());
+        }
+    }
 
-    if args.execute {
-        let (output, report) = client
-            .execute(ZKIP_ELF, &stdin)
-            .run()
-            .context("failed to execute zkvm program")?;
-        println!("Program executed successfully.");
+    let (pk, vk) = client.setup(AGGREGATE_ELF);
+    let request = client.prove(&pk, &stdin);
+    let proof = if compressed {
+        request.compressed().run()
+    } else {
+        request.run()
+    }
+    .context(ZkipCliError::ProvingFailed)?;
+    info!("{}", tr!(Msg::ProofGenerated));
+    client
+        .verify(&proof, &vk)
+        .context(ZkipCliError::VerificationFailed)?;
+    Ok((proof, vk))
+}
 
-        let decoded = PublicValuesStruct::abi_decode(output.as_slice())
-            .context("failed to decode public values")?;
-        let PublicValuesStruct {
-            is_excluded,
-            timestamp,
-            excluded_countries,
-        } = decoded;
+/// Renew the compressed proof at `path` under the current time, reading none of its ranges.
+fn renew(path: &Path, save_proof: Option<&Path>, audit_log: &Path) -> anyhow::Result<()> {
+    let proof = load_proof(path).context(ZkipCliError::InvalidArguments)?;
+    let SP1Proof::Compressed(inner) = &proof.proof else {
+        return Err(
+            anyhow::anyhow!(tr!(Msg::RenewNeedsCompressed, path.display()))
+                .context(ZkipCliError::InvalidArguments),
+        );
+    };
+    let decoded =
+        decode_public_values(proof.public_values.as_slice()).context(ZkipCliError::DecodeFailed)?;
+    if decoded.renewal.is_some() {
+        return Err(anyhow::anyhow!(tr!(Msg::RenewOfRenewal, path.display()))
+            .context(ZkipCliError::InvalidArguments));
+    }
+    if decoded.validity.is_some() {
+        return Err(anyhow::anyhow!(tr!(Msg::RenewOfWindowed, path.display()))
+            .context(ZkipCliError::InvalidArguments));
+    }
 
-        println!("Result: is_excluded = {}", is_excluded);
-        println!("Timestamp: {}", timestamp);
-        println!("Checked countries: {:?}", excluded_countries);
+    // The guest takes any vkey and commits it; renewing an unknown or revoked program's
+    // proof would only give a renewal that `verify` rejects
+    let prover = ProverClient::builder().cpu().build();
+    let Some((version, variant, revoked, vk)) = program_candidates(&prover)?
+        .into_iter()
+        .find(|(_, _, _, vk)| prover.verify(&proof, vk).is_ok())
+    else {
+        return Err(
+            anyhow::anyhow!(tr!(Msg::VerifyNoMatch)).context(ZkipCliError::VerificationFailed)
+        );
+    };
+    if revoked {
+        return Err(
+            anyhow::anyhow!(tr!(Msg::VerifyRevoked, version, variant.id()))
+                .context(ZkipCliError::RevokedVersion),
+        );
+    }
 
-        let expected = zkip_lib::is_excluded(ip, excluded_ranges.clone());
-        assert_eq!(is_excluded, expected);
-        println!("Verification passed!");
+    let proof_time =
+        proof_time(TimeSource::System, &[], 0.0, None).context(ZkipCliError::ClockUntrusted)?;
+    let previous_timestamp = decoded.values.timestamp;
+    info!(
+        "{}",
+        tr!(
+            Msg::Renewing,
+            version,
+            variant.id(),
+            previous_timestamp,
+            proof_time.timestamp
+        )
+    );
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&RenewalInput {
+        vkey: vk.hash_u32(),
+        public_values: proof.public_values.to_vec(),
+        timestamp: proof_time.timestamp,
+    });
+    stdin.write_proof(*inner.clone(), vk.vk.clone());
 
-        println!("Number of cycles: {}", report.total_instruction_count());
-    } else {
-        let (pk, vk) = client.setup(ZKIP_ELF);
+    let client = ProverClient::from_env();
+    let (pk, renew_vk) = client.setup(RENEW_ELF);
+    let renewal = client
+        .prove(&pk, &stdin)
+        .compressed()
+        .run()
+        .context(ZkipCliError::ProvingFailed)?;
+    client
+        .verify(&renewal, &renew_vk)
+        .context(ZkipCliError::VerificationFailed)?;
+    info!("{}", tr!(Msg::ProofVerified));
 
-        let proof = client
-            .prove(&pk, &stdin)
-            .run()
-            .context("failed to generate proof")?;
+    record_proof(
+        audit_log,
+        "compressed",
+        "renew",
+        &renew_vk.bytes32(),
+        &decoded.values.excluded_countries,
+        &renewal,
+        &proof_time,
+    )?;
+    if let Some(path) = save_proof {
+        write_proof(&renewal, path)?;
+    }
+    Ok(())
+}
 
-        println!("Successfully generated proof!");
+/// Wrap the compressed proof at `path` in a Groth16 or PLONK proof of the same program and
+/// public values. Only this step needs the gnark circuit artifacts, so it can run on
+/// another machine than the one that proved.
+fn wrap(
+    path: &Path,
+    system: ProofSystem,
+    save_proof: Option<&Path>,
+    audit_log: &Path,
+) -> anyhow::Result<()> {
+    let proof = load_proof(path).context(ZkipCliError::InvalidArguments)?;
+    let SP1Proof::Compressed(inner) = &proof.proof else {
+        return Err(
+            anyhow::anyhow!(tr!(Msg::WrapNeedsCompressed, path.display()))
+                .context(ZkipCliError::InvalidArguments),
+        );
+    };
+    let decoded =
+        decode_public_values(proof.public_values.as_slice()).context(ZkipCliError::DecodeFailed)?;
 
-        client.verify(&proof, &vk).context("failed to verify proof")?;
-        println!("Successfully verified proof!");
+    // The wrapped proof keeps the program's vkey; checking it first means a proof no
+    // release made fails here rather than after the expensive wrap
+    let prover = ProverClient::builder().cpu().build();
+    let (_, aggregate_vk) = prover.setup(AGGREGATE_ELF);
+    let (_, renew_vk) = prover.setup(RENEW_ELF);
+    let mut programs = vec![
+        ("aggregate".to_string(), aggregate_vk),
+        ("renew".to_string(), renew_vk),
+    ];
+    for (version, variant, _, vk) in program_candidates(&prover)? {
+        programs.push((format!("{} {}", version, variant.id()), vk));
+    }
+    let Some((program, vk)) = programs
+        .into_iter()
+        .find(|(_, vk)| prover.verify(&proof, vk).is_ok())
+    else {
+        return Err(
+            anyhow::anyhow!(tr!(Msg::VerifyNoMatch)).context(ZkipCliError::VerificationFailed)
+        );
+    };
+    info!("{}", tr!(Msg::Wrapping, program, system.id()));
+
+    // The same steps `prove().groth16()` and `prove().plonk()` take after compressing
+    let opts = SP1ProverOpts::auto();
+    let shrunk = prover
+        .inner()
+        .shrink(*inner.clone(), opts)
+        .context(ZkipCliError::ProvingFailed)?;
+    let outer = prover
+        .inner()
+        .wrap_bn254(shrunk, opts)
+        .context(ZkipCliError::ProvingFailed)?;
+    let mut wrapped = proof.clone();
+    wrapped.proof = match system {
+        ProofSystem::Groth16 => {
+            let artifacts = try_install_circuit_artifacts("groth16");
+            SP1Proof::Groth16(prover.inner().wrap_groth16_bn254(outer, &artifacts))
+        }
+        ProofSystem::Plonk => {
+            let artifacts = try_install_circuit_artifacts("plonk");
+            SP1Proof::Plonk(prover.inner().wrap_plonk_bn254(outer, &artifacts))
+        }
+    };
+    info!("{}", tr!(Msg::ProofGenerated));
+    prover
+        .verify(&wrapped, &vk)
+        .context(ZkipCliError::VerificationFailed)?;
+    info!("{}", tr!(Msg::ProofVerified));
+
+    // Wrapping changes the proof, not the claim: the timestamp is still the one committed
+    let proof_time = ProofTime {
+        timestamp: decoded.values.timestamp,
+        source: "wrapped".to_string(),
+        beacon: None,
+    };
+    record_proof(
+        audit_log,
+        system.id(),
+        "wrap",
+        &vk.bytes32(),
+        &decoded.values.excluded_countries,
+        &wrapped,
+        &proof_time,
+    )?;
+    if let Some(path) = save_proof {
+        write_proof(&wrapped, path)?;
+    }
+    Ok(())
+}
+
+/// Bundle the proof at `path` (`zkip_script::bundle`) with the program that verifies it,
+/// found as `verify` finds it, sign it with the seed in `sign_key` if given, and write the
+/// bundle to `save_bundle`.
+fn bundle(path: &Path, save_bundle: Option<&Path>, sign_key: Option<&Path>) -> anyhow::Result<()> {
+    let proof = load_proof(path).context(ZkipCliError::InvalidArguments)?;
+    let prover = ProverClient::builder().cpu().build();
+    let version = env!("CARGO_PKG_VERSION").to_string();
+    let (_, aggregate_vk) = prover.setup(AGGREGATE_ELF);
+    let (_, renew_vk) = prover.setup(RENEW_ELF);
+    let mut programs = vec![
+        (version.clone(), "aggregate", false, aggregate_vk),
+        (version, "renew", false, renew_vk),
+    ];
+    for (version, variant, revoked, vk) in program_candidates(&prover)? {
+        programs.push((version, variant.id(), revoked, vk));
+    }
+    let Some((version, variant, revoked, vk)) = programs
+        .into_iter()
+        .find(|(_, _, _, vk)| prover.verify(&proof, vk).is_ok())
+    else {
+        return Err(
+            anyhow::anyhow!(tr!(Msg::VerifyNoMatch)).context(ZkipCliError::VerificationFailed)
+        );
+    };
+    if revoked {
+        return Err(anyhow::anyhow!(tr!(Msg::VerifyRevoked, version, variant))
+            .context(ZkipCliError::RevokedVersion));
+    }
+
+    let output = match save_bundle {
+        Some(output) => output.to_path_buf(),
+        None if is_stdio(path) => PathBuf::from("proof").with_extension(BUNDLE_EXTENSION),
+        None => path.with_extension(BUNDLE_EXTENSION),
+    };
+    let mut bundle = ProofBundle::new(&proof, &vk.bytes32(), &version, variant)?;
+    if let Some(key) = sign_key {
+        let key = load_signing_key(key).context(ZkipCliError::InvalidArguments)?;
+        bundle.sign(&key)?;
+    }
+    bundle
+        .save(&output)
+        .context(ZkipCliError::InvalidArguments)?;
+    info!(
+        "{}",
+        tr!(Msg::BundleWritten, version, variant, output.display())
+    );
+    if let Some(signer) = &bundle.signer {
+        info!("{}", tr!(Msg::BundleSignedBy, signer));
+    }
+    Ok(())
+}
+
+/// Check the bundle at `path` and print the verdict on stdout: its vkey must be a known,
+/// unrevoked build's, as must the program a renewal or aggregate names, the proof must
+/// verify against it, the public values must be a layout no newer than this CLI's, and a
+/// signature must be valid and, with `operators`, one of theirs.
+fn verify_bundle(path: &Path, operators: &[VerifyingKey]) -> anyhow::Result<()> {
+    let bundle = ProofBundle::load(path).context(ZkipCliError::InvalidArguments)?;
+    let proof = bundle.proof().context(ZkipCliError::InvalidArguments)?;
+    let decoded = decode_public_values(&bundle.public_values);
+    let mut verdict = BundleVerdict::new(&bundle, &proof, decoded.as_ref(), operators);
+    let decoded = decoded.ok();
+
+    let prover = ProverClient::builder().cpu().build();
+    let version = env!("CARGO_PKG_VERSION").to_string();
+    let (_, aggregate_vk) = prover.setup(AGGREGATE_ELF);
+    let (_, renew_vk) = prover.setup(RENEW_ELF);
+    let candidates = program_candidates(&prover)?;
+    let mut programs = vec![
+        (version.clone(), "aggregate", false, aggregate_vk),
+        (version, "renew", false, renew_vk),
+    ];
+    for (version, variant, revoked, vk) in &candidates {
+        programs.push((version.clone(), variant.id(), *revoked, vk.clone()));
+    }
+    let found = programs
+        .into_iter()
+        .find(|(_, _, _, vk)| vk.bytes32().eq_ignore_ascii_case(&bundle.vkey));
+    match found {
+        None => verdict.reject(Rejection::UnknownProgram),
+        Some((version, variant, revoked, vk)) => {
+            if revoked {
+                verdict.reject(Rejection::RevokedProgram);
+            }
+            if bundle.program_version != version || bundle.program_variant != variant {
+                verdict.reject(Rejection::ProgramMismatch);
+            }
+            let valid = prover.verify(&proof, &vk).is_ok();
+            if !valid {
+                verdict.reject(Rejection::InvalidProof);
+            }
+            verdict.proof_valid = Some(valid);
+            verdict.program_version = Some(version);
+            verdict.program_variant = Some(variant.to_string());
+
+            // As in `verify`: an aggregate must name this release's chunk guest, and a
+            // renewal a known, unrevoked program
+            if variant == "aggregate" {
+                let (_, chunk_vk) = prover.setup(CHUNK_ELF);
+                let chunk_vkey = decoded
+                    .as_ref()
+                    .and_then(|decoded| decoded.aggregation.as_ref())
+                    .map(|aggregation| aggregation.chunk_vkey);
+                if chunk_vkey != Some(vkey_bytes(&chunk_vk.hash_u32())) {
+                    verdict.reject(Rejection::UnknownProgram);
+                }
+            } else if variant == "renew" {
+                let previous = decoded
+                    .as_ref()
+                    .and_then(|decoded| decoded.renewal.as_ref())
+                    .map(|renewal| renewal.previous_vkey);
+                match candidates
+                    .iter()
+                    .find(|(_, _, _, vk)| previous == Some(vkey_bytes(&vk.hash_u32())))
+                {
+                    None => verdict.reject(Rejection::UnknownProgram),
+                    Some((_, _, true, _)) => verdict.reject(Rejection::RevokedProgram),
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    let json =
+        serde_json::to_string_pretty(&verdict).with_context(|| tr!(Msg::SerializeVerdictFailed))?;
+    println!("{}", json);
+    if let Some(&first) = verdict.rejections.first() {
+        let reasons: Vec<&str> = verdict
+            .rejections
+            .iter()
+            .map(|rejection| rejection.id())
+            .collect();
+        return Err(
+            anyhow::anyhow!(tr!(Msg::BundleRejected, path.display(), reasons.join(", ")))
+                .context(first.error()),
+        );
+    }
+    info!(
+        "{}",
+        tr!(
+            Msg::BundleAccepted,
+            path.display(),
+            bundle.program_version,
+            bundle.program_variant,
+            bundle.vkey
+        )
+    );
+    Ok(())
+}
+
+/// Check the operator signature of the bundle at `path`, if signed, against `operators`, if
+/// any.
+fn check_operator(
+    bundle: &ProofBundle,
+    path: &Path,
+    operators: &[VerifyingKey],
+) -> anyhow::Result<()> {
+    match bundle.verify_signature(operators) {
+        Ok(Some(signer)) => {
+            let signer = hex::encode(signer.to_bytes());
+            if operators.is_empty() {
+                warn!("{}", tr!(Msg::OperatorUnpinned, signer));
+            } else {
+                info!("{}", tr!(Msg::BundleSignedBy, signer));
+            }
+            Ok(())
+        }
+        Ok(None) => Ok(()),
+        Err(rejection) => {
+            Err(
+                anyhow::anyhow!(tr!(Msg::BundleRejected, path.display(), rejection.id()))
+                    .context(rejection.error()),
+            )
+        }
+    }
+}
+
+/// Parse `--operator` keys.
+fn parse_operators(operators: &[String]) -> anyhow::Result<Vec<VerifyingKey>> {
+    operators
+        .iter()
+        .map(|key| parse_public_key(key))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .context(ZkipCliError::InvalidArguments)
+}
+
+/// Log how many addresses a batch proof checked, and how many of them are clear.
+fn log_batch(address_count: Option<u32>, clear_count: Option<u32>) {
+    if let Some(count) = address_count {
+        info!(count, "{}", tr!(Msg::AddressCount, count));
+    }
+    if let (Some(count), Some(clear)) = (address_count, clear_count) {
+        info!(clear, "{}", tr!(Msg::ClearCount, clear, count));
+    }
+}
+
+/// Log how many of a threshold proof's addresses had to be clear.
+fn log_threshold(threshold: Option<u32>, address_count: Option<u32>) {
+    if let (Some(threshold), Some(count)) = (threshold, address_count) {
+        info!(threshold, "{}", tr!(Msg::Threshold, threshold, count));
+    }
+}
+
+/// Log the inventory root a batch proof committed, if any.
+fn log_inventory(inventory_root: Option<[u8; 32]>) {
+    if let Some(root) = inventory_root {
+        info!(
+            "{}",
+            tr!(Msg::InventoryRoot, format!("0x{}", hex::encode(root)))
+        );
+    }
+}
+
+/// Print the inventory root of the IP list at `path`, as `--ip-file --inventory` commits it.
+fn print_inventory_root(path: &Path) -> anyhow::Result<()> {
+    let addrs = load_ip_list(path).context(ZkipCliError::InvalidIp)?;
+    let (ips, ips_v6) = split_addresses(&addrs);
+    let root = format!("0x{}", hex::encode(inventory_root(&ips, &ips_v6)));
+    info!("{}", tr!(Msg::InventoryRoot, root));
+    println!("{}", root);
+    Ok(())
+}
+
+/// Log the chunk proofs an aggregated proof combines.
+fn log_aggregation(aggregation: Option<&AggregationValues>) {
+    if let Some(aggregation) = aggregation {
+        let chunk_vkey = format!("0x{}", hex::encode(aggregation.chunk_vkey));
+        let chunk_count = aggregation.chunk_count;
+        let message = tr!(Msg::AggregatedFrom, chunk_count, chunk_vkey);
+        info!(chunk_count, "{}", message);
+    }
+}
+
+/// Log the proof a renewal renews.
+fn log_renewal(renewal: Option<&RenewalValues>) {
+    if let Some(renewal) = renewal {
+        let previous_vkey = format!("0x{}", hex::encode(renewal.previous_vkey));
+        let previous_timestamp = renewal.previous_timestamp;
+        let message = tr!(Msg::RenewalOf, previous_vkey, previous_timestamp);
+        info!(previous_timestamp, "{}", message);
+    }
+}
+
+/// Log the companion proof a composed proof was joined to.
+fn log_companion(companion: Option<&CompanionValues>) {
+    if let Some(companion) = companion {
+        let vkey = format!("0x{}", hex::encode(companion.vkey));
+        let digest = format!("0x{}", hex::encode(companion.public_values_digest));
+        info!("{}", tr!(Msg::CompanionOf, vkey, digest));
+    }
+}
+
+/// Log the verifier challenge a proof is bound to, if any.
+fn log_challenge(challenge: Option<[u8; 32]>) {
+    if let Some(challenge) = challenge {
+        info!(
+            "{}",
+            tr!(Msg::Challenge, format!("0x{}", hex::encode(challenge)))
+        );
+    }
+}
+
+/// Log when a windowed proof may be accepted.
+fn log_validity(validity: Option<ValidityWindow>) {
+    if let Some(validity) = validity {
+        let not_before = format_utc(validity.not_before);
+        let not_after = format_utc(validity.not_after);
+        info!("{}", tr!(Msg::Validity, not_before, not_after));
+    }
+}
+
+/// Load the compressed companion proof at `path` for the guest to verify, with the vkey of
+/// the program built as `elf` that made it. Checking it here fails before proving starts.
+fn load_companion(
+    path: &Path,
+    elf: &Path,
+) -> anyhow::Result<(CompanionInput, SP1ProofWithPublicValues, SP1VerifyingKey)> {
+    let proof = load_proof(path).context(ZkipCliError::InvalidArguments)?;
+    if !matches!(proof.proof, SP1Proof::Compressed(_)) {
+        return Err(
+            anyhow::anyhow!(tr!(Msg::CompanionNeedsCompressed, path.display()))
+                .context(ZkipCliError::InvalidArguments),
+        );
+    }
+    let bytes = std::fs::read(elf)
+        .with_context(|| tr!(Msg::ReadFailed, elf.display()))
+        .context(ZkipCliError::InvalidArguments)?;
+    let prover = ProverClient::builder().cpu().build();
+    let (_, vk) = prover.setup(&bytes);
+    prover
+        .verify(&proof, &vk)
+        .context(ZkipCliError::VerificationFailed)?;
+    info!("{}", tr!(Msg::CompanionLoaded, vk.bytes32()));
+    let input = CompanionInput {
+        vkey: vk.hash_u32(),
+        public_values: proof.public_values.to_vec(),
+    };
+    Ok((input, proof, vk))
+}
+
+/// The vkey a composed proof commits for a companion from the program built as `elf`.
+fn companion_vkey(elf: &Path) -> anyhow::Result<[u8; 32]> {
+    let bytes = std::fs::read(elf).with_context(|| tr!(Msg::ReadFailed, elf.display()))?;
+    let (_, vk) = ProverClient::builder().cpu().build().setup(&bytes);
+    Ok(vkey_bytes(&vk.hash_u32()))
+}
+
+/// Reject `proof` if it is on the revocation list at `path`, when there is one.
+fn check_revocations(
+    proof: &SP1ProofWithPublicValues,
+    path: &Path,
+    trusted_key: Option<&str>,
+) -> anyhow::Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let list = RevocationList::load(path).context(ZkipCliError::InvalidArguments)?;
+    if trusted_key.is_none() {
+        warn!("{}", tr!(Msg::RevocationUnpinned, list.signer));
+    }
+    list.verify(trusted_key)
+        .context(ZkipCliError::VerificationFailed)?;
+    let id = proof_id(proof)?;
+    // A nullifier outlives the proof's bytes, so it catches wrapped and re-serialized copies
+    let nullifier = decode_public_values(proof.public_values.as_slice())
+        .ok()
+        .and_then(|decoded| decoded.nullifier)
+        .map(|nullifier| nullifier.nullifier)
+        .filter(|&nullifier| nullifier != [0; 32])
+        .map(hex::encode);
+    let found = list
+        .find(&id, RevocationKind::ProofId)
+        .map(|entry| (id, entry))
+        .or_else(|| {
+            let nullifier = nullifier?;
+            let entry = list.find(&nullifier, RevocationKind::Nullifier)?;
+            Some((format!("nullifier {}", nullifier), entry))
+        });
+    if let Some((revoked, entry)) = found {
+        let reason = entry
+            .reason
+            .as_ref()
+            .map(|r| format!(" ({})", r))
+            .unwrap_or_default();
+        return Err(
+            anyhow::anyhow!(tr!(Msg::ProofRevoked, revoked, entry.revoked_at, reason))
+                .context(ZkipCliError::ProofRevoked),
+        );
+    }
+    Ok(())
+}
+
+/// Add `id`, a proof ID or nullifier, to the revocation list at `path`, signing it with the
+/// seed in `key`.
+fn revoke_proof(
+    path: &Path,
+    id: &str,
+    kind: RevocationKind,
+    key: &Path,
+    reason: Option<String>,
+) -> anyhow::Result<()> {
+    let key = load_signing_key(key).context(ZkipCliError::InvalidArguments)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .with_context(|| tr!(Msg::ClockBeforeEpoch))?
+        .as_secs();
+    if revoke(path, &key, id, kind, reason, now).context(ZkipCliError::InvalidArguments)? {
+        let signer = hex::encode(key.verifying_key().to_bytes());
+        info!("{}", tr!(Msg::RevocationAdded, id, path.display(), signer));
+    } else {
+        info!("{}", tr!(Msg::RevocationUnchanged, id));
+    }
+    Ok(())
+}
+
+/// Fail unless the envelope carries enough signatures from the operators in `policy`.
+fn check_cosignatures(id: &str, policy: &Path, envelope: &Path) -> anyhow::Result<()> {
+    let policy = CosignPolicy::load(policy).context(ZkipCliError::InvalidArguments)?;
+    let envelope = CosignEnvelope::load(envelope).context(ZkipCliError::CosignThresholdNotMet)?;
+    let signed = policy
+        .check(&envelope, id)
+        .context(ZkipCliError::CosignThresholdNotMet)?;
+    info!(
+        "{}",
+        tr!(
+            Msg::CosignChecked,
+            signed.len(),
+            policy.threshold,
+            signed.join(", ")
+        )
+    );
+    Ok(())
+}
+
+/// Sign `proof` into its co-signature envelope as the operator holding `key`.
+fn cosign(
+    proof: &Path,
+    key: &Path,
+    envelope_path: &Path,
+    store: Option<&dyn ArtifactStore>,
+) -> anyhow::Result<()> {
+    let key = load_signing_key(key).context(ZkipCliError::InvalidArguments)?;
+    let proof = load_proof(proof).context(ZkipCliError::InvalidArguments)?;
+    let id = proof_id(&proof)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .with_context(|| tr!(Msg::ClockBeforeEpoch))?
+        .as_secs();
+    let mut envelope =
+        CosignEnvelope::open(envelope_path, &id).context(ZkipCliError::InvalidArguments)?;
+    let signer = hex::encode(key.verifying_key().to_bytes());
+    if envelope.sign(&key, now) {
+        envelope.save(envelope_path)?;
+        info!(
+            "{}",
+            tr!(Msg::CosignAdded, id, envelope_path.display(), signer)
+        );
+        if let Some(store) = store {
+            let key = format!("envelopes/{}.cosign.json", id);
+            let location =
+                put_file(store, &key, envelope_path).context(ZkipCliError::ArtifactStoreFailed)?;
+            info!("{}", tr!(Msg::ArtifactStored, key, location));
+        }
+    } else {
+        info!("{}", tr!(Msg::CosignUnchanged, signer, id));
+    }
+    Ok(())
+}
+
+/// Whether `path` is `-`, standing for stdin or stdout.
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Read a proof from `path`, from the bundle at `path` if it has the `.zkip` extension, or
+/// from stdin if it is `-`.
+fn load_proof(path: &Path) -> anyhow::Result<SP1ProofWithPublicValues> {
+    if is_stdio(path) {
+        return bincode::deserialize_from(std::io::stdin().lock())
+            .with_context(|| tr!(Msg::ReadProofStdinFailed));
+    }
+    if is_bundle(path) {
+        return ProofBundle::load(path)?.proof();
+    }
+    SP1ProofWithPublicValues::load(path).with_context(|| tr!(Msg::LoadProofFailed, path.display()))
+}
+
+/// Write `proof` to `path`, or its raw bytes to stdout if it is `-`. Logs stay on stderr, so
+/// stdout carries nothing but the proof.
+fn write_proof(proof: &SP1ProofWithPublicValues, path: &Path) -> anyhow::Result<()> {
+    if is_stdio(path) {
+        let mut stdout = std::io::stdout().lock();
+        bincode::serialize_into(&mut stdout, proof)
+            .with_context(|| tr!(Msg::WriteProofStdoutFailed))?;
+        return stdout
+            .flush()
+            .with_context(|| tr!(Msg::WriteProofStdoutFailed));
+    }
+    proof
+        .save(path)
+        .with_context(|| tr!(Msg::WriteFailed, path.display()))?;
+    info!("{}", tr!(Msg::ProofSaved, path.display()));
+    Ok(())
+}
+
+/// Copy a generated proof to the artifact store, reusing the `--save-proof` file if there is one.
+fn store_proof(
+    store: &dyn ArtifactStore,
+    proof: &SP1ProofWithPublicValues,
+    saved: Option<&Path>,
+) -> anyhow::Result<()> {
+    let key = format!("proofs/{}.proof", proof_id(proof)?);
+    let location = match saved.filter(|path| !is_stdio(path)) {
+        Some(path) => put_file(store, &key, path),
+        None => {
+            let path = std::env::temp_dir().join(format!("zkip-{}.proof", new_run_id()));
+            proof
+                .save(&path)
+                .with_context(|| tr!(Msg::WriteFailed, path.display()))?;
+            let location = put_file(store, &key, &path);
+            let _ = std::fs::remove_file(&path);
+            location
+        }
+    }
+    .context(ZkipCliError::ArtifactStoreFailed)?;
+    info!("{}", tr!(Msg::ArtifactStored, key, location));
+    Ok(())
+}
+
+/// Prove on the local machine, verify on a throwaway anvil node, and compare results.
+fn e2e(
+    variant: ProgramVariant,
+    system: ProofSystem,
+    contracts: &Path,
+    port: u16,
+    ip_arg: &str,
+    exclude: &str,
+) -> anyhow::Result<()> {
+    forge_build(contracts).context(ZkipCliError::E2eFailed)?;
+
+    let geoip_path = ensure_geoip_database(false).context(ZkipCliError::DatabaseUnavailable)?;
+    let (ips, ips_v6) = split_addresses(&[parse_ip(ip_arg).context(ZkipCliError::InvalidIp)?]);
+    let (alpha2_codes, excluded_countries) =
+        parse_excluded_countries(exclude).context(ZkipCliError::InvalidCountry)?;
+    let excluded = excluded_ranges(&geoip_path, &alpha2_codes, false)?;
+    let timestamp = proof_time(TimeSource::System, &[], 0.0, None)
+        .context(ZkipCliError::ClockUntrusted)?
+        .timestamp;
+    let stdin = build_stdin(&SharedInputs {
+        ips: &ips,
+        excluded_ranges: &excluded.ranges,
+        excluded_countries: &excluded_countries,
+        timestamp,
+        disclosure: None,
+        anchor: None,
+        batch: false,
+        inventory: false,
+        threshold: None,
+        ips_v6: &ips_v6,
+        excluded_ranges_v6: &excluded.ranges_v6,
+        range_counts: &excluded.counts,
+        include: false,
+        denylist: None,
+        policy: None,
+        per_country: false,
+        gap: None,
+        attestation: None,
+        web_proof: None,
+        snapshot_signature: None,
+        time_beacon: None,
+        companion: None,
+        nullifier: None,
+        challenge: None,
+        valid_for: None,
+        snapshot: None,
+        app_context: None,
+        prefix_salt: None,
+        block: None,
+    });
+
+    let client = ProverClient::from_env();
+    let (pk, vk) = client.setup(variant.elf());
+    info!("{}", tr!(Msg::EvmProofSystem, format!("{:?}", system)));
+    let proof = match system {
+        ProofSystem::Plonk => client.prove(&pk, &stdin).plonk().run(),
+        ProofSystem::Groth16 => client.prove(&pk, &stdin).groth16().run(),
+    }
+    .context(ZkipCliError::ProvingFailed)?;
+    info!("{}", tr!(Msg::ProofGenerated));
+    let expected = decode_public_values(proof.public_values.as_slice())
+        .context(ZkipCliError::DecodeFailed)?
+        .values;
+
+    let node = spawn_anvil(port).context(ZkipCliError::E2eFailed)?;
+    info!("{}", tr!(Msg::E2eAnvil, node.rpc_url));
+    let onchain = verify_onchain(
+        &node.rpc_url,
+        contracts,
+        system,
+        vk.bytes32_raw(),
+        proof.public_values.as_slice(),
+        &proof.bytes(),
+    )
+    .context(ZkipCliError::E2eFailed)?;
+    info!("{}", tr!(Msg::E2eDeployed, onchain.verifier, onchain.zkip));
+
+    if onchain.is_excluded != expected.is_excluded
+        || onchain.timestamp != expected.timestamp
+        || onchain.excluded_countries != expected.excluded_countries
+    {
+        return Err(anyhow::anyhow!(tr!(
+            Msg::ContractResultMismatch,
+            onchain.is_excluded,
+            onchain.timestamp,
+            format!("{:?}", onchain.excluded_countries),
+            expected.is_excluded,
+            expected.timestamp,
+            format!("{:?}", expected.excluded_countries)
+        ))
+        .context(ZkipCliError::E2eFailed));
+    }
+    info!(
+        "{}",
+        tr!(
+            Msg::E2ePassed,
+            onchain.is_excluded,
+            onchain.timestamp,
+            format!("{:?}", onchain.excluded_countries)
+        )
+    );
+    Ok(())
+}
+
+/// Print what a set of raw public values attests.
+fn explain_public_values(public_values: &str) -> anyhow::Result<()> {
+    let bytes = parse_public_values(public_values).context(ZkipCliError::InvalidArguments)?;
+    let explanation = explain(&bytes).context(ZkipCliError::DecodeFailed)?;
+    let decoded = &explanation.decoded;
+
+    info!(
+        "{}",
+        tr!(Msg::ExplainLayout, explanation.layout, bytes.len())
+    );
+    log_result(decoded.mode, decoded.values.is_excluded);
+    let timestamp = decoded.values.timestamp;
+    info!(
+        "{}",
+        tr!(Msg::ExplainTimestamp, timestamp, format_utc(timestamp))
+    );
+    log_renewal(decoded.renewal.as_ref());
+    info!(
+        "{}",
+        tr!(
+            Msg::CheckedCountries,
+            format!("{:?}", decoded.values.excluded_countries)
+        )
+    );
+    log_dataset_root(decoded.values.dataset_root.0, decoded.legacy);
+    log_coverage(decoded.coverage);
+    log_ranges_digest(decoded.ranges_digest);
+    log_nullifier(decoded.nullifier.as_ref());
+    log_version(decoded.version);
+    log_snapshot(decoded.snapshot.as_ref());
+    log_policy_id(decoded.policy_id);
+    log_app_context(decoded.app_context);
+    log_prefix(decoded.prefix.as_ref());
+    log_block(decoded.block.as_ref());
+    log_public_ip(decoded.is_public_ip);
+    log_gap(decoded.gap);
+    log_attestation(decoded.attestation.as_ref());
+    log_publisher(decoded.publisher.as_ref());
+    log_time_beacon(decoded.time_beacon.as_ref());
+    log_aggregation(decoded.aggregation.as_ref());
+    log_country_results(
+        &decoded.values.excluded_countries,
+        decoded.country_results.as_deref(),
+    );
+    log_denylist(decoded.denylist.as_ref());
+    log_policy(decoded.policy.as_ref());
+    for (code, name) in &explanation.countries {
+        match name {
+            Some((alpha2, name)) => info!("{}", tr!(Msg::ExplainCountry, alpha2, name, code)),
+            None => info!("{}", tr!(Msg::ExplainUnknownCountry, code)),
+        }
+    }
+    if let Some((granularity, region)) = decoded.disclosed {
+        let name = explanation.region_name.as_deref().unwrap_or("?");
+        info!(region, "{}", tr!(disclosure_msg(granularity), region, name));
+    }
+    log_anchor(decoded.anchor);
+    log_batch(decoded.address_count, decoded.clear_count);
+    log_threshold(decoded.threshold, decoded.address_count);
+    log_inventory(decoded.inventory_root);
+    log_companion(decoded.companion.as_ref());
+    log_challenge(decoded.challenge);
+    log_validity(decoded.validity);
+    for warning in &explanation.warnings {
+        warn!("{}", tr!(Msg::ExplainWarning, warning));
+    }
+    Ok(())
+}
+
+/// Print cost trends from the run history.
+fn stats(path: &Path) -> anyhow::Result<()> {
+    let runs = load_history(path)?;
+    if runs.is_empty() {
+        info!("{}", tr!(Msg::StatsEmpty, path.display()));
+        return Ok(());
+    }
+    for group in summarize(&runs) {
+        let cycles = group
+            .median_cycles
+            .map_or_else(|| "-".to_string(), |c| c.to_string());
+        let rss = group
+            .max_peak_rss_kib
+            .map_or_else(|| "-".to_string(), |kib| format!("{} MiB", kib / 1024));
+        let proof = group
+            .median_proof_bytes
+            .map_or_else(|| "-".to_string(), |bytes| format!("{} KiB", bytes / 1024));
+        let trend = group
+            .trend_percent
+            .map_or_else(|| "-".to_string(), |t| format!("{:+.1}%", t));
+        info!(
+            "{}",
+            tr!(
+                Msg::StatsGroup,
+                group.mode,
+                group.variant,
+                format!("{:?}", group.excluded_countries),
+                group.runs
+            )
+        );
+        info!(
+            "{}",
+            tr!(
+                Msg::StatsCost,
+                cycles,
+                group.median_wall_ms,
+                rss,
+                proof,
+                trend
+            )
+        );
+    }
+    Ok(())
+}
+
+/// Check the audit log's hash chain end to end.
+fn audit_verify(path: &Path) -> anyhow::Result<()> {
+    let summary = verify_chain(path).context(ZkipCliError::AuditLogCorrupted)?;
+    info!("{}", tr!(Msg::AuditIntact, summary.entries, summary.head));
+    Ok(())
+}
+
+/// Log the committed result under the name its mode gives it.
+fn log_result(mode: u8, result: bool) {
+    match mode {
+        MODE_EXCLUSION => info!(
+            is_excluded = result,
+            "{}",
+            tr!(Msg::ResultIsExcluded, result)
+        ),
+        MODE_POLICY => info!(is_allowed = result, "{}", tr!(Msg::ResultIsAllowed, result)),
+        MODE_THRESHOLD => info!(is_met = result, "{}", tr!(Msg::ResultIsMet, result)),
+        MODE_BLOCK => info!(is_clear = result, "{}", tr!(Msg::ResultIsClear, result)),
+        _ => info!(
+            is_included = result,
+            "{}",
+            tr!(Msg::ResultIsIncluded, result)
+        ),
+    }
+}
+
+/// Log the denylist half of a combined proof, if any.
+fn log_denylist(denylist: Option<&DenylistValues>) {
+    if let Some(denylist) = denylist {
+        let is_excluded = denylist.is_excluded;
+        info!(is_excluded, "{}", tr!(Msg::ResultIsExcluded, is_excluded));
+        info!(
+            "{}",
+            tr!(
+                Msg::DeniedCountries,
+                format!("{:?}", denylist.excluded_countries)
+            )
+        );
+        info!(
+            "{}",
+            tr!(
+                Msg::DenylistRoot,
+                format!("0x{}", hex::encode(denylist.dataset_root))
+            )
+        );
+    }
+}
+
+/// Log how many ranges the proof checked, for layouts that commit it.
+fn log_coverage(coverage: Option<(u32, u64)>) {
+    if let Some((range_count, covered_ipv4)) = coverage {
+        let message = tr!(Msg::RangeCoverage, range_count, covered_ipv4);
+        info!(range_count, covered_ipv4, "{}", message);
+    }
+}
+
+/// Log the ranges digest, for layouts that commit it.
+fn log_ranges_digest(ranges_digest: Option<[u8; 32]>) {
+    if let Some(digest) = ranges_digest {
+        info!(
+            "{}",
+            tr!(Msg::RangesDigest, format!("0x{}", hex::encode(digest)))
+        );
+    }
+}
+
+/// Log the IP's nullifier, if the proof commits a nonzero one.
+fn log_nullifier(nullifier: Option<&NullifierValues>) {
+    if let Some(values) = nullifier.filter(|values| values.nullifier != [0; 32]) {
+        let nullifier = format!("0x{}", hex::encode(values.nullifier));
+        let scope = format!("0x{}", hex::encode(values.scope));
+        let epoch = values.epoch;
+        info!(epoch, "{}", tr!(Msg::Nullifier, nullifier, epoch, scope));
+    }
+}
+
+/// Log the snapshot a plain proof says it was made from, if the prover reported one.
+fn log_snapshot(snapshot: Option<&SnapshotMetadata>) {
+    if let Some(snapshot) = snapshot.filter(|snapshot| snapshot.hash != [0; 32]) {
+        let source = format!("0x{}", hex::encode(snapshot.source));
+        let published_at = format_utc(snapshot.published_at);
+        let hash = format!("0x{}", hex::encode(snapshot.hash));
+        info!("{}", tr!(Msg::Snapshot, source, published_at, hash));
+    }
+}
+
+/// Log the policy ID of the excluded countries, for layouts that commit it.
+fn log_policy_id(policy_id: Option<[u8; 32]>) {
+    if let Some(id) = policy_id {
+        info!("{}", tr!(Msg::PolicyId, format!("0x{}", hex::encode(id))));
+    }
+}
+
+/// Log the IP's network prefix commitment, if the proof commits a nonzero one.
+fn log_prefix(prefix: Option<&PrefixValues>) {
+    if let Some(values) = prefix.filter(|values| values.commitment != [0; 32]) {
+        let commitment = format!("0x{}", hex::encode(values.commitment));
+        let scope = format!("0x{}", hex::encode(values.scope));
+        info!("{}", tr!(Msg::NetworkPrefix, commitment, scope));
+    }
+}
+
+/// Log the private prefix of a block proof, if any.
+fn log_block(block: Option<&BlockValues>) {
+    if let Some(block) = block {
+        let family = if block.is_v6 { 6 } else { 4 };
+        let commitment = format!("0x{}", hex::encode(block.commitment));
+        let scope = format!("0x{}", hex::encode(block.scope));
+        let message = tr!(
+            Msg::BlockPrefix,
+            family,
+            block.prefix_len,
+            commitment,
+            scope
+        );
+        info!(prefix_len = block.prefix_len, "{}", message);
+    }
+}
+
+/// Log the integrator's context, if the proof commits a nonzero one.
+fn log_app_context(app_context: Option<[u8; 32]>) {
+    if let Some(context) = app_context.filter(|&context| context != [0; 32]) {
+        info!(
+            "{}",
+            tr!(Msg::AppContext, format!("0x{}", hex::encode(context)))
+        );
+    }
+}
+
+/// Decode `bytes`, failing with `PublicValuesVersionUnsupported` if they are plain public
+/// values of a newer version than this build's, and `DecodeFailed` if they are no layout.
+fn decode_versioned(bytes: &[u8]) -> anyhow::Result<DecodedPublicValues> {
+    match decode_public_values(bytes) {
+        Err(ZkipError::UnsupportedPublicValuesVersion(version)) => Err(anyhow::anyhow!(tr!(
+            Msg::PublicValuesVersionUnsupported,
+            version,
+            PUBLIC_VALUES_VERSION
+        ))
+        .context(ZkipCliError::PublicValuesVersionUnsupported)),
+        decoded => decoded.context(ZkipCliError::DecodeFailed),
+    }
+}
+
+/// Log the plain layout's version, for layouts that commit it.
+fn log_version(version: Option<u16>) {
+    if let Some(version) = version {
+        info!(version, "{}", tr!(Msg::PublicValuesVersion, version));
+    }
+}
+
+/// Log whether the proven IP is public, for layouts that commit it.
+fn log_public_ip(is_public_ip: Option<bool>) {
+    match is_public_ip {
+        Some(true) => info!(is_public_ip = true, "{}", tr!(Msg::ResultIsPublicIp, true)),
+        Some(false) => warn!(is_public_ip = false, "{}", tr!(Msg::ReservedIpProof)),
+        None => {}
+    }
+}
+
+/// Note proofs from gap witnesses, which commit no range count or digest.
+fn log_gap(gap: bool) {
+    if gap {
+        info!(gap, "{}", tr!(Msg::GapWitnessProof));
+    }
+}
+
+/// Log who signed the IP, for attested proofs.
+fn log_attestation(attestation: Option<&AttestationValues>) {
+    if let Some(attestation) = attestation {
+        let attester = format!("0x{}", hex::encode(&attestation.pubkey));
+        let nonce = format!("0x{}", hex::encode(attestation.nonce));
+        let attested_at = attestation.attested_at;
+        let message = tr!(Msg::AttestedBy, attester, attested_at, nonce);
+        info!(attested_at, "{}", message);
+    }
+}
+
+/// Log who signed the snapshot the ranges came from, for signed-snapshot proofs.
+fn log_publisher(publisher: Option<&PublisherValues>) {
+    if let Some(publisher) = publisher {
+        let pubkey = format!("0x{}", hex::encode(publisher.pubkey));
+        let snapshot_root = format!("0x{}", hex::encode(publisher.snapshot_root));
+        let published_at = publisher.published_at;
+        let message = tr!(Msg::SignedSnapshotBy, snapshot_root, pubkey, published_at);
+        info!(published_at, "{}", message);
+        let max_db_age = publisher.max_age;
+        info!(
+            max_db_age,
+            "{}",
+            tr!(Msg::DbMaxAge, max_db_age / SECONDS_PER_DAY)
+        );
+    }
+}
+
+/// Log who signed the timestamp, for timed proofs.
+fn log_time_beacon(time_beacon: Option<&TimeBeaconValues>) {
+    if let Some(time_beacon) = time_beacon {
+        let time_radius = time_beacon.radius;
+        let server = format!("0x{}", hex::encode(time_beacon.pubkey));
+        info!(time_radius, "{}", tr!(Msg::TimedBy, server, time_radius));
+    }
+}
+
+/// Log each excluded country's own result, for proofs that commit them.
+fn log_country_results(excluded_countries: &[u16], country_results: Option<&[bool]>) {
+    let country_results = country_results.unwrap_or_default();
+    for (&country, &is_excluded) in excluded_countries.iter().zip(country_results) {
+        info!(
+            country,
+            is_excluded,
+            "{}",
+            tr!(Msg::CountryResult, country, is_excluded)
+        );
+    }
+}
+
+/// Log the policy a policy proof committed, if any.
+fn log_policy(policy: Option<&PolicyValues>) {
+    if let Some(policy) = policy {
+        info!(
+            "{}",
+            tr!(Msg::PolicyRuleHash, hex::encode(policy.policy_hash))
+        );
+        info!(
+            "{}",
+            tr!(Msg::AsnRoot, format!("0x{}", hex::encode(policy.asn_root)))
+        );
+    }
+}
+
+/// Compile the rule of the policy file at `path`, with the alpha-2 codes of the countries
+/// it names, ordered like `PolicyExpr::countries`.
+fn load_policy_rule(path: &Path) -> anyhow::Result<(PolicyExpr, Vec<String>)> {
+    let policy = load_policy(path).context(ZkipCliError::InvalidPolicy)?;
+    let country_codes = load_country_codes()?;
+    let expr = compile_rule(&policy, &country_codes).context(ZkipCliError::InvalidPolicy)?;
+    let alpha2_codes = expr
+        .countries()
+        .into_iter()
+        .filter_map(|code| {
+            country_codes
+                .iter()
+                .find(|(_, &numeric)| numeric == code)
+                .map(|(alpha2, _)| alpha2.clone())
+        })
+        .collect();
+    Ok((expr, alpha2_codes))
+}
+
+/// Log the committed dataset root, or that the proof predates it.
+fn log_dataset_root(root: [u8; 32], legacy: bool) {
+    if legacy {
+        warn!("{}", tr!(Msg::DatasetRootMissing));
+    } else {
+        info!(
+            "{}",
+            tr!(Msg::DatasetRoot, format!("0x{}", hex::encode(root)))
+        );
+    }
+}
+
+/// Parse a 32-byte hex root or digest, with or without `0x`.
+fn parse_root(root: &str) -> anyhow::Result<[u8; 32]> {
+    hex::decode(root.trim().trim_start_matches("0x"))
+        .with_context(|| tr!(Msg::InvalidHex32, root))?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!(tr!(Msg::Not32Bytes, root)))
+}
+
+/// Log a disclosed region or country, if the public values carry one.
+fn log_disclosure(disclosed: Option<(u8, u16)>) {
+    if let Some((granularity, region)) = disclosed {
+        let name = region_name(granularity, region).unwrap_or_else(|| "?".to_string());
+        info!(region, "{}", tr!(disclosure_msg(granularity), region, name));
+    }
+}
+
+/// The message naming a disclosed code of this granularity.
+fn disclosure_msg(granularity: u8) -> Msg {
+    match granularity {
+        GRANULARITY_COUNTRY => Msg::DisclosedCountry,
+        _ => Msg::DisclosedRegion,
+    }
+}
+
+/// Log the block anchor, if the public values carry one.
+fn log_anchor(anchor: Option<AnchorInput>) {
+    if let Some((number, hash)) = anchor {
+        let hash = format!("0x{}", hex::encode(hash));
+        info!(block = number, "{}", tr!(Msg::AnchorBlock, number, hash));
+    }
+}
+
+/// Sign a release manifest for `elf`.
+fn manifest_sign(elf: &Path, key: &Path, version: Option<String>) -> anyhow::Result<()> {
+    let key = load_signing_key(key).context(ZkipCliError::InvalidArguments)?;
+    let bytes = std::fs::read(elf)
+        .with_context(|| tr!(Msg::ReadFailed, elf.display()))
+        .context(ZkipCliError::InvalidArguments)?;
+    let name = elf
+        .file_name()
+        .and_then(|n| n.to_str())
+        .with_context(|| tr!(Msg::ElfNoFileName))
+        .context(ZkipCliError::InvalidArguments)?;
+
+    let prover = ProverClient::builder().cpu().build();
+    let (_, vk) = prover.setup(&bytes);
+    let body = ManifestBody {
+        version: version.unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string()),
+        vkey: vk.bytes32(),
+        artifacts: vec![Artifact {
+            name: name.to_string(),
+            sha256: hex::encode(sha256_file(elf)?),
+        }],
+    };
+    let manifest = ReleaseManifest::sign(body, &key)?;
+    let path = default_manifest_path(elf);
+    manifest.save(&path)?;
+    info!(
+        "{}",
+        tr!(
+            Msg::ManifestWritten,
+            path.display(),
+            hex::encode(key.verifying_key().to_bytes())
+        )
+    );
+    Ok(())
+}
+
+/// Accept a manifest failure with a warning under `--allow-unsigned`, refuse it otherwise.
+fn allow_untrusted(err: anyhow::Error, allow_unsigned: bool) -> anyhow::Result<()> {
+    if allow_unsigned {
+        warn!("{}", tr!(Msg::UnsignedAllowed, format!("{:#}", err)));
+        Ok(())
+    } else {
+        Err(err.context(ZkipCliError::UntrustedArtifact))
+    }
+}
+
+/// Read an external guest ELF and verify its signed manifest.
+fn load_external_elf(
+    path: &Path,
+    manifest: Option<&Path>,
+    allow_unsigned: bool,
+) -> anyhow::Result<(Vec<u8>, Option<ReleaseManifest>)> {
+    let bytes = std::fs::read(path)
+        .with_context(|| tr!(Msg::ReadFailed, path.display()))
+        .context(ZkipCliError::InvalidArguments)?;
+    let manifest_path = manifest.map_or_else(|| default_manifest_path(path), Path::to_path_buf);
+    match verify_elf_manifest(path, &manifest_path) {
+        Ok(manifest) => {
+            info!(
+                "{}",
+                tr!(Msg::ManifestVerified, path.display(), manifest.body.version)
+            );
+            Ok((bytes, Some(manifest)))
+        }
+        Err(err) => {
+            allow_untrusted(err, allow_unsigned)?;
+            Ok((bytes, None))
+        }
+    }
+}
+
+/// Print the embedded vkey or, with `reproduce`, rebuild the guest and compare vkeys.
+fn vkey(variant: ProgramVariant, reproduce: bool, expected: Option<String>) -> anyhow::Result<()> {
+    let prover = ProverClient::builder().cpu().build();
+    let (_, vk) = prover.setup(variant.elf());
+    let embedded = vk.bytes32();
+    if !reproduce {
+        println!("{}", embedded);
+        return Ok(());
+    }
+    info!("{}", tr!(Msg::VkeyEmbedded, embedded));
+
+    let out_dir = std::env::temp_dir().join("zkip-reproduce");
+    let elf_path = build_guest_in_docker(&out_dir, variant.binary_name())
+        .context(ZkipCliError::BuildFailed)?;
+    let elf = std::fs::read(&elf_path)
+        .with_context(|| tr!(Msg::ReadFailed, elf_path.display()))
+        .context(ZkipCliError::BuildFailed)?;
+    let (_, rebuilt_vk) = prover.setup(&elf);
+    let rebuilt = rebuilt_vk.bytes32();
+    info!("{}", tr!(Msg::VkeyRebuilt, rebuilt));
+
+    let expected = expected.unwrap_or(embedded);
+    info!("{}", tr!(Msg::VkeyExpected, expected));
+    if !rebuilt.eq_ignore_ascii_case(expected.trim()) {
+        return Err(anyhow::anyhow!(tr!(Msg::VkeyMismatch, rebuilt, expected))
+            .context(ZkipCliError::VkeyMismatch));
+    }
+    info!("{}", tr!(Msg::VkeyReproduced));
+    Ok(())
+}
+
+fn main() {
+    dotenv::dotenv().ok();
+
+    let args = Args::parse();
+    let json = args.json;
+    set_lang(resolve_lang(args.lang));
+    if let Err(err) = init_logging(args.log_format, args.log_level.as_deref()) {
+        exit_with_error(err.context(ZkipCliError::InvalidArguments), json);
+    }
+
+    let _run = info_span!("run", run_id = %new_run_id()).entered();
+    let proving = args.command.is_none();
+    let mut usage = UsageEvent::new(if args.execute { "execute" } else { "core" });
+    let result = run(args, &mut usage);
+    if proving {
+        usage.success = result.is_ok();
+        telemetry::report(&usage);
+    }
+    if let Err(err) = result {
+        exit_with_error(err, json);
+    }
+}
+
+fn run(args: Args, usage: &mut UsageEvent) -> anyhow::Result<()> {
+    let variant = args.variant;
+    let audit_log = args
+        .audit_log
+        .clone()
+        .unwrap_or_else(default_audit_log_path);
+    let history = args.history.clone().unwrap_or_else(default_history_path);
+    let revocations = args
+        .revocations
+        .clone()
+        .unwrap_or_else(default_revocation_path);
+    let config = args.config.clone().unwrap_or_else(default_config_path);
+    if let Some(date) = &args.snapshot {
+        use_embedded_snapshot(date).context(ZkipCliError::SnapshotUnavailable)?;
+    }
+    if let Some(command) = args.command {
+        return match command {
+            Command::Db(DbCommand::Verify) => db_verify(),
+            Command::Db(DbCommand::Status) => db_status(),
+            Command::Db(DbCommand::Root {
+                countries,
+                manifest,
+                sign,
+            }) => db_root(&countries, manifest.as_deref(), sign.as_deref()),
+            Command::Selftest { mock_prove } => selftest(mock_prove),
+            Command::Policy(PolicyCommand::Lint { path }) => policy_lint(&path),
+            Command::Verify {
+                proof,
+                revocation_key,
+                replay_store,
+                replay_ttl,
+                cosigners,
+                envelope,
+                dataset_root,
+                root_manifest,
+                ranges_digest,
+                min_anonymity,
+                policy,
+                attester,
+                web_server,
+                publisher,
+                max_db_age,
+                time_server,
+                anchor_rpc_url,
+                max_anchor_age,
+                inventory_root,
+                companion_elf,
+                challenge,
+                snapshot_hashes,
+                app_context,
+                operators,
+            } => {
+                let expected_root = dataset_root
+                    .as_deref()
+                    .map(parse_root)
+                    .transpose()
+                    .context(ZkipCliError::InvalidArguments)?;
+                let expected_digest = ranges_digest
+                    .as_deref()
+                    .map(parse_root)
+                    .transpose()
+                    .context(ZkipCliError::InvalidArguments)?;
+                let root_manifest = root_manifest
+                    .as_deref()
+                    .map(RootManifest::load)
+                    .transpose()
+                    .context(ZkipCliError::InvalidArguments)?;
+                let expected_policy = match &policy {
+                    Some(path) => Some(load_policy_rule(path)?.0.hash()),
+                    None => None,
+                };
+                let expected_attester = attester
+                    .as_deref()
+                    .map(parse_attester)
+                    .transpose()
+                    .context(ZkipCliError::InvalidArguments)?;
+                let expected_publisher = publisher
+                    .as_deref()
+                    .map(parse_publisher)
+                    .transpose()
+                    .context(ZkipCliError::InvalidArguments)?;
+                let expected_time_server = time_server
+                    .as_deref()
+                    .map(parse_roughtime_key)
+                    .transpose()
+                    .context(ZkipCliError::InvalidArguments)?;
+                let expected_inventory = inventory_root
+                    .as_deref()
+                    .map(parse_root)
+                    .transpose()
+                    .context(ZkipCliError::InvalidArguments)?;
+                let expected_companion = companion_elf
+                    .as_deref()
+                    .map(companion_vkey)
+                    .transpose()
+                    .context(ZkipCliError::InvalidArguments)?;
+                let expected_challenge = challenge
+                    .as_deref()
+                    .map(parse_root)
+                    .transpose()
+                    .context(ZkipCliError::InvalidArguments)?;
+                let snapshot_hashes = snapshot_hashes
+                    .iter()
+                    .map(|hash| parse_root(hash))
+                    .collect::<anyhow::Result<Vec<_>>>()
+                    .context(ZkipCliError::InvalidArguments)?;
+                // The guest commits the hash as the host reports it, so only pinned ranges
+                // tie it to the snapshot it names
+                let ranges_pinned =
+                    expected_digest.is_some() || expected_root.is_some() || root_manifest.is_some();
+                if !snapshot_hashes.is_empty() && !ranges_pinned {
+                    return Err(anyhow::anyhow!(tr!(Msg::SnapshotHashUnpinned))
+                        .context(ZkipCliError::InvalidArguments));
+                }
+                let expected_app_context = app_context
+                    .as_deref()
+                    .map(parse_root)
+                    .transpose()
+                    .context(ZkipCliError::InvalidArguments)?;
+                let operators = parse_operators(&operators)?;
+                verify(
+                    &proof,
+                    &revocations,
+                    revocation_key.as_deref(),
+                    replay_store.as_deref().map(|store| (store, replay_ttl)),
+                    cosigners.as_deref().map(|policy| {
+                        let envelope = envelope.unwrap_or_else(|| default_envelope_path(&proof));
+                        (policy, envelope)
+                    }),
+                    Requirements {
+                        dataset_root: expected_root,
+                        root_manifest: root_manifest.as_ref(),
+                        min_anonymity,
+                        policy_hash: expected_policy,
+                        ranges_digest: expected_digest,
+                        attester: expected_attester,
+                        web_server: web_server.as_deref(),
+                        publisher: expected_publisher,
+                        max_db_age: max_db_age.map(|days| u64::from(days) * SECONDS_PER_DAY),
+                        time_server: expected_time_server,
+                        anchor: anchor_rpc_url
+                            .as_deref()
+                            .map(|rpc_url| (rpc_url, max_anchor_age.unwrap_or(BLOCKHASH_WINDOW))),
+                        inventory_root: expected_inventory,
+                        companion: expected_companion,
+                        challenge: expected_challenge,
+                        snapshot_hashes,
+                        app_context: expected_app_context,
+                        operators,
+                    },
+                )
+            }
+            Command::Cosign {
+                proof,
+                key,
+                envelope,
+            } => {
+                let store = configured_store(&config).context(ZkipCliError::ArtifactStoreFailed)?;
+                cosign(
+                    &proof,
+                    &key,
+                    &envelope.unwrap_or_else(|| default_envelope_path(&proof)),
+                    store.as_deref(),
+                )
+            }
+            Command::Revoke {
+                id,
+                nullifier,
+                key,
+                reason,
+            } => {
+                let kind = if nullifier {
+                    RevocationKind::Nullifier
+                } else {
+                    RevocationKind::ProofId
+                };
+                revoke_proof(&revocations, &id, kind, &key, reason)
+            }
+            Command::Registry(RegistryCommand::Add { version }) => registry_add(version),
+            Command::Registry(RegistryCommand::List) => registry_list(),
+            Command::Registry(RegistryCommand::Revoke { version }) => registry_revoke(&version),
+            Command::Manifest(ManifestCommand::Sign { elf, key, version }) => {
+                manifest_sign(&elf, &key, version)
+            }
+            Command::Audit(AuditCommand::Verify) => audit_verify(&audit_log),
+            Command::Stats => stats(&history),
+            Command::E2e {
+                system,
+                contracts,
+                port,
+                ip,
+                exclude,
+            } => e2e(
+                variant,
+                system,
+                &contracts.unwrap_or_else(default_contracts_dir),
+                port,
+                &ip,
+                &exclude,
+            ),
+            Command::Explain { public_values } => explain_public_values(&public_values),
+            Command::InventoryRoot { ip_file } => print_inventory_root(&ip_file),
+            Command::Renew { proof, save_proof } => {
+                renew(&proof, save_proof.as_deref(), &audit_log)
+            }
+            Command::Wrap {
+                proof,
+                system,
+                save_proof,
+            } => wrap(&proof, system, save_proof.as_deref(), &audit_log),
+            Command::Bundle {
+                proof,
+                save_bundle,
+                sign_key,
+            } => bundle(&proof, save_bundle.as_deref(), sign_key.as_deref()),
+            Command::VerifyBundle { bundle, operators } => {
+                verify_bundle(&bundle, &parse_operators(&operators)?)
+            }
+            Command::Admin(AdminCommand::PushRoot {
+                countries,
+                rpc_url,
+                contract,
+                private_key,
+                dry_run,
+            }) => admin_push_root(&countries, &rpc_url, contract, &private_key, dry_run),
+            Command::Vkey {
+                reproduce,
+                expected,
+            } => vkey(variant, reproduce, expected),
+        };
+    }
+
+    if args.execute == args.prove {
+        return Err(anyhow::anyhow!(tr!(Msg::MissingMode)).context(ZkipCliError::InvalidArguments));
+    }
+    if args.gap_witness && args.elf.is_none() && variant == ProgramVariant::ConstantWork {
+        return Err(anyhow::anyhow!(tr!(Msg::GapWitnessConstantWork))
+            .context(ZkipCliError::InvalidArguments));
+    }
+    if args.block.is_some() && args.elf.is_none() && variant == ProgramVariant::ConstantWork {
+        return Err(
+            anyhow::anyhow!(tr!(Msg::BlockConstantWork)).context(ZkipCliError::InvalidArguments)
+        );
+    }
+
+    // Fail on a broken store before spending time proving
+    let store = if args.prove {
+        configured_store(&config).context(ZkipCliError::ArtifactStoreFailed)?
+    } else {
+        None
+    };
+
+    // Ensure GeoIP database is available and fresh
+    let geoip_path =
+        ensure_geoip_database(args.refresh).context(ZkipCliError::DatabaseUnavailable)?;
+
+    let (circuit, manifest): (Box<dyn PolicyCircuit>, _) = match &args.elf {
+        Some(path) => {
+            let (elf, manifest) =
+                load_external_elf(path, args.manifest.as_deref(), args.allow_unsigned)?;
+            let circuit = ExternalCircuit {
+                id: "external".to_string(),
+                elf,
+            };
+            (Box::new(circuit), manifest)
+        }
+        None => (Box::new(variant), None),
+    };
+    let elf = circuit.elf();
+    let program = circuit.id();
+
+    let client = ProverClient::from_env();
+    let (pk, vk) = client.setup(elf);
+    if let Some(manifest) = &manifest {
+        if let Err(err) = manifest.verify_vkey(&vk.bytes32()) {
+            allow_untrusted(err, args.allow_unsigned)?;
+        }
+    }
+
+    let current = ZkipLock::current(&geoip_path, program, &vk.bytes32())?;
+    enforce_lock(&default_lock_path(), &current, args.update_lock)?;
+
+    let web_proof = match &args.web_proof {
+        Some(path) => Some(load_web_proof(path).context(ZkipCliError::InvalidArguments)?),
+        None => None,
+    };
+    let (addrs, target) = match (&args.domain, &args.ip_file, &web_proof) {
+        (Some(domain), ..) => (
+            resolve_domain(domain, args.skip_ipv6).context(ZkipCliError::InvalidDomain)?,
+            domain.clone(),
+        ),
+        (None, Some(path), _) => (
+            load_ip_list(path).context(ZkipCliError::InvalidIp)?,
+            path.display().to_string(),
+        ),
+        // A web proof's IP is the one the notarised server saw, not one typed here
+        (None, None, Some(web_proof)) => {
+            let observed = String::from_utf8_lossy(&web_proof.response)
+                .trim()
+                .to_string();
+            (
+                vec![parse_ip(&observed).context(ZkipCliError::InvalidIp)?],
+                observed,
+            )
+        }
+        (None, None, None) => (
+            vec![parse_ip(&args.ip).context(ZkipCliError::InvalidIp)?],
+            args.ip.clone(),
+        ),
+    };
+    let (ips, ips_v6) = split_addresses(&addrs);
+    // Inclusion proofs check the IP against the included countries' ranges instead, and
+    // --exclude next to --include becomes a denylist checked in the same proof
+    let include = args.include.is_some();
+    let countries = match &args.include {
+        Some(include) => include.as_str(),
+        None => args.exclude.as_deref().unwrap_or("FR"),
+    };
+    // Policy proofs evaluate a rule over the countries and ASNs it names, whose ranges
+    // stand in for the excluded ones
+    let policy = match &args.policy {
+        Some(path) => Some(load_policy_rule(path)?),
+        None => None,
+    };
+    // An allowlist becomes the exclusion of every other country, so the guest is unchanged
+    let allowed = match &args.allow_only {
+        Some(allowed) => {
+            let (allowed, _) =
+                parse_excluded_countries(allowed).context(ZkipCliError::InvalidCountry)?;
+            let v6_path = ensure_geoip_v6_database(args.refresh)
+                .context(ZkipCliError::DatabaseUnavailable)?;
+            let others = allow_only(&geoip_path, &v6_path, &allowed)
+                .context(ZkipCliError::DatabaseCorrupted)?;
+            let names = format!("{:?}", allowed);
+            info!(
+                "{}",
+                tr!(Msg::AllowOnly, names, others.excluded_countries.len())
+            );
+            if others.unlisted_ipv4 > 0 {
+                warn!("{}", tr!(Msg::AllowOnlyUnlisted, others.unlisted_ipv4));
+            }
+            Some(others)
+        }
+        None => None,
+    };
+    let (alpha2_codes, excluded_countries) = match (&policy, &allowed) {
+        (Some((expr, alpha2_codes)), _) => (alpha2_codes.clone(), expr.countries()),
+        (None, Some(others)) => (
+            others.alpha2_codes.clone(),
+            others.excluded_countries.clone(),
+        ),
+        (None, None) => {
+            parse_excluded_countries(countries).context(ZkipCliError::InvalidCountry)?
+        }
+    };
+    let policy = match policy {
+        Some((expr, _)) => {
+            let asns = expr.asns();
+            let ranges = asn_ranges(&asns, args.refresh)?;
+            Some((expr, asns, ranges))
+        }
+        None => None,
+    };
+
+    // Already refreshed, if asked, for --allow-only
+    let refresh = args.refresh && allowed.is_none();
+    let excluded = excluded_ranges(&geoip_path, &alpha2_codes, refresh)?;
+    let snapshot = snapshot_metadata(&geoip_path, &get_v6_cache_path())
+        .context(ZkipCliError::DatabaseUnavailable)?;
+    if include && !zkip_lib::inclusion_set_valid(&excluded_countries, &excluded.counts) {
+        return Err(
+            anyhow::anyhow!(tr!(Msg::InclusionSetInvalid, format!("{:?}", alpha2_codes)))
+                .context(ZkipCliError::InvalidCountry),
+        );
+    }
+    // The guest rejects empty inputs too; failing here names the countries at fault
+    if policy.is_none()
+        && !zkip_lib::non_vacuous(&excluded_countries, &excluded.ranges, &excluded.ranges_v6)
+    {
+        return Err(
+            anyhow::anyhow!(tr!(Msg::NoRangesToCheck, format!("{:?}", alpha2_codes)))
+                .context(ZkipCliError::InvalidCountry),
+        );
+    }
+    let denied = match (include, &args.exclude) {
+        (true, Some(exclude)) => {
+            let (alpha2_codes, codes) =
+                parse_excluded_countries(exclude).context(ZkipCliError::InvalidCountry)?;
+            // Already refreshed, if asked, with the included countries' ranges
+            let ranges = excluded_ranges(&geoip_path, &alpha2_codes, false)?;
+            if !zkip_lib::non_vacuous(&codes, &ranges.ranges, &ranges.ranges_v6) {
+                return Err(anyhow::anyhow!(tr!(
+                    Msg::NoRangesToCheck,
+                    format!("{:?}", alpha2_codes)
+                ))
+                .context(ZkipCliError::InvalidCountry));
+            }
+            Some((codes, ranges))
+        }
+        _ => None,
+    };
+
+    let servers = ntp_servers(&args.ntp_servers);
+    let roughtime_key = args
+        .roughtime_key
+        .as_deref()
+        .map(parse_roughtime_key)
+        .transpose()
+        .context(ZkipCliError::InvalidArguments)?;
+    let roughtime = args.roughtime_server.as_deref().zip(roughtime_key);
+    let proof_time = proof_time(args.time_source, &servers, args.max_clock_skew, roughtime)
+        .context(ZkipCliError::ClockUntrusted)?;
+    let timestamp = proof_time.timestamp;
+
+    let disclosure = match (args.disclose, ips.first()) {
+        (Some(level), Some(&ip)) => Some(
+            resolve_disclosure(ip, level, &excluded_countries, &excluded)
+                .context(ZkipCliError::InvalidIp)?,
+        ),
+        (Some(_), None) => {
+            return Err(
+                anyhow::anyhow!(tr!(Msg::DiscloseIpv4Only)).context(ZkipCliError::InvalidIp)
+            );
+        }
+        (None, _) => None,
+    };
+
+    let anchor = match (args.anchor_block, &args.rpc_url) {
+        (true, Some(rpc_url)) => {
+            Some(fetch_anchor(rpc_url).context(ZkipCliError::AnchorUnavailable)?)
+        }
+        _ => None,
+    };
+
+    // The guest only accepts a signature over the proven IP; checking here names the problem
+    let attestation = match &args.attestation {
+        Some(path) => {
+            let attestation = load_attestation(path).context(ZkipCliError::InvalidArguments)?;
+            let address = Address::from(addrs[0]);
+            if !attestation.verify(address) {
+                return Err(anyhow::anyhow!(tr!(Msg::AttestationInvalid, args.ip))
+                    .context(ZkipCliError::AttestationInvalid));
+            }
+            Some(attestation)
+        }
+        None => None,
+    };
+    if let Some(web_proof) = &web_proof {
+        let address = Address::from(addrs[0]);
+        if !web_proof.verify(address) {
+            return Err(anyhow::anyhow!(tr!(Msg::WebProofInvalid, target))
+                .context(ZkipCliError::AttestationInvalid));
+        }
+    }
+
+    // The guest only accepts ranges in the signed snapshot; checking here names the problem
+    let snapshot_signature = match &args.signed_snapshot {
+        Some(path) => {
+            let manifest = RootManifest::load(path).context(ZkipCliError::InvalidArguments)?;
+            let max_days = args.max_db_age.unwrap_or(CACHE_MAX_AGE_DAYS);
+            let snapshot = manifest
+                .snapshot_signature(&excluded_countries, u64::from(max_days) * SECONDS_PER_DAY)
+                .context(ZkipCliError::SnapshotSignatureInvalid)?;
+            if !snapshot.verify(&excluded.country_leaves(&excluded_countries)) {
+                return Err(anyhow::anyhow!(tr!(Msg::SnapshotStale, path.display()))
+                    .context(ZkipCliError::DatasetRootMismatch));
+            }
+            if !snapshot.fresh(timestamp) {
+                let age = timestamp.saturating_sub(snapshot.published_at);
+                let days = age / SECONDS_PER_DAY;
+                return Err(
+                    anyhow::anyhow!(tr!(Msg::DbTooOld, path.display(), days, max_days))
+                        .context(ZkipCliError::DatabaseTooOld),
+                );
+            }
+            Some(snapshot)
+        }
+        None => None,
+    };
+
+    // Witnesses only exist for an IP outside every country; failing here says so plainly
+    let gap = args
+        .gap_witness
+        .then(|| {
+            gap_input(&ips, &ips_v6, &excluded).ok_or_else(|| {
+                let countries = format!("{:?}", alpha2_codes);
+                anyhow::anyhow!(tr!(Msg::GapWitnessCovered, args.ip, countries))
+                    .context(ZkipCliError::InvalidArguments)
+            })
+        })
+        .transpose()?;
+
+    let nullifier = match (args.nullifier_epoch, &args.nullifier_salt) {
+        (Some(epoch), Some(salt)) => Some(NullifierInput {
+            epoch,
+            salt: parse_root(salt).context(ZkipCliError::InvalidArguments)?,
+        }),
+        _ => None,
+    };
+    let challenge = args
+        .challenge
+        .as_deref()
+        .map(parse_root)
+        .transpose()
+        .context(ZkipCliError::InvalidArguments)?;
+    let app_context = args
+        .app_context
+        .as_deref()
+        .map(parse_root)
+        .transpose()
+        .context(ZkipCliError::InvalidArguments)?;
+    let prefix_salt = args
+        .prefix_salt
+        .as_deref()
+        .map(parse_root)
+        .transpose()
+        .context(ZkipCliError::InvalidArguments)?;
+    let companion = match (&args.companion_proof, &args.companion_elf) {
+        (Some(proof), Some(elf)) => Some(load_companion(proof, elf)?),
+        _ => None,
+    };
+
+    // The guest only takes a public prefix that fits the address; checking here names the
+    // problem
+    let block = match (args.block, &args.block_salt) {
+        (Some(prefix_len), Some(salt)) => {
+            let address = Address::from(addrs[0]);
+            match block::clear(address, prefix_len, RESERVED_RANGES, RESERVED_RANGES_V6) {
+                None => {
+                    return Err(
+                        anyhow::anyhow!(tr!(Msg::BlockTooLong, prefix_len, addrs[0]))
+                            .context(ZkipCliError::InvalidArguments),
+                    );
+                }
+                Some(false) => {
+                    return Err(
+                        anyhow::anyhow!(tr!(Msg::BlockReserved, prefix_len, addrs[0]))
+                            .context(ZkipCliError::InvalidArguments),
+                    );
+                }
+                Some(true) => {}
+            }
+            let salt = parse_root(salt).context(ZkipCliError::InvalidArguments)?;
+            Some(BlockInput { prefix_len, salt })
+        }
+        _ => None,
+    };
+
+    if args.chunked {
+        let address = Address::from(addrs[0]);
+        let countries = format!("{:?}", excluded_countries);
+        info!("{}", tr!(Msg::TestingIp, target, addrs[0], countries));
+        let _phase = info_span!("phase", phase = "prove").entered();
+        let started = Instant::now();
+        let (proof, aggregate_vk) = prove_chunked(
+            &client,
+            address,
+            timestamp,
+            &excluded_countries,
+            &excluded,
+            args.compressed,
+        )?;
+        let wall = started.elapsed();
+        info!("{}", tr!(Msg::ProofVerified));
+        let decoded = decode_public_values(proof.public_values.as_slice())
+            .context(ZkipCliError::DecodeFailed)?;
+        log_dataset_root(decoded.values.dataset_root.0, decoded.legacy);
+        log_coverage(decoded.coverage);
+        log_aggregation(decoded.aggregation.as_ref());
+
+        let vkey = aggregate_vk.bytes32();
+        let system = if args.compressed {
+            "compressed"
+        } else {
+            "core"
+        };
+        record_proof(
+            &audit_log,
+            system,
+            "chunked",
+            &vkey,
+            &excluded_countries,
+            &proof,
+            &proof_time,
+        )?;
+        let run_label = RunLabel {
+            mode: "core",
+            variant: "chunked",
+            vkey: &vkey,
+            excluded_countries: &excluded_countries,
+            ranges: excluded.ranges.len() + excluded.ranges_v6.len(),
+            geoip_path: &geoip_path,
+        };
+        let cost = RunCost {
+            cycles: None,
+            wall,
+            proof: Some(&proof),
+        };
+        record_run(&history, run_label, cost)?;
+        if let Some(path) = &args.save_proof {
+            write_proof(&proof, path)?;
+        }
+        if let Some(dir) = &args.output_dir {
+            let metadata = ArtifactMetadata::new(
+                system,
+                "chunked",
+                &vkey,
+                &proof,
+                &excluded,
+                &excluded_countries,
+                &proof_time,
+            )?;
+            write_artifacts(dir, &proof, &metadata)?;
+        }
+        if let Some(store) = &store {
+            store_proof(store.as_ref(), &proof, args.save_proof.as_deref())?;
+        }
+        return Ok(());
+    }
+
+    let inputs = SharedInputs {
+        ips: &ips,
+        excluded_ranges: &excluded.ranges,
+        excluded_countries: &excluded_countries,
+        timestamp,
+        disclosure,
+        anchor,
+        batch: args.domain.is_some() || args.ip_file.is_some(),
+        inventory: args.inventory,
+        threshold: args.threshold,
+        ips_v6: &ips_v6,
+        excluded_ranges_v6: &excluded.ranges_v6,
+        range_counts: &excluded.counts,
+        include,
+        denylist: denied
+            .as_ref()
+            .map(|(codes, ranges)| (codes.as_slice(), ranges)),
+        policy: policy
+            .as_ref()
+            .map(|(expr, asns, ranges)| (expr, asns.as_slice(), ranges)),
+        per_country: args.per_country,
+        gap: gap.as_ref(),
+        attestation: attestation.as_ref(),
+        web_proof: web_proof.as_ref(),
+        snapshot_signature: snapshot_signature.as_ref(),
+        time_beacon: proof_time.beacon.as_ref(),
+        companion: companion.as_ref().map(|(input, ..)| input),
+        nullifier,
+        challenge,
+        valid_for: args.valid_for,
+        snapshot: Some(snapshot),
+        app_context,
+        prefix_salt,
+        block,
+    };
+    let mut stdin =
+        circuit_stdin(circuit.as_ref(), &inputs).context(ZkipCliError::InvalidArguments)?;
+    if let Some((_, proof, companion_vk)) = &companion {
+        if let SP1Proof::Compressed(inner) = &proof.proof {
+            stdin.write_proof(*inner.clone(), companion_vk.vk.clone());
+        }
+    }
+
+    let listed = match addrs.as_slice() {
+        [addr] => addr.to_string(),
+        _ => format!("{:?}", addrs),
+    };
+    match &policy {
+        Some((expr, ..)) => {
+            let hash = hex::encode(expr.hash());
+            info!("{}", tr!(Msg::TestingIpPolicy, target, listed, hash));
+        }
+        None => {
+            let testing = if include {
+                Msg::TestingIpInclusion
+            } else {
+                Msg::TestingIp
+            };
+            info!(
+                "{}",
+                tr!(testing, target, listed, format!("{:?}", excluded_countries))
+            );
+        }
+    }
+    if let Some((codes, _)) = &denied {
+        info!("{}", tr!(Msg::DeniedCountries, format!("{:?}", codes)));
+    }
+
+    let vkey = vk.bytes32();
+    let run_label = RunLabel {
+        mode: if args.execute { "execute" } else { "core" },
+        variant: program,
+        vkey: &vkey,
+        excluded_countries: &excluded_countries,
+        ranges: excluded.ranges.len() + excluded.ranges_v6.len(),
+        geoip_path: &geoip_path,
+    };
+
+    if args.execute {
+        let _phase = info_span!("phase", phase = "execute").entered();
+        let started = Instant::now();
+        let (output, report) = client
+            .execute(elf, &stdin)
+            .run()
+            .context(ZkipCliError::ExecutionFailed)?;
+        let wall = started.elapsed();
+        info!("{}", tr!(Msg::ProgramExecuted));
+
+        let DecodedPublicValues {
+            values: decoded,
+            mode,
+            denylist,
+            policy: committed_policy,
+            country_results,
+            coverage,
+            is_public_ip,
+            ranges_digest,
+            nullifier: committed_nullifier,
+            version: committed_version,
+            snapshot: committed_snapshot,
+            policy_id: committed_policy_id,
+            app_context: committed_app_context,
+            prefix: committed_prefix,
+            disclosed,
+            anchor,
+            address_count,
+            clear_count,
+            inventory_root: committed_inventory,
+            threshold: committed_threshold,
+            block: committed_block,
+            gap: gap_proof,
+            attestation: committed_attestation,
+            publisher: committed_publisher,
+            time_beacon: committed_time_beacon,
+            aggregation: _,
+            renewal: _,
+            companion: committed_companion,
+            challenge: committed_challenge,
+            validity: committed_validity,
+            legacy,
+        } = circuit
+            .decode(output.as_slice())
+            .context(ZkipCliError::DecodeFailed)?;
+        let PublicValuesStruct {
+            is_excluded: result,
+            timestamp,
+            excluded_countries,
+            dataset_root: committed_root,
+            ..
+        } = decoded;
+
+        log_result(mode, result);
+        info!("{}", tr!(Msg::Timestamp, timestamp));
+        info!(
+            "{}",
+            tr!(Msg::CheckedCountries, format!("{:?}", excluded_countries))
+        );
+        log_dataset_root(committed_root.0, legacy);
+        log_coverage(coverage);
+        log_ranges_digest(ranges_digest);
+        log_nullifier(committed_nullifier.as_ref());
+        log_version(committed_version);
+        if let Some(version) = committed_version {
+            if version != PUBLIC_VALUES_VERSION {
+                return Err(anyhow::anyhow!(tr!(
+                    Msg::PublicValuesVersionUnsupported,
+                    version,
+                    PUBLIC_VALUES_VERSION
+                ))
+                .context(ZkipCliError::PublicValuesVersionUnsupported));
+            }
+        }
+        log_snapshot(committed_snapshot.as_ref());
+        if let Some(committed) = committed_snapshot.filter(|committed| *committed != snapshot) {
+            return Err(anyhow::anyhow!(tr!(
+                Msg::SnapshotMismatch,
+                format!("0x{}", hex::encode(committed.hash)),
+                format_utc(committed.published_at),
+                format!("0x{}", hex::encode(snapshot.hash)),
+                format_utc(snapshot.published_at)
+            ))
+            .context(ZkipCliError::DecodeFailed));
+        }
+        log_policy_id(committed_policy_id);
+        if let Some(id) = committed_policy_id {
+            let expected = policy_hash(&excluded_countries);
+            if id != expected {
+                return Err(anyhow::anyhow!(tr!(
+                    Msg::PolicyIdMismatch,
+                    format!("0x{}", hex::encode(id)),
+                    format!("0x{}", hex::encode(expected))
+                ))
+                .context(ZkipCliError::DecodeFailed));
+            }
+        }
+        log_app_context(committed_app_context);
+        if let Some(context) = committed_app_context {
+            let expected = app_context.unwrap_or_default();
+            if context != expected {
+                return Err(anyhow::anyhow!(tr!(
+                    Msg::AppContextMismatch,
+                    format!("0x{}", hex::encode(context)),
+                    format!("0x{}", hex::encode(expected))
+                ))
+                .context(ZkipCliError::DecodeFailed));
+            }
+        }
+        log_prefix(committed_prefix.as_ref());
+        log_block(committed_block.as_ref());
+        log_public_ip(is_public_ip);
+        log_gap(gap_proof);
+        log_attestation(committed_attestation.as_ref());
+        log_publisher(committed_publisher.as_ref());
+        log_time_beacon(committed_time_beacon.as_ref());
+        log_denylist(denylist.as_ref());
+        log_country_results(&excluded_countries, country_results.as_deref());
+        log_policy(committed_policy.as_ref());
+        log_disclosure(disclosed);
+        log_anchor(anchor);
+        log_batch(address_count, clear_count);
+        log_threshold(committed_threshold, address_count);
+        log_inventory(committed_inventory);
+        log_companion(committed_companion.as_ref());
+        log_challenge(committed_challenge);
+        assert_eq!(committed_challenge, challenge);
+        log_validity(committed_validity);
+        assert_eq!(
+            committed_validity.map(|window| window.not_after - window.not_before),
+            args.valid_for.map(u64::from)
+        );
+        assert_eq!(committed_threshold, args.threshold);
+        assert_eq!(
+            committed_companion.map(|c| (c.vkey, c.public_values_digest)),
+            companion
+                .as_ref()
+                .map(|(input, ..)| (vkey_bytes(&input.vkey), input.public_values_digest()))
+        );
+        assert_eq!(committed_inventory.is_some(), args.inventory);
+        if let Some(root) = committed_inventory {
+            assert_eq!(root, inventory_root(&ips, &ips_v6));
+        }
+        if let Some(count) = address_count {
+            assert_eq!(count as usize, addrs.len());
+        }
+        if let Some(count) = clear_count {
+            let expected = zkip_lib::clear_count(&ips, &excluded.ranges)
+                + zkip_lib::clear_count(&ips_v6, &excluded.ranges_v6);
+            assert_eq!(count, expected);
+        }
+
+        let expected = if let Some((expr, asns, asn)) = &policy {
+            let address = Address::from(addrs[0]);
+            let countries = range_groups(
+                &excluded_countries,
+                &excluded.ranges,
+                &excluded.ranges_v6,
+                &excluded.counts,
+            );
+            let asn_groups = range_groups(asns, &asn.ranges, &asn.ranges_v6, &asn.counts);
+            expr.evaluate(address, &countries, &asn_groups)
+        } else if include {
+            zkip_lib::all_included(&ips, &excluded.ranges)
+                && zkip_lib::all_included(&ips_v6, &excluded.ranges_v6)
+        } else if let Some(threshold) = args.threshold {
+            zkip_lib::clear_count(&ips, &excluded.ranges)
+                + zkip_lib::clear_count(&ips_v6, &excluded.ranges_v6)
+                >= threshold
+        } else if let Some(block) = &block {
+            let address = Address::from(addrs[0]);
+            let committed = committed_block.as_ref().map(|values| values.commitment);
+            assert_eq!(committed, Some(block.values(address).commitment));
+            block::clear(
+                address,
+                block.prefix_len,
+                &excluded.ranges,
+                &excluded.ranges_v6,
+            ) == Some(true)
+        } else {
+            zkip_lib::all_excluded(&ips, &excluded.ranges)
+                && zkip_lib::all_excluded(&ips_v6, &excluded.ranges_v6)
+        };
+        let expected_mode = match (&policy, include, &denied) {
+            (Some(_), ..) => MODE_POLICY,
+            (None, true, Some(_)) => MODE_COMBINED,
+            (None, true, None) => MODE_INCLUSION,
+            (None, false, _) if args.threshold.is_some() => MODE_THRESHOLD,
+            (None, false, _) if block.is_some() => MODE_BLOCK,
+            (None, false, _) => MODE_EXCLUSION,
+        };
+        assert_eq!(mode, expected_mode);
+        assert_eq!(result, expected);
+        assert_eq!(gap_proof, gap.is_some());
+        let attestation = attestation
+            .as_ref()
+            .map(AttestationInput::values)
+            .or_else(|| web_proof.as_ref().map(WebProofInput::values));
+        assert_eq!(committed_attestation.is_some(), attestation.is_some());
+        if let (Some(committed), Some(attestation)) = (&committed_attestation, &attestation) {
+            assert_eq!(committed.pubkey, attestation.pubkey);
+            assert_eq!(committed.nonce, attestation.nonce);
+            assert_eq!(committed.attested_at, attestation.attested_at);
+        }
+        assert_eq!(committed_publisher.is_some(), snapshot_signature.is_some());
+        if let (Some(committed), Some(snapshot)) = (&committed_publisher, &snapshot_signature) {
+            assert_eq!(committed.pubkey, snapshot.pubkey);
+            assert_eq!(committed.snapshot_root, snapshot.snapshot_root);
+            assert_eq!(committed.published_at, snapshot.published_at);
+            assert_eq!(committed.max_age, snapshot.max_age);
+        }
+        assert_eq!(committed_time_beacon.is_some(), proof_time.beacon.is_some());
+        if let (Some(committed), Some(beacon)) = (&committed_time_beacon, &proof_time.beacon) {
+            assert_eq!(committed.pubkey, beacon.pubkey);
+            assert_eq!(timestamp, proof_time.timestamp);
+        }
+        if !legacy {
+            assert_eq!(committed_root.0, excluded.root(&excluded_countries));
+        }
+        assert_eq!(denylist.is_some(), denied.is_some());
+        if let (Some(denylist), Some((codes, ranges))) = (&denylist, &denied) {
+            let expected = zkip_lib::all_excluded(&ips, &ranges.ranges)
+                && zkip_lib::all_excluded(&ips_v6, &ranges.ranges_v6);
+            assert_eq!(denylist.is_excluded, expected);
+            assert_eq!(denylist.excluded_countries, *codes);
+            assert_eq!(denylist.dataset_root, ranges.root(codes));
+        }
+        let expected_results = args.per_country.then(|| {
+            zkip_lib::country_results(
+                &ips,
+                &ips_v6,
+                &excluded.ranges,
+                &excluded.ranges_v6,
+                &excluded.counts,
+            )
+        });
+        assert_eq!(country_results, expected_results);
+        if let Some(coverage) = coverage {
+            assert_eq!(
+                coverage,
+                zkip_lib::range_coverage(&excluded.ranges, &excluded.ranges_v6)
+            );
+        }
+        if let Some(is_public_ip) = is_public_ip {
+            assert_eq!(is_public_ip, zkip_lib::all_public(&ips, &ips_v6));
+        }
+        if let Some(digest) = ranges_digest {
+            assert_eq!(digest, excluded.digest(&excluded_countries));
+        }
+        if let Some(committed) = &committed_nullifier {
+            let expected = nullifier.map(|input| {
+                let address = Address::from(addrs[0]);
+                input.values(address).nullifier
+            });
+            let expected = expected.unwrap_or_default();
+            if committed.nullifier != expected {
+                return Err(anyhow::anyhow!(tr!(
+                    Msg::NullifierMismatch,
+                    format!("0x{}", hex::encode(committed.nullifier)),
+                    format!("0x{}", hex::encode(expected))
+                ))
+                .context(ZkipCliError::DecodeFailed));
+            }
+        }
+        if let Some(committed) = &committed_prefix {
+            let expected = prefix_salt.map(|salt| {
+                let address = Address::from(addrs[0]);
+                prefix::commitment(address, &salt)
+            });
+            let expected = expected.unwrap_or_default();
+            if committed.commitment != expected {
+                return Err(anyhow::anyhow!(tr!(
+                    Msg::PrefixMismatch,
+                    format!("0x{}", hex::encode(committed.commitment)),
+                    format!("0x{}", hex::encode(expected))
+                ))
+                .context(ZkipCliError::DecodeFailed));
+            }
+        }
+        assert_eq!(committed_policy.is_some(), policy.is_some());
+        if let (Some(committed), Some((expr, asns, asn))) = (&committed_policy, &policy) {
+            assert_eq!(committed.policy_hash, expr.hash());
+            let expected = asn_root(asns, &asn.ranges, &asn.ranges_v6, &asn.counts);
+            assert_eq!(committed.asn_root, expected);
+        }
+        info!("{}", tr!(Msg::VerificationPassed));
+
+        let cycles = report.total_instruction_count();
+        info!(cycles, "{}", tr!(Msg::Cycles, cycles));
+        usage.cycle_bucket = Some(cycle_bucket(cycles));
+
+        let cost = RunCost {
+            cycles: Some(cycles),
+            wall,
+            proof: None,
+        };
+        record_run(&history, run_label, cost)?;
+    } else {
+        let _phase = info_span!("phase", phase = "prove").entered();
+        let started = Instant::now();
+        let request = client.prove(&pk, &stdin);
+        // SP1 verifies the companion proof while compressing
+        let compressed = args.compressed || companion.is_some();
+        let proof = if compressed {
+            request.compressed().run()
+        } else {
+            request.run()
+        }
+        .context(ZkipCliError::ProvingFailed)?;
+        let wall = started.elapsed();
+
+        info!("{}", tr!(Msg::ProofGenerated));
+
+        client
+            .verify(&proof, &vk)
+            .context(ZkipCliError::VerificationFailed)?;
+        info!("{}", tr!(Msg::ProofVerified));
+        let decoded = circuit
+            .decode(proof.public_values.as_slice())
+            .context(ZkipCliError::DecodeFailed)?;
+        log_dataset_root(decoded.values.dataset_root.0, decoded.legacy);
+        log_coverage(decoded.coverage);
+        log_ranges_digest(decoded.ranges_digest);
+        log_nullifier(decoded.nullifier.as_ref());
+        log_version(decoded.version);
+        log_snapshot(decoded.snapshot.as_ref());
+        log_policy_id(decoded.policy_id);
+        log_app_context(decoded.app_context);
+        log_prefix(decoded.prefix.as_ref());
+        log_block(decoded.block.as_ref());
+        log_public_ip(decoded.is_public_ip);
+
+        record_proof(
+            &audit_log,
+            if compressed { "compressed" } else { "core" },
+            program,
+            &vk.bytes32(),
+            &excluded_countries,
+            &proof,
+            &proof_time,
+        )?;
+        let cost = RunCost {
+            cycles: None,
+            wall,
+            proof: Some(&proof),
+        };
+        record_run(&history, run_label, cost)?;
+
+        if let Some(path) = &args.save_proof {
+            write_proof(&proof, path)?;
+        }
+        if let Some(dir) = &args.output_dir {
+            let metadata = ArtifactMetadata::new(
+                if compressed { "compressed" } else { "core" },
+                program,
+                &vk.bytes32(),
+                &proof,
+                &excluded,
+                &excluded_countries,
+                &proof_time,
+            )?;
+            write_artifacts(dir, &proof, &metadata)?;
+        }
+        if let Some(store) = &store {
+            store_proof(store.as_ref(), &proof, args.save_proof.as_deref())?;
+        }
     }
     Ok(())
 }
@@ -0,0 +1,367 @@
+//! `.zkip` proof bundles, written by `zkip bundle`.
+//!
+//! A saved proof is enough for `zkip verify`, which finds the program that made it among
+//! the registered versions. A verifier without the registry, or without SP1, needs the
+//! rest spelled out: which vkey and program version the proof is for, which proof system
+//! wrapped it, and the snapshot its public values commit. A bundle carries all of it in
+//! one file, a CBOR map (RFC 8949) that any CBOR library reads. Its `proof` is the proof
+//! as `--save-proof` writes it, so the commands that take a proof take a bundle as well.
+//!
+//! An operator may sign a bundle with its ed25519 key, so relying parties can tell which
+//! proving service made it. The signature covers `zkip-bundle-v1` followed by the CBOR of
+//! the bundle with `signer` set and no `signature`; an unsigned bundle has neither. Readers
+//! reject keys the format doesn't define, so every field of a bundle that loads is signed.
+//!
+//! `zkip verify-bundle` checks a bundle before trusting what it says, and reports the
+//! outcome as a `BundleVerdict`.
+
+use anyhow::Context;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{SP1Proof, SP1ProofWithPublicValues};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use zkip_lib::error::ZkipError;
+use zkip_lib::snapshot::SnapshotMetadata;
+use zkip_lib::{decode_public_values, DecodedPublicValues, PUBLIC_VALUES_VERSION};
+
+use crate::error::ZkipCliError;
+use crate::i18n::Msg;
+use crate::manifest::parse_public_key;
+use crate::tr;
+
+/// File extension of a bundle.
+pub const EXTENSION: &str = "zkip";
+/// `format` of every bundle, so a reader tells one apart from other CBOR.
+pub const BUNDLE_FORMAT: &str = "zkip-bundle";
+/// Layout version of the bundle map. Readers reject versions they don't know.
+pub const BUNDLE_VERSION: u32 = 1;
+/// Domain tag prefixed to the bundle before signing.
+const SIGNING_DOMAIN: &[u8] = b"zkip-bundle-v1";
+
+/// The snapshot metadata a plain proof commits, as `SnapshotMetadata`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BundleSnapshot {
+    #[serde(with = "serde_bytes")]
+    pub source: [u8; 32],
+    pub published_at: u64,
+    #[serde(with = "serde_bytes")]
+    pub hash: [u8; 32],
+}
+
+impl From<SnapshotMetadata> for BundleSnapshot {
+    fn from(snapshot: SnapshotMetadata) -> Self {
+        Self {
+            source: snapshot.source,
+            published_at: snapshot.published_at,
+            hash: snapshot.hash,
+        }
+    }
+}
+
+/// One proof and what a verifier needs to know about it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProofBundle {
+    /// Always `BUNDLE_FORMAT`.
+    pub format: String,
+    /// `BUNDLE_VERSION` of the writer.
+    pub version: u32,
+    /// The bincode `SP1ProofWithPublicValues`, as `--save-proof` writes it.
+    #[serde(with = "serde_bytes")]
+    pub proof: Vec<u8>,
+    /// The raw public values the guest committed, for verifiers that don't decode `proof`.
+    #[serde(with = "serde_bytes")]
+    pub public_values: Vec<u8>,
+    /// `bytes32` hash of the verifying key, hex.
+    pub vkey: String,
+    /// Proof system: `core`, `compressed`, `groth16` or `plonk`.
+    pub proof_system: String,
+    /// Release of the program that made the proof.
+    pub program_version: String,
+    /// Its variant id, or `aggregate` or `renew` for those guests.
+    pub program_variant: String,
+    /// The snapshot the public values commit, for the layouts that commit one.
+    pub snapshot: Option<BundleSnapshot>,
+    /// Hex ed25519 public key of the operator that signed the bundle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer: Option<String>,
+    /// Hex ed25519 signature of the operator.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl ProofBundle {
+    /// Bundle `proof`, made by `program_version` `program_variant`, whose vkey hash is
+    /// `vkey`.
+    pub fn new(
+        proof: &SP1ProofWithPublicValues,
+        vkey: &str,
+        program_version: &str,
+        program_variant: &str,
+    ) -> anyhow::Result<Self> {
+        let snapshot = decode_public_values(proof.public_values.as_slice())
+            .ok()
+            .and_then(|decoded| decoded.snapshot)
+            .map(BundleSnapshot::from);
+        Ok(Self {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            proof: bincode::serialize(proof).with_context(|| tr!(Msg::SerializeProofFailed))?,
+            public_values: proof.public_values.as_slice().to_vec(),
+            vkey: vkey.to_string(),
+            proof_system: proof_system(&proof.proof).to_string(),
+            program_version: program_version.to_string(),
+            program_variant: program_variant.to_string(),
+            snapshot,
+            signer: None,
+            signature: None,
+        })
+    }
+
+    /// The bytes an operator signs: the domain tag and the bundle without its signature.
+    fn signing_message(&self) -> anyhow::Result<Vec<u8>> {
+        let unsigned = Self {
+            signature: None,
+            ..self.clone()
+        };
+        let mut message = SIGNING_DOMAIN.to_vec();
+        ciborium::into_writer(&unsigned, &mut message)
+            .with_context(|| tr!(Msg::SerializeBundleFailed))?;
+        Ok(message)
+    }
+
+    /// Sign the bundle with the operator's `key`, replacing any earlier signature.
+    pub fn sign(&mut self, key: &SigningKey) -> anyhow::Result<()> {
+        self.signer = Some(hex::encode(key.verifying_key().to_bytes()));
+        let signature = key.sign(&self.signing_message()?);
+        self.signature = Some(hex::encode(signature.to_bytes()));
+        Ok(())
+    }
+
+    /// Check the operator signature, if any, and return the signer. With `operators`, the
+    /// bundle must be signed by one of them.
+    pub fn verify_signature(
+        &self,
+        operators: &[VerifyingKey],
+    ) -> Result<Option<VerifyingKey>, Rejection> {
+        let (signer, signature) = match (&self.signer, &self.signature) {
+            (None, None) if operators.is_empty() => return Ok(None),
+            (None, None) => return Err(Rejection::UntrustedSigner),
+            (Some(signer), Some(signature)) => (signer, signature),
+            _ => return Err(Rejection::InvalidSignature),
+        };
+        let signer = parse_public_key(signer).map_err(|_| Rejection::InvalidSignature)?;
+        let bytes: [u8; 64] = hex::decode(signature)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(Rejection::InvalidSignature)?;
+        let message = self
+            .signing_message()
+            .map_err(|_| Rejection::InvalidSignature)?;
+        signer
+            .verify(&message, &Signature::from_bytes(&bytes))
+            .map_err(|_| Rejection::InvalidSignature)?;
+        if !operators.is_empty() && !operators.contains(&signer) {
+            return Err(Rejection::UntrustedSigner);
+        }
+        Ok(Some(signer))
+    }
+
+    /// The bundled proof. Fails if its public values differ from the bundle's.
+    pub fn proof(&self) -> anyhow::Result<SP1ProofWithPublicValues> {
+        let proof: SP1ProofWithPublicValues =
+            bincode::deserialize(&self.proof).with_context(|| tr!(Msg::BundleDecodeFailed))?;
+        if proof.public_values.as_slice() != self.public_values.as_slice() {
+            anyhow::bail!(tr!(Msg::BundleValuesDiffer));
+        }
+        Ok(proof)
+    }
+
+    /// Write the bundle to `path`.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let file = File::create(path).with_context(|| tr!(Msg::CreateFailed, path.display()))?;
+        let mut writer = BufWriter::new(file);
+        ciborium::into_writer(self, &mut writer)
+            .with_context(|| tr!(Msg::BundleWriteFailed, path.display()))?;
+        writer
+            .flush()
+            .with_context(|| tr!(Msg::BundleWriteFailed, path.display()))
+    }
+
+    /// Read a bundle from `path`, rejecting other CBOR, unknown keys and unknown versions.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path).with_context(|| tr!(Msg::OpenFailed, path.display()))?;
+        let bundle: Self = ciborium::from_reader(BufReader::new(file))
+            .with_context(|| tr!(Msg::BundleReadFailed, path.display()))?;
+        if bundle.format != BUNDLE_FORMAT {
+            anyhow::bail!(tr!(Msg::NotBundle, path.display()));
+        }
+        if bundle.version != BUNDLE_VERSION {
+            anyhow::bail!(tr!(
+                Msg::BundleVersion,
+                path.display(),
+                bundle.version,
+                BUNDLE_VERSION
+            ));
+        }
+        Ok(bundle)
+    }
+}
+
+/// Why `zkip verify-bundle` rejects a bundle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rejection {
+    /// The vkey, or that of the program a renewal or aggregate names, is no known build's.
+    UnknownProgram,
+    /// It is a revoked build's.
+    RevokedProgram,
+    /// The bundle names another version or variant than the build with its vkey.
+    ProgramMismatch,
+    /// `proof_system` is not that of the bundled proof.
+    ProofSystemMismatch,
+    /// `snapshot` is not the snapshot the public values commit.
+    SnapshotMismatch,
+    /// The proof does not verify against the vkey.
+    InvalidProof,
+    /// The public values are no layout this CLI decodes.
+    UndecodablePublicValues,
+    /// They are a layout version newer than `PUBLIC_VALUES_VERSION`.
+    UnsupportedPublicValuesVersion,
+    /// The operator signature does not verify.
+    InvalidSignature,
+    /// The bundle is unsigned, or signed by none of the required operators.
+    UntrustedSigner,
+}
+
+impl Rejection {
+    /// Snake-case identifier, as in the verdict.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::UnknownProgram => "unknown_program",
+            Self::RevokedProgram => "revoked_program",
+            Self::ProgramMismatch => "program_mismatch",
+            Self::ProofSystemMismatch => "proof_system_mismatch",
+            Self::SnapshotMismatch => "snapshot_mismatch",
+            Self::InvalidProof => "invalid_proof",
+            Self::UndecodablePublicValues => "undecodable_public_values",
+            Self::UnsupportedPublicValuesVersion => "unsupported_public_values_version",
+            Self::InvalidSignature => "invalid_signature",
+            Self::UntrustedSigner => "untrusted_signer",
+        }
+    }
+
+    /// The error `verify-bundle` exits with when this is the first rejection.
+    pub fn error(self) -> ZkipCliError {
+        match self {
+            Self::UnknownProgram | Self::InvalidProof => ZkipCliError::VerificationFailed,
+            Self::RevokedProgram => ZkipCliError::RevokedVersion,
+            Self::ProgramMismatch => ZkipCliError::VkeyMismatch,
+            Self::ProofSystemMismatch | Self::SnapshotMismatch => ZkipCliError::BundleMismatch,
+            Self::UndecodablePublicValues => ZkipCliError::DecodeFailed,
+            Self::UnsupportedPublicValuesVersion => ZkipCliError::PublicValuesVersionUnsupported,
+            Self::InvalidSignature | Self::UntrustedSigner => {
+                ZkipCliError::OperatorSignatureInvalid
+            }
+        }
+    }
+}
+
+/// What `zkip verify-bundle` found, printed as JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleVerdict {
+    /// True if nothing was rejected.
+    pub accepted: bool,
+    /// The bundle's vkey hash.
+    pub vkey: String,
+    /// The known build with that vkey, if any.
+    pub program_version: Option<String>,
+    pub program_variant: Option<String>,
+    pub proof_system: String,
+    /// Hex key of the operator whose signature verified, if the bundle is signed.
+    pub signer: Option<String>,
+    /// Whether the proof verifies against the vkey; `None` if no known build has it, so
+    /// there was nothing to check it against.
+    pub proof_valid: Option<bool>,
+    /// The committed `PUBLIC_VALUES_VERSION`, for layouts that commit it.
+    pub public_values_version: Option<u16>,
+    /// This CLI's `PUBLIC_VALUES_VERSION`, the newest it accepts.
+    pub supported_version: u16,
+    /// Every reason to reject the bundle, in the order found.
+    pub rejections: Vec<Rejection>,
+}
+
+impl BundleVerdict {
+    /// A verdict on `bundle`, holding `proof`, whose public values decode to `decoded`.
+    /// Checks the layout, that `proof_system` and `snapshot` match what the proof holds,
+    /// and the operator signature, and leaves the program to the caller. With `operators`,
+    /// the bundle must be signed by one of them.
+    pub fn new(
+        bundle: &ProofBundle,
+        proof: &SP1ProofWithPublicValues,
+        decoded: Result<&DecodedPublicValues, &ZkipError>,
+        operators: &[VerifyingKey],
+    ) -> Self {
+        let mut verdict = Self {
+            accepted: true,
+            vkey: bundle.vkey.clone(),
+            program_version: None,
+            program_variant: None,
+            proof_system: bundle.proof_system.clone(),
+            signer: None,
+            proof_valid: None,
+            public_values_version: match decoded {
+                Ok(decoded) => decoded.version,
+                Err(ZkipError::UnsupportedPublicValuesVersion(version)) => Some(*version),
+                Err(_) => None,
+            },
+            supported_version: PUBLIC_VALUES_VERSION,
+            rejections: Vec::new(),
+        };
+        match decoded {
+            Ok(decoded) => {
+                if bundle.snapshot != decoded.snapshot.map(BundleSnapshot::from) {
+                    verdict.reject(Rejection::SnapshotMismatch);
+                }
+            }
+            Err(ZkipError::UnsupportedPublicValuesVersion(_)) => {
+                verdict.reject(Rejection::UnsupportedPublicValuesVersion)
+            }
+            Err(_) => verdict.reject(Rejection::UndecodablePublicValues),
+        }
+        if bundle.proof_system != proof_system(&proof.proof) {
+            verdict.reject(Rejection::ProofSystemMismatch);
+        }
+        match bundle.verify_signature(operators) {
+            Ok(signer) => verdict.signer = signer.map(|key| hex::encode(key.to_bytes())),
+            Err(rejection) => verdict.reject(rejection),
+        }
+        verdict
+    }
+
+    pub fn reject(&mut self, rejection: Rejection) {
+        self.accepted = false;
+        if !self.rejections.contains(&rejection) {
+            self.rejections.push(rejection);
+        }
+    }
+}
+
+/// True if `path` names a bundle by its extension.
+pub fn is_bundle(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == EXTENSION)
+}
+
+/// The name of the proof system of `proof`.
+pub fn proof_system(proof: &SP1Proof) -> &'static str {
+    match proof {
+        SP1Proof::Core(_) => "core",
+        SP1Proof::Compressed(_) => "compressed",
+        SP1Proof::Groth16(_) => "groth16",
+        SP1Proof::Plonk(_) => "plonk",
+    }
+}
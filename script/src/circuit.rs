@@ -0,0 +1,75 @@
+//! The `PolicyCircuit` extension point for custom guest programs.
+//!
+//! A circuit is a guest ELF plus the host-side knowledge needed to drive it: a stable ID
+//! (recorded in `zkip.lock`, the audit log and the run history), any inputs it reads after
+//! the shared ones, and how to decode what it commits. The built-in variants are circuits;
+//! teams add their own predicates or datasets by scaffolding a guest from
+//! `templates/policy-circuit` and loading it with `--elf`, or by implementing this trait in
+//! their own binary, instead of forking the proving pipeline.
+//!
+//! Every circuit reads the shared inputs first, the `zkip_lib::ZkipInput` and packed ranges
+//! `inputs::build_stdin` writes, so the IP lookup, timestamp source, disclosure and anchor
+//! support carry over.
+
+use sp1_sdk::SP1Stdin;
+use zkip_lib::{decode_public_values, DecodedPublicValues};
+
+use crate::inputs::build_stdin;
+pub use crate::inputs::SharedInputs;
+use crate::variants::ProgramVariant;
+
+pub trait PolicyCircuit {
+    /// Stable identifier, recorded wherever the built-in variant ID is.
+    fn id(&self) -> &str;
+
+    fn elf(&self) -> &[u8];
+
+    /// Write the circuit's own inputs, which the guest reads after the shared ones.
+    fn write_extra_inputs(&self, _stdin: &mut SP1Stdin) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Decode the committed public values. Circuits that commit one of the shared layouts
+    /// keep the default; fixtures and `zkip verify` rely on it.
+    fn decode(&self, public_values: &[u8]) -> anyhow::Result<DecodedPublicValues> {
+        Ok(decode_public_values(public_values)?)
+    }
+}
+
+/// Build the guest stdin for `circuit`: the shared inputs, then its own.
+pub fn circuit_stdin(
+    circuit: &dyn PolicyCircuit,
+    inputs: &SharedInputs,
+) -> anyhow::Result<SP1Stdin> {
+    let mut stdin = build_stdin(inputs);
+    circuit.write_extra_inputs(&mut stdin)?;
+    Ok(stdin)
+}
+
+impl PolicyCircuit for ProgramVariant {
+    fn id(&self) -> &str {
+        ProgramVariant::id(*self)
+    }
+
+    fn elf(&self) -> &[u8] {
+        ProgramVariant::elf(*self)
+    }
+}
+
+/// A guest loaded from disk with `--elf`, e.g. one built from the scaffold. It reads only
+/// the shared inputs and commits a shared layout.
+#[derive(Debug, Clone)]
+pub struct ExternalCircuit {
+    pub id: String,
+    pub elf: Vec<u8>,
+}
+
+impl PolicyCircuit for ExternalCircuit {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn elf(&self) -> &[u8] {
+        &self.elf
+    }
+}
@@ -0,0 +1,36 @@
+//! `zkip.toml`: operator settings shared by the zkip binaries.
+//!
+//! Unlike `zkip.lock`, which zkip writes, this file is edited by hand. Every section is
+//! optional, and a missing file means defaults throughout.
+
+use anyhow::Context;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::Msg;
+use crate::store::StoreConfig;
+use crate::tr;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ZkipConfig {
+    /// Where proofs, envelopes and fixtures are copied after each run.
+    pub store: Option<StoreConfig>,
+}
+
+pub fn default_config_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../zkip.toml")
+}
+
+impl ZkipConfig {
+    /// Load the config at `path`, or the defaults if there is none.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content =
+            fs::read_to_string(path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+        toml::from_str(&content).with_context(|| tr!(Msg::ParseFailed, path.display()))
+    }
+}
@@ -0,0 +1,206 @@
+//! Multi-operator co-signed proofs.
+//!
+//! For deployments where no single proving host is trusted to have fed honest inputs,
+//! independent operators each check a proof (typically by re-running `--execute` with the
+//! same IP against their own snapshot) and add a detached ed25519 signature to an envelope
+//! stored next to it. `zkip verify --cosigners` then requires signatures from at least
+//! `threshold` of the configured operators:
+//!
+//! ```json
+//! {
+//!   "threshold": 2,
+//!   "operators": [
+//!     { "name": "eu-1", "key": "d75a..." },
+//!     { "name": "us-1", "key": "3d40..." },
+//!     { "name": "ap-1", "key": "fc51..." }
+//!   ]
+//! }
+//! ```
+//!
+//! Each signature covers `zkip-cosign-v1` followed by the proof ID.
+
+use anyhow::{bail, Context};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::Msg;
+use crate::manifest::parse_public_key;
+use crate::tr;
+
+/// Domain tag prefixed to the proof ID before signing.
+const COSIGN_DOMAIN: &[u8] = b"zkip-cosign-v1";
+
+/// Envelope path used when none is given: `<proof>.cosign.json`.
+pub fn default_envelope_path(proof: &Path) -> PathBuf {
+    let mut name = proof.as_os_str().to_owned();
+    name.push(".cosign.json");
+    PathBuf::from(name)
+}
+
+fn signing_message(proof_id: &str) -> Vec<u8> {
+    let mut message = COSIGN_DOMAIN.to_vec();
+    message.extend(normalize_hex(proof_id).as_bytes());
+    message
+}
+
+/// Lowercase hex without a `0x` prefix, for comparing IDs and keys.
+fn normalize_hex(value: &str) -> String {
+    value.trim().trim_start_matches("0x").to_lowercase()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cosignature {
+    /// Hex ed25519 public key of the operator.
+    pub signer: String,
+    pub signature: String,
+    pub signed_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CosignEnvelope {
+    /// Proof ID, as recorded in the audit log.
+    pub proof_id: String,
+    pub signatures: Vec<Cosignature>,
+}
+
+impl CosignEnvelope {
+    /// Load the envelope at `path`, or start an empty one for `proof_id` if there is none.
+    pub fn open(path: &Path, proof_id: &str) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self {
+                proof_id: normalize_hex(proof_id),
+                signatures: Vec::new(),
+            });
+        }
+        let envelope = Self::load(path)?;
+        if envelope.proof_id != normalize_hex(proof_id) {
+            bail!(tr!(
+                Msg::EnvelopeProofMismatch,
+                path.display(),
+                envelope.proof_id,
+                proof_id
+            ));
+        }
+        Ok(envelope)
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content =
+            fs::read_to_string(path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+        serde_json::from_str(&content).with_context(|| tr!(Msg::ParseFailed, path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .with_context(|| tr!(Msg::SerializeEnvelopeFailed))?;
+        fs::write(path, content).with_context(|| tr!(Msg::WriteFailed, path.display()))
+    }
+
+    /// Add `key`'s signature. Returns `false` if that key has already signed.
+    pub fn sign(&mut self, key: &SigningKey, signed_at: u64) -> bool {
+        let signer = hex::encode(key.verifying_key().to_bytes());
+        if self
+            .signatures
+            .iter()
+            .any(|s| normalize_hex(&s.signer) == signer)
+        {
+            return false;
+        }
+        let signature = key.sign(&signing_message(&self.proof_id));
+        self.signatures.push(Cosignature {
+            signer,
+            signature: hex::encode(signature.to_bytes()),
+            signed_at,
+        });
+        true
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operator {
+    pub name: String,
+    /// Hex ed25519 public key.
+    pub key: String,
+}
+
+/// The operators a verifier trusts and how many of them must sign.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CosignPolicy {
+    pub threshold: usize,
+    pub operators: Vec<Operator>,
+}
+
+impl CosignPolicy {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content =
+            fs::read_to_string(path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+        let policy: Self = serde_json::from_str(&content)
+            .with_context(|| tr!(Msg::ParseFailed, path.display()))?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.threshold == 0 || self.threshold > self.operators.len() {
+            bail!(tr!(
+                Msg::CosignThreshold,
+                self.threshold,
+                self.operators.len()
+            ));
+        }
+        let mut keys = HashSet::new();
+        for operator in &self.operators {
+            parse_public_key(&operator.key)
+                .with_context(|| tr!(Msg::OperatorKeyInvalid, operator.name))?;
+            if !keys.insert(normalize_hex(&operator.key)) {
+                bail!(tr!(Msg::OperatorKeyDuplicate, operator.key));
+            }
+        }
+        Ok(())
+    }
+
+    /// Names of the configured operators with a valid signature on `envelope` for
+    /// `proof_id`. Signatures from unknown keys are ignored.
+    pub fn signed_by(&self, envelope: &CosignEnvelope, proof_id: &str) -> Vec<&str> {
+        if normalize_hex(&envelope.proof_id) != normalize_hex(proof_id) {
+            return Vec::new();
+        }
+        let message = signing_message(proof_id);
+        self.operators
+            .iter()
+            .filter(|operator| {
+                envelope.signatures.iter().any(|cosignature| {
+                    normalize_hex(&cosignature.signer) == normalize_hex(&operator.key)
+                        && verify_signature(&operator.key, &cosignature.signature, &message)
+                })
+            })
+            .map(|operator| operator.name.as_str())
+            .collect()
+    }
+
+    /// Fail unless at least `threshold` operators signed. Returns their names.
+    pub fn check(&self, envelope: &CosignEnvelope, proof_id: &str) -> anyhow::Result<Vec<&str>> {
+        let signed = self.signed_by(envelope, proof_id);
+        if signed.len() < self.threshold {
+            bail!(tr!(Msg::CosignShort, signed.len(), self.threshold));
+        }
+        Ok(signed)
+    }
+}
+
+fn verify_signature(key: &str, signature: &str, message: &[u8]) -> bool {
+    let Ok(key) = parse_public_key(key) else {
+        return false;
+    };
+    let Some(bytes) = hex::decode(signature)
+        .ok()
+        .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+    else {
+        return false;
+    };
+    key.verify(message, &Signature::from_bytes(&bytes)).is_ok()
+}
@@ -0,0 +1,280 @@
+//! ISO 3166-1 country code resolution.
+
+use anyhow::{bail, Context};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use zkip_lib::country::CountryCode;
+
+use crate::i18n::Msg;
+use crate::tr;
+
+const COUNTRIES_CSV: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../data/countries.csv");
+
+/// UN M49 region and sub-region of a country.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountryRegion {
+    pub region_code: u16,
+    pub region: String,
+    pub sub_region_code: u16,
+    pub sub_region: String,
+}
+
+/// Split a CSV line, honouring double-quoted fields (several country names contain commas).
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Data rows of `countries.csv`, split into fields.
+fn country_rows() -> anyhow::Result<Vec<Vec<String>>> {
+    let file = File::open(COUNTRIES_CSV).with_context(|| tr!(Msg::CountriesOpenFailed))?;
+    let reader = BufReader::new(file);
+
+    let mut rows = Vec::new();
+    for line in reader.lines().skip(1) {
+        let line = line.with_context(|| tr!(Msg::ReadLineFailed))?;
+        rows.push(split_csv_line(&line));
+    }
+    Ok(rows)
+}
+
+/// Numeric codes by alpha-2 code, from the table in `zkip_lib::country`.
+pub fn load_country_codes() -> anyhow::Result<HashMap<String, u16>> {
+    Ok(CountryCode::all()
+        .map(|code| (code.alpha2().to_string(), code.numeric()))
+        .collect())
+}
+
+/// Alpha-2 codes and English names by numeric code, from the table in `zkip_lib::country`.
+pub fn load_country_names() -> anyhow::Result<HashMap<u16, (String, String)>> {
+    Ok(CountryCode::all()
+        .map(|code| {
+            let entry = (code.alpha2().to_string(), code.name().to_string());
+            (code.numeric(), entry)
+        })
+        .collect())
+}
+
+/// Load UN M49 regions by alpha-2 code. Countries without a region (Antarctica) are omitted.
+pub fn load_country_regions() -> anyhow::Result<HashMap<String, CountryRegion>> {
+    let mut regions = HashMap::new();
+    for fields in country_rows()? {
+        if fields.len() < 10 {
+            continue;
+        }
+        let (Ok(region_code), Ok(sub_region_code)) =
+            (fields[8].parse::<u16>(), fields[9].parse::<u16>())
+        else {
+            continue;
+        };
+        regions.insert(
+            fields[1].to_uppercase(),
+            CountryRegion {
+                region_code,
+                region: fields[5].clone(),
+                sub_region_code,
+                sub_region: fields[6].clone(),
+            },
+        );
+    }
+    Ok(regions)
+}
+
+/// A row of `countries.csv`, as needed to resolve user input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Country {
+    pub name: String,
+    pub alpha2: String,
+    pub alpha3: String,
+    pub numeric: u16,
+}
+
+/// Common names that differ from the ISO short name, mapped to alpha-2 codes.
+const COUNTRY_ALIASES: &[(&str, &str)] = &[
+    ("america", "US"),
+    ("britain", "GB"),
+    ("burma", "MM"),
+    ("cape verde", "CV"),
+    ("czech republic", "CZ"),
+    ("drc", "CD"),
+    ("england", "GB"),
+    ("great britain", "GB"),
+    ("holland", "NL"),
+    ("iran", "IR"),
+    ("ivory coast", "CI"),
+    ("laos", "LA"),
+    ("macedonia", "MK"),
+    ("north korea", "KP"),
+    ("russia", "RU"),
+    ("south korea", "KR"),
+    ("swaziland", "SZ"),
+    ("syria", "SY"),
+    ("turkey", "TR"),
+    ("uae", "AE"),
+    ("uk", "GB"),
+    ("united kingdom", "GB"),
+    ("united states", "US"),
+    ("vatican", "VA"),
+    ("vietnam", "VN"),
+];
+
+/// Every country with its names and codes, from the table in `zkip_lib::country`.
+pub fn load_countries() -> anyhow::Result<Vec<Country>> {
+    Ok(CountryCode::all()
+        .map(|code| Country {
+            name: code.name().to_string(),
+            alpha2: code.alpha2().to_string(),
+            alpha3: code.alpha3().to_string(),
+            numeric: code.numeric(),
+        })
+        .collect())
+}
+
+/// Lowercase, strip accents and punctuation, and collapse whitespace, so "Côte d'Ivoire"
+/// and "cote d ivoire" compare equal.
+fn normalize_name(name: &str) -> String {
+    let folded: String = name
+        .chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+            'ç' => 'c',
+            'è' | 'é' | 'ê' | 'ë' => 'e',
+            'ì' | 'í' | 'î' | 'ï' => 'i',
+            'ñ' => 'n',
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' => 'o',
+            'ù' | 'ú' | 'û' | 'ü' => 'u',
+            c if c.is_ascii_alphanumeric() => c,
+            _ => ' ',
+        })
+        .collect();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Levenshtein distance, for "did you mean" suggestions.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn describe(countries: &[&Country]) -> String {
+    countries
+        .iter()
+        .map(|c| format!("{} ({})", c.name, c.alpha2))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Resolve an alpha-2 code, alpha-3 code, country name or common alias.
+///
+/// Names may be abbreviated as long as exactly one country starts with (or, failing
+/// that, contains) the input; otherwise the error lists the candidates or suggests the
+/// closest name.
+pub fn resolve_country<'a>(input: &str, countries: &'a [Country]) -> anyhow::Result<&'a Country> {
+    let upper = input.trim().to_uppercase();
+    let by_code = |code: &str| countries.iter().find(|c| c.alpha2 == code);
+    if let Some(country) = by_code(&upper) {
+        return Ok(country);
+    }
+    if let Some(country) = countries.iter().find(|c| c.alpha3 == upper) {
+        return Ok(country);
+    }
+
+    let wanted = normalize_name(input);
+    if let Some(country) = COUNTRY_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == wanted)
+        .and_then(|(_, code)| by_code(code))
+    {
+        return Ok(country);
+    }
+    let names: Vec<(String, &Country)> = countries
+        .iter()
+        .map(|c| (normalize_name(&c.name), c))
+        .collect();
+    if let Some((_, country)) = names.iter().find(|(name, _)| *name == wanted) {
+        return Ok(country);
+    }
+
+    // Two-letter inputs that are not codes would match too many names by substring.
+    if wanted.len() >= 3 {
+        let mut matches: Vec<&Country> = names
+            .iter()
+            .filter(|(name, _)| name.starts_with(&wanted))
+            .map(|(_, c)| *c)
+            .collect();
+        if matches.is_empty() {
+            matches = names
+                .iter()
+                .filter(|(name, _)| name.contains(&wanted))
+                .map(|(_, c)| *c)
+                .collect();
+        }
+        match matches.as_slice() {
+            [country] => return Ok(country),
+            [] => {}
+            _ => bail!(tr!(Msg::CountryAmbiguous, input.trim(), describe(&matches))),
+        }
+    }
+
+    let suggestion =
+        names
+            .iter()
+            .map(|(name, c)| (edit_distance(&wanted, name), *c))
+            .chain(COUNTRY_ALIASES.iter().filter_map(|(alias, code)| {
+                by_code(code).map(|c| (edit_distance(&wanted, alias), c))
+            }))
+            .filter(|(distance, _)| *distance <= (wanted.len() / 3).max(1))
+            .min_by_key(|(distance, _)| *distance);
+    match suggestion {
+        Some((_, country)) => bail!(tr!(
+            Msg::CountryUnknownSuggest,
+            input.trim(),
+            describe(&[country])
+        )),
+        None => bail!(tr!(Msg::CountryUnknown, input.trim())),
+    }
+}
+
+/// Parse comma-separated country codes or names and resolve to numeric codes.
+pub fn parse_excluded_countries(exclude_arg: &str) -> anyhow::Result<(Vec<String>, Vec<u16>)> {
+    let countries = load_countries()?;
+    let mut alpha2_codes = Vec::new();
+    let mut numeric_codes = Vec::new();
+
+    for input in exclude_arg.split(',') {
+        if input.trim().is_empty() {
+            continue;
+        }
+        let country = resolve_country(input, &countries)?;
+        alpha2_codes.push(country.alpha2.clone());
+        numeric_codes.push(country.numeric);
+    }
+
+    if numeric_codes.is_empty() {
+        bail!(tr!(Msg::NoCountryCodes));
+    }
+
+    Ok((alpha2_codes, numeric_codes))
+}
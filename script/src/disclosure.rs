@@ -0,0 +1,74 @@
+//! Opt-in coarse location disclosure.
+//!
+//! With `--disclose`, the guest also commits the UN M49 region or sub-region of the IP's
+//! country, or the country itself for verifiers that need the jurisdiction. The IP stays
+//! private either way. The host only picks the granularity: the guest finds the excluded
+//! country whose ranges contain the IP, the ranges it hashes into `dataset_root`, and
+//! commits that country's code. So the IP must be in one of the listed countries, and the
+//! disclosure carries the same dataset trust as the result.
+
+use anyhow::anyhow;
+use clap::ValueEnum;
+use zkip_lib::{
+    disclosed_code, DisclosureInput, GRANULARITY_COUNTRY, GRANULARITY_REGION,
+    GRANULARITY_SUB_REGION,
+};
+
+use crate::countries::{load_country_names, load_country_regions};
+use crate::i18n::Msg;
+use crate::snapshot::ExcludedRanges;
+use crate::tr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Disclosure {
+    /// Continent-level UN M49 region (e.g. 150 = Europe)
+    Region,
+    /// UN M49 sub-region (e.g. 155 = Western Europe)
+    SubRegion,
+    /// ISO 3166-1 numeric country code (e.g. 250 = France)
+    Country,
+}
+
+impl Disclosure {
+    pub fn granularity(self) -> u8 {
+        match self {
+            Self::Region => GRANULARITY_REGION,
+            Self::SubRegion => GRANULARITY_SUB_REGION,
+            Self::Country => GRANULARITY_COUNTRY,
+        }
+    }
+}
+
+/// Build the guest disclosure input for `ip`, checking first that the guest will find it in
+/// one of `excluded_countries`, with their ranges `excluded`.
+pub fn resolve_disclosure(
+    ip: u32,
+    disclosure: Disclosure,
+    excluded_countries: &[u16],
+    excluded: &ExcludedRanges,
+) -> anyhow::Result<DisclosureInput> {
+    let granularity = disclosure.granularity();
+    disclosed_code(
+        granularity,
+        ip,
+        excluded_countries,
+        &excluded.ranges,
+        &excluded.counts,
+    )
+    .ok_or_else(|| anyhow!(tr!(Msg::DiscloseNotListed)))?;
+    Ok(granularity)
+}
+
+/// Human-readable name of a disclosed M49 code, if it is known.
+pub fn region_name(granularity: u8, code: u16) -> Option<String> {
+    if granularity == GRANULARITY_COUNTRY {
+        let (_, name) = load_country_names().ok()?.remove(&code)?;
+        return Some(name);
+    }
+    let regions = load_country_regions().ok()?;
+    regions.into_values().find_map(|r| match granularity {
+        GRANULARITY_REGION if r.region_code == code => Some(r.region),
+        GRANULARITY_SUB_REGION if r.sub_region_code == code => Some(r.sub_region),
+        _ => None,
+    })
+}
@@ -0,0 +1,47 @@
+//! `zkip prove --domain`: batch proofs over every address a host name resolves to.
+//!
+//! IPv4 and IPv6 answers are proved together. `--skip-ipv6` leaves the AAAA answers out,
+//! e.g. to avoid fetching the IPv6 database; the proof then covers fewer addresses than the
+//! domain has, and says so in its address count.
+
+use anyhow::{bail, Context};
+use std::net::{IpAddr, ToSocketAddrs};
+use tracing::{info, warn};
+
+use crate::i18n::Msg;
+use crate::tr;
+
+/// Resolve `domain` with the system resolver and return its addresses, sorted (IPv4
+/// first) and deduplicated.
+pub fn resolve_domain(domain: &str, skip_ipv6: bool) -> anyhow::Result<Vec<IpAddr>> {
+    let domain = domain.trim().trim_end_matches('.');
+    if domain.is_empty() || domain.parse::<IpAddr>().is_ok() {
+        bail!(tr!(Msg::NotHostName, format!("{:?}", domain)));
+    }
+    let mut addrs: Vec<IpAddr> = (domain, 0)
+        .to_socket_addrs()
+        .with_context(|| tr!(Msg::ResolveFailed, domain))?
+        .map(|addr| addr.ip())
+        .collect();
+    addrs.sort_unstable();
+    addrs.dedup();
+
+    if skip_ipv6 {
+        let ipv6 = addrs.iter().filter(|addr| addr.is_ipv6()).count();
+        if ipv6 > 0 {
+            warn!("{}", tr!(Msg::DomainIpv6Skipped, ipv6, domain));
+        }
+        addrs.retain(IpAddr::is_ipv4);
+    }
+    if addrs.is_empty() {
+        bail!(tr!(Msg::DomainNoAddresses, domain));
+    }
+
+    let listed = addrs
+        .iter()
+        .map(IpAddr::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    info!("{}", tr!(Msg::DomainResolved, domain, addrs.len(), listed));
+    Ok(addrs)
+}
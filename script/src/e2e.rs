@@ -0,0 +1,221 @@
+//! `zkip e2e`: a one-command full-stack smoke test on a local anvil node.
+//!
+//! Boots `anvil`, builds the Foundry project in `contracts/`, deploys the SP1 verifier for
+//! the chosen proof system and the `Zkip` contract, then submits a freshly generated
+//! proof and returns what the contract decoded. The contract is expected to expose:
+//!
+//! ```solidity
+//! constructor(address verifier, bytes32 programVKey);
+//! function verifyZkipProof(bytes calldata publicValues, bytes calldata proofBytes)
+//!     external view
+//!     returns (bool isExcluded, uint64 timestamp, uint16[] memory excludedCountries);
+//! ```
+
+use alloy::contract::RawCallBuilder;
+use alloy::network::EthereumWallet;
+use alloy::primitives::{Address, Bytes, FixedBytes};
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::sol;
+use alloy::sol_types::SolValue;
+use anyhow::{bail, Context};
+use clap::ValueEnum;
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::i18n::Msg;
+use crate::tr;
+
+sol! {
+    #[sol(rpc)]
+    interface IZkip {
+        function verifyZkipProof(bytes calldata publicValues, bytes calldata proofBytes)
+            external
+            view
+            returns (bool isExcluded, uint64 timestamp, uint16[] memory excludedCountries);
+    }
+}
+
+/// Private key of anvil's first default account. Only ever used against the local node.
+const ANVIL_DEV_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// Enum representing the available proof systems
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+pub enum ProofSystem {
+    Plonk,
+    Groth16,
+}
+
+impl ProofSystem {
+    /// Stable identifier, as recorded in the audit log and run history.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Plonk => "plonk",
+            Self::Groth16 => "groth16",
+        }
+    }
+
+    /// Source file and contract name of the matching verifier in `sp1-contracts`.
+    fn verifier_artifact(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Plonk => ("SP1VerifierPlonk.sol", "SP1Verifier"),
+            Self::Groth16 => ("SP1VerifierGroth16.sol", "SP1Verifier"),
+        }
+    }
+}
+
+pub fn default_contracts_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../contracts")
+}
+
+/// A running anvil node, killed on drop.
+pub struct AnvilNode {
+    child: Child,
+    pub rpc_url: String,
+}
+
+impl Drop for AnvilNode {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Start anvil on `port` and wait until it accepts connections.
+pub fn spawn_anvil(port: u16) -> anyhow::Result<AnvilNode> {
+    let child = Command::new("anvil")
+        .args(["--port", &port.to_string(), "--silent"])
+        .stdout(Stdio::null())
+        .spawn()
+        .with_context(|| tr!(Msg::AnvilStartFailed))?;
+    let node = AnvilNode {
+        child,
+        rpc_url: format!("http://127.0.0.1:{}", port),
+    };
+    let started = Instant::now();
+    while TcpStream::connect(("127.0.0.1", port)).is_err() {
+        if started.elapsed() > Duration::from_secs(15) {
+            bail!(tr!(Msg::AnvilNotListening, port));
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Ok(node)
+}
+
+/// Run `forge build` in `contracts`.
+pub fn forge_build(contracts: &Path) -> anyhow::Result<()> {
+    if !contracts.join("foundry.toml").exists() {
+        bail!(tr!(Msg::NotFoundryProject, contracts.display()));
+    }
+    let status = Command::new("forge")
+        .arg("build")
+        .current_dir(contracts)
+        .status()
+        .with_context(|| tr!(Msg::ForgeRunFailed))?;
+    if !status.success() {
+        bail!(tr!(Msg::ForgeBuildFailed, status));
+    }
+    Ok(())
+}
+
+/// Creation bytecode of `name` from Foundry's `out/<file>/<name>.json`.
+fn artifact_bytecode(contracts: &Path, file: &str, name: &str) -> anyhow::Result<Vec<u8>> {
+    let path = contracts
+        .join("out")
+        .join(file)
+        .join(format!("{}.json", name));
+    let content =
+        std::fs::read_to_string(&path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+    let artifact: serde_json::Value =
+        serde_json::from_str(&content).with_context(|| tr!(Msg::ParseFailed, path.display()))?;
+    let object = artifact["bytecode"]["object"]
+        .as_str()
+        .with_context(|| tr!(Msg::NoBytecode, path.display()))?;
+    hex::decode(object.trim_start_matches("0x"))
+        .with_context(|| tr!(Msg::BytecodeNotHex, path.display()))
+}
+
+/// What the deployed contract returned for the submitted proof.
+#[derive(Debug, Clone)]
+pub struct OnchainResult {
+    pub verifier: Address,
+    pub zkip: Address,
+    pub is_excluded: bool,
+    pub timestamp: u64,
+    pub excluded_countries: Vec<u16>,
+}
+
+/// Deploy the verifier and `Zkip` to `rpc_url` and submit the proof.
+pub fn verify_onchain(
+    rpc_url: &str,
+    contracts: &Path,
+    system: ProofSystem,
+    vkey: [u8; 32],
+    public_values: &[u8],
+    proof: &[u8],
+) -> anyhow::Result<OnchainResult> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .with_context(|| tr!(Msg::RuntimeFailed))?
+        .block_on(verify_onchain_async(
+            rpc_url,
+            contracts,
+            system,
+            vkey,
+            public_values,
+            proof,
+        ))
+}
+
+async fn verify_onchain_async(
+    rpc_url: &str,
+    contracts: &Path,
+    system: ProofSystem,
+    vkey: [u8; 32],
+    public_values: &[u8],
+    proof: &[u8],
+) -> anyhow::Result<OnchainResult> {
+    let signer: PrivateKeySigner = ANVIL_DEV_KEY
+        .parse()
+        .with_context(|| tr!(Msg::InvalidDevKey))?;
+    let provider = ProviderBuilder::new()
+        .wallet(EthereumWallet::from(signer))
+        .connect_http(rpc_url.parse().with_context(|| tr!(Msg::InvalidRpcUrl))?);
+    provider
+        .get_chain_id()
+        .await
+        .with_context(|| tr!(Msg::AnvilNotAnswering))?;
+
+    let (file, name) = system.verifier_artifact();
+    let code = artifact_bytecode(contracts, file, name)?;
+    let verifier = RawCallBuilder::new_raw_deploy(&provider, Bytes::from(code))
+        .deploy()
+        .await
+        .with_context(|| tr!(Msg::DeployVerifierFailed))?;
+
+    let mut code = artifact_bytecode(contracts, "Zkip.sol", "Zkip")?;
+    code.extend((verifier, FixedBytes::from(vkey)).abi_encode_params());
+    let zkip = RawCallBuilder::new_raw_deploy(&provider, Bytes::from(code))
+        .deploy()
+        .await
+        .with_context(|| tr!(Msg::DeployZkipFailed))?;
+
+    let result = IZkip::new(zkip, &provider)
+        .verifyZkipProof(
+            Bytes::copy_from_slice(public_values),
+            Bytes::copy_from_slice(proof),
+        )
+        .call()
+        .await
+        .with_context(|| tr!(Msg::VerifyZkipReverted))?;
+    Ok(OnchainResult {
+        verifier,
+        zkip,
+        is_excluded: result.isExcluded,
+        timestamp: result.timestamp,
+        excluded_countries: result.excludedCountries,
+    })
+}
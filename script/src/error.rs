@@ -0,0 +1,361 @@
+//! Stable error categories for the CLI.
+//!
+//! Call sites attach a `ZkipCliError` as anyhow context; the binaries recover it at the
+//! top level to pick the exit envelope. Codes are part of the CLI contract: never reuse
+//! or renumber them, only add new ones.
+
+use serde::Serialize;
+use std::fmt;
+
+use crate::i18n::{lang, Lang};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u16)]
+pub enum ZkipCliError {
+    InvalidArguments = 100,
+    InvalidIp = 101,
+    InvalidCountry = 102,
+    InvalidPolicy = 103,
+    InvalidDomain = 104,
+    DatabaseUnavailable = 200,
+    DatabaseCorrupted = 201,
+    UpstreamUnavailable = 202,
+    LockMismatch = 203,
+    AuditLogCorrupted = 204,
+    ClockUntrusted = 205,
+    AnchorUnavailable = 206,
+    RootPushFailed = 207,
+    ArtifactStoreFailed = 208,
+    SnapshotUnavailable = 209,
+    ExecutionFailed = 300,
+    ProvingFailed = 301,
+    DecodeFailed = 302,
+    BuildFailed = 303,
+    VerificationFailed = 400,
+    SelftestFailed = 401,
+    VkeyMismatch = 402,
+    RevokedVersion = 403,
+    UntrustedArtifact = 404,
+    ProofRevoked = 405,
+    ProofReplayed = 406,
+    CosignThresholdNotMet = 407,
+    E2eFailed = 408,
+    DatasetRootMismatch = 409,
+    AnonymitySetTooSmall = 410,
+    PolicyMismatch = 411,
+    ReservedIp = 412,
+    AttestationInvalid = 413,
+    SnapshotSignatureInvalid = 414,
+    DatabaseTooOld = 415,
+    TimeBeaconInvalid = 416,
+    AnchorStale = 417,
+    InventoryMismatch = 418,
+    CompanionMismatch = 419,
+    ChallengeMismatch = 420,
+    ProofExpired = 421,
+    PublicValuesVersionUnsupported = 422,
+    AppContextMismatch = 423,
+    OperatorSignatureInvalid = 424,
+    BundleMismatch = 425,
+    Internal = 900,
+}
+
+impl ZkipCliError {
+    pub fn code(self) -> u16 {
+        self as u16
+    }
+
+    /// Snake-case identifier, stable like the numeric code.
+    pub fn kind(self) -> &'static str {
+        match self {
+            Self::InvalidArguments => "invalid_arguments",
+            Self::InvalidIp => "invalid_ip",
+            Self::InvalidCountry => "invalid_country",
+            Self::InvalidPolicy => "invalid_policy",
+            Self::InvalidDomain => "invalid_domain",
+            Self::DatabaseUnavailable => "database_unavailable",
+            Self::DatabaseCorrupted => "database_corrupted",
+            Self::UpstreamUnavailable => "upstream_unavailable",
+            Self::LockMismatch => "lock_mismatch",
+            Self::AuditLogCorrupted => "audit_log_corrupted",
+            Self::ClockUntrusted => "clock_untrusted",
+            Self::AnchorUnavailable => "anchor_unavailable",
+            Self::RootPushFailed => "root_push_failed",
+            Self::ArtifactStoreFailed => "artifact_store_failed",
+            Self::SnapshotUnavailable => "snapshot_unavailable",
+            Self::ExecutionFailed => "execution_failed",
+            Self::ProvingFailed => "proving_failed",
+            Self::DecodeFailed => "decode_failed",
+            Self::BuildFailed => "build_failed",
+            Self::VerificationFailed => "verification_failed",
+            Self::SelftestFailed => "selftest_failed",
+            Self::VkeyMismatch => "vkey_mismatch",
+            Self::RevokedVersion => "revoked_version",
+            Self::UntrustedArtifact => "untrusted_artifact",
+            Self::ProofRevoked => "proof_revoked",
+            Self::ProofReplayed => "proof_replayed",
+            Self::CosignThresholdNotMet => "cosign_threshold_not_met",
+            Self::E2eFailed => "e2e_failed",
+            Self::DatasetRootMismatch => "dataset_root_mismatch",
+            Self::AnonymitySetTooSmall => "anonymity_set_too_small",
+            Self::PolicyMismatch => "policy_mismatch",
+            Self::ReservedIp => "reserved_ip",
+            Self::AttestationInvalid => "attestation_invalid",
+            Self::SnapshotSignatureInvalid => "snapshot_signature_invalid",
+            Self::DatabaseTooOld => "database_too_old",
+            Self::TimeBeaconInvalid => "time_beacon_invalid",
+            Self::AnchorStale => "anchor_stale",
+            Self::InventoryMismatch => "inventory_mismatch",
+            Self::CompanionMismatch => "companion_mismatch",
+            Self::ChallengeMismatch => "challenge_mismatch",
+            Self::ProofExpired => "proof_expired",
+            Self::PublicValuesVersionUnsupported => "public_values_version_unsupported",
+            Self::AppContextMismatch => "app_context_mismatch",
+            Self::OperatorSignatureInvalid => "operator_signature_invalid",
+            Self::BundleMismatch => "bundle_mismatch",
+            Self::Internal => "internal",
+        }
+    }
+
+    /// Localized remediation hint.
+    pub fn hint(self) -> &'static str {
+        match (self, lang()) {
+            (Self::InvalidArguments, Lang::En) => "Run with --help to see the expected arguments.",
+            (Self::InvalidArguments, Lang::Fr) => "Lancez avec --help pour voir les arguments attendus.",
+            (Self::InvalidIp, Lang::En) => "Pass an IPv4 address as 8.8.8.8, 134744072 or 0x08080808, or an IPv6 address as 2001:db8::1.",
+            (Self::InvalidIp, Lang::Fr) => "Indiquez une adresse IPv4 sous la forme 8.8.8.8, 134744072 ou 0x08080808, ou une adresse IPv6 comme 2001:db8::1.",
+            (Self::InvalidCountry, Lang::En) => "Use ISO 3166-1 codes or English country names separated by commas, e.g. --exclude FR,Germany.",
+            (Self::InvalidCountry, Lang::Fr) => "Utilisez des codes ISO 3166-1 ou des noms de pays en anglais séparés par des virgules, par ex. --exclude FR,Germany.",
+            (Self::InvalidPolicy, Lang::En) => "Run `zkip policy lint <file>` and fix the reported errors.",
+            (Self::InvalidPolicy, Lang::Fr) => "Lancez `zkip policy lint <fichier>` et corrigez les erreurs signalées.",
+            (Self::InvalidDomain, Lang::En) => "Check the host name resolves.",
+            (Self::InvalidDomain, Lang::Fr) => "Vérifiez que le nom d'hôte se résout.",
+            (Self::DatabaseUnavailable, Lang::En) => "Check network access to jsDelivr, or retry with --refresh.",
+            (Self::DatabaseUnavailable, Lang::Fr) => "Vérifiez l'accès réseau à jsDelivr, ou relancez avec --refresh.",
+            (Self::DatabaseCorrupted, Lang::En) => "Re-download the database with --refresh.",
+            (Self::DatabaseCorrupted, Lang::Fr) => "Téléchargez à nouveau la base avec --refresh.",
+            (Self::UpstreamUnavailable, Lang::En) => "Check network access to jsDelivr and retry.",
+            (Self::UpstreamUnavailable, Lang::Fr) => "Vérifiez l'accès réseau à jsDelivr et réessayez.",
+            (Self::LockMismatch, Lang::En) => "Restore the pinned inputs, or accept the new ones with --update-lock.",
+            (Self::LockMismatch, Lang::Fr) => "Restaurez les entrées épinglées, ou acceptez les nouvelles avec --update-lock.",
+            (Self::AuditLogCorrupted, Lang::En) => "The audit log was modified; restore it from a backup and investigate.",
+            (Self::AuditLogCorrupted, Lang::Fr) => "Le journal d'audit a été modifié ; restaurez-le depuis une sauvegarde et enquêtez.",
+            (Self::ClockUntrusted, Lang::En) => "Synchronize the system clock (e.g. chrony), check UDP port 123, or pass other --ntp-server values; with roughtime, check --roughtime-server and --roughtime-key.",
+            (Self::ClockUntrusted, Lang::Fr) => "Synchronisez l'horloge système (p. ex. chrony), vérifiez le port UDP 123, ou passez d'autres --ntp-server ; avec roughtime, vérifiez --roughtime-server et --roughtime-key.",
+            (Self::AnchorUnavailable, Lang::En) => "Check --rpc-url (or ZKIP_RPC_URL) points to a reachable Ethereum JSON-RPC endpoint.",
+            (Self::AnchorUnavailable, Lang::Fr) => "Vérifiez que --rpc-url (ou ZKIP_RPC_URL) désigne un point JSON-RPC Ethereum joignable.",
+            (Self::RootPushFailed, Lang::En) => "Check --contract, that ZKIP_ADMIN_PRIVATE_KEY holds ROOT_UPDATER_ROLE, and try --dry-run first.",
+            (Self::RootPushFailed, Lang::Fr) => "Vérifiez --contract, que ZKIP_ADMIN_PRIVATE_KEY détient ROOT_UPDATER_ROLE, et essayez d'abord --dry-run.",
+            (Self::ArtifactStoreFailed, Lang::En) => "Check the [store] section of zkip.toml and the backend's credentials (AWS_* or GOOGLE_OAUTH_ACCESS_TOKEN).",
+            (Self::ArtifactStoreFailed, Lang::Fr) => "Vérifiez la section [store] de zkip.toml et les identifiants du stockage (AWS_* ou GOOGLE_OAUTH_ACCESS_TOKEN).",
+            (Self::SnapshotUnavailable, Lang::En) => "Build with --features embedded-data,zkip-data/snapshot-<date>, or drop --snapshot to use the downloaded database.",
+            (Self::SnapshotUnavailable, Lang::Fr) => "Compilez avec --features embedded-data,zkip-data/snapshot-<date>, ou retirez --snapshot pour utiliser la base téléchargée.",
+            (Self::ExecutionFailed, Lang::En) => "Run `zkip selftest` to check the installation.",
+            (Self::ExecutionFailed, Lang::Fr) => "Lancez `zkip selftest` pour vérifier l'installation.",
+            (Self::ProvingFailed, Lang::En) => "Check SP1_PROVER and, for the network prover, NETWORK_PRIVATE_KEY.",
+            (Self::ProvingFailed, Lang::Fr) => "Vérifiez SP1_PROVER et, pour le prouveur réseau, NETWORK_PRIVATE_KEY.",
+            (Self::DecodeFailed, Lang::En) => "The guest program and this CLI are out of sync; rebuild both.",
+            (Self::DecodeFailed, Lang::Fr) => "Le programme invité et cette CLI sont désynchronisés ; recompilez les deux.",
+            (Self::BuildFailed, Lang::En) => "Install the SP1 toolchain (sp1up) and make sure docker is running.",
+            (Self::BuildFailed, Lang::Fr) => "Installez la chaîne SP1 (sp1up) et vérifiez que docker est lancé.",
+            (Self::VerificationFailed, Lang::En) => "The proof does not match the program's verification key.",
+            (Self::VerificationFailed, Lang::Fr) => "La preuve ne correspond pas à la clé de vérification du programme.",
+            (Self::SelftestFailed, Lang::En) => "The installation is broken; rebuild the program and retry.",
+            (Self::SelftestFailed, Lang::Fr) => "L'installation est défectueuse ; recompilez le programme et réessayez.",
+            (Self::VkeyMismatch, Lang::En) => "The source does not produce this vkey; check the checked-out revision.",
+            (Self::VkeyMismatch, Lang::Fr) => "Les sources ne produisent pas cette vkey ; vérifiez la révision extraite.",
+            (Self::RevokedVersion, Lang::En) => "Ask the prover to regenerate the proof with a current release.",
+            (Self::RevokedVersion, Lang::Fr) => "Demandez au prouveur de régénérer la preuve avec une version actuelle.",
+            (Self::UntrustedArtifact, Lang::En) => "Use an ELF from an official release, or pass --allow-unsigned for local builds.",
+            (Self::UntrustedArtifact, Lang::Fr) => "Utilisez un ELF d'une version officielle, ou passez --allow-unsigned pour une compilation locale.",
+            (Self::ProofRevoked, Lang::En) => "Request a fresh proof; this one is on the operator's revocation list.",
+            (Self::ProofRevoked, Lang::Fr) => "Demandez une nouvelle preuve ; celle-ci figure sur la liste de révocation de l'opérateur.",
+            (Self::ProofReplayed, Lang::En) => "Each proof is accepted once; request a fresh proof.",
+            (Self::ProofReplayed, Lang::Fr) => "Chaque preuve n'est acceptée qu'une fois ; demandez une nouvelle preuve.",
+            (Self::CosignThresholdNotMet, Lang::En) => "Have more of the configured operators run `zkip cosign` on the proof.",
+            (Self::CosignThresholdNotMet, Lang::Fr) => "Faites exécuter `zkip cosign` sur la preuve par davantage d'opérateurs configurés.",
+            (Self::E2eFailed, Lang::En) => "Check that anvil and forge are installed and that `forge build` succeeds in contracts/.",
+            (Self::E2eFailed, Lang::Fr) => "Vérifiez qu'anvil et forge sont installés et que `forge build` réussit dans contracts/.",
+            (Self::DatasetRootMismatch, Lang::En) => "The proof checked other ranges than the pinned snapshot; compare with `zkip db root` for the same countries, or pass its manifest with --root-manifest.",
+            (Self::DatasetRootMismatch, Lang::Fr) => "La preuve a vérifié d'autres plages que l'instantané épinglé ; comparez avec `zkip db root` pour les mêmes pays, ou passez son manifeste avec --root-manifest.",
+            (Self::AnonymitySetTooSmall, Lang::En) => "Ask the prover for an inclusion proof over at least --min-anonymity countries.",
+            (Self::AnonymitySetTooSmall, Lang::Fr) => "Demandez au prouveur une preuve d'inclusion portant sur au moins --min-anonymity pays.",
+            (Self::PolicyMismatch, Lang::En) => "Check that prover and verifier use the same policy file; `zkip policy lint` prints its rule hash.",
+            (Self::PolicyMismatch, Lang::Fr) => "Vérifiez que le prouveur et le vérificateur utilisent le même fichier de politique ; `zkip policy lint` affiche l'empreinte de sa règle.",
+            (Self::ReservedIp, Lang::En) => "Ask for a proof of the client's public address; private, loopback, link-local and CGNAT addresses prove nothing about location.",
+            (Self::ReservedIp, Lang::Fr) => "Demandez une preuve pour l'adresse publique du client ; les adresses privées, de bouclage, lien-local et CGNAT ne prouvent rien sur la localisation.",
+            (Self::AttestationInvalid, Lang::En) => "Prove the address the attester signed, with its attestation file; verifiers pin the attester's key with --attester.",
+            (Self::AttestationInvalid, Lang::Fr) => "Prouvez l'adresse signée par l'attesteur, avec son fichier d'attestation ; les vérificateurs épinglent sa clé avec --attester.",
+            (Self::SnapshotSignatureInvalid, Lang::En) => "Pass a root manifest signed with `zkip db root --sign` that lists every checked country; verifiers pin the publisher's key with --publisher.",
+            (Self::SnapshotSignatureInvalid, Lang::Fr) => "Passez un manifeste de racine signé avec `zkip db root --sign` qui liste chaque pays vérifié ; les vérificateurs épinglent la clé de l'éditeur avec --publisher.",
+            (Self::DatabaseTooOld, Lang::En) => "Prove with a recently signed snapshot, or raise --max-db-age if the verifier accepts older ranges.",
+            (Self::DatabaseTooOld, Lang::Fr) => "Prouvez avec un instantané signé récemment, ou augmentez --max-db-age si le vérificateur accepte des plages plus anciennes.",
+            (Self::TimeBeaconInvalid, Lang::En) => "Prove with --time-source roughtime against the server the verifier trusts; verifiers pin its key with --time-server.",
+            (Self::TimeBeaconInvalid, Lang::Fr) => "Prouvez avec --time-source roughtime auprès du serveur auquel le vérificateur fait confiance ; les vérificateurs épinglent sa clé avec --time-server.",
+            (Self::AnchorStale, Lang::En) => "Regenerate the proof with --anchor-block against the same chain, or raise --max-anchor-age if the verifier accepts older anchors.",
+            (Self::AnchorStale, Lang::Fr) => "Régénérez la preuve avec --anchor-block sur la même chaîne, ou augmentez --max-anchor-age si le vérificateur accepte des ancrages plus anciens.",
+            (Self::InventoryMismatch, Lang::En) => "Prove with --ip-file --inventory over the published list; `zkip inventory-root` prints a list's root.",
+            (Self::InventoryMismatch, Lang::Fr) => "Prouvez avec --ip-file --inventory sur la liste publiée ; `zkip inventory-root` affiche la racine d'une liste.",
+            (Self::CompanionMismatch, Lang::En) => "Prove with --companion-proof and --companion-elf of the program the verifier expects.",
+            (Self::CompanionMismatch, Lang::Fr) => "Prouvez avec --companion-proof et --companion-elf du programme qu'attend le vérificateur.",
+            (Self::ChallengeMismatch, Lang::En) => "Prove again with --challenge set to the challenge the verifier issued for this session.",
+            (Self::ChallengeMismatch, Lang::Fr) => "Prouvez à nouveau avec --challenge égal au défi émis par le vérificateur pour cette session.",
+            (Self::ProofExpired, Lang::En) => "Prove again for a proof valid now, or check this machine's clock.",
+            (Self::ProofExpired, Lang::Fr) => "Prouvez à nouveau pour obtenir une preuve valide maintenant, ou vérifiez l'horloge de cette machine.",
+            (Self::PublicValuesVersionUnsupported, Lang::En) => "Upgrade zkip to a release that knows this public values version.",
+            (Self::PublicValuesVersionUnsupported, Lang::Fr) => "Mettez zkip à jour vers une version qui connaît cette version des valeurs publiques.",
+            (Self::AppContextMismatch, Lang::En) => "Prove again with --app-context set to the context this application expects.",
+            (Self::AppContextMismatch, Lang::Fr) => "Prouvez à nouveau avec --app-context égal au contexte attendu par cette application.",
+            (Self::OperatorSignatureInvalid, Lang::En) => "Ask the proving service for a bundle made with `zkip bundle --sign-key`, and pin its key with --operator.",
+            (Self::OperatorSignatureInvalid, Lang::Fr) => "Demandez au service de preuve un paquet créé avec `zkip bundle --sign-key`, et épinglez sa clé avec --operator.",
+            (Self::BundleMismatch, Lang::En) => "Rebuild the bundle from the proof with `zkip bundle`.",
+            (Self::BundleMismatch, Lang::Fr) => "Recréez le paquet à partir de la preuve avec `zkip bundle`.",
+            (Self::Internal, Lang::En) => "This is a bug; please open an issue.",
+            (Self::Internal, Lang::Fr) => "Il s'agit d'un bug ; merci d'ouvrir un ticket.",
+        }
+    }
+}
+
+impl fmt::Display for ZkipCliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match (self, lang()) {
+            (Self::InvalidArguments, Lang::En) => "invalid arguments",
+            (Self::InvalidArguments, Lang::Fr) => "arguments invalides",
+            (Self::InvalidIp, Lang::En) => "failed to parse IP address",
+            (Self::InvalidIp, Lang::Fr) => "adresse IP invalide",
+            (Self::InvalidCountry, Lang::En) => "failed to resolve excluded countries",
+            (Self::InvalidCountry, Lang::Fr) => "pays exclus non reconnus",
+            (Self::InvalidPolicy, Lang::En) => "invalid policy",
+            (Self::InvalidPolicy, Lang::Fr) => "politique invalide",
+            (Self::InvalidDomain, Lang::En) => "failed to resolve domain",
+            (Self::InvalidDomain, Lang::Fr) => "impossible de résoudre le domaine",
+            (Self::DatabaseUnavailable, Lang::En) => "GeoIP database unavailable",
+            (Self::DatabaseUnavailable, Lang::Fr) => "base GeoIP indisponible",
+            (Self::DatabaseCorrupted, Lang::En) => "GeoIP database is corrupted",
+            (Self::DatabaseCorrupted, Lang::Fr) => "base GeoIP corrompue",
+            (Self::UpstreamUnavailable, Lang::En) => "failed to reach upstream GeoIP source",
+            (Self::UpstreamUnavailable, Lang::Fr) => "source GeoIP en amont injoignable",
+            (Self::LockMismatch, Lang::En) => "inputs do not match zkip.lock",
+            (Self::LockMismatch, Lang::Fr) => "les entrées ne correspondent pas à zkip.lock",
+            (Self::AuditLogCorrupted, Lang::En) => "audit log chain is broken",
+            (Self::AuditLogCorrupted, Lang::Fr) => "la chaîne du journal d'audit est rompue",
+            (Self::ClockUntrusted, Lang::En) => "local clock could not be verified against NTP, or Roughtime gave no valid time",
+            (Self::ClockUntrusted, Lang::Fr) => "l'horloge locale n'a pas pu être vérifiée par NTP, ou Roughtime n'a donné aucune heure valide",
+            (Self::AnchorUnavailable, Lang::En) => "failed to fetch the anchor block",
+            (Self::AnchorUnavailable, Lang::Fr) => "échec de la récupération du bloc d'ancrage",
+            (Self::RootPushFailed, Lang::En) => "failed to update the on-chain root registry",
+            (Self::RootPushFailed, Lang::Fr) => "échec de la mise à jour du registre de racines on-chain",
+            (Self::ArtifactStoreFailed, Lang::En) => "failed to upload artifacts to the configured store",
+            (Self::ArtifactStoreFailed, Lang::Fr) => "échec de l'envoi des artefacts vers le stockage configuré",
+            (Self::SnapshotUnavailable, Lang::En) => "the requested embedded snapshot is unavailable",
+            (Self::SnapshotUnavailable, Lang::Fr) => "l'instantané embarqué demandé est indisponible",
+            (Self::ExecutionFailed, Lang::En) => "failed to execute zkvm program",
+            (Self::ExecutionFailed, Lang::Fr) => "échec de l'exécution du programme zkvm",
+            (Self::ProvingFailed, Lang::En) => "failed to generate proof",
+            (Self::ProvingFailed, Lang::Fr) => "échec de la génération de la preuve",
+            (Self::DecodeFailed, Lang::En) => "failed to decode public values",
+            (Self::DecodeFailed, Lang::Fr) => "échec du décodage des valeurs publiques",
+            (Self::BuildFailed, Lang::En) => "failed to rebuild the guest program",
+            (Self::BuildFailed, Lang::Fr) => "échec de la recompilation du programme invité",
+            (Self::VerificationFailed, Lang::En) => "failed to verify proof",
+            (Self::VerificationFailed, Lang::Fr) => "échec de la vérification de la preuve",
+            (Self::SelftestFailed, Lang::En) => "selftest failed",
+            (Self::SelftestFailed, Lang::Fr) => "échec de l'autotest",
+            (Self::VkeyMismatch, Lang::En) => "verification key mismatch",
+            (Self::VkeyMismatch, Lang::Fr) => "clé de vérification différente",
+            (Self::RevokedVersion, Lang::En) => "proof comes from a revoked program version",
+            (Self::RevokedVersion, Lang::Fr) => "la preuve provient d'une version révoquée du programme",
+            (Self::UntrustedArtifact, Lang::En) => "artifact failed manifest verification",
+            (Self::UntrustedArtifact, Lang::Fr) => "l'artefact a échoué à la vérification du manifeste",
+            (Self::ProofRevoked, Lang::En) => "proof has been revoked",
+            (Self::ProofRevoked, Lang::Fr) => "la preuve a été révoquée",
+            (Self::ProofReplayed, Lang::En) => "proof was already accepted",
+            (Self::ProofReplayed, Lang::Fr) => "la preuve a déjà été acceptée",
+            (Self::CosignThresholdNotMet, Lang::En) => "not enough operator signatures",
+            (Self::CosignThresholdNotMet, Lang::Fr) => "signatures d'opérateurs insuffisantes",
+            (Self::E2eFailed, Lang::En) => "end-to-end on-chain check failed",
+            (Self::E2eFailed, Lang::Fr) => "échec de la vérification on-chain de bout en bout",
+            (Self::DatasetRootMismatch, Lang::En) => "proof commits an untrusted dataset root",
+            (Self::DatasetRootMismatch, Lang::Fr) => "la preuve engage une racine de jeu de données non fiable",
+            (Self::AnonymitySetTooSmall, Lang::En) => "inclusion proof's country set is smaller than required",
+            (Self::AnonymitySetTooSmall, Lang::Fr) => "l'ensemble de pays de la preuve d'inclusion est plus petit que requis",
+            (Self::PolicyMismatch, Lang::En) => "proof does not commit the expected policy",
+            (Self::PolicyMismatch, Lang::Fr) => "la preuve n'engage pas la politique attendue",
+            (Self::ReservedIp, Lang::En) => "proof is for a reserved IP address",
+            (Self::ReservedIp, Lang::Fr) => "la preuve porte sur une adresse IP réservée",
+            (Self::AttestationInvalid, Lang::En) => "attestation is missing, invalid or from another attester",
+            (Self::AttestationInvalid, Lang::Fr) => "attestation absente, invalide ou d'un autre attesteur",
+            (Self::SnapshotSignatureInvalid, Lang::En) => "snapshot signature is missing, invalid or from another publisher",
+            (Self::SnapshotSignatureInvalid, Lang::Fr) => "signature d'instantané absente, invalide ou d'un autre éditeur",
+            (Self::DatabaseTooOld, Lang::En) => "GeoIP database is older than allowed",
+            (Self::DatabaseTooOld, Lang::Fr) => "la base GeoIP est plus ancienne que permis",
+            (Self::TimeBeaconInvalid, Lang::En) => "timestamp is not signed by the expected time server",
+            (Self::TimeBeaconInvalid, Lang::Fr) => "l'horodatage n'est pas signé par le serveur de temps attendu",
+            (Self::AnchorStale, Lang::En) => "anchor block is missing, not on the chain or too old",
+            (Self::AnchorStale, Lang::Fr) => "bloc d'ancrage absent, hors de la chaîne ou trop ancien",
+            (Self::InventoryMismatch, Lang::En) => "proof does not cover the expected IP inventory",
+            (Self::InventoryMismatch, Lang::Fr) => "la preuve ne couvre pas l'inventaire d'IP attendu",
+            (Self::CompanionMismatch, Lang::En) => "proof is not joined to a companion proof of the expected program",
+            (Self::CompanionMismatch, Lang::Fr) => "la preuve n'est pas jointe à une preuve compagnon du programme attendu",
+            (Self::ChallengeMismatch, Lang::En) => "proof is not bound to the expected challenge",
+            (Self::ChallengeMismatch, Lang::Fr) => "la preuve n'est pas liée au défi attendu",
+            (Self::ProofExpired, Lang::En) => "proof is outside its validity window",
+            (Self::ProofExpired, Lang::Fr) => "la preuve est hors de sa fenêtre de validité",
+            (Self::PublicValuesVersionUnsupported, Lang::En) => "proof commits an unknown public values version",
+            (Self::PublicValuesVersionUnsupported, Lang::Fr) => "la preuve engage une version inconnue des valeurs publiques",
+            (Self::AppContextMismatch, Lang::En) => "proof does not commit the expected app context",
+            (Self::AppContextMismatch, Lang::Fr) => "la preuve n'engage pas le contexte d'application attendu",
+            (Self::OperatorSignatureInvalid, Lang::En) => "bundle signature is missing, invalid or from another operator",
+            (Self::OperatorSignatureInvalid, Lang::Fr) => "signature du paquet absente, invalide ou d'un autre opérateur",
+            (Self::BundleMismatch, Lang::En) => "bundle metadata contradicts its proof",
+            (Self::BundleMismatch, Lang::Fr) => "les métadonnées du paquet contredisent sa preuve",
+            (Self::Internal, Lang::En) => "internal error",
+            (Self::Internal, Lang::Fr) => "erreur interne",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for ZkipCliError {}
+
+/// The outermost category attached to `err`, or `Internal` if none was.
+pub fn classify(err: &anyhow::Error) -> ZkipCliError {
+    err.downcast_ref::<ZkipCliError>()
+        .copied()
+        .unwrap_or(ZkipCliError::Internal)
+}
+
+/// JSON body emitted on failure when `--json` is passed.
+#[derive(Debug, Serialize)]
+pub struct ErrorEnvelope {
+    pub code: u16,
+    pub kind: &'static str,
+    pub message: String,
+    pub hint: &'static str,
+}
+
+impl ErrorEnvelope {
+    pub fn from_error(err: &anyhow::Error) -> Self {
+        let category = classify(err);
+        Self {
+            code: category.code(),
+            kind: category.kind(),
+            message: format!("{:#}", err),
+            hint: category.hint(),
+        }
+    }
+}
+
+/// Print `err` to stderr, as a JSON envelope or as plain text, and exit non-zero.
+pub fn exit_with_error(err: anyhow::Error, json: bool) -> ! {
+    if json {
+        let envelope = ErrorEnvelope::from_error(&err);
+        eprintln!(
+            "{}",
+            serde_json::to_string(&envelope).expect("error envelope serializes")
+        );
+    } else {
+        eprintln!("Error: {:?}", err);
+    }
+    std::process::exit(1)
+}
@@ -0,0 +1,129 @@
+//! `zkip explain`: make raw public values readable.
+//!
+//! Decodes the bytes an on-chain proof attested, names the layout they use, and flags
+//! unknown country or region codes.
+
+use anyhow::Context;
+use zkip_lib::{
+    decode_public_values, DecodedPublicValues, MODE_BLOCK, MODE_COMBINED, MODE_INCLUSION,
+    MODE_POLICY, MODE_THRESHOLD,
+};
+
+use crate::countries::load_country_names;
+use crate::disclosure::region_name;
+use crate::i18n::Msg;
+use crate::tr;
+
+/// Public values with everything needed to print them.
+pub struct Explanation {
+    /// Name of the Solidity struct the bytes decode as.
+    pub layout: &'static str,
+    pub decoded: DecodedPublicValues,
+    /// `(numeric code, alpha-2 and name)` per committed country, denylist last; `None` if
+    /// unknown.
+    pub countries: Vec<(u16, Option<(String, String)>)>,
+    pub region_name: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Parse `0x`-prefixed or bare hex public values.
+pub fn parse_public_values(hex_str: &str) -> anyhow::Result<Vec<u8>> {
+    let hex_str = hex_str.trim();
+    let hex_str = hex_str
+        .strip_prefix("0x")
+        .or_else(|| hex_str.strip_prefix("0X"))
+        .unwrap_or(hex_str);
+    hex::decode(hex_str).with_context(|| tr!(Msg::PublicValuesNotHex))
+}
+
+/// Decode `bytes` and collect what a reader needs to make sense of them.
+pub fn explain(bytes: &[u8]) -> anyhow::Result<Explanation> {
+    let decoded = decode_public_values(bytes)?;
+    let mut warnings = Vec::new();
+
+    let layout = match (decoded.anchor, decoded.disclosed, decoded.address_count) {
+        _ if decoded.renewal.is_some() => "RenewedPublicValuesStruct",
+        _ if decoded.companion.is_some() => "ComposedPublicValuesStruct",
+        _ if decoded.challenge.is_some() => "ChallengedPublicValuesStruct",
+        _ if decoded.validity.is_some() => "WindowedPublicValuesStruct",
+        _ if decoded.mode == MODE_POLICY => "PolicyPublicValuesStruct",
+        _ if decoded.mode == MODE_THRESHOLD => "ThresholdPublicValuesStruct",
+        _ if decoded.mode == MODE_BLOCK => "BlockPublicValuesStruct",
+        _ if decoded.mode == MODE_COMBINED => "CombinedPublicValuesStruct",
+        _ if decoded.mode == MODE_INCLUSION => "InclusionPublicValuesStruct",
+        _ if decoded.gap => "GapPublicValuesStruct",
+        _ if decoded.attestation.is_some() => "AttestedPublicValuesStruct",
+        _ if decoded.publisher.is_some() => "SignedSnapshotPublicValuesStruct",
+        _ if decoded.time_beacon.is_some() => "TimedPublicValuesStruct",
+        _ if decoded.aggregation.is_some() => "AggregatedPublicValuesStruct",
+        _ if decoded.inventory_root.is_some() => "InventoryPublicValuesStruct",
+        _ if decoded.country_results.is_some() => "CountryResultsPublicValuesStruct",
+        (Some(_), ..) => "AnchoredPublicValuesStruct",
+        (None, Some(_), _) => "DisclosedPublicValuesStruct",
+        (None, None, Some(_)) => "BatchPublicValuesStruct",
+        (None, None, None) if decoded.legacy => "LegacyPublicValuesStruct",
+        (None, None, None) => "PublicValuesStruct",
+    };
+
+    let names = load_country_names().unwrap_or_default();
+    let denied = decoded
+        .denylist
+        .iter()
+        .flat_map(|denylist| &denylist.excluded_countries);
+    let countries = decoded
+        .values
+        .excluded_countries
+        .iter()
+        .chain(denied)
+        .map(|&code| (code, names.get(&code).cloned()))
+        .collect::<Vec<_>>();
+    for (code, name) in &countries {
+        if name.is_none() {
+            warnings.push(format!("unknown ISO 3166-1 numeric country code {}", code));
+        }
+    }
+
+    let region_name = decoded.disclosed.and_then(|(granularity, code)| {
+        let name = region_name(granularity, code);
+        if name.is_none() {
+            warnings.push(format!(
+                "unknown M49 code {} at granularity {}",
+                code, granularity
+            ));
+        }
+        name
+    });
+
+    Ok(Explanation {
+        layout,
+        decoded,
+        countries,
+        region_name,
+        warnings,
+    })
+}
+
+/// Render Unix seconds as an ISO 8601 UTC date-time.
+pub fn format_utc(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let secs = timestamp % 86_400;
+    // Civil-from-days (Howard Hinnant), valid for the whole u64 timestamp range.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
@@ -0,0 +1,39 @@
+//! `zkip prove --ip-file`: batch proofs over a list of addresses, such as a fleet's egress
+//! IPs.
+//!
+//! The file holds one address per line, in any form `--ip` takes. Blank lines and
+//! everything after a `#` are ignored. The proof commits how many addresses it checked and
+//! how many of them are clear, not which ones.
+
+use anyhow::{bail, Context};
+use std::fs;
+use std::net::IpAddr;
+use std::path::Path;
+use tracing::info;
+use zkip_lib::parse_ip;
+
+use crate::i18n::Msg;
+use crate::tr;
+
+/// Read the addresses listed in `path`, sorted (IPv4 first) and deduplicated.
+pub fn load_ip_list(path: &Path) -> anyhow::Result<Vec<IpAddr>> {
+    let content = fs::read_to_string(path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+    let mut addrs = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let entry = line.split('#').next().unwrap_or_default().trim();
+        if entry.is_empty() {
+            continue;
+        }
+        let addr = parse_ip(entry)
+            .with_context(|| format!("{}:{}: {:?}", path.display(), index + 1, entry))?;
+        addrs.push(addr);
+    }
+    addrs.sort_unstable();
+    addrs.dedup();
+    if addrs.is_empty() {
+        bail!(tr!(Msg::IpListEmpty, path.display()));
+    }
+
+    info!("{}", tr!(Msg::IpListLoaded, addrs.len(), path.display()));
+    Ok(addrs)
+}
@@ -0,0 +1,777 @@
+//! GeoIP database caching, loading and integrity checks.
+
+use anyhow::{bail, Context};
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+use zkip_lib::geo::GeoDb;
+use zkip_lib::range::{Ipv4Range, RangeSet};
+use zkip_lib::rangefile::{self, RangeAddress, RangeFile};
+use zkip_lib::snapshot::{content_hash, source_id, SnapshotMetadata};
+use zkip_lib::RESERVED_RANGES;
+
+use crate::countries::load_country_codes;
+use crate::error::ZkipCliError;
+use crate::i18n::{t, Msg};
+use crate::snapshot::ExcludedRanges;
+use crate::tr;
+
+pub const GEOIP_URL: &str = "https://cdn.jsdelivr.net/npm/@ip-location-db/geo-whois-asn-country/geo-whois-asn-country-ipv4-num.csv";
+/// Same dataset for IPv6, with addresses as decimal u128s.
+pub const GEOIP_V6_URL: &str = "https://cdn.jsdelivr.net/npm/@ip-location-db/geo-whois-asn-country/geo-whois-asn-country-ipv6-num.csv";
+/// ASN datasets from the same project, rows `start,end,asn,organization`. Only fetched for
+/// policies that name an ASN.
+pub const ASN_URL: &str = "https://cdn.jsdelivr.net/npm/@ip-location-db/asn/asn-ipv4-num.csv";
+pub const ASN_V6_URL: &str = "https://cdn.jsdelivr.net/npm/@ip-location-db/asn/asn-ipv6-num.csv";
+pub const CACHE_MAX_AGE_DAYS: u32 = 30;
+
+/// Human-readable name of the dataset behind `GEOIP_URL`.
+pub const GEOIP_PROVIDER: &str = "ip-location-db geo-whois-asn-country (via jsDelivr)";
+
+/// npm package and file name behind `GEOIP_URL`, used to look up its upstream checksum.
+const GEOIP_PACKAGE: &str = "@ip-location-db/geo-whois-asn-country";
+const GEOIP_FILE: &str = "geo-whois-asn-country-ipv4-num.csv";
+const JSDELIVR_DATA_API: &str = "https://data.jsdelivr.com/v1/packages/npm";
+
+/// `(IPv4, IPv6)` files of the embedded snapshot `use_embedded_snapshot` wrote out, which
+/// stand in for the cached databases for the rest of the run.
+static EMBEDDED_SNAPSHOT: OnceLock<(PathBuf, PathBuf)> = OnceLock::new();
+
+pub fn get_cache_path() -> PathBuf {
+    if let Some((path, _)) = EMBEDDED_SNAPSHOT.get() {
+        return path.clone();
+    }
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../data/ipv4-country.csv")
+}
+
+pub fn get_v6_cache_path() -> PathBuf {
+    if let Some((_, path)) = EMBEDDED_SNAPSHOT.get() {
+        return path.clone();
+    }
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../data/ipv6-country.csv")
+}
+
+pub fn get_asn_cache_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../data/ipv4-asn.csv")
+}
+
+pub fn get_asn_v6_cache_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../data/ipv6-asn.csv")
+}
+
+pub fn is_cache_stale(path: &Path) -> bool {
+    let Some(age) = cache_age(path) else {
+        return true;
+    };
+    age > Duration::from_secs((CACHE_MAX_AGE_DAYS * 24 * 60 * 60) as u64)
+}
+
+/// Download attempts before giving up; each one resumes where the last stopped.
+const DOWNLOAD_ATTEMPTS: u32 = 5;
+
+/// Where an in-progress download of `path` is kept until it is complete.
+pub fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+/// Stores the `ETag` or `Last-Modified` of the partial download, so a resumed request
+/// only appends to bytes of the same upstream file.
+fn validator_path(partial: &Path) -> PathBuf {
+    let mut name = partial.file_name().unwrap_or_default().to_os_string();
+    name.push(".validator");
+    partial.with_file_name(name)
+}
+
+/// Fetch the database into `path`. Bytes are written to `partial_path(path)` and the file
+/// is only moved into place once complete; an interrupted transfer is resumed with an
+/// HTTP range request, here or on the next run.
+pub fn fetch_geoip_database(path: &Path) -> anyhow::Result<()> {
+    fetch_dataset(GEOIP_URL, path)
+}
+
+fn fetch_dataset(url: &str, path: &Path) -> anyhow::Result<()> {
+    info!("{}", tr!(Msg::Fetching, url));
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| tr!(Msg::DataDirFailed))?;
+    }
+    let partial = partial_path(path);
+    let client = reqwest::blocking::Client::builder()
+        .connect_timeout(Duration::from_secs(30))
+        // A stalled attempt times out and the next one resumes from what it wrote.
+        .timeout(Duration::from_secs(600))
+        .build()
+        .with_context(|| tr!(Msg::HttpClientFailed))?;
+
+    let mut attempt = 1;
+    loop {
+        match download_attempt(&client, url, &partial) {
+            Ok(()) => break,
+            Err(e) if attempt < DOWNLOAD_ATTEMPTS => {
+                let have = fs::metadata(&partial).map(|m| m.len()).unwrap_or(0);
+                warn!(
+                    "{}",
+                    tr!(
+                        Msg::DownloadInterrupted,
+                        e,
+                        have,
+                        attempt,
+                        DOWNLOAD_ATTEMPTS
+                    )
+                );
+                std::thread::sleep(Duration::from_secs(1 << attempt));
+                attempt += 1;
+            }
+            Err(e) => return Err(e.context(tr!(Msg::GeoipFetchFailed))),
+        }
+    }
+
+    fs::rename(&partial, path).with_context(|| tr!(Msg::DownloadMoveFailed))?;
+    let _ = fs::remove_file(validator_path(&partial));
+
+    info!("{}", tr!(Msg::Cached, path.display()));
+    Ok(())
+}
+
+/// One request for the rest of `partial`. Returns once the body is fully written.
+fn download_attempt(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    partial: &Path,
+) -> anyhow::Result<()> {
+    use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+    use reqwest::StatusCode;
+
+    let validator_file = validator_path(partial);
+    let have = fs::metadata(partial).map(|m| m.len()).unwrap_or(0);
+    let validator = fs::read_to_string(&validator_file).ok();
+
+    let mut request = client.get(url);
+    if let (true, Some(validator)) = (have > 0, &validator) {
+        // With If-Range the server sends the whole file instead if it changed upstream.
+        request = request
+            .header(RANGE, format!("bytes={}-", have))
+            .header(IF_RANGE, validator.trim());
+    }
+    let mut response = request.send().with_context(|| tr!(Msg::RequestFailed))?;
+
+    let status = response.status();
+    let mut file = match status {
+        StatusCode::PARTIAL_CONTENT => {
+            let start = response
+                .headers()
+                .get(CONTENT_RANGE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(content_range_start);
+            if start != Some(have) {
+                fs::remove_file(partial).ok();
+                bail!(tr!(Msg::ResumeOffsetMismatch, format!("{:?}", start), have));
+            }
+            info!("{}", tr!(Msg::DownloadResumed, have));
+            fs::OpenOptions::new()
+                .append(true)
+                .open(partial)
+                .with_context(|| tr!(Msg::PartialOpenFailed))?
+        }
+        StatusCode::RANGE_NOT_SATISFIABLE => {
+            // The partial file is not a prefix of the current upstream file.
+            fs::remove_file(partial).ok();
+            fs::remove_file(&validator_file).ok();
+            bail!(tr!(Msg::ResumeRejected));
+        }
+        status if status.is_success() => {
+            let validator = response
+                .headers()
+                .get(ETAG)
+                .filter(|v| !v.as_bytes().starts_with(b"W/"))
+                .or_else(|| response.headers().get(LAST_MODIFIED))
+                .and_then(|v| v.to_str().ok());
+            match validator {
+                Some(validator) => fs::write(&validator_file, validator)
+                    .with_context(|| tr!(Msg::ValidatorWriteFailed))?,
+                None => {
+                    fs::remove_file(&validator_file).ok();
+                }
+            }
+            File::create(partial).with_context(|| tr!(Msg::PartialCreateFailed))?
+        }
+        status => bail!(tr!(Msg::HttpError, status)),
+    };
+
+    let expected = response.content_length();
+    let written = std::io::copy(&mut response, &mut file)
+        .with_context(|| tr!(Msg::DownloadInterruptedError))?;
+    file.flush().with_context(|| tr!(Msg::PartialWriteFailed))?;
+    if let Some(expected) = expected {
+        if written != expected {
+            bail!(tr!(Msg::DownloadTruncated, written, expected));
+        }
+    }
+    Ok(())
+}
+
+/// First byte of a `Content-Range: bytes <start>-<end>/<total>` header.
+fn content_range_start(value: &str) -> Option<u64> {
+    value
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Why the cache at `path` would be re-fetched, or `None` if it would be used as is.
+pub fn refresh_reason(path: &Path, refresh: bool) -> Option<&'static str> {
+    if refresh {
+        Some(t(Msg::ReasonRefreshRequested))
+    } else if !path.exists() {
+        Some(t(Msg::ReasonCacheNotFound))
+    } else if is_cache_stale(path) {
+        Some(t(Msg::ReasonCacheStale))
+    } else {
+        None
+    }
+}
+
+/// Age of the cache file, based on its modification time.
+pub fn cache_age(path: &Path) -> Option<Duration> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    SystemTime::now().duration_since(modified).ok()
+}
+
+pub fn ensure_geoip_database(refresh: bool) -> anyhow::Result<PathBuf> {
+    ensure_dataset(get_cache_path(), GEOIP_URL, refresh)
+}
+
+/// Same as `ensure_geoip_database`, for the IPv6 database.
+pub fn ensure_geoip_v6_database(refresh: bool) -> anyhow::Result<PathBuf> {
+    ensure_dataset(get_v6_cache_path(), GEOIP_V6_URL, refresh)
+}
+
+fn ensure_dataset(path: PathBuf, url: &str, refresh: bool) -> anyhow::Result<PathBuf> {
+    // An embedded snapshot is pinned, so it is never refreshed
+    if EMBEDDED_SNAPSHOT
+        .get()
+        .is_some_and(|(v4, v6)| path == *v4 || path == *v6)
+    {
+        return Ok(path);
+    }
+    if let Some(reason) = refresh_reason(&path, refresh) {
+        info!("{}", tr!(Msg::Updating, reason));
+
+        if let Err(e) = fetch_dataset(url, &path) {
+            if path.exists() {
+                warn!("{}", tr!(Msg::FetchFailedUsingCache, e));
+            } else {
+                return Err(e);
+            }
+        }
+    }
+
+    Ok(path)
+}
+
+/// Use the snapshot published on `date` that this build embeds (`zkip_data`) instead of the
+/// cached databases, for the rest of the run. Its files are written out under
+/// `data/snapshots/<date>/` unless already there, with their publication time as their
+/// modification time, so `snapshot_metadata` reports the same snapshot as for the
+/// downloaded files. They are never fetched or refreshed.
+#[cfg(feature = "embedded-data")]
+pub fn use_embedded_snapshot(date: &str) -> anyhow::Result<()> {
+    let Some(snapshot) = zkip_data::find(date) else {
+        let available: Vec<&str> = zkip_data::SNAPSHOTS.iter().map(|s| s.date).collect();
+        bail!(tr!(Msg::SnapshotNotEmbedded, date, available.join(", ")));
+    };
+    if !snapshot.verify() {
+        bail!(tr!(Msg::SnapshotPinMismatch, date));
+    }
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../data/snapshots")
+        .join(snapshot.date);
+    fs::create_dir_all(&dir).with_context(|| tr!(Msg::SnapshotDirFailed))?;
+    let paths = (dir.join("ipv4-country.csv"), dir.join("ipv6-country.csv"));
+    let published = UNIX_EPOCH + Duration::from_secs(snapshot.published_at);
+    for (path, contents, sha256) in [
+        (&paths.0, snapshot.ipv4, snapshot.sha256),
+        (&paths.1, snapshot.ipv6, snapshot.sha256_v6),
+    ] {
+        if !path.exists() || sha256_file(path)? != sha256 {
+            fs::write(path, contents).with_context(|| tr!(Msg::SnapshotWriteFailed))?;
+        }
+        File::options()
+            .write(true)
+            .open(path)
+            .and_then(|file| file.set_modified(published))
+            .with_context(|| tr!(Msg::SnapshotDateFailed))?;
+    }
+    info!("{}", tr!(Msg::UsingEmbeddedSnapshot, date, dir.display()));
+    // Only set once, before anything reads the cache paths
+    let _ = EMBEDDED_SNAPSHOT.set(paths);
+    Ok(())
+}
+
+/// Without the `embedded-data` feature, the build has no snapshots to use.
+#[cfg(not(feature = "embedded-data"))]
+pub fn use_embedded_snapshot(_date: &str) -> anyhow::Result<()> {
+    bail!(t(Msg::SnapshotsNotBuilt))
+}
+
+/// The range of a row's `start,end` fields. Fails unless both are IPv4 addresses as
+/// integers with `start <= end`.
+fn parse_range(start: &str, end: &str) -> anyhow::Result<Ipv4Range> {
+    let start: u32 = start.parse().with_context(|| tr!(Msg::InvalidStartIp))?;
+    let end: u32 = end.parse().with_context(|| tr!(Msg::InvalidEndIp))?;
+    Ok(Ipv4Range::new(start, end)?)
+}
+
+/// Load IPv4 ranges for specified countries from the GeoIP database, as the set of
+/// addresses they cover.
+pub fn load_ip_ranges_for_countries(
+    path: &Path,
+    country_codes: &[String],
+) -> anyhow::Result<RangeSet> {
+    let file = File::open(path).with_context(|| tr!(Msg::GeoipOpenFailed))?;
+    let reader = BufReader::new(file);
+
+    let mut ranges = Vec::new();
+    for line in reader.lines() {
+        let line = line.with_context(|| tr!(Msg::ReadLineFailed))?;
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() >= 3 {
+            let country = fields[2].to_uppercase();
+            if country_codes.contains(&country) {
+                ranges.push(parse_range(fields[0], fields[1])?);
+            }
+        }
+    }
+
+    Ok(RangeSet::new(ranges))
+}
+
+/// Ranges of the specified countries from either GeoIP database, keyed by (uppercase)
+/// country code, sorted and deduplicated. Each country's ranges are cached as a
+/// `.zkranges` file (`zkip_lib::rangefile`) next to the database, and read from there
+/// while the database is older; the database is only parsed for the other countries.
+pub fn load_ranges_by_country<T>(
+    path: &Path,
+    country_codes: &[String],
+) -> anyhow::Result<HashMap<String, Vec<(T, T)>>>
+where
+    T: FromStr + RangeAddress,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let mut ranges: HashMap<String, Vec<(T, T)>> = HashMap::new();
+    let mut missing: Vec<String> = Vec::new();
+    for code in country_codes {
+        if ranges.contains_key(code) || missing.contains(code) {
+            continue;
+        }
+        match read_cached_ranges(path, code) {
+            Some(cached) => {
+                ranges.insert(code.clone(), cached);
+            }
+            None => missing.push(code.clone()),
+        }
+    }
+    if missing.is_empty() {
+        return Ok(ranges);
+    }
+
+    let file = File::open(path).with_context(|| tr!(Msg::GeoipOpenPathFailed, path.display()))?;
+    let reader = BufReader::new(file);
+
+    for line in reader.lines() {
+        let line = line.with_context(|| tr!(Msg::ReadLineFailed))?;
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() >= 3 {
+            let country = fields[2].to_uppercase();
+            if missing.contains(&country) {
+                let start: T = fields[0]
+                    .parse()
+                    .with_context(|| tr!(Msg::InvalidStartIp))?;
+                let end: T = fields[1].parse().with_context(|| tr!(Msg::InvalidEndIp))?;
+                ranges.entry(country).or_default().push((start, end));
+            }
+        }
+    }
+
+    for code in missing {
+        let group = ranges.entry(code.clone()).or_default();
+        group.sort_unstable();
+        group.dedup();
+        write_cached_ranges(path, &code, group);
+    }
+
+    Ok(ranges)
+}
+
+/// Where `load_ranges_by_country` caches `country`'s ranges from the database at `path`:
+/// `<database>.ranges/<country>.zkranges`. `None` unless `country` is a two-letter code.
+fn range_cache_path(path: &Path, country: &str) -> Option<PathBuf> {
+    if country.len() != 2 || !country.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return None;
+    }
+    let file = format!("{}.{}", country, rangefile::EXTENSION);
+    Some(path.with_extension("ranges").join(file))
+}
+
+/// `country`'s cached ranges, unless the cache is missing, older than the database at
+/// `path` or not a valid range file of that family.
+fn read_cached_ranges<T: RangeAddress>(path: &Path, country: &str) -> Option<Vec<(T, T)>> {
+    let cache = range_cache_path(path, country)?;
+    let database_modified = fs::metadata(path).ok()?.modified().ok()?;
+    let cache_modified = fs::metadata(&cache).ok()?.modified().ok()?;
+    if cache_modified < database_modified {
+        return None;
+    }
+    let bytes = fs::read(&cache).ok()?;
+    RangeFile::<T>::parse(&bytes).ok().map(|file| file.to_vec())
+}
+
+/// Cache `country`'s ranges from the database at `path`. Failing only costs a parse of the
+/// database next time, so it is logged rather than returned.
+fn write_cached_ranges<T: RangeAddress>(path: &Path, country: &str, ranges: &[(T, T)]) {
+    let Some(cache) = range_cache_path(path, country) else {
+        return;
+    };
+    let written = rangefile::write(ranges)
+        .map_err(anyhow::Error::from)
+        .and_then(|bytes| {
+            if let Some(dir) = cache.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            Ok(fs::write(&cache, bytes)?)
+        });
+    if let Err(e) = written {
+        warn!("{}", tr!(Msg::RangeCacheFailed, cache.display(), e));
+    }
+}
+
+/// Excluded ranges of `alpha2_codes` from both databases, grouped by country as the guest
+/// reads them. The IPv6 database is always fetched: the committed dataset root covers it
+/// whether or not an IPv6 address is proved.
+pub fn excluded_ranges(
+    geoip_path: &Path,
+    alpha2_codes: &[String],
+    refresh: bool,
+) -> anyhow::Result<ExcludedRanges> {
+    let v6_path = ensure_geoip_v6_database(refresh).context(ZkipCliError::DatabaseUnavailable)?;
+    let ranges = ExcludedRanges::load(geoip_path, Some(&v6_path), alpha2_codes)
+        .context(ZkipCliError::DatabaseCorrupted)?;
+    let countries = format!("{:?}", alpha2_codes);
+    info!("{}", tr!(Msg::LoadedRanges, ranges.ranges.len(), countries));
+    info!(
+        "{}",
+        tr!(Msg::LoadedRangesV6, ranges.ranges_v6.len(), countries)
+    );
+    Ok(ranges)
+}
+
+/// Ranges of `asns` from both ASN databases, grouped by ASN like `excluded_ranges`. The
+/// databases are only fetched when there is an ASN to look up.
+pub fn asn_ranges(asns: &[u32], refresh: bool) -> anyhow::Result<ExcludedRanges> {
+    if asns.is_empty() {
+        return Ok(ExcludedRanges::default());
+    }
+    let path = ensure_dataset(get_asn_cache_path(), ASN_URL, refresh)
+        .context(ZkipCliError::DatabaseUnavailable)?;
+    let v6_path = ensure_dataset(get_asn_v6_cache_path(), ASN_V6_URL, refresh)
+        .context(ZkipCliError::DatabaseUnavailable)?;
+    // The ASN column sits where the country column does, so the loader works unchanged
+    let keys: Vec<String> = asns.iter().map(u32::to_string).collect();
+    let ranges = ExcludedRanges::load(&path, Some(&v6_path), &keys)
+        .context(ZkipCliError::DatabaseCorrupted)?;
+    let (count, count_v6) = (ranges.ranges.len(), ranges.ranges_v6.len());
+    info!(
+        "{}",
+        tr!(Msg::LoadedAsnRanges, count, count_v6, format!("{:?}", asns))
+    );
+    Ok(ranges)
+}
+
+/// An allowlist turned into the exclusion set it stands for, for `--allow-only`.
+#[derive(Debug, Clone)]
+pub struct AllowOnly {
+    /// Every other country with ranges in either database, sorted by numeric code.
+    pub alpha2_codes: Vec<String>,
+    pub excluded_countries: Vec<u16>,
+    /// IPv4 addresses outside the allowed countries that no other country covers either,
+    /// reserved ranges aside. An exclusion proof reads them as clear.
+    pub unlisted_ipv4: u64,
+}
+
+/// The countries to exclude so that only `allowed` (uppercase alpha-2 codes) stay clear:
+/// every other country with ranges in the database at `geoip_path` or the IPv6 one at
+/// `geoip_v6_path`. Rows of codes without an ISO numeric code count as unlisted.
+pub fn allow_only(
+    geoip_path: &Path,
+    geoip_v6_path: &Path,
+    allowed: &[String],
+) -> anyhow::Result<AllowOnly> {
+    let codes = load_country_codes()?;
+    let mut others = BTreeMap::new();
+    let mut listed = Vec::new();
+    let file = File::open(geoip_path).with_context(|| tr!(Msg::GeoipOpenFailed))?;
+    for line in BufReader::new(file).lines() {
+        let line = line.with_context(|| tr!(Msg::ReadLineFailed))?;
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() < 3 {
+            continue;
+        }
+        let country = fields[2].trim().to_uppercase();
+        let Some(&code) = codes.get(&country) else {
+            continue;
+        };
+        listed.push(parse_range(fields[0], fields[1])?);
+        if !allowed.contains(&country) {
+            others.insert(code, country);
+        }
+    }
+    for country in count_ranges_by_country(geoip_v6_path)?.into_keys() {
+        match codes.get(&country) {
+            Some(&code) if !allowed.contains(&country) => {
+                others.insert(code, country);
+            }
+            _ => {}
+        }
+    }
+
+    // Addresses in no country's ranges are in none of the excluded ones either
+    let reserved = RangeSet::from_pairs(RESERVED_RANGES)?;
+    let unlisted =
+        RangeSet::new(listed.into_iter().chain(reserved.ranges().iter().copied())).complement();
+    Ok(AllowOnly {
+        alpha2_codes: others.values().cloned().collect(),
+        excluded_countries: others.into_keys().collect(),
+        unlisted_ipv4: unlisted.address_count(),
+    })
+}
+
+/// Number of ranges per (uppercase) country code in the GeoIP database.
+pub fn count_ranges_by_country(path: &Path) -> anyhow::Result<HashMap<String, usize>> {
+    let file = File::open(path).with_context(|| tr!(Msg::GeoipOpenFailed))?;
+    let reader = BufReader::new(file);
+
+    let mut counts = HashMap::new();
+    for line in reader.lines() {
+        let line = line.with_context(|| tr!(Msg::ReadLineFailed))?;
+        if let Some(country) = line.split(',').nth(2) {
+            *counts.entry(country.trim().to_uppercase()).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// Checksum of the GeoIP file as published upstream.
+#[derive(Debug, Clone)]
+pub struct UpstreamChecksum {
+    pub version: String,
+    pub sha256: [u8; 32],
+}
+
+/// Resolve the current upstream release and fetch the SHA-256 jsDelivr publishes for it.
+pub fn fetch_upstream_checksum() -> anyhow::Result<UpstreamChecksum> {
+    let resolved = fetch_json(&format!(
+        "{}/{}/resolved?specifier=latest",
+        JSDELIVR_DATA_API, GEOIP_PACKAGE
+    ))?;
+    let version = resolved["version"]
+        .as_str()
+        .with_context(|| tr!(Msg::UpstreamNoVersion))?
+        .to_string();
+
+    let listing = fetch_json(&format!(
+        "{}/{}@{}?structure=flat",
+        JSDELIVR_DATA_API, GEOIP_PACKAGE, version
+    ))?;
+    let files = listing["files"]
+        .as_array()
+        .with_context(|| tr!(Msg::UpstreamNoFiles))?;
+    let entry = files
+        .iter()
+        .find(|f| f["name"].as_str() == Some(&format!("/{}", GEOIP_FILE)))
+        .with_context(|| tr!(Msg::UpstreamFileMissing, GEOIP_FILE))?;
+    let hash = entry["hash"]
+        .as_str()
+        .with_context(|| tr!(Msg::UpstreamNoHash))?;
+
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(hash)
+        .with_context(|| tr!(Msg::UpstreamHashBase64))?;
+    let sha256: [u8; 32] = decoded
+        .try_into()
+        .map_err(|_| anyhow::anyhow!(tr!(Msg::UpstreamHashLength)))?;
+
+    Ok(UpstreamChecksum { version, sha256 })
+}
+
+fn fetch_json(url: &str) -> anyhow::Result<serde_json::Value> {
+    let response = reqwest::blocking::get(url).with_context(|| tr!(Msg::FetchUrlFailed, url))?;
+
+    if !response.status().is_success() {
+        bail!(tr!(Msg::HttpError, response.status()));
+    }
+
+    let body = response
+        .text()
+        .with_context(|| tr!(Msg::ReadResponseFailed))?;
+    serde_json::from_str(&body).with_context(|| tr!(Msg::UpstreamParseFailed))
+}
+
+/// Number of non-empty rows in the GeoIP CSV.
+pub fn count_rows(path: &Path) -> anyhow::Result<usize> {
+    let file = File::open(path).with_context(|| tr!(Msg::GeoipOpenFailed))?;
+    let reader = BufReader::new(file);
+
+    let mut rows = 0;
+    for line in reader.lines() {
+        if !line
+            .with_context(|| tr!(Msg::ReadLineFailed))?
+            .trim()
+            .is_empty()
+        {
+            rows += 1;
+        }
+    }
+    Ok(rows)
+}
+
+/// SHA-256 of a file's contents.
+pub fn sha256_file(path: &Path) -> anyhow::Result<[u8; 32]> {
+    let mut file = File::open(path).with_context(|| tr!(Msg::GeoipOpenFailed))?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .with_context(|| tr!(Msg::GeoipReadFailed))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// What a plain proof commits about the snapshots at `path` and `v6_path`
+/// (`zkip_lib::snapshot`). Upstream doesn't date its files, so the publication date is the
+/// IPv4 file's modification time: when it was fetched, no earlier than it was published.
+pub fn snapshot_metadata(path: &Path, v6_path: &Path) -> anyhow::Result<SnapshotMetadata> {
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .with_context(|| tr!(Msg::GeoipReadFailed))?;
+    let published_at = modified
+        .duration_since(UNIX_EPOCH)
+        .with_context(|| tr!(Msg::GeoipBeforeEpoch))?
+        .as_secs();
+    Ok(SnapshotMetadata {
+        source: source_id(GEOIP_URL),
+        published_at,
+        hash: content_hash(&sha256_file(path)?, &sha256_file(v6_path)?),
+    })
+}
+
+/// The database at `path` and, if given, the IPv6 one at `v6_path`, parsed once into a
+/// `zkip_lib::geo::GeoDb` for callers that look up many addresses or country lists. With
+/// both it records their `snapshot_metadata`.
+pub fn load_geo_db(path: &Path, v6_path: Option<&Path>) -> anyhow::Result<GeoDb> {
+    let csv =
+        fs::read_to_string(path).with_context(|| tr!(Msg::GeoipReadPathFailed, path.display()))?;
+    let csv_v6 = match v6_path {
+        Some(v6_path) => Some(
+            fs::read_to_string(v6_path)
+                .with_context(|| tr!(Msg::GeoipReadPathFailed, v6_path.display()))?,
+        ),
+        None => None,
+    };
+    let db = GeoDb::from_csv(&csv, csv_v6.as_deref())?;
+    Ok(match v6_path {
+        Some(v6_path) => db.with_metadata(snapshot_metadata(path, v6_path)?),
+        None => db,
+    })
+}
+
+/// A structural problem found in the GeoIP CSV.
+#[derive(Debug, Clone)]
+pub struct CsvIssue {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CsvIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Result of a structural scan of the GeoIP CSV.
+#[derive(Debug, Clone, Default)]
+pub struct CsvReport {
+    pub rows: usize,
+    pub issues: Vec<CsvIssue>,
+}
+
+/// Check that every row is `start,end,CC` with `start <= end`, and that rows are
+/// sorted by start address without overlapping the previous row.
+pub fn validate_csv(path: &Path) -> anyhow::Result<CsvReport> {
+    let file = File::open(path).with_context(|| tr!(Msg::GeoipOpenFailed))?;
+    let reader = BufReader::new(file);
+
+    let mut report = CsvReport::default();
+    let mut prev_end: Option<u32> = None;
+    for (i, line) in reader.lines().enumerate() {
+        let line_no = i + 1;
+        let line = line.with_context(|| tr!(Msg::ReadLineFailed))?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        report.rows += 1;
+
+        let mut issue = |message: String| {
+            report.issues.push(CsvIssue {
+                line: line_no,
+                message,
+            })
+        };
+
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() != 3 {
+            issue(format!("expected 3 fields, found {}", fields.len()));
+            continue;
+        }
+        let (Ok(start), Ok(end)) = (fields[0].parse::<u32>(), fields[1].parse::<u32>()) else {
+            issue("invalid range bounds".to_string());
+            continue;
+        };
+        let country = fields[2];
+        if country.len() != 2 || !country.bytes().all(|b| b.is_ascii_alphabetic()) {
+            issue(format!("invalid country code {:?}", country));
+        }
+        if let Err(err) = Ipv4Range::new(start, end) {
+            issue(err.to_string());
+        }
+        if let Some(prev_end) = prev_end {
+            if start <= prev_end {
+                issue(format!(
+                    "range starting at {} overlaps or precedes previous range ending at {}",
+                    start, prev_end
+                ));
+            }
+        }
+        prev_end = Some(end);
+    }
+
+    Ok(report)
+}
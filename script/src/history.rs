@@ -0,0 +1,192 @@
+//! Per-run cost history for `zkip stats`.
+//!
+//! Every execute or prove run appends one JSON line to `data/history.jsonl` with its
+//! cost: cycles (execute runs), wall time of the execute/prove phase, peak RSS and
+//! proof size. `zkip stats` groups runs by mode, variant and country set and compares
+//! the first and latest runs, so regressions after a DB refresh or program upgrade stand
+//! out.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sp1_sdk::SP1ProofWithPublicValues;
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::geoip::sha256_file;
+use crate::i18n::Msg;
+use crate::policy::policy_hash;
+use crate::tr;
+
+pub fn default_history_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../data/history.jsonl")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunMetrics {
+    /// Unix time the run finished.
+    pub recorded_at: u64,
+    /// `execute`, `core`, `plonk` or `groth16`.
+    pub mode: String,
+    pub variant: String,
+    pub vkey: String,
+    /// `policy_hash` of the excluded countries, hex.
+    pub policy_hash: String,
+    pub excluded_countries: Vec<u16>,
+    /// Number of ranges fed to the guest.
+    pub ranges: usize,
+    /// SHA-256 of the GeoIP snapshot, hex.
+    pub geoip_sha256: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycles: Option<u64>,
+    /// Wall time of the execute or prove phase.
+    pub wall_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_rss_kib: Option<u64>,
+    /// Size of the JSON-serialized proof (the encoding `proof_id` hashes).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof_bytes: Option<u64>,
+}
+
+/// Peak resident set size of this process, from `/proc/self/status` (Linux only).
+pub fn peak_rss_kib() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+pub fn append(path: &Path, metrics: &RunMetrics) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| tr!(Msg::CreateFailed, parent.display()))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| tr!(Msg::OpenFailed, path.display()))?;
+    let line = serde_json::to_string(metrics).with_context(|| tr!(Msg::SerializeMetricsFailed))?;
+    writeln!(file, "{}", line).with_context(|| tr!(Msg::WriteFailed, path.display()))
+}
+
+/// What a run was: everything recorded besides its cost.
+#[derive(Debug, Clone, Copy)]
+pub struct RunLabel<'a> {
+    pub mode: &'a str,
+    pub variant: &'a str,
+    pub vkey: &'a str,
+    pub excluded_countries: &'a [u16],
+    pub ranges: usize,
+    pub geoip_path: &'a Path,
+}
+
+/// What a run cost, as measured by the caller.
+#[derive(Debug, Clone, Copy)]
+pub struct RunCost<'a> {
+    pub cycles: Option<u64>,
+    pub wall: Duration,
+    pub proof: Option<&'a SP1ProofWithPublicValues>,
+}
+
+/// Append a run to the history at `path`.
+pub fn record_run(path: &Path, label: RunLabel, cost: RunCost) -> anyhow::Result<()> {
+    let proof_bytes = match cost.proof {
+        Some(proof) => Some(
+            serde_json::to_vec(proof)
+                .with_context(|| tr!(Msg::SerializeProofFailed))?
+                .len() as u64,
+        ),
+        None => None,
+    };
+    let metrics = RunMetrics {
+        recorded_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .with_context(|| tr!(Msg::ClockBeforeEpoch))?
+            .as_secs(),
+        mode: label.mode.to_string(),
+        variant: label.variant.to_string(),
+        vkey: label.vkey.to_string(),
+        policy_hash: hex::encode(policy_hash(label.excluded_countries)),
+        excluded_countries: label.excluded_countries.to_vec(),
+        ranges: label.ranges,
+        geoip_sha256: hex::encode(sha256_file(label.geoip_path)?),
+        cycles: cost.cycles,
+        wall_ms: cost.wall.as_millis() as u64,
+        peak_rss_kib: peak_rss_kib(),
+        proof_bytes,
+    };
+    append(path, &metrics)
+}
+
+/// Every run in the history at `path`, oldest first. Unparseable lines are skipped.
+pub fn load(path: &Path) -> anyhow::Result<Vec<RunMetrics>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Aggregate over the runs of one mode, variant and country set.
+#[derive(Debug, Clone)]
+pub struct RunStats {
+    pub mode: String,
+    pub variant: String,
+    pub excluded_countries: Vec<u16>,
+    pub runs: usize,
+    pub median_cycles: Option<u64>,
+    pub median_wall_ms: u64,
+    pub max_peak_rss_kib: Option<u64>,
+    pub median_proof_bytes: Option<u64>,
+    /// Change of the latest run against the first, in percent, by cycles when known and
+    /// wall time otherwise.
+    pub trend_percent: Option<f64>,
+}
+
+fn median(mut values: Vec<u64>) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    Some(values[values.len() / 2])
+}
+
+fn cost(run: &RunMetrics) -> u64 {
+    run.cycles.unwrap_or(run.wall_ms)
+}
+
+/// Group `runs` by mode, variant and policy hash.
+pub fn summarize(runs: &[RunMetrics]) -> Vec<RunStats> {
+    let mut groups: BTreeMap<(&str, &str, &str), Vec<&RunMetrics>> = BTreeMap::new();
+    for run in runs {
+        groups
+            .entry((&run.mode, &run.variant, &run.policy_hash))
+            .or_default()
+            .push(run);
+    }
+
+    groups
+        .into_values()
+        .map(|group| {
+            let first = group[0];
+            let last = group[group.len() - 1];
+            let trend_percent = (group.len() > 1 && cost(first) > 0)
+                .then(|| (cost(last) as f64 - cost(first) as f64) * 100.0 / cost(first) as f64);
+            RunStats {
+                mode: first.mode.clone(),
+                variant: first.variant.clone(),
+                excluded_countries: first.excluded_countries.clone(),
+                runs: group.len(),
+                median_cycles: median(group.iter().filter_map(|r| r.cycles).collect()),
+                median_wall_ms: median(group.iter().map(|r| r.wall_ms).collect()).unwrap_or(0),
+                max_peak_rss_kib: group.iter().filter_map(|r| r.peak_rss_kib).max(),
+                median_proof_bytes: median(group.iter().filter_map(|r| r.proof_bytes).collect()),
+                trend_percent,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,1459 @@
+//! Message catalog for user-facing CLI output.
+//!
+//! Each message has one template per language; `{}` placeholders are filled in order by
+//! [`tr!`](crate::tr). Log field names, error codes and error kinds stay in English so
+//! tooling does not depend on the selected language.
+
+use clap::ValueEnum;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Lang {
+    #[default]
+    En,
+    Fr,
+}
+
+impl Lang {
+    /// Pick a supported language from a POSIX locale such as `fr_FR.UTF-8`.
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let code = locale.split(['_', '.', '-']).next()?.to_lowercase();
+        match code.as_str() {
+            "en" => Some(Self::En),
+            "fr" => Some(Self::Fr),
+            _ => None,
+        }
+    }
+}
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+/// Select the output language. Only the first call has an effect.
+pub fn set_lang(lang: Lang) {
+    let _ = LANG.set(lang);
+}
+
+/// Resolve the language from an explicit choice, then `LC_ALL`/`LANG`, then English.
+pub fn resolve_lang(explicit: Option<Lang>) -> Lang {
+    explicit
+        .or_else(|| {
+            ["LC_ALL", "LANG"]
+                .iter()
+                .filter_map(|var| std::env::var(var).ok())
+                .find_map(|locale| Lang::from_locale(&locale))
+        })
+        .unwrap_or_default()
+}
+
+pub fn lang() -> Lang {
+    LANG.get().copied().unwrap_or_default()
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Msg {
+    // Proving flow
+    MissingMode,
+    LoadedRanges,
+    LoadedRangesV6,
+    LoadedAsnRanges,
+    TestingIp,
+    TestingIpInclusion,
+    TestingIpPolicy,
+    DiscloseIpv4Only,
+    DiscloseNotListed,
+    ProgramExecuted,
+    ResultIsExcluded,
+    ResultIsIncluded,
+    ResultIsAllowed,
+    ResultIsMet,
+    ResultIsClear,
+    CountryResult,
+    Timestamp,
+    CheckedCountries,
+    VerificationPassed,
+    Cycles,
+    ProofGenerated,
+    DisclosedRegion,
+    DisclosedCountry,
+    ProofVerified,
+    // EVM binary
+    EvmIp,
+    EvmExcludedCountries,
+    EvmProofSystem,
+    VerificationKey,
+    PublicValues,
+    ProofBytes,
+    // GeoIP cache
+    Fetching,
+    Cached,
+    DownloadInterrupted,
+    DownloadResumed,
+    Updating,
+    FetchFailedUsingCache,
+    RangeCacheFailed,
+    UsingEmbeddedSnapshot,
+    SnapshotNotEmbedded,
+    SnapshotPinMismatch,
+    SnapshotsNotBuilt,
+    ReasonRefreshRequested,
+    ReasonCacheNotFound,
+    ReasonCacheStale,
+    // db status / verify
+    Provider,
+    Source,
+    Location,
+    Size,
+    Age,
+    AgeUnknown,
+    Sha256,
+    Rows,
+    NotDownloaded,
+    NextRunRefresh,
+    NextRunCache,
+    CountryRoot,
+    SetRoot,
+    RootManifestWritten,
+    DbNotFound,
+    Verifying,
+    LocalSha256,
+    UpstreamSha256,
+    ChecksumOk,
+    ChecksumMismatch,
+    StructureEmpty,
+    StructureOk,
+    StructureIssues,
+    MoreIssues,
+    DbVerified,
+    DbVerifyFailed,
+    // selftest
+    SelftestVariant,
+    SelftestPass,
+    SelftestFail,
+    MockProofOk,
+    SelftestPassed,
+    SelftestFailedCount,
+    // policy lint
+    PolicyNoSets,
+    PolicyEmptySet,
+    PolicyUnknownCode,
+    PolicyDuplicate,
+    PolicyOverlap,
+    PolicyLargeCountry,
+    PolicyRangeCountsSkipped,
+    PolicyCountries,
+    PolicyHash,
+    PolicyRuleHash,
+    PolicyNoRule,
+    PolicyRuleNode,
+    PolicyUnknownSet,
+    PolicyRuleUnknownCode,
+    PolicyInvalidCidr,
+    PolicyOk,
+    PolicyInvalid,
+    // lockfile
+    LockCreated,
+    LockMatched,
+    LockUpdated,
+    LockMismatch,
+    // vkey
+    VkeyEmbedded,
+    VkeyRebuilding,
+    VkeyRebuilt,
+    VkeyExpected,
+    VkeyReproduced,
+    VkeyMismatch,
+    // registry / verify
+    RegistryAdded,
+    RegistryUnchanged,
+    RegistryEmpty,
+    RegistryEntry,
+    RegistryEntryRevoked,
+    RegistryRevoked,
+    RegistryAlreadyRevoked,
+    ProofSaved,
+    ArtifactsWritten,
+    BundleWritten,
+    BundleAccepted,
+    BundleRejected,
+    BundleSignedBy,
+    OperatorUnpinned,
+    OperatorNeedsBundle,
+    VerifyOk,
+    VerifyRevoked,
+    VerifyNoMatch,
+    // renewals
+    RenewNeedsCompressed,
+    WrapNeedsCompressed,
+    Wrapping,
+    RenewOfRenewal,
+    Renewing,
+    RenewalOf,
+    // chunked proofs
+    ProvingChunk,
+    VerifyAggregateOk,
+    ChunkProgramUnknown,
+    AggregatedFrom,
+    // companion proofs
+    CompanionNeedsCompressed,
+    CompanionLoaded,
+    CompanionOf,
+    NotComposedProof,
+    CompanionMismatch,
+    // challenges
+    Challenge,
+    NotChallengedProof,
+    ChallengeMismatch,
+    // validity windows
+    Validity,
+    ProofNotYetValid,
+    ProofExpired,
+    RenewOfWindowed,
+    // public values versions
+    PublicValuesVersion,
+    PublicValuesVersionUnsupported,
+    Snapshot,
+    SnapshotHash,
+    SnapshotHashMismatch,
+    SnapshotHashUnpinned,
+    SnapshotMismatch,
+    PolicyId,
+    PolicyIdMismatch,
+    AppContext,
+    AppContextMismatch,
+    NetworkPrefix,
+    PrefixMismatch,
+    BlockPrefix,
+    // release manifests
+    ManifestVerified,
+    UnsignedAllowed,
+    ManifestWritten,
+    // audit log
+    AuditRecorded,
+    AuditIntact,
+    // revocations
+    RevocationAdded,
+    RevocationUnchanged,
+    RevocationUnpinned,
+    ProofRevoked,
+    ProofReplayed,
+    // time source
+    NtpServerFailed,
+    ClockChecked,
+    RoughtimeChecked,
+    TimedBy,
+    NotTimedProof,
+    TimeServerMismatch,
+    // block anchor
+    AnchorFetched,
+    AnchorBlock,
+    NotAnchoredProof,
+    AnchorNotOnChain,
+    AnchorTooOld,
+    AnchorRecent,
+    // dataset root
+    DatasetRoot,
+    DatasetRootMissing,
+    DatasetRootMismatch,
+    RangeCoverage,
+    ResultIsPublicIp,
+    ReservedIpProof,
+    RangesDigest,
+    Nullifier,
+    NullifierMismatch,
+    RangesDigestMismatch,
+    RangesDigestMissing,
+    // gap witnesses
+    GapWitnessProof,
+    GapWitnessCovered,
+    GapWitnessConstantWork,
+    BlockConstantWork,
+    BlockTooLong,
+    AllowOnly,
+    AllowOnlyUnlisted,
+    BlockReserved,
+    // attestations
+    AttestationInvalid,
+    AttestedBy,
+    NotAttestedProof,
+    AttesterMismatch,
+    WebProofInvalid,
+    NotWebProof,
+    // signed snapshots
+    SnapshotSigned,
+    SnapshotStale,
+    SignedSnapshotBy,
+    NotSignedSnapshotProof,
+    PublisherMismatch,
+    DbTooOld,
+    DbMaxAge,
+    DbAgeUnbounded,
+    DbAgeTooLoose,
+    // inclusion proofs
+    InclusionSetInvalid,
+    NoRangesToCheck,
+    NotInclusionProof,
+    AnonymitySetTooSmall,
+    DeniedCountries,
+    DenylistRoot,
+    DenylistRootMismatch,
+    // policy proofs
+    AsnRoot,
+    NotPolicyProof,
+    PolicyMismatch,
+    // root registry
+    RootUnchanged,
+    RootDryRun,
+    RootPushed,
+    // stats
+    StatsEmpty,
+    StatsGroup,
+    StatsCost,
+    // domains
+    DomainResolved,
+    DomainIpv6Skipped,
+    AddressCount,
+    ClearCount,
+    IpListLoaded,
+    InventoryRoot,
+    Threshold,
+    NotInventoryProof,
+    InventoryMismatch,
+    // co-signing
+    CosignAdded,
+    CosignUnchanged,
+    CosignChecked,
+    // explain
+    ExplainLayout,
+    ExplainTimestamp,
+    ExplainCountry,
+    ExplainUnknownCountry,
+    ExplainWarning,
+    // e2e
+    E2eAnvil,
+    E2eDeployed,
+    E2ePassed,
+    // artifact store
+    ArtifactStored,
+    // errors: files and clock
+    ReadFailed,
+    WriteFailed,
+    ParseFailed,
+    CreateFailed,
+    OpenFailed,
+    ReplaceFailed,
+    ReadLineFailed,
+    SerializeProofFailed,
+    ClockBeforeEpoch,
+    RuntimeFailed,
+    InvalidRpcUrl,
+    SignatureNotHex,
+    SignatureLength,
+    HttpError,
+    ResolveFailed,
+    // errors: CLI
+    GeoipMetadataFailed,
+    SerializeVerdictFailed,
+    ReadProofStdinFailed,
+    LoadProofFailed,
+    WriteProofStdoutFailed,
+    ContractResultMismatch,
+    InvalidHex32,
+    Not32Bytes,
+    ElfNoFileName,
+    // errors: FFI
+    FfiReadFailed,
+    FfiInvalidRequest,
+    FfiVersionUnsupported,
+    FfiDiscloseIpv4Only,
+    // errors: countries
+    CountriesOpenFailed,
+    CountryAmbiguous,
+    CountryUnknownSuggest,
+    CountryUnknown,
+    NoCountryCodes,
+    // errors: GeoIP database
+    DataDirFailed,
+    HttpClientFailed,
+    GeoipFetchFailed,
+    DownloadMoveFailed,
+    RequestFailed,
+    ResumeOffsetMismatch,
+    PartialOpenFailed,
+    ResumeRejected,
+    ValidatorWriteFailed,
+    PartialCreateFailed,
+    DownloadInterruptedError,
+    PartialWriteFailed,
+    DownloadTruncated,
+    SnapshotDirFailed,
+    SnapshotWriteFailed,
+    SnapshotDateFailed,
+    InvalidStartIp,
+    InvalidEndIp,
+    GeoipOpenFailed,
+    GeoipOpenPathFailed,
+    GeoipReadFailed,
+    GeoipReadPathFailed,
+    GeoipBeforeEpoch,
+    UpstreamNoVersion,
+    UpstreamNoFiles,
+    UpstreamFileMissing,
+    UpstreamNoHash,
+    UpstreamHashBase64,
+    UpstreamHashLength,
+    FetchUrlFailed,
+    ReadResponseFailed,
+    UpstreamParseFailed,
+    // errors: policies
+    PolicyReadFailed,
+    PolicyParseFailed,
+    PrefixTooLong,
+    // errors: lockfile, artifacts and history
+    SerializeLockFailed,
+    SerializeMetadataFailed,
+    SerializeMetricsFailed,
+    CargoProveFailed,
+    CargoProveExited,
+    DockerNoElf,
+    // errors: registry
+    SerializeRegistryFailed,
+    VersionRegistered,
+    VkeyRegistered,
+    SerializeVkeyFailed,
+    VersionNotRegistered,
+    VkeyParseFailed,
+    // errors: bundles
+    SerializeBundleFailed,
+    BundleDecodeFailed,
+    BundleValuesDiffer,
+    BundleWriteFailed,
+    BundleReadFailed,
+    NotBundle,
+    BundleVersion,
+    // errors: release manifests
+    SerializeManifestFailed,
+    PublicKeyNotHex,
+    PublicKeyLength,
+    InvalidEd25519Key,
+    SigningKeyNotHex,
+    SigningKeyLength,
+    ManifestReadFailed,
+    ManifestParseFailed,
+    ManifestSignatureInvalid,
+    ArtifactNoFileName,
+    ArtifactNotListed,
+    ArtifactHashMismatch,
+    ManifestVkeyMismatch,
+    NoMaintainerKey,
+    // errors: audit log
+    SerializeAuditFailed,
+    AuditParseFailed,
+    AuditEntryInvalid,
+    AuditSeqMismatch,
+    AuditPrevHashMismatch,
+    AuditHashMismatch,
+    // errors: revocations and replay
+    SerializeRevocationsFailed,
+    RevocationSigner,
+    RevocationSignatureInvalid,
+    ReplayAccepted,
+    SerializeReplayFailed,
+    // errors: time source
+    ServerNoAddress,
+    UdpSocketFailed,
+    SendFailed,
+    NoReply,
+    UnexpectedReply,
+    NtpNotServer,
+    NtpKissOfDeath,
+    NtpWrongRequest,
+    RoughtimeBadReply,
+    RoughtimeKeyEncoding,
+    RoughtimeKeyLength,
+    NtpTooFewAnswers,
+    NtpDisagree,
+    ClockSkewed,
+    RoughtimeNeedsKey,
+    // errors: block anchor
+    RpcNoBlock,
+    RpcUnreachable,
+    RpcInvalidJson,
+    RpcCallFailed,
+    InvalidBlockNumber,
+    InvalidBlockHash,
+    BlockHashLength,
+    // errors: attestations
+    AttestationNotHex,
+    AttestationSignatureLength,
+    AttestationNonceLength,
+    AttesterKeyLength,
+    WebProofSignatureLength,
+    // errors: signed snapshots
+    UnknownCountryCode,
+    InvalidRoot,
+    RootLength,
+    NoPublisherKey,
+    RootScheme,
+    SerializeRootsFailed,
+    RootsUnsigned,
+    RootsMissingCountry,
+    PublisherSignatureNotHex,
+    RootsSignatureInvalid,
+    // errors: domains and IP lists
+    NotHostName,
+    DomainNoAddresses,
+    IpListEmpty,
+    // errors: co-signing
+    EnvelopeProofMismatch,
+    SerializeEnvelopeFailed,
+    CosignThreshold,
+    OperatorKeyInvalid,
+    OperatorKeyDuplicate,
+    CosignShort,
+    // errors: explain
+    PublicValuesNotHex,
+    // errors: e2e
+    AnvilStartFailed,
+    AnvilNotListening,
+    NotFoundryProject,
+    ForgeRunFailed,
+    ForgeBuildFailed,
+    NoBytecode,
+    BytecodeNotHex,
+    InvalidDevKey,
+    AnvilNotAnswering,
+    DeployVerifierFailed,
+    DeployZkipFailed,
+    VerifyZkipReverted,
+    // errors: root updates
+    InvalidAdminKey,
+    ReadRootFailed,
+    NoUpdaterRole,
+    MissingUpdaterRole,
+    SetRootWouldRevert,
+    SetRootSendFailed,
+    SetRootReceiptFailed,
+    SetRootReverted,
+    // errors: artifact store
+    GcsTokenUnset,
+    AwsKeyIdUnset,
+    AwsSecretUnset,
+    S3UploadFailed,
+    S3UploadRejected,
+    GcsUploadFailed,
+    GcsUploadRejected,
+}
+
+fn template(msg: Msg, lang: Lang) -> &'static str {
+    use Lang::*;
+    use Msg::*;
+    match (msg, lang) {
+        (MissingMode, En) => "You must specify either --execute or --prove",
+        (MissingMode, Fr) => "Vous devez indiquer --execute ou --prove",
+        (LoadedRanges, En) => "Loaded {} IP ranges for {}",
+        (LoadedRanges, Fr) => "{} plages IP chargées pour {}",
+        (LoadedRangesV6, En) => "Loaded {} IPv6 ranges for {}",
+        (LoadedRangesV6, Fr) => "{} plages IPv6 chargées pour {}",
+        (LoadedAsnRanges, En) => "Loaded {} IPv4 and {} IPv6 ranges for ASNs {}",
+        (LoadedAsnRanges, Fr) => "{} plages IPv4 et {} plages IPv6 chargées pour les ASN {}",
+        (TestingIp, En) => "Testing IP: {} ({}) against excluded countries: {}",
+        (TestingIp, Fr) => "Test de l'IP : {} ({}) contre les pays exclus : {}",
+        (TestingIpInclusion, En) => "Testing IP: {} ({}) against included countries: {}",
+        (TestingIpInclusion, Fr) => "Test de l'IP : {} ({}) contre les pays inclus : {}",
+        (TestingIpPolicy, En) => "Testing IP: {} ({}) against policy rule 0x{}",
+        (TestingIpPolicy, Fr) => "Test de l'IP : {} ({}) contre la règle de politique 0x{}",
+        (DiscloseIpv4Only, En) => "--disclose only supports IPv4 addresses for now",
+        (DiscloseIpv4Only, Fr) => "--disclose ne prend en charge que les adresses IPv4 pour l'instant",
+        (DiscloseNotListed, En) => "--disclose needs the IP in one of the listed countries",
+        (DiscloseNotListed, Fr) => "--disclose exige une IP dans l'un des pays listés",
+        (ProgramExecuted, En) => "Program executed successfully.",
+        (ProgramExecuted, Fr) => "Programme exécuté avec succès.",
+        (ResultIsExcluded, En) => "Result: is_excluded = {}",
+        (ResultIsExcluded, Fr) => "Résultat : is_excluded = {}",
+        (ResultIsIncluded, En) => "Result: is_included = {}",
+        (ResultIsIncluded, Fr) => "Résultat : is_included = {}",
+        (ResultIsAllowed, En) => "Result: is_allowed = {}",
+        (ResultIsAllowed, Fr) => "Résultat : is_allowed = {}",
+        (ResultIsMet, En) => "Result: is_met = {}",
+        (ResultIsMet, Fr) => "Résultat : is_met = {}",
+        (ResultIsClear, En) => "Result: is_clear = {}",
+        (ResultIsClear, Fr) => "Résultat : is_clear = {}",
+        (CountryResult, En) => "  country {}: is_excluded = {}",
+        (CountryResult, Fr) => "  pays {} : is_excluded = {}",
+        (Timestamp, En) => "Timestamp: {}",
+        (Timestamp, Fr) => "Horodatage : {}",
+        (CheckedCountries, En) => "Checked countries: {}",
+        (CheckedCountries, Fr) => "Pays vérifiés : {}",
+        (VerificationPassed, En) => "Verification passed!",
+        (VerificationPassed, Fr) => "Vérification réussie !",
+        (Cycles, En) => "Number of cycles: {}",
+        (Cycles, Fr) => "Nombre de cycles : {}",
+        (DisclosedRegion, En) => "Disclosed region: {} ({})",
+        (DisclosedRegion, Fr) => "Région divulguée : {} ({})",
+        (DisclosedCountry, En) => "Disclosed country: {} ({})",
+        (DisclosedCountry, Fr) => "Pays divulgué : {} ({})",
+        (ProofGenerated, En) => "Successfully generated proof!",
+        (ProofGenerated, Fr) => "Preuve générée avec succès !",
+        (ProofVerified, En) => "Successfully verified proof!",
+        (ProofVerified, Fr) => "Preuve vérifiée avec succès !",
+
+        (EvmIp, En) => "IP: {} ({})",
+        (EvmIp, Fr) => "IP : {} ({})",
+        (EvmExcludedCountries, En) => "Excluded countries: {}",
+        (EvmExcludedCountries, Fr) => "Pays exclus : {}",
+        (EvmProofSystem, En) => "Proof System: {}",
+        (EvmProofSystem, Fr) => "Système de preuve : {}",
+        (VerificationKey, En) => "Verification Key: {}",
+        (VerificationKey, Fr) => "Clé de vérification : {}",
+        (PublicValues, En) => "Public Values: {}",
+        (PublicValues, Fr) => "Valeurs publiques : {}",
+        (ProofBytes, En) => "Proof Bytes: {}",
+        (ProofBytes, Fr) => "Octets de la preuve : {}",
+
+        (Fetching, En) => "Fetching GeoIP database from {}...",
+        (Fetching, Fr) => "Téléchargement de la base GeoIP depuis {}...",
+        (Cached, En) => "GeoIP database cached to {}",
+        (Cached, Fr) => "Base GeoIP mise en cache dans {}",
+        (DownloadInterrupted, En) => "Download interrupted: {} ({} bytes kept, attempt {}/{}); resuming",
+        (DownloadInterrupted, Fr) => "Téléchargement interrompu : {} ({} octets conservés, tentative {}/{}) ; reprise",
+        (DownloadResumed, En) => "Resuming download at byte {}",
+        (DownloadResumed, Fr) => "Reprise du téléchargement à l'octet {}",
+        (Updating, En) => "Updating GeoIP database ({})...",
+        (Updating, Fr) => "Mise à jour de la base GeoIP ({})...",
+        (FetchFailedUsingCache, En) => "Failed to fetch GeoIP database: {}. Using cached version.",
+        (FetchFailedUsingCache, Fr) => "Échec du téléchargement de la base GeoIP : {}. Utilisation de la version en cache.",
+        (RangeCacheFailed, En) => "Could not cache ranges in {}: {}",
+        (RangeCacheFailed, Fr) => "Impossible de mettre en cache les plages dans {} : {}",
+        (UsingEmbeddedSnapshot, En) => "Using the embedded GeoIP snapshot of {} ({})",
+        (UsingEmbeddedSnapshot, Fr) => "Utilisation de l'instantané GeoIP embarqué du {} ({})",
+        (SnapshotNotEmbedded, En) => "No GeoIP snapshot of {} is embedded in this build (available: {})",
+        (SnapshotNotEmbedded, Fr) => "Aucun instantané GeoIP du {} n'est embarqué dans cette version (disponibles : {})",
+        (SnapshotPinMismatch, En) => "The embedded GeoIP snapshot of {} does not match its pinned SHA-256",
+        (SnapshotPinMismatch, Fr) => "L'instantané GeoIP embarqué du {} ne correspond pas à son SHA-256 épinglé",
+        (SnapshotsNotBuilt, En) => "--snapshot needs a build with the embedded-data feature",
+        (SnapshotsNotBuilt, Fr) => "--snapshot nécessite une compilation avec la fonctionnalité embedded-data",
+        (ReasonRefreshRequested, En) => "refresh requested",
+        (ReasonRefreshRequested, Fr) => "rafraîchissement demandé",
+        (ReasonCacheNotFound, En) => "cache not found",
+        (ReasonCacheNotFound, Fr) => "cache introuvable",
+        (ReasonCacheStale, En) => "cache older than 30 days",
+        (ReasonCacheStale, Fr) => "cache de plus de 30 jours",
+
+        (Provider, En) => "Provider: {}",
+        (Provider, Fr) => "Fournisseur : {}",
+        (Source, En) => "Source: {}",
+        (Source, Fr) => "Source : {}",
+        (Location, En) => "Location: {}",
+        (Location, Fr) => "Emplacement : {}",
+        (Size, En) => "Size: {} bytes ({} MiB)",
+        (Size, Fr) => "Taille : {} octets ({} Mio)",
+        (Age, En) => "Age: {}d {}h",
+        (Age, Fr) => "Âge : {} j {} h",
+        (AgeUnknown, En) => "Age: unknown",
+        (AgeUnknown, Fr) => "Âge : inconnu",
+        (Sha256, En) => "SHA-256: {}",
+        (Sha256, Fr) => "SHA-256 : {}",
+        (Rows, En) => "Rows: {}",
+        (Rows, Fr) => "Lignes : {}",
+        (NotDownloaded, En) => "Status: not downloaded",
+        (NotDownloaded, Fr) => "État : non téléchargée",
+        (NextRunRefresh, En) => "Next run: will refresh ({})",
+        (NextRunRefresh, Fr) => "Prochaine exécution : rafraîchissement ({})",
+        (NextRunCache, En) => "Next run: will use cache",
+        (NextRunCache, Fr) => "Prochaine exécution : utilisation du cache",
+        (CountryRoot, En) => "{} ({}): {} ranges, root {}; {} IPv6 ranges, root {}",
+        (CountryRoot, Fr) => "{} ({}) : {} plages, racine {} ; {} plages IPv6, racine {}",
+        (SetRoot, En) => "Country set {}: {} IPv4 and {} IPv6 ranges, root {}",
+        (SetRoot, Fr) => "Ensemble de pays {} : {} plages IPv4 et {} IPv6, racine {}",
+        (RootManifestWritten, En) => "Wrote root manifest to {}",
+        (RootManifestWritten, Fr) => "Manifeste de racine écrit dans {}",
+        (DbNotFound, En) => "GeoIP database not found at {}",
+        (DbNotFound, Fr) => "Base GeoIP introuvable dans {}",
+        (Verifying, En) => "Verifying GeoIP database at {}",
+        (Verifying, Fr) => "Vérification de la base GeoIP dans {}",
+        (LocalSha256, En) => "Local SHA-256: {}",
+        (LocalSha256, Fr) => "SHA-256 locale : {}",
+        (UpstreamSha256, En) => "Upstream SHA-256 ({}): {}",
+        (UpstreamSha256, Fr) => "SHA-256 en amont ({}) : {}",
+        (ChecksumOk, En) => "Checksum: OK",
+        (ChecksumOk, Fr) => "Somme de contrôle : OK",
+        (ChecksumMismatch, En) => "Checksum: MISMATCH (cache is corrupted or outdated, run with --refresh)",
+        (ChecksumMismatch, Fr) => "Somme de contrôle : DIFFÉRENTE (cache corrompu ou obsolète, relancez avec --refresh)",
+        (StructureEmpty, En) => "Structure: EMPTY",
+        (StructureEmpty, Fr) => "Structure : VIDE",
+        (StructureOk, En) => "Structure: OK",
+        (StructureOk, Fr) => "Structure : OK",
+        (StructureIssues, En) => "Structure: {} issue(s)",
+        (StructureIssues, Fr) => "Structure : {} problème(s)",
+        (MoreIssues, En) => "  ... {} more",
+        (MoreIssues, Fr) => "  ... et {} de plus",
+        (DbVerified, En) => "GeoIP database verified.",
+        (DbVerified, Fr) => "Base GeoIP vérifiée.",
+        (DbVerifyFailed, En) => "GeoIP database verification failed",
+        (DbVerifyFailed, Fr) => "Échec de la vérification de la base GeoIP",
+
+        (SelftestVariant, En) => "Variant {}:",
+        (SelftestVariant, Fr) => "Variante {} :",
+        (SelftestPass, En) => "PASS {} excluding {}: is_excluded = {} (expected {})",
+        (SelftestPass, Fr) => "OK {} en excluant {} : is_excluded = {} (attendu {})",
+        (SelftestFail, En) => "FAIL {} excluding {}: is_excluded = {} (expected {})",
+        (SelftestFail, Fr) => "ÉCHEC {} en excluant {} : is_excluded = {} (attendu {})",
+        (MockProofOk, En) => "PASS mock proof generated and verified",
+        (MockProofOk, Fr) => "OK preuve simulée générée et vérifiée",
+        (SelftestPassed, En) => "Selftest passed.",
+        (SelftestPassed, Fr) => "Autotest réussi.",
+        (SelftestFailedCount, En) => "{} of {} selftest vectors failed",
+        (SelftestFailedCount, Fr) => "{} vecteurs d'autotest sur {} en échec",
+
+        (PolicyNoSets, En) => "policy defines no country sets",
+        (PolicyNoSets, Fr) => "la politique ne définit aucun ensemble de pays",
+        (PolicyEmptySet, En) => "set '{}' is empty",
+        (PolicyEmptySet, Fr) => "l'ensemble '{}' est vide",
+        (PolicyUnknownCode, En) => "set '{}': unknown country code '{}'",
+        (PolicyUnknownCode, Fr) => "ensemble '{}' : code pays inconnu '{}'",
+        (PolicyDuplicate, En) => "set '{}': '{}' listed more than once",
+        (PolicyDuplicate, Fr) => "ensemble '{}' : '{}' listé plusieurs fois",
+        (PolicyOverlap, En) => "'{}' appears in overlapping sets: {}",
+        (PolicyOverlap, Fr) => "'{}' apparaît dans plusieurs ensembles : {}",
+        (PolicyLargeCountry, En) => "'{}' has {} IP ranges; expect a high cycle count",
+        (PolicyLargeCountry, Fr) => "'{}' compte {} plages IP ; nombre de cycles élevé attendu",
+        (PolicyRangeCountsSkipped, En) => "GeoIP database not cached; skipping range-count checks",
+        (PolicyRangeCountsSkipped, Fr) => "base GeoIP absente du cache ; vérification du nombre de plages ignorée",
+        (PolicyCountries, En) => "Countries ({}): {}",
+        (PolicyCountries, Fr) => "Pays ({}) : {}",
+        (PolicyHash, En) => "Policy hash: 0x{}",
+        (PolicyHash, Fr) => "Empreinte de la politique : 0x{}",
+        (PolicyRuleHash, En) => "Policy rule hash: 0x{}",
+        (PolicyRuleHash, Fr) => "Empreinte de la règle de politique : 0x{}",
+        (PolicyNoRule, En) => "policy defines no rule to prove",
+        (PolicyNoRule, Fr) => "la politique ne définit aucune règle à prouver",
+        (PolicyRuleNode, En) => "rule node must have exactly one of and, or, not, country, set, cidr, asn",
+        (PolicyRuleNode, Fr) => "un nœud de règle doit avoir exactement une clé parmi and, or, not, country, set, cidr, asn",
+        (PolicyUnknownSet, En) => "rule: unknown set '{}'",
+        (PolicyUnknownSet, Fr) => "règle : ensemble inconnu '{}'",
+        (PolicyRuleUnknownCode, En) => "rule: unknown country code '{}'",
+        (PolicyRuleUnknownCode, Fr) => "règle : code pays inconnu '{}'",
+        (PolicyInvalidCidr, En) => "rule: invalid CIDR block '{}'",
+        (PolicyInvalidCidr, Fr) => "règle : bloc CIDR invalide '{}'",
+        (PolicyOk, En) => "Policy is valid.",
+        (PolicyOk, Fr) => "La politique est valide.",
+        (PolicyInvalid, En) => "policy has {} error(s)",
+        (PolicyInvalid, Fr) => "la politique contient {} erreur(s)",
+        (LockCreated, En) => "Wrote {}",
+        (LockCreated, Fr) => "{} écrit",
+        (LockMatched, En) => "Inputs match {}",
+        (LockMatched, Fr) => "Les entrées correspondent à {}",
+        (LockUpdated, En) => "Updated {}",
+        (LockUpdated, Fr) => "{} mis à jour",
+        (LockMismatch, En) => "{} does not match the current inputs:\n  {}",
+        (LockMismatch, Fr) => "{} ne correspond pas aux entrées actuelles :\n  {}",
+        (VkeyEmbedded, En) => "Embedded vkey: {}",
+        (VkeyEmbedded, Fr) => "vkey embarquée : {}",
+        (VkeyRebuilding, En) => "Rebuilding guest in SP1 docker image {}...",
+        (VkeyRebuilding, Fr) => "Recompilation du programme invité dans l'image docker SP1 {}...",
+        (VkeyRebuilt, En) => "Rebuilt vkey:  {}",
+        (VkeyRebuilt, Fr) => "vkey recompilée : {}",
+        (VkeyExpected, En) => "Expected vkey: {}",
+        (VkeyExpected, Fr) => "vkey attendue : {}",
+        (VkeyReproduced, En) => "vkey reproduced from source.",
+        (VkeyReproduced, Fr) => "vkey reproduite depuis les sources.",
+        (VkeyMismatch, En) => "rebuilt vkey {} does not match expected {}",
+        (VkeyMismatch, Fr) => "la vkey recompilée {} ne correspond pas à la vkey attendue {}",
+        (RegistryAdded, En) => "Registered version {} {} ({})",
+        (RegistryAdded, Fr) => "Version {} {} enregistrée ({})",
+        (RegistryUnchanged, En) => "Version {} {} is already registered",
+        (RegistryUnchanged, Fr) => "La version {} {} est déjà enregistrée",
+        (RegistryEmpty, En) => "No program versions registered in {}",
+        (RegistryEmpty, Fr) => "Aucune version du programme enregistrée dans {}",
+        (RegistryEntry, En) => "{}  {}  {}",
+        (RegistryEntry, Fr) => "{}  {}  {}",
+        (RegistryEntryRevoked, En) => "{}  {}  {}  (revoked)",
+        (RegistryEntryRevoked, Fr) => "{}  {}  {}  (révoquée)",
+        (RegistryRevoked, En) => "Revoked version {}",
+        (RegistryRevoked, Fr) => "Version {} révoquée",
+        (RegistryAlreadyRevoked, En) => "Version {} was already revoked",
+        (RegistryAlreadyRevoked, Fr) => "La version {} était déjà révoquée",
+        (ProofSaved, En) => "Proof written to {}",
+        (ProofSaved, Fr) => "Preuve écrite dans {}",
+        (ArtifactsWritten, En) => "Proof, public values, vkey and metadata written to {}",
+        (ArtifactsWritten, Fr) => "Preuve, valeurs publiques, vkey et métadonnées écrites dans {}",
+        (BundleWritten, En) => "Bundle for program version {} {} written to {}",
+        (BundleWritten, Fr) => "Paquet pour la version du programme {} {} écrit dans {}",
+        (BundleAccepted, En) => "Bundle {} accepted: program version {} {} ({})",
+        (BundleAccepted, Fr) => "Paquet {} accepté : version du programme {} {} ({})",
+        (BundleRejected, En) => "Bundle {} rejected: {}",
+        (BundleRejected, Fr) => "Paquet {} rejeté : {}",
+        (BundleSignedBy, En) => "Bundle signed by operator {}",
+        (BundleSignedBy, Fr) => "Paquet signé par l'opérateur {}",
+        (OperatorUnpinned, En) => "No --operator given; accepting the bundle's own signer {}",
+        (OperatorUnpinned, Fr) => "Aucun --operator fourni ; le signataire du paquet {} est accepté",
+        (OperatorNeedsBundle, En) => "--operator needs a signed .zkip bundle, but {} is a plain proof",
+        (OperatorNeedsBundle, Fr) => "--operator demande un paquet .zkip signé, mais {} est une preuve simple",
+        (VerifyOk, En) => "Proof verified: program version {} {} ({})",
+        (VerifyOk, Fr) => "Preuve vérifiée : version du programme {} {} ({})",
+        (VerifyRevoked, En) => "proof was produced by revoked program version {} {}",
+        (VerifyRevoked, Fr) => "la preuve a été produite par la version révoquée {} {} du programme",
+        (VerifyNoMatch, En) => "proof does not match any registered program version",
+        (VerifyNoMatch, Fr) => "la preuve ne correspond à aucune version enregistrée du programme",
+        (RenewNeedsCompressed, En) => "{} is not a compressed proof; prove it again with --prove --compressed",
+        (RenewNeedsCompressed, Fr) => "{} n'est pas une preuve compressée ; prouvez-la à nouveau avec --prove --compressed",
+        (WrapNeedsCompressed, En) => "{} is not a compressed proof; prove it with --prove --compressed to wrap it later",
+        (WrapNeedsCompressed, Fr) => "{} n'est pas une preuve compressée ; prouvez-la avec --prove --compressed pour l'envelopper plus tard",
+        (Wrapping, En) => "Wrapping proof from program {} in {}",
+        (Wrapping, Fr) => "Enveloppement de la preuve du programme {} en {}",
+        (RenewOfRenewal, En) => "{} is already a renewal; renew the original proof instead",
+        (RenewOfRenewal, Fr) => "{} est déjà un renouvellement ; renouvelez plutôt la preuve d'origine",
+        (Renewing, En) => "Renewing proof from program version {} {}: timestamp {} -> {}",
+        (Renewing, Fr) => "Renouvellement de la preuve de la version {} {} du programme : horodatage {} -> {}",
+        (RenewalOf, En) => "Renewal of a proof from program {} made at {}; its IP was not checked again",
+        (RenewalOf, Fr) => "Renouvellement d'une preuve du programme {} faite à {} ; son IP n'a pas été revérifiée",
+        (ProvingChunk, En) => "Proving chunk {} of {}",
+        (ProvingChunk, Fr) => "Preuve du morceau {} sur {}",
+        (VerifyAggregateOk, En) => "Proof verified: aggregation of chunk proofs ({})",
+        (VerifyAggregateOk, Fr) => "Preuve vérifiée : agrégation de preuves par morceaux ({})",
+        (ChunkProgramUnknown, En) => "proof aggregates chunks of program {}, not this release's chunk program",
+        (ChunkProgramUnknown, Fr) => "la preuve agrège des morceaux du programme {}, et non du programme de morceaux de cette version",
+        (AggregatedFrom, En) => "Aggregated from {} chunk proofs of program {}",
+        (AggregatedFrom, Fr) => "Agrégée à partir de {} preuves par morceaux du programme {}",
+        (CompanionNeedsCompressed, En) => "{} is not a compressed proof; prove the companion again in compressed mode",
+        (CompanionNeedsCompressed, Fr) => "{} n'est pas une preuve compressée ; prouvez à nouveau le compagnon en mode compressé",
+        (CompanionLoaded, En) => "Companion proof of program {} verified; it will be checked in the guest",
+        (CompanionLoaded, Fr) => "Preuve compagnon du programme {} vérifiée ; elle sera revérifiée dans le guest",
+        (CompanionOf, En) => "Companion: proof of program {} with public values digest {}",
+        (CompanionOf, Fr) => "Compagnon : preuve du programme {} avec l'empreinte de valeurs publiques {}",
+        (NotComposedProof, En) => "--companion-elf requires a proof joined to a companion proof, but this proof has none",
+        (NotComposedProof, Fr) => "--companion-elf exige une preuve jointe à une preuve compagnon, mais cette preuve n'en a pas",
+        (CompanionMismatch, En) => "proof is joined to a companion of program {}, expected {}",
+        (CompanionMismatch, Fr) => "la preuve est jointe à un compagnon du programme {}, attendu {}",
+        (Challenge, En) => "Challenge: {}",
+        (Challenge, Fr) => "Défi : {}",
+        (NotChallengedProof, En) => "--challenge requires a proof bound to a challenge, but this proof has none",
+        (NotChallengedProof, Fr) => "--challenge exige une preuve liée à un défi, mais cette preuve n'en a pas",
+        (ChallengeMismatch, En) => "proof is bound to challenge {}, expected {}",
+        (ChallengeMismatch, Fr) => "la preuve est liée au défi {}, attendu {}",
+        (Validity, En) => "Valid from {} to {}",
+        (Validity, Fr) => "Valide du {} au {}",
+        (ProofNotYetValid, En) => "proof is not valid before {}",
+        (ProofNotYetValid, Fr) => "la preuve n'est pas valide avant le {}",
+        (ProofExpired, En) => "proof expired at {}",
+        (ProofExpired, Fr) => "la preuve a expiré le {}",
+        (RenewOfWindowed, En) => "{} has a validity window and expires; prove again instead of renewing it",
+        (RenewOfWindowed, Fr) => "{} a une fenêtre de validité et expire ; prouvez à nouveau au lieu de la renouveler",
+        (PublicValuesVersion, En) => "Public values version: {}",
+        (PublicValuesVersion, Fr) => "Version des valeurs publiques : {}",
+        (PublicValuesVersionUnsupported, En) => "proof commits public values version {}, but this build only knows version {}",
+        (PublicValuesVersionUnsupported, Fr) => "la preuve engage la version {} des valeurs publiques, mais cette version de zkip ne connaît que la version {}",
+        (Snapshot, En) => "Snapshot: source {}, published {}, hash {}",
+        (Snapshot, Fr) => "Instantané : source {}, publié le {}, empreinte {}",
+        (SnapshotHash, En) => "Snapshot hash: {}",
+        (SnapshotHash, Fr) => "Empreinte d'instantané : {}",
+        (SnapshotHashMismatch, En) => "proof commits snapshot hash {}, expected one of {}",
+        (SnapshotHashMismatch, Fr) => "la preuve engage l'empreinte d'instantané {}, attendue parmi {}",
+        (SnapshotHashUnpinned, En) => "--snapshot-hash needs --ranges-digest, --dataset-root or --root-manifest: the hash alone proves nothing",
+        (SnapshotHashUnpinned, Fr) => "--snapshot-hash exige --ranges-digest, --dataset-root ou --root-manifest : l'empreinte seule ne prouve rien",
+        (SnapshotMismatch, En) => "execution commits snapshot {} published {}, expected {} published {}",
+        (SnapshotMismatch, Fr) => "l'exécution engage l'instantané {} publié le {}, attendu {} publié le {}",
+        (PolicyId, En) => "Policy ID: {}",
+        (PolicyId, Fr) => "Identifiant de politique : {}",
+        (PolicyIdMismatch, En) => "execution commits policy ID {}, expected {}",
+        (PolicyIdMismatch, Fr) => "l'exécution engage l'identifiant de politique {}, attendu {}",
+        (AppContext, En) => "App context: {}",
+        (AppContext, Fr) => "Contexte d'application : {}",
+        (AppContextMismatch, En) => "proof commits app context {}, expected {}",
+        (AppContextMismatch, Fr) => "la preuve engage le contexte d'application {}, attendu {}",
+        (NetworkPrefix, En) => "Network prefix commitment: {} (scope {})",
+        (NetworkPrefix, Fr) => "Engagement de préfixe réseau : {} (portée {})",
+        (PrefixMismatch, En) => "execution commits network prefix commitment {}, expected {}",
+        (PrefixMismatch, Fr) => "l'exécution engage le préfixe réseau {}, attendu {}",
+        (BlockPrefix, En) => "Block: private IPv{} /{}, commitment {} (scope {})",
+        (BlockPrefix, Fr) => "Bloc : IPv{} /{} privé, engagement {} (portée {})",
+        (ManifestVerified, En) => "Verified signed manifest for {} (version {})",
+        (ManifestVerified, Fr) => "Manifeste signé vérifié pour {} (version {})",
+        (UnsignedAllowed, En) => "Continuing with unverified artifact (--allow-unsigned): {}",
+        (UnsignedAllowed, Fr) => "Poursuite avec un artefact non vérifié (--allow-unsigned) : {}",
+        (ManifestWritten, En) => "Wrote {} (public key {})",
+        (ManifestWritten, Fr) => "{} écrit (clé publique {})",
+        (AuditRecorded, En) => "Audit log entry {} appended to {}",
+        (AuditRecorded, Fr) => "Entrée {} ajoutée au journal d'audit {}",
+        (AuditIntact, En) => "Audit log intact: {} entries, head {}",
+        (AuditIntact, Fr) => "Journal d'audit intact : {} entrées, tête {}",
+        (RevocationAdded, En) => "Revoked {} in {} (signer {})",
+        (RevocationAdded, Fr) => "{} révoquée dans {} (signataire {})",
+        (RevocationUnchanged, En) => "{} is already revoked",
+        (RevocationUnchanged, Fr) => "{} est déjà révoquée",
+        (RevocationUnpinned, En) => "No trusted revocation key given; accepting the list's own signer {}",
+        (RevocationUnpinned, Fr) => "Aucune clé de révocation de confiance ; signataire de la liste accepté : {}",
+        (ProofRevoked, En) => "proof {} was revoked at {}{}",
+        (ProofRevoked, Fr) => "la preuve {} a été révoquée à {}{}",
+        (ProofReplayed, En) => "proof with replay key {} was already accepted; replay window ends at {}",
+        (ProofReplayed, Fr) => "la preuve de clé de rejeu {} a déjà été acceptée ; fenêtre de rejeu jusqu'à {}",
+        (NtpServerFailed, En) => "NTP server {} did not answer: {}",
+        (NtpServerFailed, Fr) => "Le serveur NTP {} n'a pas répondu : {}",
+        (ClockChecked, En) => "Local clock checked against NTP: offset {}s ({} servers)",
+        (ClockChecked, Fr) => "Horloge locale vérifiée par NTP : décalage {} s ({} serveurs)",
+        (RoughtimeChecked, En) => "Timestamp {} signed by Roughtime server {} (±{}s)",
+        (RoughtimeChecked, Fr) => "Horodatage {} signé par le serveur Roughtime {} (±{} s)",
+        (TimedBy, En) => "Timestamp signed by Roughtime key {} (±{}s)",
+        (TimedBy, Fr) => "Horodatage signé par la clé Roughtime {} (±{} s)",
+        (NotTimedProof, En) => "--time-server requires a timed proof, but this proof commits no time server",
+        (NotTimedProof, Fr) => "--time-server exige une preuve horodatée, mais cette preuve n'engage aucun serveur de temps",
+        (TimeServerMismatch, En) => "Proof's timestamp is signed by {}, expected {}",
+        (TimeServerMismatch, Fr) => "L'horodatage de la preuve est signé par {}, attendu {}",
+        (AnchorFetched, En) => "Anchoring proof to block {} ({})",
+        (AnchorFetched, Fr) => "Preuve ancrée au bloc {} ({})",
+        (AnchorBlock, En) => "Anchor block: {} ({})",
+        (AnchorBlock, Fr) => "Bloc d'ancrage : {} ({})",
+        (NotAnchoredProof, En) => "--anchor-rpc-url requires an anchored proof, but this proof commits no block",
+        (NotAnchoredProof, Fr) => "--anchor-rpc-url exige une preuve ancrée, mais cette preuve n'engage aucun bloc",
+        (AnchorNotOnChain, En) => "Anchor block {} is not on the RPC endpoint's chain (reorged, or another chain)",
+        (AnchorNotOnChain, Fr) => "Le bloc d'ancrage {} n'est pas sur la chaîne du point RPC (réorganisé, ou autre chaîne)",
+        (AnchorTooOld, En) => "Anchor block {} is {} blocks old, more than --max-anchor-age {}",
+        (AnchorTooOld, Fr) => "Le bloc d'ancrage {} a {} blocs, plus que --max-anchor-age {}",
+        (AnchorRecent, En) => "Anchor block {} is on chain, {} blocks old",
+        (AnchorRecent, Fr) => "Le bloc d'ancrage {} est sur la chaîne, vieux de {} blocs",
+        (DatasetRoot, En) => "Dataset root: {}",
+        (DatasetRoot, Fr) => "Racine du jeu de données : {}",
+        (DatasetRootMissing, En) => "Legacy public values: no dataset root, the ranges checked are not pinned",
+        (DatasetRootMissing, Fr) => "Valeurs publiques anciennes : pas de racine de jeu de données, les plages vérifiées ne sont pas épinglées",
+        (DatasetRootMismatch, En) => "Proof commits dataset root {}, expected {}",
+        (DatasetRootMismatch, Fr) => "La preuve engage la racine {}, attendue {}",
+        (RangeCoverage, En) => "Ranges checked: {} (covering {} IPv4 addresses)",
+        (RangeCoverage, Fr) => "Plages vérifiées : {} (couvrant {} adresses IPv4)",
+        (RangesDigest, En) => "Ranges digest: {}",
+        (RangesDigest, Fr) => "Empreinte des plages : {}",
+        (Nullifier, En) => "Nullifier: {} (epoch {}, scope {})",
+        (Nullifier, Fr) => "Nullificateur : {} (époque {}, portée {})",
+        (NullifierMismatch, En) => "execution commits nullifier {}, expected {}",
+        (NullifierMismatch, Fr) => "l'exécution engage le nullificateur {}, attendu {}",
+        (RangesDigestMismatch, En) => "Proof commits ranges digest {}, expected {}",
+        (RangesDigestMismatch, Fr) => "La preuve engage l'empreinte de plages {}, attendue {}",
+        (RangesDigestMissing, En) => "--ranges-digest requires a plain exclusion proof, but this layout commits no ranges digest",
+        (RangesDigestMissing, Fr) => "--ranges-digest exige une preuve d'exclusion simple, mais cette disposition n'engage aucune empreinte de plages",
+        (GapWitnessProof, En) => "Proven from gap witnesses: only the two ranges around the IP in each country were checked, so no range count or digest is committed",
+        (GapWitnessProof, Fr) => "Prouvé par témoins d'écart : seules les deux plages encadrant l'IP dans chaque pays ont été vérifiées, aucun nombre de plages ni empreinte n'est donc engagé",
+        (GapWitnessCovered, En) => "{} is in one of {}, so it has no gap witness; drop --gap-witness to prove the result",
+        (GapWitnessCovered, Fr) => "{} est dans l'un des pays {}, il n'a donc pas de témoin d'écart ; retirez --gap-witness pour prouver le résultat",
+        (GapWitnessConstantWork, En) => "--gap-witness does not work with --variant constant-work: witness path lengths depend on where the IP sits",
+        (GapWitnessConstantWork, Fr) => "--gap-witness ne fonctionne pas avec --variant constant-work : la longueur des chemins des témoins dépend de la position de l'IP",
+        (BlockConstantWork, En) => "--block does not work with --variant constant-work: the scan stops at the first range that meets the prefix",
+        (BlockConstantWork, Fr) => "--block ne fonctionne pas avec --variant constant-work : le parcours s'arrête à la première plage qui touche le préfixe",
+        (BlockTooLong, En) => "--block {} is longer than the address {}",
+        (BlockTooLong, Fr) => "--block {} est plus long que l'adresse {}",
+        (AllowOnly, En) => "Allowing only {}: excluding the {} other countries of the GeoIP databases",
+        (AllowOnly, Fr) => "Seuls {} sont autorisés : exclusion des {} autres pays des bases GeoIP",
+        (AllowOnlyUnlisted, En) => "{} IPv4 addresses are in no country of the GeoIP database and would still prove clear",
+        (AllowOnlyUnlisted, Fr) => "{} adresses IPv4 ne sont dans aucun pays de la base GeoIP et seraient quand même prouvées libres",
+        (BlockReserved, En) => "the /{} holding {} meets reserved address space, so a block proof would say nothing about it",
+        (BlockReserved, Fr) => "le /{} contenant {} touche un espace d'adresses réservé, une preuve de bloc n'en dirait donc rien",
+        (AttestationInvalid, En) => "--attestation is not a valid signature over {}",
+        (AttestationInvalid, Fr) => "--attestation n'est pas une signature valide de {}",
+        (AttestedBy, En) => "Attested by {} at {} (nonce {})",
+        (AttestedBy, Fr) => "Attesté par {} à {} (nonce {})",
+        (NotAttestedProof, En) => "--attester requires an attested proof, but this proof commits no attester",
+        (NotAttestedProof, Fr) => "--attester exige une preuve attestée, mais cette preuve n'engage aucun attesteur",
+        (AttesterMismatch, En) => "Proof is attested by {}, expected {}",
+        (AttesterMismatch, Fr) => "La preuve est attestée par {}, attendu {}",
+        (WebProofInvalid, En) => "--web-proof is not a notary's signature over a response naming {}",
+        (WebProofInvalid, Fr) => "--web-proof n'est pas une signature de notaire sur une réponse désignant {}",
+        (NotWebProof, En) => "Proof is not a web proof of {}'s response",
+        (NotWebProof, Fr) => "La preuve n'est pas une preuve web de la réponse de {}",
+        (SnapshotSigned, En) => "Signed snapshot root {} with publisher key {}",
+        (SnapshotSigned, Fr) => "Racine d'instantané {} signée avec la clé d'éditeur {}",
+        (SnapshotStale, En) => "The local GeoIP ranges differ from the snapshot signed in {}; refresh the database or use the publisher's snapshot",
+        (SnapshotStale, Fr) => "Les plages GeoIP locales diffèrent de l'instantané signé dans {} ; rafraîchissez la base ou utilisez l'instantané de l'éditeur",
+        (SignedSnapshotBy, En) => "Ranges from snapshot {} signed by {} at {}",
+        (SignedSnapshotBy, Fr) => "Plages de l'instantané {} signé par {} à {}",
+        (NotSignedSnapshotProof, En) => "--publisher requires a signed-snapshot proof, but this proof commits no publisher",
+        (NotSignedSnapshotProof, Fr) => "--publisher exige une preuve sur instantané signé, mais cette preuve n'engage aucun éditeur",
+        (PublisherMismatch, En) => "Proof's snapshot is signed by {}, expected {}",
+        (PublisherMismatch, Fr) => "L'instantané de la preuve est signé par {}, attendu {}",
+        (DbTooOld, En) => "The snapshot in {} was signed {} days before the proof time, more than --max-db-age {}",
+        (DbTooOld, Fr) => "L'instantané de {} a été signé {} jours avant l'heure de la preuve, plus que --max-db-age {}",
+        (DbMaxAge, En) => "Snapshot signed at most {} days before the proof time",
+        (DbMaxAge, Fr) => "Instantané signé au plus {} jours avant l'heure de la preuve",
+        (DbAgeUnbounded, En) => "--max-db-age requires a signed-snapshot proof, but this proof does not bound the database's age",
+        (DbAgeUnbounded, Fr) => "--max-db-age exige une preuve sur instantané signé, mais cette preuve ne borne pas l'âge de la base",
+        (DbAgeTooLoose, En) => "Proof accepts a database up to {} days old, expected at most {}",
+        (DbAgeTooLoose, Fr) => "La preuve accepte une base vieille de {} jours au plus, attendu au plus {}",
+        (ResultIsPublicIp, En) => "Public IP: is_public_ip = {}",
+        (ResultIsPublicIp, Fr) => "IP publique : is_public_ip = {}",
+        (ReservedIpProof, En) => "Proof is for a reserved address (private, loopback, link-local, CGNAT or similar), so it says nothing about location",
+        (ReservedIpProof, Fr) => "La preuve porte sur une adresse réservée (privée, de bouclage, lien-local, CGNAT ou similaire) et ne dit donc rien de la localisation",
+        (InclusionSetInvalid, En) => "--include {} must list distinct countries that each have GeoIP ranges",
+        (InclusionSetInvalid, Fr) => "--include {} doit lister des pays distincts ayant chacun des plages GeoIP",
+        (NoRangesToCheck, En) => "No GeoIP ranges found for {}, so a proof would check nothing",
+        (NoRangesToCheck, Fr) => "Aucune plage GeoIP trouvée pour {}, une preuve ne vérifierait donc rien",
+        (NotInclusionProof, En) => "--min-anonymity requires an inclusion proof, but this is an exclusion or policy proof",
+        (NotInclusionProof, Fr) => "--min-anonymity exige une preuve d'inclusion, mais il s'agit d'une preuve d'exclusion ou de politique",
+        (AnonymitySetTooSmall, En) => "Inclusion proof hides the country among {} countries, fewer than {}",
+        (AnonymitySetTooSmall, Fr) => "La preuve d'inclusion cache le pays parmi {} pays, moins que {}",
+        (DeniedCountries, En) => "Denylist countries: {}",
+        (DeniedCountries, Fr) => "Pays de la liste d'exclusion : {}",
+        (DenylistRoot, En) => "Denylist dataset root: {}",
+        (DenylistRoot, Fr) => "Racine du jeu de données de la liste d'exclusion : {}",
+        (DenylistRootMismatch, En) => "Proof commits denylist root {}, expected {}",
+        (DenylistRootMismatch, Fr) => "La preuve engage la racine de liste d'exclusion {}, attendue {}",
+        (AsnRoot, En) => "ASN dataset root: {}",
+        (AsnRoot, Fr) => "Racine du jeu de données ASN : {}",
+        (NotPolicyProof, En) => "--policy requires a policy proof, but this proof commits no policy",
+        (NotPolicyProof, Fr) => "--policy exige une preuve de politique, mais cette preuve n'engage aucune politique",
+        (PolicyMismatch, En) => "Proof commits policy {}, expected {}",
+        (PolicyMismatch, Fr) => "La preuve engage la politique {}, attendue {}",
+        (RootUnchanged, En) => "Registry already holds root {} for policy {}",
+        (RootUnchanged, Fr) => "Le registre contient déjà la racine {} pour la politique {}",
+        (RootDryRun, En) => "Dry run: {} may call setRoot; calldata {}",
+        (RootDryRun, Fr) => "Simulation : {} peut appeler setRoot ; calldata {}",
+        (RootPushed, En) => "Root {} for policy {} pushed by {} in tx {}",
+        (RootPushed, Fr) => "Racine {} pour la politique {} publiée par {} dans la tx {}",
+        (StatsEmpty, En) => "No runs recorded in {} yet",
+        (StatsEmpty, Fr) => "Aucune exécution enregistrée dans {} pour l'instant",
+        (StatsGroup, En) => "{} / {} / countries {}: {} runs",
+        (StatsGroup, Fr) => "{} / {} / pays {} : {} exécutions",
+        (StatsCost, En) => "  median cycles {}, median wall {} ms, peak RSS {}, median proof {}, trend {}",
+        (StatsCost, Fr) => "  cycles médians {}, durée médiane {} ms, RSS max {}, preuve médiane {}, tendance {}",
+        (DomainResolved, En) => "Resolved {} to {} addresses: {}",
+        (DomainResolved, Fr) => "{} résolu en {} adresses : {}",
+        (DomainIpv6Skipped, En) => "Skipping {} IPv6 addresses of {}; they are not covered by the proof",
+        (DomainIpv6Skipped, Fr) => "{} adresses IPv6 de {} ignorées ; la preuve ne les couvre pas",
+        (AddressCount, En) => "Addresses checked: {}",
+        (AddressCount, Fr) => "Adresses vérifiées : {}",
+        (ClearCount, En) => "Addresses clear: {} of {}",
+        (ClearCount, Fr) => "Adresses hors des pays exclus : {} sur {}",
+        (IpListLoaded, En) => "Read {} addresses from {}",
+        (IpListLoaded, Fr) => "{} adresses lues dans {}",
+        (InventoryRoot, En) => "Inventory root: {}",
+        (InventoryRoot, Fr) => "Racine de l'inventaire : {}",
+        (Threshold, En) => "Threshold: at least {} of {} addresses clear",
+        (Threshold, Fr) => "Seuil : au moins {} adresses sur {} hors des pays exclus",
+        (NotInventoryProof, En) => "--inventory-root requires an inventory proof, but this proof commits no inventory root",
+        (NotInventoryProof, Fr) => "--inventory-root exige une preuve d'inventaire, mais cette preuve n'engage aucune racine d'inventaire",
+        (InventoryMismatch, En) => "proof covers inventory {}, expected {}",
+        (InventoryMismatch, Fr) => "la preuve couvre l'inventaire {}, attendu {}",
+        (CosignAdded, En) => "Signed proof {} in {} as {}",
+        (CosignAdded, Fr) => "Preuve {} signée dans {} par {}",
+        (CosignUnchanged, En) => "{} has already signed proof {}",
+        (CosignUnchanged, Fr) => "{} a déjà signé la preuve {}",
+        (CosignChecked, En) => "Co-signed by {} of {} required operators: {}",
+        (CosignChecked, Fr) => "Cosignée par {} des {} opérateurs requis : {}",
+        (ExplainLayout, En) => "Layout: {} ({} bytes)",
+        (ExplainLayout, Fr) => "Format : {} ({} octets)",
+        (ExplainTimestamp, En) => "Timestamp: {} ({})",
+        (ExplainTimestamp, Fr) => "Horodatage : {} ({})",
+        (ExplainCountry, En) => "  {} {} ({})",
+        (ExplainCountry, Fr) => "  {} {} ({})",
+        (ExplainUnknownCountry, En) => "  {} (unknown)",
+        (ExplainUnknownCountry, Fr) => "  {} (inconnu)",
+        (ExplainWarning, En) => "Warning: {}",
+        (ExplainWarning, Fr) => "Attention : {}",
+        (E2eAnvil, En) => "anvil listening on {}",
+        (E2eAnvil, Fr) => "anvil à l'écoute sur {}",
+        (E2eDeployed, En) => "Deployed verifier {} and Zkip {}",
+        (E2eDeployed, Fr) => "Vérificateur {} et Zkip {} déployés",
+        (E2ePassed, En) => "On-chain result matches the proof: is_excluded = {}, timestamp {}, countries {}",
+        (E2ePassed, Fr) => "Le résultat on-chain correspond à la preuve : is_excluded = {}, horodatage {}, pays {}",
+        (ArtifactStored, En) => "Stored {} at {}",
+        (ArtifactStored, Fr) => "{} stocké dans {}",
+        (ReadFailed, En) => "Failed to read {}",
+        (ReadFailed, Fr) => "Impossible de lire {}",
+        (WriteFailed, En) => "Failed to write {}",
+        (WriteFailed, Fr) => "Impossible d'écrire {}",
+        (ParseFailed, En) => "Failed to parse {}",
+        (ParseFailed, Fr) => "Impossible d'analyser {}",
+        (CreateFailed, En) => "Failed to create {}",
+        (CreateFailed, Fr) => "Impossible de créer {}",
+        (OpenFailed, En) => "Failed to open {}",
+        (OpenFailed, Fr) => "Impossible d'ouvrir {}",
+        (ReplaceFailed, En) => "Failed to replace {}",
+        (ReplaceFailed, Fr) => "Impossible de remplacer {}",
+        (ReadLineFailed, En) => "Failed to read line",
+        (ReadLineFailed, Fr) => "Impossible de lire la ligne",
+        (SerializeProofFailed, En) => "Failed to serialize proof",
+        (SerializeProofFailed, Fr) => "Impossible de sérialiser la preuve",
+        (ClockBeforeEpoch, En) => "System clock is before Unix epoch",
+        (ClockBeforeEpoch, Fr) => "L'horloge système est antérieure à l'époque Unix",
+        (RuntimeFailed, En) => "Failed to start async runtime",
+        (RuntimeFailed, Fr) => "Impossible de démarrer le runtime asynchrone",
+        (InvalidRpcUrl, En) => "Invalid RPC URL",
+        (InvalidRpcUrl, Fr) => "URL RPC invalide",
+        (SignatureNotHex, En) => "Signature is not hex",
+        (SignatureNotHex, Fr) => "La signature n'est pas en hexadécimal",
+        (SignatureLength, En) => "Signature must be 64 bytes",
+        (SignatureLength, Fr) => "La signature doit faire 64 octets",
+        (HttpError, En) => "HTTP error: {}",
+        (HttpError, Fr) => "Erreur HTTP : {}",
+        (ResolveFailed, En) => "Failed to resolve {}",
+        (ResolveFailed, Fr) => "Impossible de résoudre {}",
+        (GeoipMetadataFailed, En) => "Failed to read GeoIP database metadata",
+        (GeoipMetadataFailed, Fr) => "Impossible de lire les métadonnées de la base GeoIP",
+        (SerializeVerdictFailed, En) => "Failed to serialize verdict",
+        (SerializeVerdictFailed, Fr) => "Impossible de sérialiser le verdict",
+        (ReadProofStdinFailed, En) => "Failed to read proof from stdin",
+        (ReadProofStdinFailed, Fr) => "Impossible de lire la preuve sur l'entrée standard",
+        (LoadProofFailed, En) => "Failed to load proof {}",
+        (LoadProofFailed, Fr) => "Impossible de charger la preuve {}",
+        (WriteProofStdoutFailed, En) => "Failed to write proof to stdout",
+        (WriteProofStdoutFailed, Fr) => "Impossible d'écrire la preuve sur la sortie standard",
+        (ContractResultMismatch, En) => "contract returned ({}, {}, {}), proof committed ({}, {}, {})",
+        (ContractResultMismatch, Fr) => "le contrat a renvoyé ({}, {}, {}), la preuve a engagé ({}, {}, {})",
+        (InvalidHex32, En) => "Invalid 32-byte hex value {}",
+        (InvalidHex32, Fr) => "Valeur hexadécimale de 32 octets invalide : {}",
+        (Not32Bytes, En) => "{} is not 32 bytes",
+        (Not32Bytes, Fr) => "{} ne fait pas 32 octets",
+        (ElfNoFileName, En) => "ELF path has no file name",
+        (ElfNoFileName, Fr) => "Le chemin de l'ELF n'a pas de nom de fichier",
+        (FfiReadFailed, En) => "Failed to read request from stdin",
+        (FfiReadFailed, Fr) => "Impossible de lire la requête sur l'entrée standard",
+        (FfiInvalidRequest, En) => "Invalid FFI request",
+        (FfiInvalidRequest, Fr) => "Requête FFI invalide",
+        (FfiVersionUnsupported, En) => "unsupported FFI request version {}, expected {}",
+        (FfiVersionUnsupported, Fr) => "version de requête FFI {} non prise en charge, attendue {}",
+        (FfiDiscloseIpv4Only, En) => "disclose only supports IPv4 addresses",
+        (FfiDiscloseIpv4Only, Fr) => "disclose ne prend en charge que les adresses IPv4",
+        (CountriesOpenFailed, En) => "Failed to open countries.csv",
+        (CountriesOpenFailed, Fr) => "Impossible d'ouvrir countries.csv",
+        (CountryAmbiguous, En) => "Ambiguous country \"{}\": matches {}; use the alpha-2 code",
+        (CountryAmbiguous, Fr) => "Pays ambigu « {} » : correspond à {} ; utilisez le code alpha-2",
+        (CountryUnknownSuggest, En) => "Unknown country \"{}\"; did you mean {}?",
+        (CountryUnknownSuggest, Fr) => "Pays inconnu « {} » ; vouliez-vous dire {} ?",
+        (CountryUnknown, En) => "Unknown country code or name: {}",
+        (CountryUnknown, Fr) => "Code ou nom de pays inconnu : {}",
+        (NoCountryCodes, En) => "No valid country codes provided",
+        (NoCountryCodes, Fr) => "Aucun code pays valide fourni",
+        (DataDirFailed, En) => "Failed to create data directory",
+        (DataDirFailed, Fr) => "Impossible de créer le répertoire de données",
+        (HttpClientFailed, En) => "Failed to build HTTP client",
+        (HttpClientFailed, Fr) => "Impossible de construire le client HTTP",
+        (GeoipFetchFailed, En) => "Failed to fetch GeoIP database",
+        (GeoipFetchFailed, Fr) => "Impossible de télécharger la base GeoIP",
+        (DownloadMoveFailed, En) => "Failed to move download into place",
+        (DownloadMoveFailed, Fr) => "Impossible de mettre le téléchargement en place",
+        (RequestFailed, En) => "Request failed",
+        (RequestFailed, Fr) => "La requête a échoué",
+        (ResumeOffsetMismatch, En) => "Server resumed at {}, expected byte {}",
+        (ResumeOffsetMismatch, Fr) => "Le serveur a repris à {}, octet {} attendu",
+        (PartialOpenFailed, En) => "Failed to open partial download",
+        (PartialOpenFailed, Fr) => "Impossible d'ouvrir le téléchargement partiel",
+        (ResumeRejected, En) => "Server rejected the resume range; restarting",
+        (ResumeRejected, Fr) => "Le serveur a refusé la plage de reprise ; redémarrage",
+        (ValidatorWriteFailed, En) => "Failed to record download validator",
+        (ValidatorWriteFailed, Fr) => "Impossible d'enregistrer le validateur du téléchargement",
+        (PartialCreateFailed, En) => "Failed to create partial download",
+        (PartialCreateFailed, Fr) => "Impossible de créer le téléchargement partiel",
+        (DownloadInterruptedError, En) => "Download interrupted",
+        (DownloadInterruptedError, Fr) => "Téléchargement interrompu",
+        (PartialWriteFailed, En) => "Failed to write partial download",
+        (PartialWriteFailed, Fr) => "Impossible d'écrire le téléchargement partiel",
+        (DownloadTruncated, En) => "Download ended after {} of {} bytes",
+        (DownloadTruncated, Fr) => "Le téléchargement s'est arrêté après {} octets sur {}",
+        (SnapshotDirFailed, En) => "Failed to create the snapshot directory",
+        (SnapshotDirFailed, Fr) => "Impossible de créer le répertoire de l'instantané",
+        (SnapshotWriteFailed, En) => "Failed to write the embedded snapshot",
+        (SnapshotWriteFailed, Fr) => "Impossible d'écrire l'instantané embarqué",
+        (SnapshotDateFailed, En) => "Failed to date the embedded snapshot",
+        (SnapshotDateFailed, Fr) => "Impossible de dater l'instantané embarqué",
+        (InvalidStartIp, En) => "Invalid start IP",
+        (InvalidStartIp, Fr) => "IP de début invalide",
+        (InvalidEndIp, En) => "Invalid end IP",
+        (InvalidEndIp, Fr) => "IP de fin invalide",
+        (GeoipOpenFailed, En) => "Failed to open GeoIP database",
+        (GeoipOpenFailed, Fr) => "Impossible d'ouvrir la base GeoIP",
+        (GeoipOpenPathFailed, En) => "Failed to open GeoIP database {}",
+        (GeoipOpenPathFailed, Fr) => "Impossible d'ouvrir la base GeoIP {}",
+        (GeoipReadFailed, En) => "Failed to read GeoIP database",
+        (GeoipReadFailed, Fr) => "Impossible de lire la base GeoIP",
+        (GeoipReadPathFailed, En) => "Failed to read GeoIP database {}",
+        (GeoipReadPathFailed, Fr) => "Impossible de lire la base GeoIP {}",
+        (GeoipBeforeEpoch, En) => "GeoIP database modified before Unix epoch",
+        (GeoipBeforeEpoch, Fr) => "Base GeoIP modifiée avant l'époque Unix",
+        (UpstreamNoVersion, En) => "Upstream manifest has no resolved version",
+        (UpstreamNoVersion, Fr) => "Le manifeste amont n'a pas de version résolue",
+        (UpstreamNoFiles, En) => "Upstream manifest has no file listing",
+        (UpstreamNoFiles, Fr) => "Le manifeste amont n'a pas de liste de fichiers",
+        (UpstreamFileMissing, En) => "{} not found in upstream manifest",
+        (UpstreamFileMissing, Fr) => "{} absent du manifeste amont",
+        (UpstreamNoHash, En) => "Upstream manifest entry has no hash",
+        (UpstreamNoHash, Fr) => "L'entrée du manifeste amont n'a pas d'empreinte",
+        (UpstreamHashBase64, En) => "Invalid base64 in upstream hash",
+        (UpstreamHashBase64, Fr) => "Base64 invalide dans l'empreinte amont",
+        (UpstreamHashLength, En) => "Upstream hash is not a SHA-256 digest",
+        (UpstreamHashLength, Fr) => "L'empreinte amont n'est pas un condensé SHA-256",
+        (FetchUrlFailed, En) => "Failed to fetch {}",
+        (FetchUrlFailed, Fr) => "Impossible de télécharger {}",
+        (ReadResponseFailed, En) => "Failed to read response",
+        (ReadResponseFailed, Fr) => "Impossible de lire la réponse",
+        (UpstreamParseFailed, En) => "Failed to parse upstream manifest",
+        (UpstreamParseFailed, Fr) => "Impossible d'analyser le manifeste amont",
+        (PolicyReadFailed, En) => "Failed to read policy file {}",
+        (PolicyReadFailed, Fr) => "Impossible de lire le fichier de politique {}",
+        (PolicyParseFailed, En) => "Failed to parse policy file",
+        (PolicyParseFailed, Fr) => "Impossible d'analyser le fichier de politique",
+        (PrefixTooLong, En) => "prefix longer than {} bits",
+        (PrefixTooLong, Fr) => "préfixe de plus de {} bits",
+        (SerializeLockFailed, En) => "Failed to serialize lockfile",
+        (SerializeLockFailed, Fr) => "Impossible de sérialiser le fichier de verrouillage",
+        (SerializeMetadataFailed, En) => "Failed to serialize metadata",
+        (SerializeMetadataFailed, Fr) => "Impossible de sérialiser les métadonnées",
+        (SerializeMetricsFailed, En) => "Failed to serialize run metrics",
+        (SerializeMetricsFailed, Fr) => "Impossible de sérialiser les métriques d'exécution",
+        (CargoProveFailed, En) => "Failed to run `cargo prove build`; is the SP1 toolchain installed?",
+        (CargoProveFailed, Fr) => "Impossible de lancer `cargo prove build` ; la chaîne d'outils SP1 est-elle installée ?",
+        (CargoProveExited, En) => "`cargo prove build --docker` exited with {}",
+        (CargoProveExited, Fr) => "`cargo prove build --docker` s'est terminé avec {}",
+        (DockerNoElf, En) => "Docker build did not produce {}",
+        (DockerNoElf, Fr) => "La compilation Docker n'a pas produit {}",
+        (SerializeRegistryFailed, En) => "Failed to serialize registry",
+        (SerializeRegistryFailed, Fr) => "Impossible de sérialiser le registre",
+        (VersionRegistered, En) => "version {} ({}) is already registered with vkey {}",
+        (VersionRegistered, Fr) => "la version {} ({}) est déjà enregistrée avec la vkey {}",
+        (VkeyRegistered, En) => "vkey {} is already registered as version {}",
+        (VkeyRegistered, Fr) => "la vkey {} est déjà enregistrée comme version {}",
+        (SerializeVkeyFailed, En) => "Failed to serialize verifying key",
+        (SerializeVkeyFailed, Fr) => "Impossible de sérialiser la clé de vérification",
+        (VersionNotRegistered, En) => "version {} is not registered",
+        (VersionNotRegistered, Fr) => "la version {} n'est pas enregistrée",
+        (VkeyParseFailed, En) => "Failed to parse verifying key {}",
+        (VkeyParseFailed, Fr) => "Impossible d'analyser la clé de vérification {}",
+        (SerializeBundleFailed, En) => "Failed to serialize bundle",
+        (SerializeBundleFailed, Fr) => "Impossible de sérialiser le paquet",
+        (BundleDecodeFailed, En) => "Failed to decode bundled proof",
+        (BundleDecodeFailed, Fr) => "Impossible de décoder la preuve du paquet",
+        (BundleValuesDiffer, En) => "Bundle public values differ from those of its proof",
+        (BundleValuesDiffer, Fr) => "Les valeurs publiques du paquet diffèrent de celles de sa preuve",
+        (BundleWriteFailed, En) => "Failed to write bundle {}",
+        (BundleWriteFailed, Fr) => "Impossible d'écrire le paquet {}",
+        (BundleReadFailed, En) => "Failed to read bundle {}",
+        (BundleReadFailed, Fr) => "Impossible de lire le paquet {}",
+        (NotBundle, En) => "{} is not a zkip bundle",
+        (NotBundle, Fr) => "{} n'est pas un paquet zkip",
+        (BundleVersion, En) => "{} is bundle version {}, expected {}",
+        (BundleVersion, Fr) => "{} est un paquet de version {}, attendue {}",
+        (SerializeManifestFailed, En) => "Failed to serialize manifest",
+        (SerializeManifestFailed, Fr) => "Impossible de sérialiser le manifeste",
+        (PublicKeyNotHex, En) => "Public key is not hex",
+        (PublicKeyNotHex, Fr) => "La clé publique n'est pas en hexadécimal",
+        (PublicKeyLength, En) => "Public key must be 32 bytes",
+        (PublicKeyLength, Fr) => "La clé publique doit faire 32 octets",
+        (InvalidEd25519Key, En) => "Invalid ed25519 public key",
+        (InvalidEd25519Key, Fr) => "Clé publique ed25519 invalide",
+        (SigningKeyNotHex, En) => "Signing key is not hex",
+        (SigningKeyNotHex, Fr) => "La clé de signature n'est pas en hexadécimal",
+        (SigningKeyLength, En) => "Signing key must be a 32-byte seed",
+        (SigningKeyLength, Fr) => "La clé de signature doit être une graine de 32 octets",
+        (ManifestReadFailed, En) => "Failed to read manifest {}",
+        (ManifestReadFailed, Fr) => "Impossible de lire le manifeste {}",
+        (ManifestParseFailed, En) => "Failed to parse manifest {}",
+        (ManifestParseFailed, Fr) => "Impossible d'analyser le manifeste {}",
+        (ManifestSignatureInvalid, En) => "Manifest signature does not match the maintainer key",
+        (ManifestSignatureInvalid, Fr) => "La signature du manifeste ne correspond pas à la clé du mainteneur",
+        (ArtifactNoFileName, En) => "Artifact path has no file name",
+        (ArtifactNoFileName, Fr) => "Le chemin de l'artefact n'a pas de nom de fichier",
+        (ArtifactNotListed, En) => "{} is not listed in the manifest",
+        (ArtifactNotListed, Fr) => "{} ne figure pas dans le manifeste",
+        (ArtifactHashMismatch, En) => "{} has SHA-256 {}, manifest lists {}",
+        (ArtifactHashMismatch, Fr) => "{} a le SHA-256 {}, le manifeste indique {}",
+        (ManifestVkeyMismatch, En) => "program vkey {} does not match manifest vkey {}",
+        (ManifestVkeyMismatch, Fr) => "la vkey du programme {} ne correspond pas à la vkey du manifeste {}",
+        (NoMaintainerKey, En) => "this build has no embedded maintainer key (ZKIP_MAINTAINER_PUBKEY)",
+        (NoMaintainerKey, Fr) => "cette compilation n'embarque pas de clé de mainteneur (ZKIP_MAINTAINER_PUBKEY)",
+        (SerializeAuditFailed, En) => "Failed to serialize audit entry",
+        (SerializeAuditFailed, Fr) => "Impossible de sérialiser l'entrée d'audit",
+        (AuditParseFailed, En) => "Failed to parse audit log line {}",
+        (AuditParseFailed, Fr) => "Impossible d'analyser la ligne {} du journal d'audit",
+        (AuditEntryInvalid, En) => "line {}: not a valid audit entry",
+        (AuditEntryInvalid, Fr) => "ligne {} : entrée d'audit invalide",
+        (AuditSeqMismatch, En) => "line {}: expected seq {}, found {}",
+        (AuditSeqMismatch, Fr) => "ligne {} : seq {} attendu, {} trouvé",
+        (AuditPrevHashMismatch, En) => "line {}: prev_hash does not match the previous entry",
+        (AuditPrevHashMismatch, Fr) => "ligne {} : prev_hash ne correspond pas à l'entrée précédente",
+        (AuditHashMismatch, En) => "line {}: entry hash does not match its contents",
+        (AuditHashMismatch, Fr) => "ligne {} : l'empreinte de l'entrée ne correspond pas à son contenu",
+        (SerializeRevocationsFailed, En) => "Failed to serialize revocations",
+        (SerializeRevocationsFailed, Fr) => "Impossible de sérialiser les révocations",
+        (RevocationSigner, En) => "revocation list is signed by {}, not the trusted key",
+        (RevocationSigner, Fr) => "la liste de révocation est signée par {}, pas par la clé de confiance",
+        (RevocationSignatureInvalid, En) => "Revocation list signature is invalid",
+        (RevocationSignatureInvalid, Fr) => "La signature de la liste de révocation est invalide",
+        (ReplayAccepted, En) => "{} was already accepted; replay window ends at {}",
+        (ReplayAccepted, Fr) => "{} a déjà été acceptée ; la fenêtre de rejeu se termine le {}",
+        (SerializeReplayFailed, En) => "Failed to serialize store",
+        (SerializeReplayFailed, Fr) => "Impossible de sérialiser le registre de rejeu",
+        (ServerNoAddress, En) => "{} has no address",
+        (ServerNoAddress, Fr) => "{} n'a pas d'adresse",
+        (UdpSocketFailed, En) => "Failed to open UDP socket",
+        (UdpSocketFailed, Fr) => "Impossible d'ouvrir un socket UDP",
+        (SendFailed, En) => "Failed to send to {}",
+        (SendFailed, Fr) => "Impossible d'envoyer à {}",
+        (NoReply, En) => "No reply from {}",
+        (NoReply, Fr) => "Aucune réponse de {}",
+        (UnexpectedReply, En) => "unexpected reply from {}",
+        (UnexpectedReply, Fr) => "réponse inattendue de {}",
+        (NtpNotServer, En) => "{} did not answer in server mode",
+        (NtpNotServer, Fr) => "{} n'a pas répondu en mode serveur",
+        (NtpKissOfDeath, En) => "{} sent a kiss-o'-death packet",
+        (NtpKissOfDeath, Fr) => "{} a envoyé un paquet kiss-o'-death",
+        (NtpWrongRequest, En) => "{} replied to a different request",
+        (NtpWrongRequest, Fr) => "{} a répondu à une autre requête",
+        (RoughtimeBadReply, En) => "{} sent a reply that is not signed by its key or not for this request",
+        (RoughtimeBadReply, Fr) => "{} a envoyé une réponse qui n'est pas signée par sa clé ou pas pour cette requête",
+        (RoughtimeKeyEncoding, En) => "Roughtime key is neither hex nor base64",
+        (RoughtimeKeyEncoding, Fr) => "La clé Roughtime n'est ni en hexadécimal ni en base64",
+        (RoughtimeKeyLength, En) => "Roughtime key must be a 32-byte Ed25519 key",
+        (RoughtimeKeyLength, Fr) => "La clé Roughtime doit être une clé Ed25519 de 32 octets",
+        (NtpTooFewAnswers, En) => "need answers from at least 2 NTP servers to cross-check, got {}",
+        (NtpTooFewAnswers, Fr) => "il faut les réponses d'au moins 2 serveurs NTP pour recouper, reçu {}",
+        (NtpDisagree, En) => "NTP servers disagree by {}s (limit {}s): {}",
+        (NtpDisagree, Fr) => "les serveurs NTP divergent de {} s (limite {} s) : {}",
+        (ClockSkewed, En) => "local clock is off by {}s from NTP (limit {}s)",
+        (ClockSkewed, Fr) => "l'horloge locale s'écarte de {} s de NTP (limite {} s)",
+        (RoughtimeNeedsKey, En) => "--time-source roughtime needs a server and its key",
+        (RoughtimeNeedsKey, Fr) => "--time-source roughtime exige un serveur et sa clé",
+        (RpcNoBlock, En) => "RPC endpoint returned no block",
+        (RpcNoBlock, Fr) => "Le point RPC n'a renvoyé aucun bloc",
+        (RpcUnreachable, En) => "Failed to reach RPC endpoint {}",
+        (RpcUnreachable, Fr) => "Impossible de joindre le point RPC {}",
+        (RpcInvalidJson, En) => "RPC endpoint returned invalid JSON",
+        (RpcInvalidJson, Fr) => "Le point RPC a renvoyé un JSON invalide",
+        (RpcCallFailed, En) => "eth_getBlockByNumber failed: {}",
+        (RpcCallFailed, Fr) => "eth_getBlockByNumber a échoué : {}",
+        (InvalidBlockNumber, En) => "Invalid block number {}",
+        (InvalidBlockNumber, Fr) => "Numéro de bloc invalide : {}",
+        (InvalidBlockHash, En) => "Invalid block hash {}",
+        (InvalidBlockHash, Fr) => "Empreinte de bloc invalide : {}",
+        (BlockHashLength, En) => "Block hash must be 32 bytes",
+        (BlockHashLength, Fr) => "L'empreinte de bloc doit faire 32 octets",
+        (AttestationNotHex, En) => "Attestation {} is not hex",
+        (AttestationNotHex, Fr) => "Le champ {} de l'attestation n'est pas en hexadécimal",
+        (AttestationSignatureLength, En) => "Attestation signature must be 64 bytes",
+        (AttestationSignatureLength, Fr) => "La signature d'attestation doit faire 64 octets",
+        (AttestationNonceLength, En) => "Attestation nonce must be 32 bytes",
+        (AttestationNonceLength, Fr) => "Le nonce d'attestation doit faire 32 octets",
+        (AttesterKeyLength, En) => "Attester key must be a 33-byte compressed secp256k1 key or a 32-byte Ed25519 key",
+        (AttesterKeyLength, Fr) => "La clé d'attesteur doit être une clé secp256k1 compressée de 33 octets ou une clé Ed25519 de 32 octets",
+        (WebProofSignatureLength, En) => "Web proof signature must be 64 bytes",
+        (WebProofSignatureLength, Fr) => "La signature de preuve web doit faire 64 octets",
+        (UnknownCountryCode, En) => "Unknown country code {}",
+        (UnknownCountryCode, Fr) => "Code pays inconnu : {}",
+        (InvalidRoot, En) => "Invalid root {}",
+        (InvalidRoot, Fr) => "Racine invalide : {}",
+        (RootLength, En) => "Root {} is not 32 bytes",
+        (RootLength, Fr) => "La racine {} ne fait pas 32 octets",
+        (NoPublisherKey, En) => "This build has no maintainer key; pass the publisher's key instead",
+        (NoPublisherKey, Fr) => "Cette compilation n'a pas de clé de mainteneur ; passez plutôt la clé de l'éditeur",
+        (RootScheme, En) => "{} uses root scheme {}, expected {}",
+        (RootScheme, Fr) => "{} utilise le schéma de racine {}, attendu {}",
+        (SerializeRootsFailed, En) => "Failed to serialize root manifest",
+        (SerializeRootsFailed, Fr) => "Impossible de sérialiser le manifeste de racines",
+        (RootsUnsigned, En) => "Root manifest is not signed; sign it with `zkip db root --sign`",
+        (RootsUnsigned, Fr) => "Le manifeste de racines n'est pas signé ; signez-le avec `zkip db root --sign`",
+        (RootsMissingCountry, En) => "Root manifest has no roots for country {}",
+        (RootsMissingCountry, Fr) => "Le manifeste de racines n'a pas de racines pour le pays {}",
+        (PublisherSignatureNotHex, En) => "Publisher signature is not hex",
+        (PublisherSignatureNotHex, Fr) => "La signature de l'éditeur n'est pas en hexadécimal",
+        (RootsSignatureInvalid, En) => "Root manifest signature does not match its publisher key",
+        (RootsSignatureInvalid, Fr) => "La signature du manifeste de racines ne correspond pas à la clé de son éditeur",
+        (NotHostName, En) => "{} is not a host name",
+        (NotHostName, Fr) => "{} n'est pas un nom d'hôte",
+        (DomainNoAddresses, En) => "{} has no addresses to prove",
+        (DomainNoAddresses, Fr) => "{} n'a aucune adresse à prouver",
+        (IpListEmpty, En) => "{} lists no addresses to prove",
+        (IpListEmpty, Fr) => "{} ne liste aucune adresse à prouver",
+        (EnvelopeProofMismatch, En) => "{} belongs to proof {}, not {}",
+        (EnvelopeProofMismatch, Fr) => "{} appartient à la preuve {}, pas à {}",
+        (SerializeEnvelopeFailed, En) => "Failed to serialize envelope",
+        (SerializeEnvelopeFailed, Fr) => "Impossible de sérialiser l'enveloppe",
+        (CosignThreshold, En) => "threshold {} must be between 1 and the number of operators ({})",
+        (CosignThreshold, Fr) => "le seuil {} doit être compris entre 1 et le nombre d'opérateurs ({})",
+        (OperatorKeyInvalid, En) => "Invalid key for operator {}",
+        (OperatorKeyInvalid, Fr) => "Clé invalide pour l'opérateur {}",
+        (OperatorKeyDuplicate, En) => "operator key {} is listed twice",
+        (OperatorKeyDuplicate, Fr) => "la clé d'opérateur {} figure deux fois",
+        (CosignShort, En) => "{} of {} required operator signatures",
+        (CosignShort, Fr) => "{} signatures d'opérateurs sur les {} requises",
+        (PublicValuesNotHex, En) => "Public values are not hex",
+        (PublicValuesNotHex, Fr) => "Les valeurs publiques ne sont pas en hexadécimal",
+        (AnvilStartFailed, En) => "Failed to start anvil; install Foundry (https://getfoundry.sh)",
+        (AnvilStartFailed, Fr) => "Impossible de démarrer anvil ; installez Foundry (https://getfoundry.sh)",
+        (AnvilNotListening, En) => "anvil did not start listening on port {}",
+        (AnvilNotListening, Fr) => "anvil n'a pas commencé à écouter sur le port {}",
+        (NotFoundryProject, En) => "{} is not a Foundry project (no foundry.toml)",
+        (NotFoundryProject, Fr) => "{} n'est pas un projet Foundry (pas de foundry.toml)",
+        (ForgeRunFailed, En) => "Failed to run forge; install Foundry (https://getfoundry.sh)",
+        (ForgeRunFailed, Fr) => "Impossible de lancer forge ; installez Foundry (https://getfoundry.sh)",
+        (ForgeBuildFailed, En) => "forge build failed with {}",
+        (ForgeBuildFailed, Fr) => "forge build a échoué avec {}",
+        (NoBytecode, En) => "{} has no bytecode",
+        (NoBytecode, Fr) => "{} n'a pas de bytecode",
+        (BytecodeNotHex, En) => "{} bytecode is not hex",
+        (BytecodeNotHex, Fr) => "le bytecode de {} n'est pas en hexadécimal",
+        (InvalidDevKey, En) => "Invalid dev key",
+        (InvalidDevKey, Fr) => "Clé de développement invalide",
+        (AnvilNotAnswering, En) => "anvil is not answering",
+        (AnvilNotAnswering, Fr) => "anvil ne répond pas",
+        (DeployVerifierFailed, En) => "Failed to deploy the SP1 verifier",
+        (DeployVerifierFailed, Fr) => "Impossible de déployer le vérificateur SP1",
+        (DeployZkipFailed, En) => "Failed to deploy Zkip",
+        (DeployZkipFailed, Fr) => "Impossible de déployer Zkip",
+        (VerifyZkipReverted, En) => "verifyZkipProof reverted",
+        (VerifyZkipReverted, Fr) => "verifyZkipProof a échoué (revert)",
+        (InvalidAdminKey, En) => "Invalid admin private key",
+        (InvalidAdminKey, Fr) => "Clé privée d'administration invalide",
+        (ReadRootFailed, En) => "Failed to read the current root",
+        (ReadRootFailed, Fr) => "Impossible de lire la racine actuelle",
+        (NoUpdaterRole, En) => "Contract does not expose ROOT_UPDATER_ROLE",
+        (NoUpdaterRole, Fr) => "Le contrat n'expose pas ROOT_UPDATER_ROLE",
+        (MissingUpdaterRole, En) => "{} does not hold ROOT_UPDATER_ROLE on {}",
+        (MissingUpdaterRole, Fr) => "{} ne détient pas ROOT_UPDATER_ROLE sur {}",
+        (SetRootWouldRevert, En) => "setRoot would revert",
+        (SetRootWouldRevert, Fr) => "setRoot échouerait (revert)",
+        (SetRootSendFailed, En) => "Failed to send setRoot",
+        (SetRootSendFailed, Fr) => "Impossible d'envoyer setRoot",
+        (SetRootReceiptFailed, En) => "Failed to get the setRoot receipt",
+        (SetRootReceiptFailed, Fr) => "Impossible d'obtenir le reçu de setRoot",
+        (SetRootReverted, En) => "setRoot transaction {} reverted",
+        (SetRootReverted, Fr) => "la transaction setRoot {} a échoué (revert)",
+        (GcsTokenUnset, En) => "GOOGLE_OAUTH_ACCESS_TOKEN is not set",
+        (GcsTokenUnset, Fr) => "GOOGLE_OAUTH_ACCESS_TOKEN n'est pas défini",
+        (AwsKeyIdUnset, En) => "AWS_ACCESS_KEY_ID is not set",
+        (AwsKeyIdUnset, Fr) => "AWS_ACCESS_KEY_ID n'est pas défini",
+        (AwsSecretUnset, En) => "AWS_SECRET_ACCESS_KEY is not set",
+        (AwsSecretUnset, Fr) => "AWS_SECRET_ACCESS_KEY n'est pas défini",
+        (S3UploadFailed, En) => "Failed to upload to s3://{}",
+        (S3UploadFailed, Fr) => "Impossible de téléverser vers s3://{}",
+        (S3UploadRejected, En) => "S3 upload of {} failed: {} {}",
+        (S3UploadRejected, Fr) => "Le téléversement S3 de {} a échoué : {} {}",
+        (GcsUploadFailed, En) => "Failed to upload to gs://{}",
+        (GcsUploadFailed, Fr) => "Impossible de téléverser vers gs://{}",
+        (GcsUploadRejected, En) => "GCS upload of {} failed: {} {}",
+        (GcsUploadRejected, Fr) => "Le téléversement GCS de {} a échoué : {} {}",
+    }
+}
+
+/// The template for `msg` in the selected language.
+pub fn t(msg: Msg) -> &'static str {
+    template(msg, lang())
+}
+
+/// Translate `msg` and fill its `{}` placeholders with `args`, in order.
+pub fn format_msg(msg: Msg, args: &[&dyn Display]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter();
+    let mut parts = t(msg).split("{}").peekable();
+    while let Some(part) = parts.next() {
+        out.push_str(part);
+        if parts.peek().is_some() {
+            if let Some(arg) = args.next() {
+                out.push_str(&arg.to_string());
+            }
+        }
+    }
+    out
+}
+
+/// `tr!(Msg::X, a, b)` translates `Msg::X` and fills its placeholders with `a` and `b`.
+#[macro_export]
+macro_rules! tr {
+    ($msg:expr $(, $arg:expr)* $(,)?) => {
+        $crate::i18n::format_msg($msg, &[$(&$arg as &dyn ::std::fmt::Display),*])
+    };
+}
@@ -0,0 +1,227 @@
+//! Guest input assembly.
+
+use sp1_sdk::SP1Stdin;
+use std::net::IpAddr;
+use zkip_lib::{
+    attest::AttestationInput,
+    block::BlockInput,
+    chunk::{ChunkInput, CHUNK_SIZE},
+    compose::CompanionInput,
+    gap::{GapInput, GapWitness},
+    merkle::{ranges_root, ranges_root_v6, split_groups},
+    nullifier::NullifierInput,
+    pack_ranges, pack_ranges_v6,
+    policy::{Address, PolicyExpr},
+    publisher::SnapshotSignature,
+    roughtime::RoughtimeResponse,
+    snapshot::SnapshotMetadata,
+    webproof::WebProofInput,
+    AnchorInput, DisclosureInput, IpV4, ZkipInput,
+};
+
+use crate::snapshot::ExcludedRanges;
+
+/// Host-side view of the inputs every guest reads, borrowed from wherever the caller loaded
+/// them; `build_stdin` turns it into a `ZkipInput` and the packed ranges. Unless
+/// `batch`, `ips` and `ips_v6` hold exactly one address between them; with `inventory`
+/// too, they are sorted and distinct and the guest commits their `merkle::inventory_root`;
+/// with `threshold`, it commits only whether at least that many are clear. The ranges hold each
+/// excluded country's ranges in turn, `range_counts` of them (see `ExcludedRanges`). With
+/// `include`, the countries and ranges are the ones the address must be in, and `denylist`
+/// optionally holds countries it must also be outside of, with their ranges. With `policy`,
+/// the countries are the ones its expression names, and it holds the ASNs it names with
+/// their ranges. With `per_country`, the guest also commits one result per excluded
+/// country. With `gap`, it gets those witnesses instead of the ranges (see `gap_input`).
+/// With `attestation`, it checks the attester's signature over the address and commits who
+/// signed it, and with `web_proof`, the notary's over a response naming it, committed the
+/// same way. With `snapshot_signature`, it checks the countries' ranges are in a
+/// publisher-signed snapshot (see `snapshot::RootManifest::snapshot_signature`) and
+/// commits who signed it. With `time_beacon`, it checks a Roughtime server signed
+/// `timestamp` and commits the server's key. With `companion`, it verifies that proof,
+/// which the caller writes to the stdin itself, and commits its vkey and digest. With
+/// `nullifier`, a plain proof also commits the address's nullifier for that epoch and salt,
+/// and with `prefix_salt`, its network's prefix commitment. With `block`, the guest claims
+/// the whole prefix holding the address is clear instead (see `zkip_lib::block`).
+/// With `challenge`, the guest commits it around whatever layout it commits, and with
+/// `valid_for`, a validity window inside that. With `snapshot` and `app_context`, a plain
+/// proof commits them as given.
+#[derive(Debug, Clone, Copy)]
+pub struct SharedInputs<'a> {
+    pub ips: &'a [u32],
+    pub excluded_ranges: &'a [(u32, u32)],
+    pub excluded_countries: &'a [u16],
+    pub timestamp: u64,
+    pub disclosure: Option<DisclosureInput>,
+    pub anchor: Option<AnchorInput>,
+    pub batch: bool,
+    pub inventory: bool,
+    pub threshold: Option<u32>,
+    pub ips_v6: &'a [u128],
+    pub excluded_ranges_v6: &'a [(u128, u128)],
+    pub range_counts: &'a [(u32, u32)],
+    pub include: bool,
+    pub denylist: Option<(&'a [u16], &'a ExcludedRanges)>,
+    pub policy: Option<(&'a PolicyExpr, &'a [u32], &'a ExcludedRanges)>,
+    pub per_country: bool,
+    pub gap: Option<&'a GapInput>,
+    pub attestation: Option<&'a AttestationInput>,
+    pub web_proof: Option<&'a WebProofInput>,
+    pub snapshot_signature: Option<&'a SnapshotSignature>,
+    pub time_beacon: Option<&'a RoughtimeResponse>,
+    pub companion: Option<&'a CompanionInput>,
+    pub nullifier: Option<NullifierInput>,
+    pub challenge: Option<[u8; 32]>,
+    pub valid_for: Option<u32>,
+    pub snapshot: Option<SnapshotMetadata>,
+    pub app_context: Option<[u8; 32]>,
+    pub prefix_salt: Option<[u8; 32]>,
+    pub block: Option<BlockInput>,
+}
+
+/// Write the shared guest inputs: one `ZkipInput`, then the shared ranges packed
+/// (`zkip_lib::pack_ranges`), usually the bulk of the input, for the guest to read with
+/// `read_vec`.
+pub fn build_stdin(inputs: &SharedInputs) -> SP1Stdin {
+    // Gap proofs read witnesses in place of the ranges, which the guest requires empty
+    let (ranges, ranges_v6, range_counts) = match inputs.gap {
+        Some(_) => (&[][..], &[][..], &[][..]),
+        None => (
+            inputs.excluded_ranges,
+            inputs.excluded_ranges_v6,
+            inputs.range_counts,
+        ),
+    };
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&ZkipInput {
+        ips: inputs.ips.iter().copied().map(IpV4).collect(),
+        ips_v6: inputs.ips_v6.to_vec(),
+        excluded_countries: inputs.excluded_countries.to_vec(),
+        range_counts: range_counts.to_vec(),
+        timestamp: inputs.timestamp,
+        disclosure: inputs.disclosure,
+        anchor: inputs.anchor,
+        batch: inputs.batch,
+        inventory: inputs.inventory,
+        threshold: inputs.threshold,
+        include: inputs.include,
+        denylist: inputs.denylist.map(|(countries, denied)| {
+            (
+                countries.to_vec(),
+                denied.ranges.clone(),
+                denied.ranges_v6.clone(),
+                denied.counts.clone(),
+            )
+        }),
+        policy: inputs.policy.map(|(expr, asns, ranges)| {
+            (
+                expr.clone(),
+                asns.to_vec(),
+                ranges.ranges.clone(),
+                ranges.ranges_v6.clone(),
+                ranges.counts.clone(),
+            )
+        }),
+        per_country: inputs.per_country,
+        gap: inputs.gap.cloned(),
+        attestation: inputs.attestation.cloned(),
+        web_proof: inputs.web_proof.cloned(),
+        snapshot_signature: inputs.snapshot_signature.cloned(),
+        time_beacon: inputs.time_beacon.cloned(),
+        companion: inputs.companion.cloned(),
+        nullifier: inputs.nullifier,
+        challenge: inputs.challenge,
+        valid_for: inputs.valid_for,
+        snapshot: inputs.snapshot,
+        app_context: inputs.app_context,
+        prefix_salt: inputs.prefix_salt,
+        block: inputs.block,
+    });
+    stdin.write_vec(pack_ranges(ranges));
+    stdin.write_vec(pack_ranges_v6(ranges_v6));
+    stdin
+}
+
+/// Gap witnesses (`zkip_lib::gap`) that the one address in `ips` and `ips_v6` is outside
+/// each country of `excluded`, or `None` if one of them contains it.
+pub fn gap_input(ips: &[u32], ips_v6: &[u128], excluded: &ExcludedRanges) -> Option<GapInput> {
+    let groups = split_groups(
+        &excluded.ranges,
+        excluded.counts.iter().map(|&(count, _)| count),
+    );
+    let groups_v6 = split_groups(
+        &excluded.ranges_v6,
+        excluded.counts.iter().map(|&(_, count)| count),
+    );
+    let countries = groups.into_iter().zip(groups_v6);
+    match (ips, ips_v6) {
+        (&[ip], []) => countries
+            .map(|(group, group_v6)| GapWitness::new(ip, group, ranges_root_v6(group_v6)))
+            .collect::<Option<_>>()
+            .map(GapInput::V4),
+        ([], &[ip]) => countries
+            .map(|(group, group_v6)| GapWitness::new(ip, group_v6, ranges_root(group)))
+            .collect::<Option<_>>()
+            .map(GapInput::V6),
+        _ => None,
+    }
+}
+
+/// Split addresses into the IPv4 and IPv6 lists the guest reads.
+pub fn split_addresses(addrs: &[IpAddr]) -> (Vec<u32>, Vec<u128>) {
+    let mut ips = Vec::new();
+    let mut ips_v6 = Vec::new();
+    for addr in addrs {
+        match addr {
+            IpAddr::V4(v4) => ips.push(u32::from(*v4)),
+            IpAddr::V6(v6) => ips_v6.push(u128::from(*v6)),
+        }
+    }
+    (ips, ips_v6)
+}
+
+/// One stdin per chunk of `excluded`'s ranges for the chunk guest (see `zkip_lib::chunk`),
+/// each country's IPv4 chunks then its IPv6 ones, with the `(IPv4, IPv6)` chunk counts of
+/// each country, as the aggregation guest takes them.
+pub fn chunk_stdins(
+    ip: Address,
+    excluded_countries: &[u16],
+    excluded: &ExcludedRanges,
+) -> (Vec<SP1Stdin>, Vec<(u32, u32)>) {
+    let groups = split_groups(
+        &excluded.ranges,
+        excluded.counts.iter().map(|&(count, _)| count),
+    );
+    let groups_v6 = split_groups(
+        &excluded.ranges_v6,
+        excluded.counts.iter().map(|&(_, count)| count),
+    );
+    let mut stdins = Vec::new();
+    let mut chunk_counts = Vec::new();
+    for (&country, (group, group_v6)) in excluded_countries
+        .iter()
+        .zip(groups.into_iter().zip(groups_v6))
+    {
+        let mut push = |is_v6: bool, index: usize, packed: Vec<u8>| {
+            let mut stdin = SP1Stdin::new();
+            stdin.write(&ChunkInput {
+                ip,
+                country,
+                is_v6,
+                index: index as u32,
+            });
+            stdin.write_vec(packed);
+            stdins.push(stdin);
+        };
+        for (index, chunk) in group.chunks(CHUNK_SIZE).enumerate() {
+            push(false, index, pack_ranges(chunk));
+        }
+        for (index, chunk) in group_v6.chunks(CHUNK_SIZE).enumerate() {
+            push(true, index, pack_ranges_v6(chunk));
+        }
+        chunk_counts.push((
+            group.len().div_ceil(CHUNK_SIZE) as u32,
+            group_v6.len().div_ceil(CHUNK_SIZE) as u32,
+        ));
+    }
+    (stdins, chunk_counts)
+}
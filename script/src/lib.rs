@@ -0,0 +1,36 @@
+//! Host-side helpers shared by the zkip script binaries.
+
+pub mod admin;
+pub mod anchor;
+pub mod artifacts;
+pub mod attestation;
+pub mod audit;
+pub mod bundle;
+pub mod circuit;
+pub mod config;
+pub mod cosign;
+pub mod countries;
+pub mod disclosure;
+pub mod domain;
+pub mod e2e;
+pub mod error;
+pub mod explain;
+pub mod fleet;
+pub mod geoip;
+pub mod history;
+pub mod i18n;
+pub mod inputs;
+pub mod lock;
+pub mod logging;
+pub mod manifest;
+pub mod policy;
+pub mod registry;
+pub mod replay;
+pub mod reproduce;
+pub mod revocation;
+pub mod selftest;
+pub mod snapshot;
+pub mod store;
+pub mod telemetry;
+pub mod timesource;
+pub mod variants;
@@ -0,0 +1,153 @@
+//! `zkip.lock`: pins the inputs that determine a proof.
+//!
+//! The first proving run writes the lockfile; later runs compare the current GeoIP
+//! snapshot, country table and program variant and vkey against it and refuse to continue on any
+//! difference unless `--update-lock` is passed. Commit the file to make proof generation
+//! reproducible across machines.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::error::ZkipCliError;
+use crate::geoip::{sha256_file, GEOIP_PROVIDER, GEOIP_URL};
+use crate::i18n::Msg;
+use crate::tr;
+
+pub const LOCK_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZkipLock {
+    pub version: u32,
+    pub geoip: GeoipLock,
+    pub countries: CountriesLock,
+    pub program: ProgramLock,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeoipLock {
+    pub provider: String,
+    pub source: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CountriesLock {
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgramLock {
+    pub variant: String,
+    pub vkey: String,
+}
+
+pub fn default_lock_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../zkip.lock")
+}
+
+fn countries_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../data/countries.csv")
+}
+
+impl ZkipLock {
+    /// Describe the inputs of the current run.
+    pub fn current(geoip_path: &Path, variant: &str, vkey: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            version: LOCK_VERSION,
+            geoip: GeoipLock {
+                provider: GEOIP_PROVIDER.to_string(),
+                source: GEOIP_URL.to_string(),
+                sha256: hex::encode(sha256_file(geoip_path)?),
+            },
+            countries: CountriesLock {
+                sha256: hex::encode(sha256_file(&countries_path())?),
+            },
+            program: ProgramLock {
+                variant: variant.to_string(),
+                vkey: vkey.to_string(),
+            },
+        })
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content =
+            fs::read_to_string(path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+        toml::from_str(&content).with_context(|| tr!(Msg::ParseFailed, path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let content =
+            toml::to_string_pretty(self).with_context(|| tr!(Msg::SerializeLockFailed))?;
+        fs::write(
+            path,
+            format!(
+                "# Generated by zkip. Update with --update-lock.\n{}",
+                content
+            ),
+        )
+        .with_context(|| tr!(Msg::WriteFailed, path.display()))
+    }
+
+    /// Human-readable list of fields that differ from `other`.
+    pub fn differences(&self, other: &Self) -> Vec<String> {
+        let mut diffs = Vec::new();
+        let mut compare = |field: &str, locked: &str, current: &str| {
+            if locked != current {
+                diffs.push(format!("{}: locked {}, current {}", field, locked, current));
+            }
+        };
+        compare(
+            "version",
+            &self.version.to_string(),
+            &other.version.to_string(),
+        );
+        compare(
+            "geoip.provider",
+            &self.geoip.provider,
+            &other.geoip.provider,
+        );
+        compare("geoip.source", &self.geoip.source, &other.geoip.source);
+        compare("geoip.sha256", &self.geoip.sha256, &other.geoip.sha256);
+        compare(
+            "countries.sha256",
+            &self.countries.sha256,
+            &other.countries.sha256,
+        );
+        compare(
+            "program.variant",
+            &self.program.variant,
+            &other.program.variant,
+        );
+        compare("program.vkey", &self.program.vkey, &other.program.vkey);
+        diffs
+    }
+}
+
+/// Compare `current` with the lockfile at `path`, writing it if absent or if `update` is set.
+/// Fails with [`ZkipCliError::LockMismatch`] listing the differences otherwise.
+pub fn enforce_lock(path: &Path, current: &ZkipLock, update: bool) -> anyhow::Result<()> {
+    if !path.exists() {
+        current.save(path)?;
+        info!("{}", tr!(Msg::LockCreated, path.display()));
+        return Ok(());
+    }
+    if update {
+        current.save(path)?;
+        info!("{}", tr!(Msg::LockUpdated, path.display()));
+        return Ok(());
+    }
+
+    let locked = ZkipLock::load(path)?;
+    let diffs = locked.differences(current);
+    if !diffs.is_empty() {
+        return Err(
+            anyhow::anyhow!(tr!(Msg::LockMismatch, path.display(), diffs.join("\n  ")))
+                .context(ZkipCliError::LockMismatch),
+        );
+    }
+    info!("{}", tr!(Msg::LockMatched, path.display()));
+    Ok(())
+}
@@ -0,0 +1,63 @@
+//! Logging setup shared by the binaries.
+
+use clap::ValueEnum;
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing_subscriber::EnvFilter;
+
+/// Output format for log lines.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Crates whose logs drown out ours at `info`, silenced the same way `sp1_sdk::utils::setup_logger` does.
+const NOISY_TARGETS: &[&str] = &[
+    "hyper=off",
+    "p3_keccak_air=off",
+    "p3_fri=off",
+    "p3_dft=off",
+    "p3_challenger=off",
+];
+
+/// Install the global tracing subscriber, writing to stderr.
+///
+/// `level` is an `EnvFilter` directive (e.g. `debug` or `zkip=trace`); when absent,
+/// `RUST_LOG` is used, falling back to `info`.
+pub fn init_logging(format: LogFormat, level: Option<&str>) -> anyhow::Result<()> {
+    let mut filter = match level {
+        Some(level) => EnvFilter::try_new(level)?,
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+    };
+    for directive in NOISY_TARGETS {
+        filter = filter.add_directive(directive.parse()?);
+    }
+
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    let result = match format {
+        LogFormat::Text => builder.try_init(),
+        LogFormat::Json => builder
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .try_init(),
+    };
+    result.map_err(|e| anyhow::anyhow!(e))
+}
+
+/// Short identifier attached to every log line of a run, so lines from concurrent
+/// proofs can be told apart.
+pub fn new_run_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let mut hasher = Sha256::new();
+    hasher.update(nanos.to_le_bytes());
+    hasher.update(std::process::id().to_le_bytes());
+    hex::encode(&hasher.finalize()[..8])
+}
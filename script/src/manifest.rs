@@ -0,0 +1,156 @@
+//! Signed release manifests for guest ELFs loaded from disk.
+//!
+//! The embedded ELF is covered by the binary itself, but `--elf` swaps in an external
+//! artifact. Such artifacts must come with a manifest listing their SHA-256, the release
+//! version and the resulting vkey, signed with the maintainer's ed25519 key:
+//!
+//! ```json
+//! {
+//!   "version": "0.1.0",
+//!   "vkey": "0x00ab...",
+//!   "artifacts": [{ "name": "zkip-program", "sha256": "9f86..." }],
+//!   "signature": "5c1e..."
+//! }
+//! ```
+//!
+//! The signature covers `zkip-manifest-v1` followed by the JSON encoding of every other
+//! field, in the order above.
+
+use anyhow::{bail, Context};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::geoip::sha256_file;
+use crate::i18n::Msg;
+use crate::tr;
+
+/// Hex ed25519 public key of the release maintainer, set when building a release.
+pub const MAINTAINER_PUBLIC_KEY: Option<&str> = option_env!("ZKIP_MAINTAINER_PUBKEY");
+
+/// Domain tag prefixed to the manifest body before signing.
+const MANIFEST_DOMAIN: &[u8] = b"zkip-manifest-v1";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Artifact {
+    pub name: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestBody {
+    pub version: String,
+    pub vkey: String,
+    pub artifacts: Vec<Artifact>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseManifest {
+    #[serde(flatten)]
+    pub body: ManifestBody,
+    pub signature: String,
+}
+
+/// Manifest path used when none is given: `<elf>.manifest.json`.
+pub fn default_manifest_path(elf: &Path) -> PathBuf {
+    let mut name = elf.as_os_str().to_owned();
+    name.push(".manifest.json");
+    PathBuf::from(name)
+}
+
+fn signing_message(body: &ManifestBody) -> anyhow::Result<Vec<u8>> {
+    let mut message = MANIFEST_DOMAIN.to_vec();
+    message.extend(serde_json::to_vec(body).with_context(|| tr!(Msg::SerializeManifestFailed))?);
+    Ok(message)
+}
+
+/// Parse a hex-encoded ed25519 public key.
+pub fn parse_public_key(hex_key: &str) -> anyhow::Result<VerifyingKey> {
+    let bytes: [u8; 32] = hex::decode(hex_key.trim().trim_start_matches("0x"))
+        .with_context(|| tr!(Msg::PublicKeyNotHex))?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!(tr!(Msg::PublicKeyLength)))?;
+    VerifyingKey::from_bytes(&bytes).with_context(|| tr!(Msg::InvalidEd25519Key))
+}
+
+/// Read a hex-encoded 32-byte ed25519 seed from `path`.
+pub fn load_signing_key(path: &Path) -> anyhow::Result<SigningKey> {
+    let content = fs::read_to_string(path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+    let seed: [u8; 32] = hex::decode(content.trim().trim_start_matches("0x"))
+        .with_context(|| tr!(Msg::SigningKeyNotHex))?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!(tr!(Msg::SigningKeyLength)))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+impl ReleaseManifest {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| tr!(Msg::ManifestReadFailed, path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| tr!(Msg::ManifestParseFailed, path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .with_context(|| tr!(Msg::SerializeManifestFailed))?;
+        fs::write(path, content).with_context(|| tr!(Msg::WriteFailed, path.display()))
+    }
+
+    pub fn sign(body: ManifestBody, key: &SigningKey) -> anyhow::Result<Self> {
+        let signature = key.sign(&signing_message(&body)?);
+        Ok(Self {
+            body,
+            signature: hex::encode(signature.to_bytes()),
+        })
+    }
+
+    /// Check the signature against `public_key` (hex).
+    pub fn verify_signature(&self, public_key: &str) -> anyhow::Result<()> {
+        let key = parse_public_key(public_key)?;
+        let bytes: [u8; 64] = hex::decode(&self.signature)
+            .with_context(|| tr!(Msg::SignatureNotHex))?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!(tr!(Msg::SignatureLength)))?;
+        key.verify(
+            &signing_message(&self.body)?,
+            &Signature::from_bytes(&bytes),
+        )
+        .with_context(|| tr!(Msg::ManifestSignatureInvalid))
+    }
+
+    /// Check that the artifact at `path` is listed, by file name, with a matching hash.
+    pub fn verify_artifact(&self, path: &Path) -> anyhow::Result<()> {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .with_context(|| tr!(Msg::ArtifactNoFileName))?;
+        let Some(entry) = self.body.artifacts.iter().find(|a| a.name == name) else {
+            bail!(tr!(Msg::ArtifactNotListed, name));
+        };
+        let actual = hex::encode(sha256_file(path)?);
+        if !actual.eq_ignore_ascii_case(&entry.sha256) {
+            bail!(tr!(Msg::ArtifactHashMismatch, name, actual, entry.sha256));
+        }
+        Ok(())
+    }
+
+    pub fn verify_vkey(&self, vkey: &str) -> anyhow::Result<()> {
+        if !self.body.vkey.eq_ignore_ascii_case(vkey) {
+            bail!(tr!(Msg::ManifestVkeyMismatch, vkey, self.body.vkey));
+        }
+        Ok(())
+    }
+}
+
+/// Load the manifest for `elf` and check its signature and the ELF hash.
+pub fn verify_elf_manifest(elf: &Path, manifest: &Path) -> anyhow::Result<ReleaseManifest> {
+    let Some(public_key) = MAINTAINER_PUBLIC_KEY else {
+        bail!(tr!(Msg::NoMaintainerKey));
+    };
+    let manifest = ReleaseManifest::load(manifest)?;
+    manifest.verify_signature(public_key)?;
+    manifest.verify_artifact(elf)?;
+    Ok(manifest)
+}
@@ -0,0 +1,299 @@
+//! Exclusion policy files.
+//!
+//! A policy groups countries into named sets; the effective exclusion list is their union:
+//!
+//! ```yaml
+//! name: sanctions
+//! sets:
+//!   comprehensive: [CU, IR, KP, SY]
+//!   regional: [RU, BY]
+//! ```
+//!
+//! A policy may also define a `rule` for `zkip --policy`, evaluated in the guest. Each node
+//! has exactly one key: `and`/`or` (lists of nodes), `not` (a node), `country` (codes),
+//! `set` (a set above), `cidr` (IPv4 or IPv6 blocks) or `asn` (AS numbers):
+//!
+//! ```yaml
+//! rule:
+//!   and:
+//!     - set: regional
+//!     - not: { asn: [13335] }
+//!     - not: { cidr: ["203.0.113.0/24", "2001:db8::/32"] }
+//! ```
+
+use anyhow::Context;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::net::IpAddr;
+use std::path::Path;
+use zkip_lib::policy::{policy_id, PolicyExpr};
+use zkip_lib::range::Ipv4Range;
+
+use crate::i18n::Msg;
+use crate::tr;
+
+/// Countries with more ranges than this noticeably increase guest cycle counts.
+pub const LARGE_RANGE_COUNT: usize = 10_000;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyFile {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub sets: BTreeMap<String, Vec<String>>,
+    pub rule: Option<RuleSpec>,
+}
+
+/// One node of a policy rule; exactly one field is set.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleSpec {
+    pub and: Option<Vec<RuleSpec>>,
+    pub or: Option<Vec<RuleSpec>>,
+    pub not: Option<Box<RuleSpec>>,
+    pub country: Option<Vec<String>>,
+    pub set: Option<String>,
+    pub cidr: Option<Vec<String>>,
+    pub asn: Option<Vec<u32>>,
+}
+
+pub fn load_policy(path: &Path) -> anyhow::Result<PolicyFile> {
+    let content =
+        fs::read_to_string(path).with_context(|| tr!(Msg::PolicyReadFailed, path.display()))?;
+    serde_yaml::from_str(&content).with_context(|| tr!(Msg::PolicyParseFailed))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct LintFinding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Outcome of linting a policy.
+#[derive(Debug, Clone, Default)]
+pub struct LintReport {
+    pub findings: Vec<LintFinding>,
+    /// Effective exclusion list as `(alpha-2, numeric)`, sorted by numeric code.
+    pub countries: Vec<(String, u16)>,
+    pub hash: [u8; 32],
+    /// `PolicyExpr::hash` of the rule, if it has one that compiles.
+    pub rule_hash: Option<[u8; 32]>,
+}
+
+impl LintReport {
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|f| f.severity == Severity::Error)
+    }
+}
+
+/// Check `policy` against the known country codes and, when available, per-country range
+/// counts from the GeoIP database.
+pub fn lint_policy(
+    policy: &PolicyFile,
+    country_codes: &HashMap<String, u16>,
+    range_counts: Option<&HashMap<String, usize>>,
+) -> LintReport {
+    let mut report = LintReport::default();
+    let mut push =
+        |severity, message: String| report.findings.push(LintFinding { severity, message });
+
+    if policy.sets.is_empty() && policy.rule.is_none() {
+        push(Severity::Error, tr!(Msg::PolicyNoSets));
+    }
+    let rule_hash = match &policy.rule {
+        Some(_) => match compile_rule(policy, country_codes) {
+            Ok(expr) => Some(expr.hash()),
+            Err(e) => {
+                push(Severity::Error, e.to_string());
+                None
+            }
+        },
+        None => None,
+    };
+
+    let mut owners: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for (set, codes) in &policy.sets {
+        if codes.is_empty() {
+            push(Severity::Error, tr!(Msg::PolicyEmptySet, set));
+        }
+        let mut seen = BTreeSet::new();
+        for code in codes {
+            let code = code.trim().to_uppercase();
+            if !country_codes.contains_key(&code) {
+                push(Severity::Error, tr!(Msg::PolicyUnknownCode, set, code));
+                continue;
+            }
+            if !seen.insert(code.clone()) {
+                push(Severity::Warning, tr!(Msg::PolicyDuplicate, set, code));
+                continue;
+            }
+            owners.entry(code).or_default().push(set);
+        }
+    }
+
+    for (code, sets) in &owners {
+        if sets.len() > 1 {
+            push(
+                Severity::Warning,
+                tr!(Msg::PolicyOverlap, code, sets.join(", ")),
+            );
+        }
+        if let Some(count) = range_counts.and_then(|counts| counts.get(code)) {
+            if *count > LARGE_RANGE_COUNT {
+                push(Severity::Warning, tr!(Msg::PolicyLargeCountry, code, count));
+            }
+        }
+    }
+
+    let mut countries: Vec<(String, u16)> = owners
+        .into_keys()
+        .map(|code| {
+            let numeric = country_codes[&code];
+            (code, numeric)
+        })
+        .collect();
+    countries.sort_by_key(|(_, numeric)| *numeric);
+
+    let numeric: Vec<u16> = countries.iter().map(|(_, n)| *n).collect();
+    report.hash = policy_hash(&numeric);
+    report.countries = countries;
+    report.rule_hash = rule_hash;
+    report
+}
+
+/// Compile the policy's rule into the expression the guest evaluates.
+pub fn compile_rule(
+    policy: &PolicyFile,
+    country_codes: &HashMap<String, u16>,
+) -> anyhow::Result<PolicyExpr> {
+    let rule = policy.rule.as_ref().context(tr!(Msg::PolicyNoRule))?;
+    compile_node(rule, policy, country_codes)
+}
+
+fn compile_node(
+    node: &RuleSpec,
+    policy: &PolicyFile,
+    country_codes: &HashMap<String, u16>,
+) -> anyhow::Result<PolicyExpr> {
+    let compile_all = |nodes: &[RuleSpec]| {
+        nodes
+            .iter()
+            .map(|node| compile_node(node, policy, country_codes))
+            .collect::<anyhow::Result<Vec<_>>>()
+    };
+    let countries = |codes: &[String]| {
+        codes
+            .iter()
+            .map(|code| {
+                let code = code.trim().to_uppercase();
+                country_codes
+                    .get(&code)
+                    .map(|&numeric| PolicyExpr::Country(numeric))
+                    .with_context(|| tr!(Msg::PolicyRuleUnknownCode, code))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map(PolicyExpr::Any)
+    };
+
+    let RuleSpec {
+        and,
+        or,
+        not,
+        country,
+        set,
+        cidr,
+        asn,
+    } = node;
+    let keys = [
+        and.is_some(),
+        or.is_some(),
+        not.is_some(),
+        country.is_some(),
+        set.is_some(),
+        cidr.is_some(),
+        asn.is_some(),
+    ];
+    if keys.iter().filter(|&&key| key).count() != 1 {
+        anyhow::bail!(tr!(Msg::PolicyRuleNode));
+    }
+
+    if let Some(nodes) = and {
+        return compile_all(nodes).map(PolicyExpr::All);
+    }
+    if let Some(nodes) = or {
+        return compile_all(nodes).map(PolicyExpr::Any);
+    }
+    if let Some(node) = not {
+        let expr = compile_node(node, policy, country_codes)?;
+        return Ok(PolicyExpr::Not(Box::new(expr)));
+    }
+    if let Some(codes) = country {
+        return countries(codes);
+    }
+    if let Some(name) = set {
+        let codes = policy
+            .sets
+            .get(name)
+            .with_context(|| tr!(Msg::PolicyUnknownSet, name))?;
+        return countries(codes);
+    }
+    if let Some(blocks) = cidr {
+        let mut ranges = Vec::new();
+        let mut ranges_v6 = Vec::new();
+        for block in blocks {
+            match parse_cidr(block).with_context(|| tr!(Msg::PolicyInvalidCidr, block))? {
+                (IpAddr::V4(start), IpAddr::V4(end)) => ranges.push((start.into(), end.into())),
+                (IpAddr::V6(start), IpAddr::V6(end)) => ranges_v6.push((start.into(), end.into())),
+                _ => unreachable!("CIDR bounds share a family"),
+            }
+        }
+        return Ok(PolicyExpr::Cidr(ranges, ranges_v6));
+    }
+    let asns = asn.as_deref().unwrap_or_default();
+    let asns = asns.iter().copied().map(PolicyExpr::Asn).collect();
+    Ok(PolicyExpr::Any(asns))
+}
+
+/// First and last address of `block`, an address with an optional `/prefix`. Host bits
+/// below the prefix are ignored.
+fn parse_cidr(block: &str) -> anyhow::Result<(IpAddr, IpAddr)> {
+    let (addr, prefix) = match block.trim().split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix.parse::<u32>()?)),
+        None => (block.trim(), None),
+    };
+    Ok(match addr.parse::<IpAddr>()? {
+        IpAddr::V4(addr) => {
+            let prefix = prefix.unwrap_or(32);
+            let range = u8::try_from(prefix)
+                .ok()
+                .and_then(|prefix| Ipv4Range::from_cidr(addr.into(), prefix))
+                .with_context(|| tr!(Msg::PrefixTooLong, 32))?;
+            (
+                IpAddr::V4(range.start().into()),
+                IpAddr::V4(range.end().into()),
+            )
+        }
+        IpAddr::V6(addr) => {
+            let prefix = prefix.unwrap_or(128);
+            anyhow::ensure!(prefix <= 128, tr!(Msg::PrefixTooLong, 128));
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            let start = u128::from(addr) & mask;
+            (IpAddr::V6(start.into()), IpAddr::V6((start | !mask).into()))
+        }
+    })
+}
+
+/// Canonical hash of an exclusion list, `zkip_lib::policy::policy_id` of its numeric codes.
+/// Independent of set names, ordering and duplicates, so equivalent policies hash the same,
+/// and it's the ID plain proofs over those countries commit.
+pub fn policy_hash(numeric_codes: &[u16]) -> [u8; 32] {
+    policy_id(numeric_codes)
+}
@@ -0,0 +1,147 @@
+//! Registry of released program versions and their verification keys.
+//!
+//! Every guest change produces a new vkey, but proofs from earlier versions stay in
+//! circulation. `vkeys/registry.toml` lists each released version and variant with its vkey
+//! and the full verifying key needed to check proofs offline; revoked versions stay listed
+//! so `zkip verify` can say why a proof is rejected.
+//!
+//! ```toml
+//! [[program]]
+//! version = "0.1.0"
+//! variant = "linear"
+//! vkey = "0x00ab..."
+//! vk_file = "0.1.0-linear.json"
+//! revoked = false
+//! ```
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use sp1_sdk::SP1VerifyingKey;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::Msg;
+use crate::tr;
+use crate::variants::ProgramVariant;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Registry {
+    #[serde(default, rename = "program")]
+    pub programs: Vec<ProgramVersion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgramVersion {
+    pub version: String,
+    #[serde(default)]
+    pub variant: ProgramVariant,
+    pub vkey: String,
+    /// Serialized `SP1VerifyingKey`, relative to the registry directory.
+    pub vk_file: String,
+    #[serde(default)]
+    pub revoked: bool,
+}
+
+pub fn registry_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../vkeys")
+}
+
+fn registry_file(dir: &Path) -> PathBuf {
+    dir.join("registry.toml")
+}
+
+impl Registry {
+    /// Load the registry in `dir`, or an empty one if it does not exist yet.
+    pub fn load(dir: &Path) -> anyhow::Result<Self> {
+        let path = registry_file(dir);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content =
+            fs::read_to_string(&path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+        toml::from_str(&content).with_context(|| tr!(Msg::ParseFailed, path.display()))
+    }
+
+    pub fn save(&self, dir: &Path) -> anyhow::Result<()> {
+        fs::create_dir_all(dir).with_context(|| tr!(Msg::CreateFailed, dir.display()))?;
+        let path = registry_file(dir);
+        let content =
+            toml::to_string_pretty(self).with_context(|| tr!(Msg::SerializeRegistryFailed))?;
+        fs::write(&path, content).with_context(|| tr!(Msg::WriteFailed, path.display()))
+    }
+
+    pub fn find_version(&self, version: &str, variant: ProgramVariant) -> Option<&ProgramVersion> {
+        self.programs
+            .iter()
+            .find(|p| p.version == version && p.variant == variant)
+    }
+
+    pub fn find_vkey(&self, vkey: &str) -> Option<&ProgramVersion> {
+        self.programs
+            .iter()
+            .find(|p| p.vkey.eq_ignore_ascii_case(vkey))
+    }
+
+    /// Register `version` of `variant` with its verifying key, writing the key next to the
+    /// registry. Returns `false` if the same version, variant and vkey are already registered.
+    pub fn add(
+        &mut self,
+        dir: &Path,
+        version: &str,
+        variant: ProgramVariant,
+        vkey: &str,
+        vk: &SP1VerifyingKey,
+    ) -> anyhow::Result<bool> {
+        if let Some(existing) = self.find_version(version, variant) {
+            if existing.vkey.eq_ignore_ascii_case(vkey) {
+                return Ok(false);
+            }
+            bail!(tr!(
+                Msg::VersionRegistered,
+                version,
+                variant.id(),
+                existing.vkey
+            ));
+        }
+        if let Some(existing) = self.find_vkey(vkey) {
+            bail!(tr!(Msg::VkeyRegistered, vkey, existing.version));
+        }
+
+        fs::create_dir_all(dir).with_context(|| tr!(Msg::CreateFailed, dir.display()))?;
+        let vk_file = format!("{}-{}.json", version, variant.id());
+        let vk_json = serde_json::to_string(vk).with_context(|| tr!(Msg::SerializeVkeyFailed))?;
+        fs::write(dir.join(&vk_file), vk_json).with_context(|| tr!(Msg::WriteFailed, vk_file))?;
+
+        self.programs.push(ProgramVersion {
+            version: version.to_string(),
+            variant,
+            vkey: vkey.to_string(),
+            vk_file,
+            revoked: false,
+        });
+        Ok(true)
+    }
+
+    /// Mark every variant of `version` as revoked. Returns `false` if all already were.
+    pub fn revoke(&mut self, version: &str) -> anyhow::Result<bool> {
+        let mut found = false;
+        let mut changed = false;
+        for entry in self.programs.iter_mut().filter(|p| p.version == version) {
+            found = true;
+            changed |= !entry.revoked;
+            entry.revoked = true;
+        }
+        if !found {
+            bail!(tr!(Msg::VersionNotRegistered, version));
+        }
+        Ok(changed)
+    }
+}
+
+/// Read the verifying key of a registered version.
+pub fn load_verifying_key(dir: &Path, entry: &ProgramVersion) -> anyhow::Result<SP1VerifyingKey> {
+    let path = dir.join(&entry.vk_file);
+    let content =
+        fs::read_to_string(&path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+    serde_json::from_str(&content).with_context(|| tr!(Msg::VkeyParseFailed, path.display()))
+}
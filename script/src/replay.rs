@@ -0,0 +1,88 @@
+//! Replay protection for off-chain verification.
+//!
+//! A verified proof is recorded as consumed until its TTL runs out; presenting it again
+//! within that window is rejected. Entries are keyed by what the proof commits, not by
+//! its bytes, so wrapping or re-serializing it does not reset the window (`replay_key`).
+//! The store is a JSON map from key to expiry (Unix seconds), pruned and atomically
+//! rewritten on every insert.
+
+use anyhow::{bail, Context};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use zkip_lib::decode_public_values;
+
+use crate::i18n::Msg;
+use crate::tr;
+
+/// Key a verified proof is consumed under: its nullifier if it commits one, else the
+/// challenge it is bound to, else the SHA-256 of its public values.
+pub fn replay_key(public_values: &[u8]) -> String {
+    let decoded = decode_public_values(public_values).ok();
+    let nullifier = decoded
+        .as_ref()
+        .and_then(|decoded| decoded.nullifier.as_ref())
+        .map(|nullifier| nullifier.nullifier)
+        .filter(|&nullifier| nullifier != [0; 32]);
+    let challenge = decoded.and_then(|decoded| decoded.challenge);
+    match (nullifier, challenge) {
+        (Some(nullifier), _) => format!("nullifier:0x{}", hex::encode(nullifier)),
+        (None, Some(challenge)) => format!("challenge:0x{}", hex::encode(challenge)),
+        (None, None) => format!("values:0x{}", hex::encode(Sha256::digest(public_values))),
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ReplayStore {
+    path: PathBuf,
+    /// Key to expiry, Unix seconds.
+    consumed: BTreeMap<String, u64>,
+}
+
+impl ReplayStore {
+    /// Open the store at `path`, or an empty one if it does not exist yet.
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let consumed = if path.exists() {
+            let content =
+                fs::read_to_string(path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+            serde_json::from_str(&content).with_context(|| tr!(Msg::ParseFailed, path.display()))?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            consumed,
+        })
+    }
+
+    /// Expiry of `key` if it was consumed and has not expired at `now`.
+    pub fn consumed_until(&self, key: &str, now: u64) -> Option<u64> {
+        self.consumed
+            .get(key)
+            .copied()
+            .filter(|&expires| expires > now)
+    }
+
+    /// Record `key` as consumed for `ttl` seconds, failing if it already is.
+    pub fn consume(&mut self, key: &str, now: u64, ttl: u64) -> anyhow::Result<()> {
+        if let Some(expires) = self.consumed_until(key, now) {
+            bail!(tr!(Msg::ReplayAccepted, key, expires));
+        }
+        self.consumed.retain(|_, &mut expires| expires > now);
+        self.consumed
+            .insert(key.to_string(), now.saturating_add(ttl));
+        self.save()
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| tr!(Msg::CreateFailed, parent.display()))?;
+        }
+        let content = serde_json::to_string_pretty(&self.consumed)
+            .with_context(|| tr!(Msg::SerializeReplayFailed))?;
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, content).with_context(|| tr!(Msg::WriteFailed, tmp.display()))?;
+        fs::rename(&tmp, &self.path).with_context(|| tr!(Msg::ReplaceFailed, self.path.display()))
+    }
+}
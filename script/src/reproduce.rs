@@ -0,0 +1,54 @@
+//! Rebuild the guest ELF in SP1's pinned docker image for `zkip vkey --reproduce`.
+//!
+//! The docker build uses a fixed toolchain, so anyone with the same source gets a
+//! byte-identical ELF and therefore the same vkey as the published one.
+
+use anyhow::{bail, Context};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::info;
+
+use crate::i18n::Msg;
+use crate::tr;
+
+/// SP1 docker image tag; keep in step with the `sp1-sdk`/`sp1-build` versions.
+pub const SP1_DOCKER_TAG: &str = "v5.0.8";
+
+pub fn program_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../program")
+}
+
+/// Build guest binary `binary` with `cargo prove build --docker` into `out_dir` and return
+/// the ELF path.
+pub fn build_guest_in_docker(out_dir: &Path, binary: &str) -> anyhow::Result<PathBuf> {
+    fs::create_dir_all(out_dir).with_context(|| tr!(Msg::CreateFailed, out_dir.display()))?;
+    info!("{}", tr!(Msg::VkeyRebuilding, SP1_DOCKER_TAG));
+
+    let status = Command::new("cargo")
+        .args([
+            "prove",
+            "build",
+            "--docker",
+            "--tag",
+            SP1_DOCKER_TAG,
+            "--binaries",
+            binary,
+            "--elf-name",
+            binary,
+        ])
+        .arg("--output-directory")
+        .arg(out_dir)
+        .current_dir(program_dir())
+        .status()
+        .with_context(|| tr!(Msg::CargoProveFailed))?;
+    if !status.success() {
+        bail!(tr!(Msg::CargoProveExited, status));
+    }
+
+    let elf = out_dir.join(binary);
+    if !elf.exists() {
+        bail!(tr!(Msg::DockerNoElf, elf.display()));
+    }
+    Ok(elf)
+}
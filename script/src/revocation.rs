@@ -0,0 +1,169 @@
+//! Signed revocation list for relying parties.
+//!
+//! Operators revoke proofs issued under a bad GeoIP snapshot or from a compromised host by
+//! listing their IDs (the `proof_id` recorded in the audit log), or every proof committing
+//! a nullifier, which a wrapped or re-serialized proof keeps. The file is signed with the
+//! operator's ed25519 key so relying parties can pin that key and consume the list from
+//! untrusted storage:
+//!
+//! ```json
+//! {
+//!   "entries": [
+//!     { "id": "3f1c...", "reason": "bad snapshot", "revoked_at": 1700000000 },
+//!     { "id": "a2e9...", "kind": "nullifier", "revoked_at": 1700000000 }
+//!   ],
+//!   "signer": "d75a...",
+//!   "signature": "9b04..."
+//! }
+//! ```
+//!
+//! The signature covers `zkip-revocations-v1` followed by the JSON of `entries`.
+
+use anyhow::{bail, Context};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::Msg;
+use crate::manifest::parse_public_key;
+use crate::tr;
+
+/// Domain tag prefixed to the entries before signing.
+const REVOCATION_DOMAIN: &[u8] = b"zkip-revocations-v1";
+
+pub fn default_revocation_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../data/revocations.json")
+}
+
+/// What a revocation's `id` names.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RevocationKind {
+    /// A proof ID.
+    #[default]
+    ProofId,
+    /// A nullifier, revoking every proof that commits it.
+    Nullifier,
+}
+
+impl RevocationKind {
+    /// Proof IDs are left out of the JSON, so lists signed before nullifiers still verify.
+    fn is_proof_id(&self) -> bool {
+        *self == Self::ProofId
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Revocation {
+    /// Revoked proof ID or nullifier, lowercase hex.
+    pub id: String,
+    #[serde(default, skip_serializing_if = "RevocationKind::is_proof_id")]
+    pub kind: RevocationKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub revoked_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevocationList {
+    pub entries: Vec<Revocation>,
+    /// Hex ed25519 public key of the operator.
+    pub signer: String,
+    pub signature: String,
+}
+
+fn signing_message(entries: &[Revocation]) -> anyhow::Result<Vec<u8>> {
+    let mut message = REVOCATION_DOMAIN.to_vec();
+    message
+        .extend(serde_json::to_vec(entries).with_context(|| tr!(Msg::SerializeRevocationsFailed))?);
+    Ok(message)
+}
+
+/// Lowercase hex without a `0x` prefix, for comparing IDs and keys.
+fn normalize_hex(id: &str) -> String {
+    id.trim().trim_start_matches("0x").to_lowercase()
+}
+
+impl RevocationList {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content =
+            fs::read_to_string(path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+        serde_json::from_str(&content).with_context(|| tr!(Msg::ParseFailed, path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| tr!(Msg::CreateFailed, parent.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .with_context(|| tr!(Msg::SerializeRevocationsFailed))?;
+        fs::write(path, content).with_context(|| tr!(Msg::WriteFailed, path.display()))
+    }
+
+    /// Sign `entries` with `key`.
+    pub fn sign(entries: Vec<Revocation>, key: &SigningKey) -> anyhow::Result<Self> {
+        let signature = key.sign(&signing_message(&entries)?);
+        Ok(Self {
+            entries,
+            signer: hex::encode(key.verifying_key().to_bytes()),
+            signature: hex::encode(signature.to_bytes()),
+        })
+    }
+
+    /// Check the signature. With `trusted_key`, the list must also be signed by that key.
+    pub fn verify(&self, trusted_key: Option<&str>) -> anyhow::Result<()> {
+        if let Some(trusted) = trusted_key {
+            if normalize_hex(trusted) != normalize_hex(&self.signer) {
+                bail!(tr!(Msg::RevocationSigner, self.signer));
+            }
+        }
+        let key = parse_public_key(&self.signer)?;
+        let bytes: [u8; 64] = hex::decode(&self.signature)
+            .with_context(|| tr!(Msg::SignatureNotHex))?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!(tr!(Msg::SignatureLength)))?;
+        key.verify(
+            &signing_message(&self.entries)?,
+            &Signature::from_bytes(&bytes),
+        )
+        .with_context(|| tr!(Msg::RevocationSignatureInvalid))
+    }
+
+    pub fn find(&self, id: &str, kind: RevocationKind) -> Option<&Revocation> {
+        let id = normalize_hex(id);
+        self.entries
+            .iter()
+            .find(|entry| entry.id == id && entry.kind == kind)
+    }
+}
+
+/// Add `id` to the list at `path` (creating it if needed) and re-sign it with `key`.
+/// Returns `false` if `id` was already revoked.
+pub fn revoke(
+    path: &Path,
+    key: &SigningKey,
+    id: &str,
+    kind: RevocationKind,
+    reason: Option<String>,
+    revoked_at: u64,
+) -> anyhow::Result<bool> {
+    let mut entries = if path.exists() {
+        let list = RevocationList::load(path)?;
+        list.verify(Some(&hex::encode(key.verifying_key().to_bytes())))?;
+        if list.find(id, kind).is_some() {
+            return Ok(false);
+        }
+        list.entries
+    } else {
+        Vec::new()
+    };
+    entries.push(Revocation {
+        id: normalize_hex(id),
+        kind,
+        reason,
+        revoked_at,
+    });
+    RevocationList::sign(entries, key)?.save(path)?;
+    Ok(true)
+}
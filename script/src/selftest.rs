@@ -0,0 +1,56 @@
+//! Known-answer vectors for `zkip selftest`.
+//!
+//! The vectors run against `data/selftest.csv`, a handful of hand-picked ranges in the
+//! same `start,end,CC` format as the GeoIP cache, so the check needs no network access.
+
+use std::path::PathBuf;
+
+/// Fixed timestamp committed by every selftest run.
+pub const SELFTEST_TIMESTAMP: u64 = 1_700_000_000;
+
+/// A single known-answer case.
+#[derive(Debug, Clone, Copy)]
+pub struct SelftestVector {
+    pub ip: &'static str,
+    pub exclude: &'static str,
+    pub expected: bool,
+    /// When set, also disclose the region and expect this UN M49 code.
+    pub region: Option<u16>,
+}
+
+pub const SELFTEST_VECTORS: &[SelftestVector] = &[
+    SelftestVector {
+        ip: "8.8.8.8",
+        exclude: "FR",
+        expected: true,
+        region: None,
+    },
+    SelftestVector {
+        ip: "2.2.2.2",
+        exclude: "FR",
+        expected: false,
+        region: None,
+    },
+    SelftestVector {
+        ip: "90.1.2.3",
+        exclude: "FR,US",
+        expected: false,
+        region: Some(150),
+    },
+    SelftestVector {
+        ip: "8.8.8.8",
+        exclude: "FR,US",
+        expected: false,
+        region: Some(19),
+    },
+    SelftestVector {
+        ip: "1.1.1.1",
+        exclude: "FR,US",
+        expected: true,
+        region: None,
+    },
+];
+
+pub fn selftest_dataset_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../data/selftest.csv")
+}
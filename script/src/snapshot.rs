@@ -0,0 +1,355 @@
+//! Published commitments to a GeoIP snapshot, for `zkip db root`.
+//!
+//! Verifiers pin the per-country roots of a snapshot instead of trusting each host's CSV
+//! download. The manifest records which snapshots and scheme the roots came from, so a
+//! verifier can recompute the dataset root a proof must commit for any subset of countries.
+//!
+//! A publisher can also sign the manifest's root with `db root --sign`. Provers pass the
+//! signed manifest with `--signed-snapshot`, and the guest checks their countries' ranges
+//! are in it (`zkip_lib::publisher`), so verifiers only need to trust the publisher's key.
+
+use anyhow::Context;
+use ed25519_dalek::{Signer, SigningKey};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use zkip_lib::country::CountryCode;
+use zkip_lib::geo::{CountryRanges, GeoDb};
+use zkip_lib::merkle::{
+    country_leaf, country_leaves, country_set_root, dataset_root, leaves_root, ranges_root,
+    ranges_root_v6, split_groups, MerkleTree,
+};
+use zkip_lib::publisher::{snapshot_message, SnapshotSignature};
+use zkip_lib::snapshot::content_hash;
+use zkip_lib::{pack_ranges, pack_ranges_v6, ranges_digest};
+
+use crate::geoip::{load_ranges_by_country, sha256_file, GEOIP_PROVIDER, GEOIP_URL, GEOIP_V6_URL};
+use crate::i18n::Msg;
+use crate::manifest::{parse_public_key, MAINTAINER_PUBLIC_KEY};
+use crate::tr;
+
+/// Identifies the leaf/node hashing in `zkip_lib::merkle`.
+pub const ROOT_SCHEME: &str = "zkip-ranges-sha256-v2";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountryRoot {
+    /// ISO 3166-1 alpha-2 code.
+    pub country: String,
+    /// ISO 3166-1 numeric code, as committed in `excluded_countries`.
+    pub code: u16,
+    pub ranges: usize,
+    pub root: String,
+    pub ranges_v6: usize,
+    pub root_v6: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RootManifest {
+    pub scheme: String,
+    pub provider: String,
+    pub source: String,
+    pub snapshot_sha256: String,
+    pub source_v6: String,
+    pub snapshot_v6_sha256: String,
+    pub excluded_countries: Vec<u16>,
+    pub ranges: usize,
+    pub ranges_v6: usize,
+    /// Dataset root of the whole set, as its proofs commit it.
+    pub root: String,
+    /// `zkip_lib::ranges_digest` of the whole set, in this country order. Absent from
+    /// manifests written before plain proofs committed it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ranges_digest: Option<String>,
+    /// `zkip_lib::snapshot::content_hash` of the two snapshots, as plain proofs over them
+    /// commit it. Absent from manifests written before they did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_hash: Option<String>,
+    pub countries: Vec<CountryRoot>,
+    pub generated_at: u64,
+    /// Publisher's signature over `root`, from `db root --sign`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<PublisherSignature>,
+}
+
+/// A publisher's Ed25519 signature over a manifest's root (`zkip_lib::publisher`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublisherSignature {
+    pub pubkey: String,
+    pub published_at: u64,
+    pub signature: String,
+}
+
+/// Each excluded country's canonical ranges in turn, as the guest reads them.
+#[derive(Debug, Clone, Default)]
+pub struct ExcludedRanges {
+    pub ranges: Vec<(u32, u32)>,
+    pub ranges_v6: Vec<(u128, u128)>,
+    /// `(IPv4, IPv6)` range counts per country, in order.
+    pub counts: Vec<(u32, u32)>,
+}
+
+impl ExcludedRanges {
+    /// Load the ranges of `countries`, in that order, from the snapshot at `geoip_path` and
+    /// the IPv6 snapshot at `geoip_v6_path`. Without one, every country has no IPv6 ranges.
+    pub fn load(
+        geoip_path: &Path,
+        geoip_v6_path: Option<&Path>,
+        countries: &[String],
+    ) -> anyhow::Result<Self> {
+        let by_country = load_ranges_by_country::<u32>(geoip_path, countries)?;
+        let by_country_v6 = match geoip_v6_path {
+            Some(path) => load_ranges_by_country::<u128>(path, countries)?,
+            None => Default::default(),
+        };
+
+        // Countries listed twice get their ranges twice, like their codes in the public values
+        let mut excluded = Self::default();
+        for country in countries {
+            let mut group = by_country.get(country).cloned().unwrap_or_default();
+            group.sort_unstable();
+            group.dedup();
+            let mut group_v6 = by_country_v6.get(country).cloned().unwrap_or_default();
+            group_v6.sort_unstable();
+            group_v6.dedup();
+            excluded
+                .counts
+                .push((group.len() as u32, group_v6.len() as u32));
+            excluded.ranges.extend(group);
+            excluded.ranges_v6.extend(group_v6);
+        }
+        Ok(excluded)
+    }
+
+    /// The ranges of `excluded_countries` (numeric codes), in that order, from a database
+    /// already in memory. Fails on a code outside the ISO table.
+    pub fn from_geo_db(db: &GeoDb, excluded_countries: &[u16]) -> anyhow::Result<Self> {
+        let countries = excluded_countries
+            .iter()
+            .map(|&code| {
+                CountryCode::from_numeric(code).with_context(|| tr!(Msg::UnknownCountryCode, code))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let CountryRanges {
+            ranges,
+            ranges_v6,
+            counts,
+        } = db.ranges_for(&countries);
+        Ok(Self {
+            ranges,
+            ranges_v6,
+            counts,
+        })
+    }
+
+    /// The dataset root a guest commits for these ranges and `excluded_countries`.
+    pub fn root(&self, excluded_countries: &[u16]) -> [u8; 32] {
+        dataset_root(
+            excluded_countries,
+            &self.ranges,
+            &self.ranges_v6,
+            &self.counts,
+        )
+    }
+
+    /// The country leaves under `root`, which a signed snapshot must hold.
+    pub fn country_leaves(&self, excluded_countries: &[u16]) -> Vec<[u8; 32]> {
+        country_leaves(
+            excluded_countries,
+            &self.ranges,
+            &self.ranges_v6,
+            &self.counts,
+        )
+    }
+
+    /// The ranges digest a plain proof commits for these ranges and `excluded_countries`.
+    pub fn digest(&self, excluded_countries: &[u16]) -> [u8; 32] {
+        ranges_digest(
+            excluded_countries,
+            &self.counts,
+            &pack_ranges(&self.ranges),
+            &pack_ranges_v6(&self.ranges_v6),
+        )
+    }
+}
+
+fn hex_root(root: [u8; 32]) -> String {
+    format!("0x{}", hex::encode(root))
+}
+
+fn parse_root(root: &str) -> anyhow::Result<[u8; 32]> {
+    hex::decode(root.trim().trim_start_matches("0x"))
+        .with_context(|| tr!(Msg::InvalidRoot, root))?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!(tr!(Msg::RootLength, root)))
+}
+
+/// Compute the dataset root and per-country roots of `alpha2_codes` (with their numeric
+/// `codes`, in the same order) over the snapshots at `geoip_path` and `geoip_v6_path`.
+pub fn root_manifest(
+    geoip_path: &Path,
+    geoip_v6_path: &Path,
+    alpha2_codes: &[String],
+    codes: &[u16],
+    generated_at: u64,
+) -> anyhow::Result<RootManifest> {
+    let excluded = ExcludedRanges::load(geoip_path, Some(geoip_v6_path), alpha2_codes)?;
+    let groups = split_groups(&excluded.ranges, excluded.counts.iter().map(|c| c.0));
+    let groups_v6 = split_groups(&excluded.ranges_v6, excluded.counts.iter().map(|c| c.1));
+    let mut countries = Vec::new();
+    for ((alpha2, &code), (group, group_v6)) in alpha2_codes
+        .iter()
+        .zip(codes)
+        .zip(groups.into_iter().zip(groups_v6))
+    {
+        countries.push(CountryRoot {
+            country: alpha2.clone(),
+            code,
+            ranges: group.len(),
+            root: hex_root(ranges_root(group)),
+            ranges_v6: group_v6.len(),
+            root_v6: hex_root(ranges_root_v6(group_v6)),
+        });
+    }
+
+    let (sha256, sha256_v6) = (sha256_file(geoip_path)?, sha256_file(geoip_v6_path)?);
+    Ok(RootManifest {
+        scheme: ROOT_SCHEME.to_string(),
+        provider: GEOIP_PROVIDER.to_string(),
+        source: GEOIP_URL.to_string(),
+        snapshot_sha256: hex::encode(sha256),
+        source_v6: GEOIP_V6_URL.to_string(),
+        snapshot_v6_sha256: hex::encode(sha256_v6),
+        excluded_countries: codes.to_vec(),
+        ranges: excluded.ranges.len(),
+        ranges_v6: excluded.ranges_v6.len(),
+        root: hex_root(excluded.root(codes)),
+        ranges_digest: Some(hex_root(excluded.digest(codes))),
+        snapshot_hash: Some(hex_root(content_hash(&sha256, &sha256_v6))),
+        countries,
+        generated_at,
+        publisher: None,
+    })
+}
+
+/// Parse a publisher's hex Ed25519 key, or `maintainer` for the key this build trusts.
+pub fn parse_publisher(value: &str) -> anyhow::Result<[u8; 32]> {
+    let key = match value.trim() {
+        "maintainer" => MAINTAINER_PUBLIC_KEY.with_context(|| tr!(Msg::NoPublisherKey))?,
+        key => key,
+    };
+    Ok(parse_public_key(key)?.to_bytes())
+}
+
+impl RootManifest {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content =
+            fs::read_to_string(path).with_context(|| tr!(Msg::ReadFailed, path.display()))?;
+        let manifest: Self = serde_json::from_str(&content)
+            .with_context(|| tr!(Msg::ParseFailed, path.display()))?;
+        if manifest.scheme != ROOT_SCHEME {
+            anyhow::bail!(tr!(
+                Msg::RootScheme,
+                path.display(),
+                manifest.scheme,
+                ROOT_SCHEME
+            ));
+        }
+        Ok(manifest)
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let content =
+            serde_json::to_string_pretty(self).with_context(|| tr!(Msg::SerializeRootsFailed))?;
+        fs::write(path, content).with_context(|| tr!(Msg::WriteFailed, path.display()))
+    }
+
+    /// Leaves of `root`: every country's leaf, in manifest order.
+    fn leaves(&self) -> anyhow::Result<Vec<[u8; 32]>> {
+        self.countries
+            .iter()
+            .map(|country| {
+                let root = parse_root(&country.root)?;
+                let root_v6 = parse_root(&country.root_v6)?;
+                Ok(country_leaf(country.code, &root, &root_v6))
+            })
+            .collect()
+    }
+
+    /// Sign the root over this manifest's countries as of `published_at`.
+    pub fn sign(&mut self, key: &SigningKey, published_at: u64) -> anyhow::Result<()> {
+        let root = leaves_root(&self.leaves()?);
+        let signature = key.sign(&snapshot_message(&root, published_at));
+        self.publisher = Some(PublisherSignature {
+            pubkey: hex::encode(key.verifying_key().to_bytes()),
+            published_at,
+            signature: hex::encode(signature.to_bytes()),
+        });
+        Ok(())
+    }
+
+    /// The guest input showing `excluded_countries` are in this signed snapshot, at most
+    /// `max_age` seconds old. Fails if the manifest is unsigned, its signature does not
+    /// cover its countries' roots, or it lacks one of the countries.
+    pub fn snapshot_signature(
+        &self,
+        excluded_countries: &[u16],
+        max_age: u64,
+    ) -> anyhow::Result<SnapshotSignature> {
+        let publisher = self
+            .publisher
+            .as_ref()
+            .with_context(|| tr!(Msg::RootsUnsigned))?;
+        let tree = MerkleTree::new(self.leaves()?);
+        let paths = excluded_countries
+            .iter()
+            .map(|&code| {
+                let index = self
+                    .countries
+                    .iter()
+                    .position(|country| country.code == code)
+                    .with_context(|| tr!(Msg::RootsMissingCountry, code))?;
+                Ok((index as u32, tree.path(index)))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let snapshot = SnapshotSignature {
+            pubkey: parse_public_key(&publisher.pubkey)?.to_bytes(),
+            signature: hex::decode(publisher.signature.trim_start_matches("0x"))
+                .with_context(|| tr!(Msg::PublisherSignatureNotHex))?,
+            snapshot_root: tree.root(),
+            country_count: tree.leaf_count() as u32,
+            published_at: publisher.published_at,
+            max_age,
+            paths,
+        };
+        let expected: Vec<_> = snapshot
+            .paths
+            .iter()
+            .map(|&(index, _)| tree.leaves()[index as usize])
+            .collect();
+        if !snapshot.verify(&expected) {
+            anyhow::bail!(tr!(Msg::RootsSignatureInvalid));
+        }
+        Ok(snapshot)
+    }
+
+    /// The dataset root a proof over this snapshot must commit for `excluded_countries`,
+    /// from the per-country roots. Fails if the manifest lacks one of the countries.
+    pub fn expected_root(&self, excluded_countries: &[u16]) -> anyhow::Result<[u8; 32]> {
+        let countries = excluded_countries
+            .iter()
+            .map(|&code| {
+                let country = self
+                    .countries
+                    .iter()
+                    .find(|country| country.code == code)
+                    .with_context(|| tr!(Msg::RootsMissingCountry, code))?;
+                Ok((
+                    code,
+                    parse_root(&country.root)?,
+                    parse_root(&country.root_v6)?,
+                ))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(country_set_root(&countries))
+    }
+}
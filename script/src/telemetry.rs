@@ -0,0 +1,76 @@
+//! Opt-in anonymous usage counters.
+//!
+//! Nothing is sent unless `ZKIP_TELEMETRY=1` and `ZKIP_TELEMETRY_ENDPOINT` are both set.
+//! Events carry no IP, country list, timestamp or identifier: only the proof system, a
+//! coarse cycle bucket and whether the run succeeded. Building without the `telemetry`
+//! feature removes the reporting code entirely.
+
+use serde::Serialize;
+
+/// One run's contribution to the aggregate counters.
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageEvent {
+    pub count: u32,
+    pub proof_system: &'static str,
+    pub cycle_bucket: Option<&'static str>,
+    pub success: bool,
+    pub version: &'static str,
+}
+
+impl UsageEvent {
+    pub fn new(proof_system: &'static str) -> Self {
+        Self {
+            count: 1,
+            proof_system,
+            cycle_bucket: None,
+            success: false,
+            version: env!("CARGO_PKG_VERSION"),
+        }
+    }
+}
+
+/// Coarse, order-of-magnitude bucket for a cycle count.
+pub fn cycle_bucket(cycles: u64) -> &'static str {
+    match cycles {
+        0..=999_999 => "<1M",
+        1_000_000..=9_999_999 => "1M-10M",
+        10_000_000..=99_999_999 => "10M-100M",
+        100_000_000..=999_999_999 => "100M-1B",
+        _ => ">=1B",
+    }
+}
+
+/// Send `event` if the user opted in. Failures are logged at debug level and ignored.
+#[cfg(feature = "telemetry")]
+pub fn report(event: &UsageEvent) {
+    let enabled = std::env::var("ZKIP_TELEMETRY")
+        .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "on"))
+        .unwrap_or(false);
+    if !enabled {
+        return;
+    }
+    let Ok(endpoint) = std::env::var("ZKIP_TELEMETRY_ENDPOINT") else {
+        tracing::warn!("ZKIP_TELEMETRY is set but ZKIP_TELEMETRY_ENDPOINT is not; skipping");
+        return;
+    };
+
+    let result = serde_json::to_string(event)
+        .map_err(anyhow::Error::from)
+        .and_then(|body| {
+            reqwest::blocking::Client::builder()
+                .timeout(std::time::Duration::from_secs(2))
+                .build()?
+                .post(&endpoint)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body)
+                .send()?
+                .error_for_status()?;
+            Ok(())
+        });
+    if let Err(e) = result {
+        tracing::debug!("Failed to send usage metrics: {}", e);
+    }
+}
+
+#[cfg(not(feature = "telemetry"))]
+pub fn report(_event: &UsageEvent) {}
@@ -0,0 +1,291 @@
+//! Where the committed proof timestamp comes from.
+//!
+//! By default the local clock is used as-is. With `--time-source ntp` the host queries
+//! several NTP servers (SNTPv4), requires them to agree with each other, and refuses to
+//! prove if the local clock is further off than `--max-clock-skew`. The servers that
+//! answered are recorded as the time source in the audit log.
+//!
+//! With `--time-source roughtime` the timestamp is no longer the local clock's: the host
+//! asks `--roughtime-server` for the time, and the guest checks the reply against
+//! `--roughtime-key` and commits the signed time (see `zkip_lib::roughtime`).
+
+use anyhow::{bail, Context};
+use base64::Engine;
+use clap::ValueEnum;
+use sha2::{Digest, Sha512};
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+use zkip_lib::roughtime::{request, RoughtimeResponse, SignedTime, NONCE_SIZE};
+
+use crate::i18n::Msg;
+use crate::tr;
+
+/// Queried when no `--ntp-server` is given; run by different operators.
+pub const DEFAULT_NTP_SERVERS: &[&str] =
+    &["time.cloudflare.com", "time.google.com", "pool.ntp.org"];
+
+/// Seconds between the NTP epoch (1900) and the Unix epoch (1970).
+const NTP_UNIX_OFFSET: f64 = 2_208_988_800.0;
+
+const NTP_TIMEOUT: Duration = Duration::from_secs(3);
+
+const ROUGHTIME_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Larger than any Roughtime reply to a single request.
+const ROUGHTIME_REPLY_SIZE: usize = 4096;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TimeSource {
+    /// Trust the local system clock
+    #[default]
+    System,
+    /// Check the local clock against several NTP servers
+    Ntp,
+    /// Commit the time a Roughtime server signs, checked in the guest
+    Roughtime,
+}
+
+/// A timestamp to commit, and where it came from.
+#[derive(Debug, Clone)]
+pub struct ProofTime {
+    pub timestamp: u64,
+    /// `system`, `ntp:` followed by the servers that answered, or `roughtime:` and the
+    /// server.
+    pub source: String,
+    /// The server's signed reply, for `--time-source roughtime`.
+    pub beacon: Option<RoughtimeResponse>,
+}
+
+fn unix_now() -> anyhow::Result<f64> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .with_context(|| tr!(Msg::ClockBeforeEpoch))?
+        .as_secs_f64())
+}
+
+fn read_ntp_time(bytes: &[u8]) -> f64 {
+    let seconds = u32::from_be_bytes(bytes[0..4].try_into().unwrap()) as f64;
+    let fraction = u32::from_be_bytes(bytes[4..8].try_into().unwrap()) as f64;
+    seconds + fraction / 4_294_967_296.0 - NTP_UNIX_OFFSET
+}
+
+fn write_ntp_time(buf: &mut [u8], unix: f64) {
+    let ntp = unix + NTP_UNIX_OFFSET;
+    buf[0..4].copy_from_slice(&(ntp.trunc() as u32).to_be_bytes());
+    buf[4..8].copy_from_slice(&((ntp.fract() * 4_294_967_296.0) as u32).to_be_bytes());
+}
+
+/// Offset of the local clock from `server`'s, in seconds (positive when the local clock
+/// is behind).
+pub fn query_ntp_offset(server: &str) -> anyhow::Result<f64> {
+    let addr = (server, 123)
+        .to_socket_addrs()
+        .with_context(|| tr!(Msg::ResolveFailed, server))?
+        .next()
+        .with_context(|| tr!(Msg::ServerNoAddress, server))?;
+    let local = if addr.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+    let socket = UdpSocket::bind(local).with_context(|| tr!(Msg::UdpSocketFailed))?;
+    socket.set_read_timeout(Some(NTP_TIMEOUT))?;
+
+    // LI = 0, version 4, mode 3 (client); our transmit time is echoed back as the
+    // originate time, which ties the reply to this request.
+    let mut request = [0u8; 48];
+    request[0] = 0x23;
+    let sent = unix_now()?;
+    write_ntp_time(&mut request[40..48], sent);
+    socket
+        .send_to(&request, addr)
+        .with_context(|| tr!(Msg::SendFailed, server))?;
+
+    let mut reply = [0u8; 48];
+    let (len, from) = socket
+        .recv_from(&mut reply)
+        .with_context(|| tr!(Msg::NoReply, server))?;
+    let received = unix_now()?;
+    if len < 48 || from != addr {
+        bail!(tr!(Msg::UnexpectedReply, from));
+    }
+    if reply[0] & 0x07 != 4 {
+        bail!(tr!(Msg::NtpNotServer, server));
+    }
+    if reply[1] == 0 {
+        bail!(tr!(Msg::NtpKissOfDeath, server));
+    }
+    if reply[24..32] != request[40..48] {
+        bail!(tr!(Msg::NtpWrongRequest, server));
+    }
+
+    let server_received = read_ntp_time(&reply[32..40]);
+    let server_sent = read_ntp_time(&reply[40..48]);
+    Ok(((server_received - sent) + (server_sent - received)) / 2.0)
+}
+
+/// A nonce for one Roughtime request. It only has to be unique, not secret: a replayed
+/// reply carries an older time, which makes a proof look older, never newer.
+fn roughtime_nonce(server: &str) -> anyhow::Result<[u8; NONCE_SIZE]> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .with_context(|| tr!(Msg::ClockBeforeEpoch))?;
+    Ok(Sha512::new()
+        .chain_update(b"zkip-roughtime-nonce")
+        .chain_update(now.as_nanos().to_le_bytes())
+        .chain_update(std::process::id().to_le_bytes())
+        .chain_update(server)
+        .finalize()
+        .into())
+}
+
+/// Ask the Roughtime `server` (`host:port`) for the time, checking the reply against its
+/// long-term `pubkey` as the guest will.
+pub fn query_roughtime(
+    server: &str,
+    pubkey: [u8; 32],
+) -> anyhow::Result<(RoughtimeResponse, SignedTime)> {
+    let addr = server
+        .to_socket_addrs()
+        .with_context(|| tr!(Msg::ResolveFailed, server))?
+        .next()
+        .with_context(|| tr!(Msg::ServerNoAddress, server))?;
+    let local = if addr.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+    let socket = UdpSocket::bind(local).with_context(|| tr!(Msg::UdpSocketFailed))?;
+    socket.set_read_timeout(Some(ROUGHTIME_TIMEOUT))?;
+
+    let nonce = roughtime_nonce(server)?;
+    socket
+        .send_to(&request(&nonce), addr)
+        .with_context(|| tr!(Msg::SendFailed, server))?;
+    let mut reply = vec![0u8; ROUGHTIME_REPLY_SIZE];
+    let (len, from) = socket
+        .recv_from(&mut reply)
+        .with_context(|| tr!(Msg::NoReply, server))?;
+    if from != addr {
+        bail!(tr!(Msg::UnexpectedReply, from));
+    }
+    reply.truncate(len);
+
+    let response = RoughtimeResponse {
+        pubkey,
+        nonce: nonce.to_vec(),
+        reply,
+    };
+    let time = response
+        .verify()
+        .with_context(|| tr!(Msg::RoughtimeBadReply, server))?;
+    Ok((response, time))
+}
+
+fn roughtime_time(server: &str, pubkey: [u8; 32]) -> anyhow::Result<ProofTime> {
+    let (beacon, time) = query_roughtime(server, pubkey)?;
+    info!(
+        radius = time.radius,
+        "{}",
+        tr!(Msg::RoughtimeChecked, time.timestamp, server, time.radius)
+    );
+    Ok(ProofTime {
+        timestamp: time.timestamp,
+        source: format!("roughtime:{}", server),
+        beacon: Some(beacon),
+    })
+}
+
+/// Parse a Roughtime server's long-term Ed25519 key, as servers publish it in base64 or as
+/// hex.
+pub fn parse_roughtime_key(value: &str) -> anyhow::Result<[u8; 32]> {
+    let value = value.trim();
+    let key = match hex::decode(value.trim_start_matches("0x")) {
+        Ok(key) => key,
+        Err(_) => base64::engine::general_purpose::STANDARD
+            .decode(value)
+            .with_context(|| tr!(Msg::RoughtimeKeyEncoding))?,
+    };
+    key.try_into()
+        .map_err(|_| anyhow::anyhow!(tr!(Msg::RoughtimeKeyLength)))
+}
+
+/// Check a set of NTP offsets: the servers must agree within `max_skew` seconds and the
+/// median must be within `max_skew` of the local clock. Returns the median.
+pub fn check_offsets(offsets: &[(String, f64)], max_skew: f64) -> anyhow::Result<f64> {
+    if offsets.len() < 2 {
+        bail!(tr!(Msg::NtpTooFewAnswers, offsets.len()));
+    }
+    let mut sorted: Vec<f64> = offsets.iter().map(|(_, offset)| *offset).collect();
+    sorted.sort_by(f64::total_cmp);
+    let spread = sorted[sorted.len() - 1] - sorted[0];
+    if spread > max_skew {
+        bail!(tr!(
+            Msg::NtpDisagree,
+            format!("{:.3}", spread),
+            max_skew,
+            format!("{:?}", offsets)
+        ));
+    }
+    let median = sorted[sorted.len() / 2];
+    if median.abs() > max_skew {
+        bail!(tr!(Msg::ClockSkewed, format!("{:+.3}", median), max_skew));
+    }
+    Ok(median)
+}
+
+/// Timestamp to commit in the proof, checked as `source` requires.
+/// `roughtime` is the server and its long-term key, for `TimeSource::Roughtime`.
+pub fn proof_time(
+    source: TimeSource,
+    servers: &[String],
+    max_skew: f64,
+    roughtime: Option<(&str, [u8; 32])>,
+) -> anyhow::Result<ProofTime> {
+    let now = unix_now()?;
+    if source == TimeSource::System {
+        return Ok(ProofTime {
+            timestamp: now as u64,
+            source: "system".to_string(),
+            beacon: None,
+        });
+    }
+    if source == TimeSource::Roughtime {
+        let (server, pubkey) = roughtime.with_context(|| tr!(Msg::RoughtimeNeedsKey))?;
+        return roughtime_time(server, pubkey);
+    }
+
+    let mut offsets = Vec::new();
+    for server in servers {
+        match query_ntp_offset(server) {
+            Ok(offset) => offsets.push((server.clone(), offset)),
+            Err(err) => warn!(
+                "{}",
+                tr!(Msg::NtpServerFailed, server, format!("{:#}", err))
+            ),
+        }
+    }
+    let offset = check_offsets(&offsets, max_skew)?;
+    info!(
+        offset,
+        "{}",
+        tr!(Msg::ClockChecked, format!("{:+.3}", offset), offsets.len())
+    );
+
+    let answered: Vec<&str> = offsets.iter().map(|(server, _)| server.as_str()).collect();
+    Ok(ProofTime {
+        timestamp: now as u64,
+        source: format!("ntp:{}", answered.join(",")),
+        beacon: None,
+    })
+}
+
+/// `servers`, or `DEFAULT_NTP_SERVERS` when none were given.
+pub fn ntp_servers(servers: &[String]) -> Vec<String> {
+    if servers.is_empty() {
+        DEFAULT_NTP_SERVERS.iter().map(|s| s.to_string()).collect()
+    } else {
+        servers.to_vec()
+    }
+}
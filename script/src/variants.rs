@@ -0,0 +1,62 @@
+//! Guest program variants embedded in the binaries.
+//!
+//! Every variant reads the same inputs and commits the same `PublicValuesStruct`, but has
+//! its own ELF and therefore its own vkey. Consumers pin the variant whose vkey they trust;
+//! new strategies ship as new variants without changing existing ones.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use sp1_sdk::include_elf;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProgramVariant {
+    /// Linear scan over the excluded ranges (the original program)
+    #[default]
+    Linear,
+    /// Binary search over sorted, disjoint ranges, checked in the guest
+    BinarySearch,
+    /// Full scan of every range, so cycle counts don't depend on the private IP
+    ConstantWork,
+}
+
+impl ProgramVariant {
+    pub const ALL: &'static [ProgramVariant] =
+        &[Self::Linear, Self::BinarySearch, Self::ConstantWork];
+
+    /// Name of the guest binary in `program/Cargo.toml`.
+    pub fn binary_name(self) -> &'static str {
+        match self {
+            Self::Linear => "zkip-program",
+            Self::BinarySearch => "zkip-program-bsearch",
+            Self::ConstantWork => "zkip-program-constant",
+        }
+    }
+
+    /// Stable identifier, as accepted by `--variant`.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Linear => "linear",
+            Self::BinarySearch => "binary-search",
+            Self::ConstantWork => "constant-work",
+        }
+    }
+
+    pub fn elf(self) -> &'static [u8] {
+        match self {
+            Self::Linear => include_elf!("zkip-program"),
+            Self::BinarySearch => include_elf!("zkip-program-bsearch"),
+            Self::ConstantWork => include_elf!("zkip-program-constant"),
+        }
+    }
+}
+
+/// The renewal guest behind `zkip renew`. It reads an earlier proof instead of ranges, so it
+/// is no variant and not in `ProgramVariant::ALL`.
+pub const RENEW_ELF: &[u8] = include_elf!("zkip-program-renew");
+
+/// The guests behind `--chunked`: the chunk guest checks one chunk of ranges, and the
+/// aggregation guest combines the chunk proofs (`zkip_lib::chunk`). Neither reads the
+/// variants' inputs, so they are no variants either.
+pub const CHUNK_ELF: &[u8] = include_elf!("zkip-program-chunk");
+pub const AGGREGATE_ELF: &[u8] = include_elf!("zkip-program-aggregate");
@@ -0,0 +1,53 @@
+//! GeoIP snapshots embedded in the binary, for proving without any network fetch.
+//!
+//! Each snapshot is ip-location-db's IPv4 and IPv6 country files as published on one day,
+//! embedded byte for byte behind a feature named after that date, with the SHA-256 of
+//! each file pinned next to it. The host writes an enabled snapshot out where it would
+//! otherwise download the files (see `zkip --snapshot`), so proofs over it commit the same
+//! snapshot hash, dataset root and ranges digest as proofs over the downloaded files.
+//!
+//! No snapshot is enabled by default. To add one, put its files in
+//! `snapshots/data/<date>/`, add a `snapshot-<date>` feature, and add an entry to
+//! `SNAPSHOTS` behind it with the files' SHA-256.
+
+#![no_std]
+
+use sha2::{Digest, Sha256};
+
+/// One embedded snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Publication date, `YYYY-MM-DD`, as in its feature's name.
+    pub date: &'static str,
+    /// When it was published, in Unix seconds, as `SnapshotMetadata::published_at`.
+    pub published_at: u64,
+    /// Contents of `ipv4-country.csv`.
+    pub ipv4: &'static [u8],
+    /// Contents of `ipv6-country.csv`.
+    pub ipv6: &'static [u8],
+    /// Pinned SHA-256 of `ipv4`.
+    pub sha256: [u8; 32],
+    /// Pinned SHA-256 of `ipv6`.
+    pub sha256_v6: [u8; 32],
+}
+
+impl Snapshot {
+    /// True if both files hash to their pinned SHA-256.
+    pub fn verify(&self) -> bool {
+        Sha256::digest(self.ipv4)[..] == self.sha256
+            && Sha256::digest(self.ipv6)[..] == self.sha256_v6
+    }
+}
+
+/// The snapshots this build embeds, oldest first.
+pub const SNAPSHOTS: &[Snapshot] = &[];
+
+/// The embedded snapshot published on `date` (`YYYY-MM-DD`).
+pub fn find(date: &str) -> Option<&'static Snapshot> {
+    SNAPSHOTS.iter().find(|snapshot| snapshot.date == date)
+}
+
+/// The most recent embedded snapshot.
+pub fn latest() -> Option<&'static Snapshot> {
+    SNAPSHOTS.last()
+}
@@ -0,0 +1,210 @@
+//! {{project-name}} - custom zkip policy circuit
+//! Adds one check on top of the shared exclusion logic: {{predicate}}.
+//!
+//! The shared inputs are read exactly as zkip's built-in guests read them, so the zkip
+//! CLI can drive this program with `--elf` and decode what it commits. Data compiled into
+//! the guest (like `EXTRA_BLOCKED`) is covered by its vkey.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use zkip_lib::{
+    all_excluded, all_public, clear_count, disclosed_code, encode_batch_public_values,
+    encode_challenged_public_values, encode_public_values, encode_windowed_public_values,
+    groups_sorted, merkle::dataset_root, non_vacuous, policy::Address, prefix, range_coverage,
+    ranges_digest, unpack_ranges, unpack_ranges_v6, ValidityWindow, ZkipInput,
+};
+
+/// Extra dataset baked into the guest: inclusive `(start, end)` IPv4 ranges.
+const EXTRA_BLOCKED: &[(u32, u32)] = &[];
+
+/// The custom predicate. Return `true` if `ip` passes.
+fn policy(ip: u32) -> bool {
+    EXTRA_BLOCKED
+        .iter()
+        .all(|&(start, end)| ip < start || ip > end)
+}
+
+pub fn main() {
+    // Shared inputs, as `zkip_script::inputs::build_stdin` writes them
+    let ZkipInput {
+        ips,
+        ips_v6,
+        excluded_countries,
+        range_counts,
+        timestamp,
+        disclosure,
+        anchor,
+        batch,
+        inventory,
+        threshold,
+        include,
+        denylist,
+        policy: policy_expr,
+        per_country,
+        gap,
+        attestation,
+        web_proof,
+        snapshot_signature,
+        time_beacon,
+        companion,
+        nullifier,
+        challenge,
+        valid_for,
+        snapshot,
+        app_context,
+        prefix_salt,
+        block,
+    } = sp1_zkvm::io::read::<ZkipInput>();
+    let packed = sp1_zkvm::io::read_vec();
+    let packed_v6 = sp1_zkvm::io::read_vec();
+    // The checks below compare addresses with the bare `u32` bounds of the ranges
+    let ips: Vec<u32> = ips.into_iter().map(u32::from).collect();
+    let excluded_ranges = unpack_ranges(&packed);
+    let excluded_ranges_v6 = unpack_ranges_v6(&packed_v6);
+
+    // Inputs from `PolicyCircuit::write_extra_inputs`, if your host writes any, go here.
+
+    // `policy` is an exclusion check, so this circuit does not prove inclusion.
+    assert!(
+        !include && denylist.is_none() && policy_expr.is_none() && !per_country,
+        "this circuit only proves exclusion"
+    );
+    // Gap witnesses replace the ranges the extra check runs next to
+    assert!(gap.is_none(), "this circuit reads the full range lists");
+    // Attested, signed-snapshot, timed, inventory, threshold, block and composed proofs
+    // commit their own layouts; add them here if your circuit needs one
+    assert!(attestation.is_none(), "this circuit takes no attestation");
+    assert!(web_proof.is_none(), "this circuit takes no web proof");
+    assert!(
+        snapshot_signature.is_none(),
+        "this circuit takes no snapshot signature"
+    );
+    assert!(time_beacon.is_none(), "this circuit takes no time beacon");
+    assert!(block.is_none(), "this circuit takes no block");
+    assert!(!inventory, "this circuit takes no inventory");
+    assert!(threshold.is_none(), "this circuit takes no threshold");
+    assert!(companion.is_none(), "this circuit takes no companion proof");
+
+    // Malformed host input aborts the proof instead of skewing the result
+    assert!(
+        groups_sorted(&excluded_ranges, &excluded_ranges_v6, &range_counts),
+        "excluded ranges must be sorted and disjoint"
+    );
+    // Empty inputs would pass every IP
+    assert!(
+        non_vacuous(&excluded_countries, &excluded_ranges, &excluded_ranges_v6),
+        "proofs need at least one country and one range"
+    );
+
+    // `policy` sees IPv4 addresses only; extend it if your dataset covers IPv6.
+    let is_excluded = all_excluded(&ips, &excluded_ranges)
+        && all_excluded(&ips_v6, &excluded_ranges_v6)
+        && ips.iter().all(|&ip| policy(ip));
+
+    // Reserved addresses say nothing about location. Plain proofs commit whether the IP is
+    // public; batch, disclosure and anchored proofs require it.
+    let is_public_ip = all_public(&ips, &ips_v6);
+    assert!(
+        is_public_ip || (!batch && disclosure.is_none() && anchor.is_none()),
+        "only plain proofs take a reserved address"
+    );
+
+    // Commit to each country's ranges, so verifiers can check they are a trusted dataset's
+    // complete ranges for the claimed countries
+    let dataset_root = dataset_root(
+        &excluded_countries,
+        &excluded_ranges,
+        &excluded_ranges_v6,
+        &range_counts,
+    );
+
+    // Commit one of the shared layouts so fixtures and `zkip verify` decode it unchanged.
+    let bytes = if batch {
+        assert!(
+            disclosure.is_none()
+                && anchor.is_none()
+                && nullifier.is_none()
+                && app_context.is_none()
+                && prefix_salt.is_none(),
+            "batch proofs take no disclosure, anchor, nullifier, app context or prefix"
+        );
+        let address_count = (ips.len() + ips_v6.len()) as u32;
+        let clear_count = ips
+            .iter()
+            .filter(|&&ip| all_excluded(&[ip], &excluded_ranges) && policy(ip))
+            .count() as u32
+            + clear_count(&ips_v6, &excluded_ranges_v6);
+        encode_batch_public_values(
+            is_excluded,
+            timestamp,
+            excluded_countries,
+            address_count,
+            dataset_root,
+            clear_count,
+        )
+    } else {
+        assert_eq!(
+            ips.len() + ips_v6.len(),
+            1,
+            "single proofs take exactly one address"
+        );
+        assert!(
+            ips_v6.is_empty() || disclosure.is_none(),
+            "disclosure takes an IPv4 address"
+        );
+        let address = match ips.first() {
+            Some(&ip) => Address::V4(ip),
+            None => Address::V6(ips_v6[0]),
+        };
+        // Derived from the address the proof is about, so one IP gets one nullifier per
+        // epoch and salt, and one network one prefix commitment per salt
+        let nullifier = nullifier.map(|input| input.values(address));
+        let prefix = prefix_salt.map(|salt| prefix::values(address, &salt));
+        // Hashed as read, with SP1's SHA-256 precompile, so verifiers holding the same
+        // snapshot can recompute it without the Merkle scheme
+        let digest = ranges_digest(&excluded_countries, &range_counts, &packed, &packed_v6);
+        // Derived from the excluded country whose ranges contain the IP, the ranges hashed
+        // into `dataset_root`, so the host cannot name a region of its choosing
+        let disclosed = disclosure.map(|granularity| {
+            let code = disclosed_code(
+                granularity,
+                ips[0],
+                &excluded_countries,
+                &excluded_ranges,
+                &range_counts,
+            )
+            .expect("disclosure needs the IP in one of the countries");
+            (granularity, code)
+        });
+        encode_public_values(
+            is_excluded,
+            is_public_ip,
+            timestamp,
+            excluded_countries,
+            disclosed,
+            anchor,
+            dataset_root,
+            range_coverage(&excluded_ranges, &excluded_ranges_v6),
+            digest,
+            nullifier,
+            snapshot,
+            app_context,
+            prefix,
+        )
+    };
+    // A validity window, then a verifier's challenge, wrap whichever layout was committed
+    let bytes = match valid_for {
+        Some(valid_for) => {
+            let window = ValidityWindow::starting_at(timestamp, valid_for)
+                .expect("validity window must end by 2106");
+            encode_windowed_public_values(bytes, window)
+        }
+        None => bytes,
+    };
+    let bytes = match challenge {
+        Some(challenge) => encode_challenged_public_values(bytes, challenge),
+        None => bytes,
+    };
+    sp1_zkvm::io::commit_slice(&bytes);
+}